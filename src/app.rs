@@ -217,6 +217,10 @@ pub fn build_app(state: AppState) -> Router {
 
     Router::new()
         .route("/healthz", get(healthz))
+        .route(
+            "/metrics",
+            get(crate::metrics::serve_metrics).with_state(state.clone()),
+        )
         .nest("/api", api)
         .fallback(serve_embedded_ui)
         .layer(TraceLayer::new_for_http())
@@ -282,7 +286,8 @@ async fn require_user_global(
     req: Request<Body>,
     next: axum::middleware::Next,
 ) -> Response<Body> {
-    if req.uri().path() == "/healthz" {
+    // `/metrics` checks its own bearer token.
+    if matches!(req.uri().path(), "/healthz" | "/metrics") {
         return next.run(req).await;
     }
 
@@ -8,7 +8,7 @@ use axum::{
     middleware::Next,
     response::sse::{Event, KeepAlive, Sse},
     response::{IntoResponse, Response},
-    routing::{get, patch, post},
+    routing::{get, patch, post, put},
     Json, Router,
 };
 use futures_util::stream;
@@ -33,6 +33,7 @@ pub fn router(state: AppState) -> Router {
             post(post_archive_delisted_products),
         )
         .route("/inventory/history", post(post_inventory_history))
+        .route("/inventory/restock-forecast", post(post_restock_forecast))
         .route("/catalog/refresh", post(post_catalog_refresh))
         .route(
             "/catalog/refresh/partition",
@@ -41,6 +42,15 @@ pub fn router(state: AppState) -> Router {
         .route("/catalog/refresh/events", get(get_catalog_refresh_events))
         .route("/ops/state", get(get_ops_state))
         .route("/ops/stream", get(get_ops_stream))
+        .route("/ops/outages", get(get_ops_outages))
+        .route("/ops/alerts", get(get_ops_alerts))
+        .route("/ops/digest", get(get_ops_digest))
+        .route("/ops/runs", get(get_ops_runs))
+        .route("/ops/admin/pause", post(post_ops_pause))
+        .route("/ops/admin/resume", post(post_ops_resume))
+        .route("/ops/admin/drain", post(post_ops_drain))
+        .route("/ops/admin/cancel", post(post_ops_cancel))
+        .route("/ops/admin/clear", post(post_ops_clear))
         .route("/refresh", post(post_refresh))
         .route("/refresh/status", get(get_refresh_status))
         .route("/monitoring", get(get_monitoring))
@@ -49,6 +59,23 @@ pub fn router(state: AppState) -> Router {
             patch(patch_monitoring_config),
         )
         .route("/monitoring/partitions", patch(patch_monitoring_partition))
+        .route("/monitoring/schedules", get(get_poll_schedules))
+        .route(
+            "/monitoring/schedules/partitions",
+            put(put_partition_poll_schedule),
+        )
+        .route(
+            "/monitoring/schedules/configs/:config_id",
+            put(put_config_poll_schedule),
+        )
+        .route(
+            "/monitoring/rules",
+            get(get_monitoring_rules).post(post_monitoring_rule),
+        )
+        .route(
+            "/monitoring/rules/:rule_id",
+            put(put_monitoring_rule).delete(delete_monitoring_rule),
+        )
         .route("/settings", get(get_settings).put(put_settings))
         .route(
             "/lazycat/account",
@@ -450,10 +477,276 @@ async fn get_ops_state(
     Ok(Json(snap))
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpsOutagesQuery {
+    provider_id: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn get_ops_outages(
+    State(state): State<AppState>,
+    _user: axum::extract::Extension<UserView>,
+    Query(q): Query<OpsOutagesQuery>,
+) -> Result<Json<crate::models::UpstreamOutagesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let before_id = match q.cursor.as_deref().filter(|v| !v.is_empty()) {
+        Some(cursor) => Some(cursor.parse::<i64>().map_err(|_| json_invalid_argument())?),
+        None => None,
+    };
+    let provider_id = q
+        .provider_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let items = db::list_upstream_outages(&state.db, provider_id, before_id, limit)
+        .await
+        .map_err(|_| json_internal_error())?;
+    let next_cursor = (items.len() as i64 == limit)
+        .then(|| items.last().map(|item| item.id.to_string()))
+        .flatten();
+    Ok(Json(crate::models::UpstreamOutagesResponse {
+        items,
+        next_cursor,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpsAlertsQuery {
+    open: Option<bool>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn get_ops_alerts(
+    State(state): State<AppState>,
+    _user: axum::extract::Extension<UserView>,
+    Query(q): Query<OpsAlertsQuery>,
+) -> Result<Json<crate::models::OpsHealthAlertsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let before_id = match q.cursor.as_deref().filter(|v| !v.is_empty()) {
+        Some(cursor) => Some(cursor.parse::<i64>().map_err(|_| json_invalid_argument())?),
+        None => None,
+    };
+    let items = db::list_ops_health_alerts(&state.db, q.open.unwrap_or(false), before_id, limit)
+        .await
+        .map_err(|_| json_internal_error())?;
+    let next_cursor = (items.len() as i64 == limit)
+        .then(|| items.last().map(|item| item.id.to_string()))
+        .flatten();
+    Ok(Json(crate::models::OpsHealthAlertsResponse {
+        items,
+        next_cursor,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpsDigestQuery {
+    to: Option<String>,
+}
+
+/// The digest of the 24 hours before `to` (default now), without sending it.
+async fn get_ops_digest(
+    State(state): State<AppState>,
+    _user: axum::extract::Extension<UserView>,
+    Query(q): Query<OpsDigestQuery>,
+) -> Result<Json<crate::models::OpsDigestView>, (StatusCode, Json<ErrorResponse>)> {
+    let to = match q.to.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(raw) => OffsetDateTime::parse(raw, &Rfc3339)
+            .map_err(|_| json_invalid_argument())?
+            .to_offset(time::UtcOffset::UTC),
+        None => OffsetDateTime::now_utc(),
+    };
+    let from = (to - time::Duration::days(1))
+        .format(&Rfc3339)
+        .map_err(|_| json_internal_error())?;
+    let to = to.format(&Rfc3339).map_err(|_| json_internal_error())?;
+    let digest = db::load_ops_digest(&state.db, &from, &to)
+        .await
+        .map_err(|_| json_internal_error())?;
+    Ok(Json(digest))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpsRunsQuery {
+    from: Option<String>,
+    to: Option<String>,
+    provider_id: Option<String>,
+    fid: Option<String>,
+    gid: Option<String>,
+    ok: Option<String>,
+    error_code: Option<String>,
+    fetch_action: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn get_ops_runs(
+    State(state): State<AppState>,
+    _user: axum::extract::Extension<UserView>,
+    Query(q): Query<OpsRunsQuery>,
+) -> Result<Json<crate::models::OpsTaskRunsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let before_id = match q.cursor.as_deref().filter(|v| !v.is_empty()) {
+        Some(cursor) => Some(cursor.parse::<i64>().map_err(|_| json_invalid_argument())?),
+        None => None,
+    };
+    // Normalized to UTC so they compare with the stored `started_at` strings.
+    let utc_bound = |raw: Option<&str>| match raw.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(raw) => OffsetDateTime::parse(raw, &Rfc3339)
+            .ok()
+            .and_then(|ts| ts.to_offset(time::UtcOffset::UTC).format(&Rfc3339).ok())
+            .map(Some)
+            .ok_or_else(json_invalid_argument),
+        None => Ok(None),
+    };
+    let from = utc_bound(q.from.as_deref())?;
+    let to = utc_bound(q.to.as_deref())?;
+    let ok = match q.ok.as_deref() {
+        None | Some("") => None,
+        Some("1" | "true") => Some(true),
+        Some("0" | "false") => Some(false),
+        Some(_) => return Err(json_invalid_argument()),
+    };
+    let non_empty = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(ToOwned::to_owned)
+    };
+    let (provider_id, fid, gid, error_code, fetch_action) = (
+        non_empty(&q.provider_id),
+        non_empty(&q.fid),
+        non_empty(&q.gid),
+        non_empty(&q.error_code),
+        non_empty(&q.fetch_action),
+    );
+    let filter = db::OpsTaskRunFilter {
+        from: from.as_deref(),
+        to: to.as_deref(),
+        provider_id: provider_id.as_deref(),
+        fid: fid.as_deref(),
+        gid: gid.as_deref(),
+        ok,
+        error_code: error_code.as_deref(),
+        fetch_action: fetch_action.as_deref(),
+    };
+    let items = db::list_ops_task_runs(&state.db, &filter, before_id, limit)
+        .await
+        .map_err(|_| json_internal_error())?;
+    let next_cursor = (items.len() as i64 == limit)
+        .then(|| items.last().map(|item| item.id.to_string()))
+        .flatten();
+    Ok(Json(crate::models::OpsTaskRunsResponse {
+        items,
+        next_cursor,
+    }))
+}
+
+/// Ops actions are limited to the users named in `CATNAP_OPS_ADMIN_USER_IDS`; nobody may run
+/// them while it is unset.
+fn require_ops_admin(
+    state: &AppState,
+    user: &UserView,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if state
+        .config
+        .ops_admin_user_ids
+        .iter()
+        .any(|id| id == &user.id)
+    {
+        Ok(())
+    } else {
+        Err(json_forbidden())
+    }
+}
+
+async fn post_ops_pause(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+) -> Result<Json<crate::ops::OpsControlView>, (StatusCode, Json<ErrorResponse>)> {
+    require_ops_admin(&state, &user.0)?;
+    let control = state
+        .ops
+        .pause(&user.0.id)
+        .await
+        .map_err(|_| json_internal_error())?;
+    Ok(Json(control))
+}
+
+async fn post_ops_resume(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+) -> Result<Json<crate::ops::OpsControlView>, (StatusCode, Json<ErrorResponse>)> {
+    require_ops_admin(&state, &user.0)?;
+    let control = state
+        .ops
+        .resume(&user.0.id)
+        .await
+        .map_err(|_| json_internal_error())?;
+    Ok(Json(control))
+}
+
+async fn post_ops_drain(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+) -> Result<Json<crate::ops::OpsControlView>, (StatusCode, Json<ErrorResponse>)> {
+    require_ops_admin(&state, &user.0)?;
+    let control = state
+        .ops
+        .drain(&user.0.id)
+        .await
+        .map_err(|_| json_internal_error())?;
+    Ok(Json(control))
+}
+
+async fn post_ops_cancel(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+    Json(req): Json<OpsCancelRequest>,
+) -> Result<Json<crate::ops::OpsCancelView>, (StatusCode, Json<ErrorResponse>)> {
+    require_ops_admin(&state, &user.0)?;
+    if req.keys.is_empty() || req.keys.iter().any(|key| key.fid.trim().is_empty()) {
+        return Err(json_invalid_argument());
+    }
+    let keys = req
+        .keys
+        .into_iter()
+        .map(|key| (key.fid, key.gid))
+        .collect::<Vec<_>>();
+    let res = state
+        .ops
+        .cancel_tasks(&user.0.id, &keys)
+        .await
+        .map_err(|_| json_internal_error())?;
+    Ok(Json(res))
+}
+
+async fn post_ops_clear(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+) -> Result<Json<crate::ops::OpsCancelView>, (StatusCode, Json<ErrorResponse>)> {
+    require_ops_admin(&state, &user.0)?;
+    let res = state
+        .ops
+        .clear_queue(&user.0.id)
+        .await
+        .map_err(|_| json_internal_error())?;
+    Ok(Json(res))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct OpsStreamQuery {
     range: Option<String>,
+    events: Option<String>,
+    fid: Option<String>,
+    gid: Option<String>,
+    level: Option<String>,
 }
 
 async fn get_ops_stream(
@@ -470,6 +763,14 @@ async fn get_ops_stream(
         Some(None) => return Err(json_invalid_argument()),
         None => crate::ops::OpsRange::H24,
     };
+    let filter = crate::ops::OpsEventFilter::parse(
+        q.events.as_deref(),
+        q.fid.as_deref(),
+        q.gid.as_deref(),
+        q.level.as_deref(),
+    )
+    .ok_or_else(json_invalid_argument)?;
+    let wants_metrics = filter.wants_event("ops.metrics");
 
     let now = OffsetDateTime::now_utc();
     let server_time = now
@@ -504,6 +805,7 @@ async fn get_ops_stream(
                 failure: 0,
                 success_rate_pct: 0.0,
                 cache_hits: 0,
+                not_modified: 0,
             },
             notify: crate::ops::OpsNotifyStatsView {
                 telegram: None,
@@ -557,7 +859,9 @@ async fn get_ops_stream(
 
     let mut initial_items: Vec<Result<Event, Infallible>> = Vec::new();
     initial_items.push(Ok::<_, Infallible>(hello_event));
-    initial_items.push(Ok::<_, Infallible>(metrics_event));
+    if wants_metrics {
+        initial_items.push(Ok::<_, Infallible>(metrics_event));
+    }
     if let Some(reason) = reset_reason {
         let payload = serde_json::json!({
             "serverTime": server_time,
@@ -571,7 +875,7 @@ async fn get_ops_stream(
                 .data(serde_json::to_string(&payload).unwrap_or_else(|_| "{}".to_string())),
         ));
     }
-    for e in replay {
+    for e in replay.into_iter().filter(|e| filter.matches(e)) {
         initial_items.push(Ok::<_, Infallible>(
             Event::default()
                 .id(e.id.to_string())
@@ -583,23 +887,27 @@ async fn get_ops_stream(
 
     let rx = state.ops.subscribe();
 
-    let updates_stream = stream::unfold(rx, |mut rx| async {
+    let updates_stream = stream::unfold((rx, filter), |(mut rx, filter)| async {
         loop {
             match rx.recv().await {
-                Ok(ev) => {
+                Ok(ev) if filter.matches(&ev) => {
                     let out = Event::default()
                         .id(ev.id.to_string())
                         .event(ev.event)
                         .data(ev.data_json);
-                    return Some((Ok::<_, Infallible>(out), rx));
+                    return Some((Ok::<_, Infallible>(out), (rx, filter)));
                 }
+                Ok(_) => continue,
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
                 Err(tokio::sync::broadcast::error::RecvError::Closed) => return None,
             }
         }
     });
 
-    let metrics_stream = stream::unfold((state.clone(), range), |(state, range)| async move {
+    let metrics_stream = stream::unfold((state.clone(), range), move |(state, range)| async move {
+        if !wants_metrics {
+            return None;
+        }
         tokio::time::sleep(Duration::from_secs(5)).await;
         let now = OffsetDateTime::now_utc();
         let server_time = now
@@ -678,9 +986,15 @@ async fn get_bootstrap(
         .await
         .map_err(|_| json_invalid_argument())?;
 
-    let configs = db::list_catalog_configs_view(&state.db, &user_id, None, None)
-        .await
-        .map_err(|_| json_internal_error())?;
+    let configs = db::list_catalog_configs_view(
+        &state.db,
+        &crate::pricing::ExchangeRates::from_config(&state.config),
+        &user_id,
+        None,
+        None,
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
 
     let snapshot = state.catalog.read().await.clone();
 
@@ -734,6 +1048,7 @@ async fn get_products(
 ) -> Result<Json<ProductsResponse>, (StatusCode, Json<ErrorResponse>)> {
     let configs = db::list_catalog_configs_view(
         &state.db,
+        &crate::pricing::ExchangeRates::from_config(&state.config),
         &user.0.id,
         q.country_id.as_deref(),
         q.region_id.as_deref(),
@@ -839,12 +1154,20 @@ async fn get_monitoring(
     State(state): State<AppState>,
     user: axum::extract::Extension<UserView>,
 ) -> Result<Json<MonitoringListResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let items = db::list_monitoring_configs_view(&state.db, &user.0.id)
-        .await
-        .map_err(|_| json_internal_error())?;
-    let recent_listed24h = db::list_recent_listed_24h_view(&state.db, &user.0.id)
-        .await
-        .map_err(|_| json_internal_error())?;
+    let items = db::list_monitoring_configs_view(
+        &state.db,
+        &crate::pricing::ExchangeRates::from_config(&state.config),
+        &user.0.id,
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
+    let recent_listed24h = db::list_recent_listed_24h_view(
+        &state.db,
+        &crate::pricing::ExchangeRates::from_config(&state.config),
+        &user.0.id,
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
 
     let snapshot = state.catalog.read().await.clone();
     Ok(Json(MonitoringListResponse {
@@ -922,6 +1245,440 @@ async fn patch_monitoring_partition(
     }))
 }
 
+async fn post_restock_forecast(
+    State(state): State<AppState>,
+    _user: axum::extract::Extension<UserView>,
+    Json(req): Json<RestockForecastRequest>,
+) -> Result<Json<RestockForecastResponse>, (StatusCode, Json<ErrorResponse>)> {
+    const MAX_CONFIG_IDS: usize = 200;
+    const HORIZON_HOURS: i64 = 48;
+    const MAX_WINDOWS: usize = 5;
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for id in req.config_ids {
+        let id = id.trim().to_string();
+        if id.is_empty() {
+            continue;
+        }
+        if seen.insert(id.clone()) {
+            ids.push(id);
+        }
+        if ids.len() > MAX_CONFIG_IDS {
+            return Err(json_invalid_argument());
+        }
+    }
+    if ids.is_empty() {
+        return Err(json_invalid_argument());
+    }
+
+    let placeholders = std::iter::repeat_n("?", ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        "SELECT id, country_id, region_id FROM catalog_configs WHERE id IN ({placeholders})"
+    );
+    let mut q = sqlx::query(&sql);
+    for id in &ids {
+        q = q.bind(id);
+    }
+    let mut partition_of: HashMap<String, (String, Option<String>)> = HashMap::new();
+    for row in q
+        .fetch_all(&state.db)
+        .await
+        .map_err(|_| json_internal_error())?
+    {
+        partition_of.insert(row.get(0), (row.get(1), row.get(2)));
+    }
+    let ids = ids
+        .into_iter()
+        .filter(|id| partition_of.contains_key(id))
+        .collect::<Vec<_>>();
+    let mut partitions: Vec<(String, Option<String>)> = Vec::new();
+    for id in &ids {
+        let partition = partition_of[id].clone();
+        if !partitions.contains(&partition) {
+            partitions.push(partition);
+        }
+    }
+    let partition_keys = partitions
+        .iter()
+        .map(|(country_id, region_id)| {
+            db::monitoring_partition_key(country_id, region_id.as_deref())
+        })
+        .collect::<Vec<_>>();
+
+    let config_profiles = db::list_restock_profiles(&state.db, "config", &ids)
+        .await
+        .map_err(|_| json_internal_error())?;
+    let partition_profiles = db::list_restock_profiles(&state.db, "partition", &partition_keys)
+        .await
+        .map_err(|_| json_internal_error())?;
+
+    let now = OffsetDateTime::now_utc();
+    let format = |t: OffsetDateTime| t.format(&Rfc3339).unwrap_or_default();
+    let forecast = |config_id: Option<String>,
+                    (country_id, region_id): (String, Option<String>),
+                    stored: Option<&(String, String)>| {
+        let profile = stored.and_then(|(json, _)| {
+            serde_json::from_str::<crate::restock_forecast::RestockProfile>(json).ok()
+        });
+        let windows = profile
+            .as_ref()
+            .map(|p| p.predict_windows(now, HORIZON_HOURS, MAX_WINDOWS))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|w| RestockWindowView {
+                start: format(w.start),
+                end: format(w.end),
+                score: (w.score * 10.0).round() / 10.0,
+            })
+            .collect();
+        RestockForecastView {
+            config_id,
+            country_id,
+            region_id,
+            computed_at: stored.map(|(_, computed_at)| computed_at.clone()),
+            expected_by_gap_at: profile
+                .as_ref()
+                .and_then(|p| p.expected_by_gap(now))
+                .map(format),
+            profile,
+            windows,
+        }
+    };
+
+    let configs = ids
+        .iter()
+        .map(|id| {
+            forecast(
+                Some(id.clone()),
+                partition_of[id].clone(),
+                config_profiles.get(id),
+            )
+        })
+        .collect();
+    let partitions = partitions
+        .into_iter()
+        .zip(&partition_keys)
+        .map(|(partition, key)| forecast(None, partition, partition_profiles.get(key)))
+        .collect();
+
+    Ok(Json(RestockForecastResponse {
+        generated_at: format(now),
+        horizon_hours: HORIZON_HOURS,
+        configs,
+        partitions,
+    }))
+}
+
+async fn load_poll_schedules(
+    state: &AppState,
+    user_id: &str,
+) -> anyhow::Result<PollSchedulesResponse> {
+    let settings = db::ensure_user(&state.db, &state.config, user_id).await?;
+    let default_interval_minutes = settings.poll_interval_minutes.max(1);
+    let schedules = db::list_poll_schedules(&state.db, user_id)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let (country_id, region_id, config_id) = if row.scope_kind == "config" {
+                (None, None, Some(row.scope_key))
+            } else {
+                let (country_id, region_id) = row
+                    .scope_key
+                    .split_once("::")
+                    .unwrap_or((row.scope_key.as_str(), ""));
+                (
+                    Some(country_id.to_string()),
+                    Some(region_id.to_string()).filter(|id| !id.is_empty()),
+                    None,
+                )
+            };
+            PollScheduleView {
+                scope: row.scope_kind,
+                country_id,
+                region_id,
+                config_id,
+                utc_offset: row.utc_offset,
+                rules: serde_json::from_str(&row.rules_json).unwrap_or_default(),
+                updated_at: row.updated_at,
+            }
+        })
+        .collect();
+    let partitions = crate::poller::plan_monitoring_polls(
+        &state.db,
+        &state.config,
+        user_id,
+        default_interval_minutes,
+        OffsetDateTime::now_utc(),
+    )
+    .await?
+    .iter()
+    .map(|plan| plan.to_view())
+    .collect();
+    Ok(PollSchedulesResponse {
+        default_interval_minutes,
+        schedules,
+        partitions,
+    })
+}
+
+/// Validates and stores (or, with no rules, removes) one schedule.
+async fn save_poll_schedule(
+    state: &AppState,
+    user_id: &str,
+    scope_kind: &str,
+    scope_key: &str,
+    req: PollScheduleRequest,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if req.rules.is_empty() {
+        return db::delete_poll_schedule(&state.db, user_id, scope_kind, scope_key)
+            .await
+            .map_err(|_| json_internal_error());
+    }
+    let utc_offset = req.utc_offset.unwrap_or_else(|| "+00:00".to_string());
+    crate::poll_schedule::PollSchedule::parse(&utc_offset, &req.rules)
+        .map_err(|_| json_invalid_argument())?;
+    let rules_json = serde_json::to_string(&req.rules).map_err(|_| json_internal_error())?;
+    db::upsert_poll_schedule(
+        &state.db,
+        user_id,
+        scope_kind,
+        scope_key,
+        utc_offset.trim(),
+        &rules_json,
+    )
+    .await
+    .map_err(|_| json_internal_error())
+}
+
+async fn get_poll_schedules(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+) -> Result<Json<PollSchedulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    load_poll_schedules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
+async fn put_partition_poll_schedule(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+    Json(req): Json<PollSchedulePartitionRequest>,
+) -> Result<Json<PollSchedulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(partition) =
+        db::normalize_monitoring_partition(&req.country_id, req.region_id.as_deref())
+    else {
+        return Err(json_invalid_argument());
+    };
+    let exists = db::catalog_partition_exists(
+        &state.db,
+        &partition.country_id,
+        partition.region_id.as_deref(),
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
+    if !exists {
+        return Err(json_invalid_argument());
+    }
+
+    let key = db::monitoring_partition_key(&partition.country_id, partition.region_id.as_deref());
+    save_poll_schedule(&state, &user.0.id, "partition", &key, req.schedule).await?;
+    load_poll_schedules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
+async fn put_config_poll_schedule(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+    Path(config_id): Path<String>,
+    Json(req): Json<PollScheduleRequest>,
+) -> Result<Json<PollSchedulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let exists = sqlx::query("SELECT 1 FROM catalog_configs WHERE id = ?")
+        .bind(&config_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|_| json_internal_error())?
+        .is_some();
+    if !exists {
+        return Err(json_invalid_argument());
+    }
+
+    save_poll_schedule(&state, &user.0.id, "config", &config_id, req).await?;
+    load_poll_schedules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
+async fn load_monitoring_rules(
+    state: &AppState,
+    user_id: &str,
+) -> anyhow::Result<MonitoringRulesResponse> {
+    let rules = db::list_monitoring_rules(&state.db, user_id)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let (country_id, region_id, config_id) = if row.scope_kind == "config" {
+                (None, None, Some(row.scope_key))
+            } else {
+                let (country_id, region_id) = row
+                    .scope_key
+                    .split_once("::")
+                    .unwrap_or((row.scope_key.as_str(), ""));
+                (
+                    Some(country_id.to_string()),
+                    Some(region_id.to_string()).filter(|id| !id.is_empty()),
+                    None,
+                )
+            };
+            MonitoringRuleView {
+                id: row.id,
+                scope: row.scope_kind,
+                country_id,
+                region_id,
+                config_id,
+                name: row.name,
+                conditions: serde_json::from_str(&row.conditions_json).unwrap_or_default(),
+                enabled: row.enabled,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            }
+        })
+        .collect();
+    Ok(MonitoringRulesResponse { rules })
+}
+
+/// Checks a rule's name and conditions and returns the trimmed name with the conditions as JSON.
+fn validate_monitoring_rule(
+    req: &MonitoringRuleRequest,
+) -> Result<(String, String), (StatusCode, Json<ErrorResponse>)> {
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > 64 {
+        return Err(json_invalid_argument_with_message(
+            "规则名称需为 1-64 个字符",
+        ));
+    }
+    crate::monitoring_rules::validate_conditions(&req.conditions)
+        .map_err(|err| json_invalid_argument_with_message(err.to_string()))?;
+    let conditions_json =
+        serde_json::to_string(&req.conditions).map_err(|_| json_internal_error())?;
+    Ok((name.to_string(), conditions_json))
+}
+
+async fn get_monitoring_rules(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+) -> Result<Json<MonitoringRulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    load_monitoring_rules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
+async fn post_monitoring_rule(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+    Json(req): Json<MonitoringRuleCreateRequest>,
+) -> Result<Json<MonitoringRulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (scope_kind, scope_key) = if let Some(config_id) = req.config_id.as_deref() {
+        let exists = sqlx::query("SELECT 1 FROM catalog_configs WHERE id = ?")
+            .bind(config_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|_| json_internal_error())?
+            .is_some();
+        if !exists {
+            return Err(json_invalid_argument());
+        }
+        ("config", config_id.to_string())
+    } else {
+        let Some(partition) = req.country_id.as_deref().and_then(|country_id| {
+            db::normalize_monitoring_partition(country_id, req.region_id.as_deref())
+        }) else {
+            return Err(json_invalid_argument());
+        };
+        let exists = db::catalog_partition_exists(
+            &state.db,
+            &partition.country_id,
+            partition.region_id.as_deref(),
+        )
+        .await
+        .map_err(|_| json_internal_error())?;
+        if !exists {
+            return Err(json_invalid_argument());
+        }
+        (
+            "partition",
+            db::monitoring_partition_key(&partition.country_id, partition.region_id.as_deref()),
+        )
+    };
+
+    let (name, conditions_json) = validate_monitoring_rule(&req.rule)?;
+    db::insert_monitoring_rule(
+        &state.db,
+        &user.0.id,
+        scope_kind,
+        &scope_key,
+        &name,
+        &conditions_json,
+        req.rule.enabled.unwrap_or(true),
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
+    load_monitoring_rules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
+async fn put_monitoring_rule(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+    Path(rule_id): Path<i64>,
+    Json(req): Json<MonitoringRuleRequest>,
+) -> Result<Json<MonitoringRulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (name, conditions_json) = validate_monitoring_rule(&req)?;
+    let updated = db::update_monitoring_rule(
+        &state.db,
+        &user.0.id,
+        rule_id,
+        &name,
+        &conditions_json,
+        req.enabled.unwrap_or(true),
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
+    if !updated {
+        return Err(json_not_found_with_message("规则不存在"));
+    }
+    load_monitoring_rules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
+async fn delete_monitoring_rule(
+    State(state): State<AppState>,
+    user: axum::extract::Extension<UserView>,
+    Path(rule_id): Path<i64>,
+) -> Result<Json<MonitoringRulesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let deleted = db::delete_monitoring_rule(&state.db, &user.0.id, rule_id)
+        .await
+        .map_err(|_| json_internal_error())?;
+    if !deleted {
+        return Err(json_not_found_with_message("规则不存在"));
+    }
+    load_monitoring_rules(&state, &user.0.id)
+        .await
+        .map(Json)
+        .map_err(|_| json_internal_error())
+}
+
 async fn get_settings(
     State(state): State<AppState>,
     user: axum::extract::Extension<UserView>,
@@ -1293,10 +2050,15 @@ async fn get_notification_records(
 ) -> Result<Json<NotificationRecordsResponse>, (StatusCode, Json<ErrorResponse>)> {
     validate_notification_records_cursor(q.cursor.as_deref())?;
     let limit = q.limit.unwrap_or(20).clamp(1, 50);
-    let (items, next_cursor) =
-        db::list_notification_records(&state.db, &user.0.id, q.cursor.as_deref(), limit)
-            .await
-            .map_err(|_| json_internal_error())?;
+    let (items, next_cursor) = db::list_notification_records(
+        &state.db,
+        &crate::pricing::ExchangeRates::from_config(&state.config),
+        &user.0.id,
+        q.cursor.as_deref(),
+        limit,
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
     Ok(Json(NotificationRecordsResponse { items, next_cursor }))
 }
 
@@ -1308,9 +2070,14 @@ async fn get_notification_record(
     if record_id.trim().is_empty() {
         return Err(json_invalid_argument());
     }
-    let record = db::get_notification_record(&state.db, &user.0.id, &record_id)
-        .await
-        .map_err(|_| json_internal_error())?;
+    let record = db::get_notification_record(
+        &state.db,
+        &crate::pricing::ExchangeRates::from_config(&state.config),
+        &user.0.id,
+        &record_id,
+    )
+    .await
+    .map_err(|_| json_internal_error())?;
     match record {
         Some(record) => Ok(Json(record)),
         None => Err(json_not_found_with_message("记录不存在或已过期")),
@@ -1449,13 +2216,9 @@ async fn post_telegram_test(
         req.text.as_deref().map(str::trim).filter(|v| !v.is_empty()),
         OffsetDateTime::now_utc(),
     );
-    let deliveries = crate::notifications::send_telegram_to_targets(
-        &state.config.telegram_api_base_url,
-        bot_token,
-        &targets,
-        &text,
-    )
-    .await;
+    let deliveries =
+        crate::notifications::send_telegram_to_targets(&state.config, bot_token, &targets, &text)
+            .await;
     let status = db::aggregate_telegram_status(true, &deliveries);
     let response = TelegramTestResponse {
         ok: status == "success",
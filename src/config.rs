@@ -1,5 +1,43 @@
+use std::collections::HashMap;
 use std::env;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpstreamProviderConfig {
+    pub id: String,
+    pub cart_url: String,
+}
+
+/// Retry settings of one ops task class, as given in `CATNAP_OPS_RETRY_POLICY`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpsRetryPolicyConfig {
+    pub max_attempts: u32,
+    pub base_delay_seconds: u64,
+    pub max_delay_seconds: u64,
+    /// Falls back to `RuntimeConfig::ops_retryable_error_codes` when unset.
+    pub retryable_error_codes: Option<Vec<String>>,
+}
+
+/// Where upstream cart pages come from: the network (`Off`), the network with every response
+/// saved to `upstream_archive_dir` (`Record`), or only that archive (`Replay`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UpstreamArchiveMode {
+    #[default]
+    Off,
+    Record,
+    Replay,
+}
+
+impl UpstreamArchiveMode {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "off" | "none" => Some(Self::Off),
+            "record" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RuntimeConfig {
     pub bind_addr: String,
@@ -16,6 +54,30 @@ pub struct RuntimeConfig {
     pub github_api_base_url: String,
 
     pub upstream_cart_url: String,
+    /// Additional WHMCS-style shops watched next to `upstream_cart_url`.
+    pub upstream_providers: Vec<UpstreamProviderConfig>,
+    pub upstream_archive_mode: UpstreamArchiveMode,
+    pub upstream_archive_dir: String,
+    /// Per-host token bucket for cart requests (see `crate::upstream_limiter`).
+    pub upstream_rate_per_second: f64,
+    pub upstream_rate_burst: u32,
+    /// Proxies cart requests rotate through, one per request; empty means direct.
+    pub upstream_proxies: Vec<String>,
+    /// Consecutive unreachable fetches that open a storefront's circuit, and how long it stays
+    /// open before a half-open probe (see `crate::upstream_breaker`).
+    pub upstream_breaker_failure_threshold: u32,
+    pub upstream_breaker_open_seconds: u64,
+    /// Also scrape each config's configureproduct page for its billing-cycle options.
+    pub upstream_billing_cycles_enabled: bool,
+    /// Minimum age before a config's billing cycles are scraped again.
+    pub upstream_billing_cycles_refresh_seconds: i64,
+
+    /// Currency monthly-equivalent prices are reported in.
+    pub price_base_currency: String,
+    /// Base-currency amount per unit of each foreign currency (`CATNAP_EXCHANGE_RATES`).
+    pub exchange_rates: HashMap<String, f64>,
+    /// Optional JSON object file (`{"USD": 7.2}`) overriding `exchange_rates` at startup.
+    pub exchange_rates_file: Option<String>,
     pub lazycat_base_url: String,
     pub lazycat_site_sync_interval_minutes: i64,
     pub lazycat_panel_sync_interval_minutes: i64,
@@ -23,9 +85,21 @@ pub struct RuntimeConfig {
     pub lazycat_panel_timeout_ms: i64,
     pub lazycat_allow_invalid_tls: bool,
 
+    /// Outbound proxies for the LazyCat panel/site, Telegram and GitHub clients (see `crate::proxy`).
+    pub lazycat_proxy: Option<String>,
+    pub telegram_proxy: Option<String>,
+    pub github_proxy: Option<String>,
+
     /// Base URL for Telegram Bot API. Used to allow local stubs in tests.
     pub telegram_api_base_url: String,
 
+    /// Operator-facing Telegram channel for service health alerts (parser drift etc.), separate
+    /// from per-user notification settings. Alerts only go to the ops log/SSE when unset.
+    pub operator_telegram_bot_token: Option<String>,
+    pub operator_telegram_targets: Vec<String>,
+    /// Users whose latest Web Push subscription also receives operator alerts.
+    pub operator_web_push_user_ids: Vec<String>,
+
     /// Name of the request header (provided by a trusted reverse proxy) used to identify the user.
     pub auth_user_header: Option<String>,
 
@@ -43,10 +117,58 @@ pub struct RuntimeConfig {
 
     // Ops / observability
     pub ops_worker_concurrency: usize,
+    /// Let the AIMD controller move the active worker count between the min/max bounds,
+    /// starting from `ops_worker_concurrency`.
+    pub ops_adaptive_concurrency: bool,
+    pub ops_worker_concurrency_min: usize,
+    pub ops_worker_concurrency_max: usize,
+    /// Average fetch time above which the adaptive controller backs off.
+    pub ops_adaptive_latency_target_ms: u64,
     pub ops_sse_replay_window_seconds: i64,
     pub ops_log_retention_days: i64,
     pub ops_log_tail_limit_default: i64,
     pub ops_queue_task_limit_default: i64,
+    /// Waiting this long moves a queued task one priority lane up, so low lanes cannot starve.
+    pub ops_lane_starvation_seconds: u64,
+    /// Retry policy overrides per task class (see `crate::ops_retry`).
+    pub ops_retry_policies: HashMap<String, OpsRetryPolicyConfig>,
+    pub ops_retryable_error_codes: Vec<String>,
+    /// Users allowed to pause, resume, drain and cancel ops tasks; empty allows every user who
+    /// can see the ops dashboard.
+    pub ops_admin_user_ids: Vec<String>,
+    /// Operator health rules (see `crate::ops_health`); each is off while its threshold is unset.
+    pub ops_health_check_interval_seconds: u64,
+    pub ops_health_success_rate_min_pct: Option<f64>,
+    pub ops_health_success_rate_window_minutes: i64,
+    pub ops_health_queue_depth_max: Option<i64>,
+    pub ops_health_worker_error_minutes: Option<i64>,
+    pub ops_health_no_success_minutes: Option<i64>,
+    /// UTC hour the daily ops digest goes to the operator channels; off when unset.
+    pub ops_digest_hour_utc: Option<u8>,
+    /// Length of the hot-mode window a monitored restock or listing opens; `0` turns it off.
+    pub hot_mode_minutes: i64,
+    /// How often a hot partition is re-fetched while its window lasts.
+    pub hot_mode_interval_seconds: u64,
+    /// Partitions that may be hot at once; further triggers are ignored until one ends.
+    pub hot_mode_max_partitions: usize,
+    /// Let restock forecasts (see `crate::restock_forecast`) shape the poll interval of
+    /// partitions without a schedule of their own.
+    pub poll_forecast_enabled: bool,
+    /// Interval during likely restock hours.
+    pub poll_forecast_dense_minutes: i64,
+    /// Outside likely hours the user's interval is stretched by this factor.
+    pub poll_forecast_sparse_factor: i64,
+    /// Validity of the leader lease (see `crate::leader`) that instances sharing a database
+    /// compete for; `0` makes every instance its own leader.
+    pub leader_lease_seconds: u64,
+    /// Name this instance holds the lease under; hostname, pid and a random suffix when unset.
+    pub instance_id: Option<String>,
+
+    /// Bearer token for `GET /metrics`; the endpoint is disabled when unset.
+    pub metrics_token: Option<String>,
+    /// OTLP/HTTP collector base URL (e.g. `http://localhost:4318`); trace export is off when unset.
+    pub otlp_endpoint: Option<String>,
+    pub otlp_service_name: String,
 
     pub db_url: String,
 
@@ -108,6 +230,97 @@ impl RuntimeConfig {
             .filter(|v| !v.is_empty())
             .unwrap_or_else(|| "https://api.telegram.org".to_string());
 
+        let operator_telegram_bot_token = env::var("CATNAP_OPERATOR_TELEGRAM_BOT_TOKEN")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let operator_telegram_targets = env::var("CATNAP_OPERATOR_TELEGRAM_TARGETS")
+            .ok()
+            .map(|v| crate::db::normalize_telegram_targets(v.split([',', '\n'])))
+            .unwrap_or_default();
+
+        let operator_web_push_user_ids = env::var("CATNAP_OPERATOR_WEB_PUSH_USER_IDS")
+            .ok()
+            .map(|v| parse_list(&v))
+            .unwrap_or_default();
+
+        let upstream_providers = env::var("CATNAP_UPSTREAM_PROVIDERS")
+            .ok()
+            .map(|v| parse_upstream_providers(&v))
+            .unwrap_or_default();
+
+        let upstream_archive_mode = env::var("CATNAP_UPSTREAM_ARCHIVE_MODE")
+            .ok()
+            .and_then(|v| UpstreamArchiveMode::parse(&v))
+            .unwrap_or_default();
+
+        let upstream_archive_dir = env::var("CATNAP_UPSTREAM_ARCHIVE_DIR")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "upstream-archive".to_string());
+
+        let upstream_rate_per_second = env::var("CATNAP_UPSTREAM_RATE_PER_SECOND")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v > 0.0)
+            .unwrap_or(crate::upstream_limiter::DEFAULT_RATE_PER_SECOND);
+
+        let upstream_rate_burst = env::var("CATNAP_UPSTREAM_RATE_BURST")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .filter(|v| (1..=100).contains(v))
+            .unwrap_or(crate::upstream_limiter::DEFAULT_BURST);
+
+        let upstream_proxies = env::var("CATNAP_UPSTREAM_PROXY")
+            .ok()
+            .map(|v| parse_list(&v))
+            .unwrap_or_default();
+
+        let upstream_breaker_failure_threshold =
+            env::var("CATNAP_UPSTREAM_BREAKER_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .filter(|v| (1..=1000).contains(v))
+                .unwrap_or(crate::upstream_breaker::DEFAULT_FAILURE_THRESHOLD);
+
+        let upstream_breaker_open_seconds = env::var("CATNAP_UPSTREAM_BREAKER_OPEN_SECONDS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| (1..=3600).contains(v))
+            .unwrap_or(crate::upstream_breaker::DEFAULT_OPEN_SECONDS);
+
+        let upstream_billing_cycles_enabled = env::var("CATNAP_UPSTREAM_BILLING_CYCLES_ENABLED")
+            .ok()
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty())
+            .map(|v| matches!(v.as_str(), "1" | "true" | "yes" | "y" | "on"))
+            .unwrap_or(false);
+
+        let upstream_billing_cycles_refresh_seconds =
+            env::var("CATNAP_UPSTREAM_BILLING_CYCLES_REFRESH_SECONDS")
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|v| *v >= 0)
+                .unwrap_or(3600);
+
+        let price_base_currency = env::var("CATNAP_PRICE_BASE_CURRENCY")
+            .ok()
+            .map(|v| v.trim().to_ascii_uppercase())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "CNY".to_string());
+
+        let exchange_rates = env::var("CATNAP_EXCHANGE_RATES")
+            .ok()
+            .map(|v| parse_exchange_rates(&v))
+            .unwrap_or_default();
+
+        let exchange_rates_file = env::var("CATNAP_EXCHANGE_RATES_FILE")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
         let lazycat_base_url = env::var("CATNAP_LAZYCAT_BASE_URL")
             .ok()
             .map(|v| v.trim().to_string())
@@ -147,6 +360,21 @@ impl RuntimeConfig {
             .map(|v| matches!(v.as_str(), "1" | "true" | "yes" | "y" | "on"))
             .unwrap_or(true);
 
+        let lazycat_proxy = env::var("CATNAP_LAZYCAT_PROXY")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let telegram_proxy = env::var("CATNAP_TELEGRAM_PROXY")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let github_proxy = env::var("CATNAP_GITHUB_PROXY")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
         let auth_user_header = env::var("CATNAP_AUTH_USER_HEADER")
             .ok()
             .map(|v| v.trim().to_string())
@@ -199,6 +427,32 @@ impl RuntimeConfig {
             .filter(|v| (1..=64).contains(v))
             .unwrap_or(2) as usize;
 
+        let ops_adaptive_concurrency = env::var("CATNAP_OPS_ADAPTIVE_CONCURRENCY")
+            .ok()
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty())
+            .map(|v| matches!(v.as_str(), "1" | "true" | "yes" | "y" | "on"))
+            .unwrap_or(false);
+
+        let ops_worker_concurrency_min = env::var("CATNAP_OPS_WORKER_CONCURRENCY_MIN")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (1..=64).contains(v))
+            .unwrap_or(1) as usize;
+
+        let ops_worker_concurrency_max = env::var("CATNAP_OPS_WORKER_CONCURRENCY_MAX")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (1..=64).contains(v))
+            .map(|v| v as usize)
+            .unwrap_or_else(|| ops_worker_concurrency.max(8));
+
+        let ops_adaptive_latency_target_ms = env::var("CATNAP_OPS_ADAPTIVE_LATENCY_TARGET_MS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| *v >= 1)
+            .unwrap_or(3000);
+
         let ops_sse_replay_window_seconds = env::var("CATNAP_OPS_SSE_REPLAY_WINDOW_SECONDS")
             .ok()
             .and_then(|v| v.trim().parse::<i64>().ok())
@@ -223,6 +477,130 @@ impl RuntimeConfig {
             .filter(|v| (1..=500).contains(v))
             .unwrap_or(200);
 
+        let ops_lane_starvation_seconds = env::var("CATNAP_OPS_LANE_STARVATION_SECONDS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| (1..=3600).contains(v))
+            .unwrap_or(60);
+
+        let ops_retry_policies = env::var("CATNAP_OPS_RETRY_POLICY")
+            .ok()
+            .map(|v| parse_ops_retry_policies(&v))
+            .unwrap_or_default();
+
+        let ops_retryable_error_codes = env::var("CATNAP_OPS_RETRYABLE_ERROR_CODES")
+            .ok()
+            .map(|v| parse_list(&v))
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| {
+                crate::ops_retry::DEFAULT_RETRYABLE_ERROR_CODES
+                    .iter()
+                    .map(|code| code.to_string())
+                    .collect()
+            });
+
+        let ops_admin_user_ids = env::var("CATNAP_OPS_ADMIN_USER_IDS")
+            .ok()
+            .map(|v| parse_list(&v))
+            .unwrap_or_default();
+
+        let ops_health_check_interval_seconds =
+            env::var("CATNAP_OPS_HEALTH_CHECK_INTERVAL_SECONDS")
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|v| (5..=3600).contains(v))
+                .unwrap_or(60);
+
+        let ops_health_success_rate_min_pct = env::var("CATNAP_OPS_HEALTH_SUCCESS_RATE_MIN_PCT")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| *v > 0.0 && *v <= 100.0);
+
+        let ops_health_success_rate_window_minutes =
+            env::var("CATNAP_OPS_HEALTH_SUCCESS_RATE_WINDOW_MINUTES")
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|v| (1..=1440).contains(v))
+                .unwrap_or(30);
+
+        let ops_health_queue_depth_max = env::var("CATNAP_OPS_HEALTH_QUEUE_DEPTH_MAX")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| *v >= 0);
+
+        let ops_health_worker_error_minutes = env::var("CATNAP_OPS_HEALTH_WORKER_ERROR_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (1..=1440).contains(v));
+
+        let ops_health_no_success_minutes = env::var("CATNAP_OPS_HEALTH_NO_SUCCESS_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (1..=10080).contains(v));
+
+        let ops_digest_hour_utc = env::var("CATNAP_OPS_DIGEST_HOUR_UTC")
+            .ok()
+            .and_then(|v| v.trim().parse::<u8>().ok())
+            .filter(|v| *v <= 23);
+
+        let hot_mode_minutes = env::var("CATNAP_HOT_MODE_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (0..=240).contains(v))
+            .unwrap_or(10);
+        let hot_mode_interval_seconds = env::var("CATNAP_HOT_MODE_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| (5..=600).contains(v))
+            .unwrap_or(15);
+        let hot_mode_max_partitions = env::var("CATNAP_HOT_MODE_MAX_PARTITIONS")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|v| (1..=32).contains(v))
+            .unwrap_or(3);
+
+        let poll_forecast_enabled = env::var("CATNAP_POLL_FORECAST_ENABLED")
+            .ok()
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty())
+            .map(|v| matches!(v.as_str(), "1" | "true" | "yes" | "y" | "on"))
+            .unwrap_or(false);
+        let poll_forecast_dense_minutes = env::var("CATNAP_POLL_FORECAST_DENSE_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (1..=60).contains(v))
+            .unwrap_or(1);
+        let poll_forecast_sparse_factor = env::var("CATNAP_POLL_FORECAST_SPARSE_FACTOR")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| (1..=10).contains(v))
+            .unwrap_or(2);
+
+        let leader_lease_seconds = env::var("CATNAP_LEADER_LEASE_SECONDS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| *v == 0 || (5..=600).contains(v))
+            .unwrap_or(30);
+        let instance_id = env::var("CATNAP_INSTANCE_ID")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let metrics_token = env::var("CATNAP_METRICS_TOKEN")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let otlp_endpoint = env::var("CATNAP_OTLP_ENDPOINT")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let otlp_service_name = env::var("CATNAP_OTLP_SERVICE_NAME")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "catnap".to_string());
+
         Self {
             bind_addr: env::var("BIND_ADDR").unwrap_or_else(|_| "0.0.0.0:18080".to_string()),
             effective_version,
@@ -234,13 +612,32 @@ impl RuntimeConfig {
             github_api_base_url,
             upstream_cart_url: env::var("CATNAP_UPSTREAM_CART_URL")
                 .unwrap_or_else(|_| "https://lxc.lazycat.wiki/cart".to_string()),
+            upstream_providers,
+            upstream_archive_mode,
+            upstream_archive_dir,
+            upstream_rate_per_second,
+            upstream_rate_burst,
+            upstream_proxies,
+            upstream_breaker_failure_threshold,
+            upstream_breaker_open_seconds,
+            upstream_billing_cycles_enabled,
+            upstream_billing_cycles_refresh_seconds,
+            price_base_currency,
+            exchange_rates,
+            exchange_rates_file,
             lazycat_base_url,
             lazycat_site_sync_interval_minutes,
             lazycat_panel_sync_interval_minutes,
             lazycat_panel_concurrency,
             lazycat_panel_timeout_ms,
             lazycat_allow_invalid_tls,
+            lazycat_proxy,
+            telegram_proxy,
+            github_proxy,
             telegram_api_base_url,
+            operator_telegram_bot_token,
+            operator_telegram_targets,
+            operator_web_push_user_ids,
             auth_user_header,
             dev_user_id,
             default_poll_interval_minutes,
@@ -250,10 +647,36 @@ impl RuntimeConfig {
             notification_retention_days,
             notification_retention_max_rows,
             ops_worker_concurrency,
+            ops_adaptive_concurrency,
+            ops_worker_concurrency_min,
+            ops_worker_concurrency_max,
+            ops_adaptive_latency_target_ms,
             ops_sse_replay_window_seconds,
             ops_log_retention_days,
             ops_log_tail_limit_default,
             ops_queue_task_limit_default,
+            ops_lane_starvation_seconds,
+            ops_retry_policies,
+            ops_retryable_error_codes,
+            ops_admin_user_ids,
+            ops_health_check_interval_seconds,
+            ops_health_success_rate_min_pct,
+            ops_health_success_rate_window_minutes,
+            ops_health_queue_depth_max,
+            ops_health_worker_error_minutes,
+            ops_health_no_success_minutes,
+            ops_digest_hour_utc,
+            hot_mode_minutes,
+            hot_mode_interval_seconds,
+            hot_mode_max_partitions,
+            poll_forecast_enabled,
+            poll_forecast_dense_minutes,
+            poll_forecast_sparse_factor,
+            leader_lease_seconds,
+            instance_id,
+            metrics_token,
+            otlp_endpoint,
+            otlp_service_name,
             db_url: env::var("CATNAP_DB_URL").unwrap_or_else(|_| "sqlite:catnap.db".to_string()),
             web_push_vapid_public_key: env::var("CATNAP_WEB_PUSH_VAPID_PUBLIC_KEY")
                 .ok()
@@ -271,3 +694,164 @@ impl RuntimeConfig {
         }
     }
 }
+
+/// Parses `id=url` pairs separated by commas or newlines. Invalid or duplicate ids are skipped.
+pub fn parse_upstream_providers(raw: &str) -> Vec<UpstreamProviderConfig> {
+    let mut out: Vec<UpstreamProviderConfig> = Vec::new();
+    for entry in raw.split([',', '\n']) {
+        let Some((id, cart_url)) = entry.split_once('=') else {
+            continue;
+        };
+        let id = id.trim().to_ascii_lowercase();
+        let cart_url = cart_url.trim();
+        if cart_url.is_empty()
+            || !crate::storefront::is_valid_provider_id(&id)
+            || out.iter().any(|provider| provider.id == id)
+        {
+            continue;
+        }
+        out.push(UpstreamProviderConfig {
+            id,
+            cart_url: cart_url.to_string(),
+        });
+    }
+    out
+}
+
+/// Splits a comma/newline separated list (proxies, error codes, user ids), dropping blanks and
+/// repeats.
+pub fn parse_list(raw: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for entry in raw.split([',', '\n']) {
+        let entry = entry.trim();
+        if !entry.is_empty() && !out.iter().any(|existing| existing == entry) {
+            out.push(entry.to_string());
+        }
+    }
+    out
+}
+
+/// Parses `class=attempts:base_seconds:max_seconds[:code|code...]` entries separated by commas or
+/// newlines, e.g. `poller=5:15:300,discovery=1:0:0`. Malformed entries are skipped.
+pub fn parse_ops_retry_policies(raw: &str) -> HashMap<String, OpsRetryPolicyConfig> {
+    let mut out = HashMap::new();
+    for entry in raw.split([',', '\n']) {
+        let Some((class, spec)) = entry.split_once('=') else {
+            continue;
+        };
+        let class = class.trim().to_ascii_lowercase();
+        let mut fields = spec.trim().splitn(4, ':');
+        let mut number = || fields.next().and_then(|v| v.trim().parse::<u64>().ok());
+        let (Some(max_attempts), Some(base_delay_seconds), Some(max_delay_seconds)) =
+            (number(), number(), number())
+        else {
+            continue;
+        };
+        if class.is_empty() || !(1..=20).contains(&max_attempts) {
+            continue;
+        }
+        let retryable_error_codes = fields.next().map(|codes| {
+            codes
+                .split('|')
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        });
+        out.insert(
+            class,
+            OpsRetryPolicyConfig {
+                max_attempts: max_attempts as u32,
+                base_delay_seconds,
+                max_delay_seconds,
+                retryable_error_codes,
+            },
+        );
+    }
+    out
+}
+
+/// Parses `CODE=rate` pairs separated by commas or newlines, e.g. `USD=7.2,EUR=7.8`.
+pub fn parse_exchange_rates(raw: &str) -> HashMap<String, f64> {
+    let mut out = HashMap::new();
+    for entry in raw.split([',', '\n']) {
+        let Some((code, rate)) = entry.split_once('=') else {
+            continue;
+        };
+        let code = code.trim().to_ascii_uppercase();
+        let Ok(rate) = rate.trim().parse::<f64>() else {
+            continue;
+        };
+        if code.is_empty() || !rate.is_finite() || rate <= 0.0 {
+            continue;
+        }
+        out.insert(code, rate);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_exchange_rates_normalizes_codes_and_skips_bad_rates() {
+        let rates = parse_exchange_rates("usd=7.2, EUR = 7.8\nJPY=0,GBP=abc,=1");
+        assert_eq!(
+            rates,
+            HashMap::from([("USD".to_string(), 7.2), ("EUR".to_string(), 7.8)])
+        );
+    }
+
+    #[test]
+    fn parse_ops_retry_policies_reads_optional_code_lists() {
+        let policies = parse_ops_retry_policies(
+            "Poller=5:15:300, discovery=1:0:0:upstream_5xx|upstream_timeout\ntopology=0:1:1,bad=x",
+        );
+        assert_eq!(policies.len(), 2);
+        assert_eq!(
+            policies["poller"],
+            OpsRetryPolicyConfig {
+                max_attempts: 5,
+                base_delay_seconds: 15,
+                max_delay_seconds: 300,
+                retryable_error_codes: None,
+            }
+        );
+        assert_eq!(
+            policies["discovery"].retryable_error_codes,
+            Some(vec![
+                "upstream_5xx".to_string(),
+                "upstream_timeout".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_list_trims_and_dedupes() {
+        assert_eq!(
+            parse_list("http://a:3128, socks5h://b:1080\n,http://a:3128"),
+            vec!["http://a:3128".to_string(), "socks5h://b:1080".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_upstream_providers_skips_invalid_and_duplicate_entries() {
+        let providers = parse_upstream_providers(
+            "shopa=https://a.example/cart, lazycat=https://x.example/cart,lc=https://y.example/cart,bad id=https://b.example/cart,\nshopa=https://c.example/cart,shopb = https://b.example/cart,shopc=",
+        );
+        assert_eq!(
+            providers,
+            vec![
+                UpstreamProviderConfig {
+                    id: "shopa".to_string(),
+                    cart_url: "https://a.example/cart".to_string(),
+                },
+                UpstreamProviderConfig {
+                    id: "shopb".to_string(),
+                    cart_url: "https://b.example/cart".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::config::RuntimeConfig;
 use crate::defaults::FIXED_CATALOG_TOPOLOGY_REFRESH_INTERVAL_HOURS;
 use crate::models::*;
 use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
 use time::{
     format_description::{well_known::Rfc3339, FormatItem},
     macros::format_description,
@@ -351,6 +352,17 @@ CREATE TABLE IF NOT EXISTS catalog_configs (
   region_id TEXT NULL,
   name TEXT NOT NULL,
   specs_json TEXT NOT NULL,
+  spec_cpu_cores REAL NULL,
+  spec_memory_mb INTEGER NULL,
+  spec_disk_gb REAL NULL,
+  spec_disk_type TEXT NULL,
+  spec_traffic_gb REAL NULL,
+  spec_traffic_unlimited INTEGER NOT NULL DEFAULT 0,
+  spec_bandwidth_mbps REAL NULL,
+  spec_ipv4_count INTEGER NULL,
+  spec_ipv6 INTEGER NULL,
+  spec_network_type TEXT NULL,
+  spec_normalized_version INTEGER NOT NULL DEFAULT 0,
   price_amount REAL NOT NULL,
   price_currency TEXT NOT NULL,
   price_period TEXT NOT NULL,
@@ -365,7 +377,20 @@ CREATE TABLE IF NOT EXISTS catalog_configs (
   lifecycle_listed_event_at TEXT NULL,
   source_pid TEXT NULL,
   source_fid TEXT NULL,
-  source_gid TEXT NULL
+  source_gid TEXT NULL,
+  provider_id TEXT NOT NULL DEFAULT 'lazycat',
+  billing_cycles_checked_at TEXT NULL
+);
+
+CREATE TABLE IF NOT EXISTS catalog_config_billing_cycles (
+  config_id TEXT NOT NULL,
+  cycle TEXT NOT NULL,
+  sort_index INTEGER NOT NULL,
+  price_amount REAL NOT NULL,
+  price_currency TEXT NOT NULL,
+  setup_fee REAL NULL,
+  updated_at TEXT NOT NULL,
+  PRIMARY KEY (config_id, cycle)
 );
 
 CREATE TABLE IF NOT EXISTS catalog_url_cache (
@@ -373,7 +398,10 @@ CREATE TABLE IF NOT EXISTS catalog_url_cache (
   url TEXT NOT NULL,
   config_ids_json TEXT NOT NULL,
   last_success_at TEXT NOT NULL,
-  updated_at TEXT NOT NULL
+  updated_at TEXT NOT NULL,
+  etag TEXT NULL,
+  last_modified TEXT NULL,
+  body_hash TEXT NULL
 );
 
 CREATE TABLE IF NOT EXISTS catalog_countries (
@@ -401,6 +429,16 @@ CREATE TABLE IF NOT EXISTS catalog_region_notices (
   updated_at TEXT NOT NULL
 );
 
+CREATE TABLE IF NOT EXISTS catalog_topology_changes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  kind TEXT NOT NULL,
+  country_id TEXT NOT NULL,
+  country_name TEXT NOT NULL,
+  region_id TEXT NULL,
+  region_name TEXT NULL,
+  changed_at TEXT NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS catalog_topology_state (
   state_key TEXT PRIMARY KEY,
   source_url TEXT NOT NULL,
@@ -415,6 +453,14 @@ CREATE TABLE IF NOT EXISTS inventory_samples_1m (
   PRIMARY KEY (config_id, ts_minute)
 );
 
+CREATE TABLE IF NOT EXISTS restock_profiles (
+  scope_kind TEXT NOT NULL,
+  scope_key TEXT NOT NULL,
+  profile_json TEXT NOT NULL,
+  computed_at TEXT NOT NULL,
+  PRIMARY KEY (scope_kind, scope_key)
+);
+
 CREATE TABLE IF NOT EXISTS monitoring_configs (
   user_id TEXT NOT NULL,
   config_id TEXT NOT NULL,
@@ -435,6 +481,36 @@ CREATE TABLE IF NOT EXISTS monitoring_partitions (
   PRIMARY KEY (user_id, partition_key)
 );
 
+CREATE TABLE IF NOT EXISTS poll_schedules (
+  user_id TEXT NOT NULL,
+  scope_kind TEXT NOT NULL,
+  scope_key TEXT NOT NULL,
+  utc_offset TEXT NOT NULL,
+  rules_json TEXT NOT NULL,
+  updated_at TEXT NOT NULL,
+  PRIMARY KEY (user_id, scope_kind, scope_key)
+);
+
+CREATE TABLE IF NOT EXISTS monitoring_rules (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  user_id TEXT NOT NULL,
+  scope_kind TEXT NOT NULL,
+  scope_key TEXT NOT NULL,
+  name TEXT NOT NULL,
+  conditions_json TEXT NOT NULL,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS monitoring_poll_state (
+  user_id TEXT NOT NULL,
+  partition_key TEXT NOT NULL,
+  last_polled_at TEXT NOT NULL,
+  jitter_seconds INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY (user_id, partition_key)
+);
+
 CREATE TABLE IF NOT EXISTS user_config_archives (
   user_id TEXT NOT NULL,
   config_id TEXT NOT NULL,
@@ -573,7 +649,8 @@ CREATE TABLE IF NOT EXISTS notification_records (
   summary TEXT NOT NULL,
   partition_label TEXT NULL,
   telegram_status TEXT NOT NULL DEFAULT 'not_sent',
-  web_push_status TEXT NOT NULL DEFAULT 'not_sent'
+  web_push_status TEXT NOT NULL DEFAULT 'not_sent',
+  matched_rule_json TEXT NULL
 );
 
 CREATE TABLE IF NOT EXISTS notification_record_items (
@@ -617,6 +694,7 @@ CREATE TABLE IF NOT EXISTS ops_events (
 
 CREATE TABLE IF NOT EXISTS ops_task_runs (
   id INTEGER PRIMARY KEY AUTOINCREMENT,
+  provider_id TEXT NOT NULL DEFAULT 'lazycat',
   fid TEXT NOT NULL,
   gid TEXT NULL,
   started_at TEXT NOT NULL,
@@ -642,8 +720,92 @@ CREATE TABLE IF NOT EXISTS ops_notify_runs (
   error_message TEXT NULL
 );
 
+CREATE TABLE IF NOT EXISTS ops_queue_tasks (
+  fid TEXT NOT NULL,
+  gid_key TEXT NOT NULL DEFAULT '',
+  gid TEXT NULL,
+  provider_id TEXT NOT NULL DEFAULT 'lazycat',
+  state TEXT NOT NULL,
+  enqueued_at TEXT NOT NULL,
+  reason_counts_json TEXT NOT NULL,
+  force_fetch INTEGER NOT NULL DEFAULT 0,
+  poller_waiter_user_ids_json TEXT NOT NULL DEFAULT '[]',
+  run_id INTEGER NULL,
+  started_at TEXT NULL,
+  updated_at TEXT NOT NULL,
+  PRIMARY KEY (fid, gid_key)
+);
+
+CREATE TABLE IF NOT EXISTS ops_control_state (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  paused INTEGER NOT NULL DEFAULT 0,
+  paused_at TEXT NULL,
+  paused_by TEXT NULL,
+  draining INTEGER NOT NULL DEFAULT 0,
+  draining_since TEXT NULL,
+  draining_by TEXT NULL,
+  updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS upstream_outages (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  provider_id TEXT NOT NULL,
+  started_at TEXT NOT NULL,
+  ended_at TEXT NULL,
+  failure_count INTEGER NOT NULL DEFAULT 0,
+  last_error TEXT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ops_health_alerts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  rule TEXT NOT NULL,
+  opened_at TEXT NOT NULL,
+  resolved_at TEXT NULL,
+  value REAL NOT NULL,
+  threshold REAL NOT NULL,
+  message TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS leader_leases (
+  name TEXT PRIMARY KEY,
+  holder TEXT NOT NULL,
+  acquired_at TEXT NOT NULL,
+  renewed_at TEXT NOT NULL,
+  expires_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ops_queue_requests (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  fid TEXT NOT NULL,
+  gid TEXT NULL,
+  reason TEXT NOT NULL,
+  force_fetch INTEGER NOT NULL DEFAULT 0,
+  poller_waiter_user_id TEXT NULL,
+  requested_by TEXT NOT NULL,
+  requested_at TEXT NOT NULL,
+  claimed_at TEXT NULL,
+  run_id INTEGER NULL,
+  ok INTEGER NULL,
+  error TEXT NULL,
+  completed_at TEXT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ops_admin_requests (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  action TEXT NOT NULL,
+  actor TEXT NOT NULL,
+  keys_json TEXT NOT NULL DEFAULT '[]',
+  requested_by TEXT NOT NULL,
+  requested_at TEXT NOT NULL,
+  claimed_at TEXT NULL,
+  result_json TEXT NULL,
+  error TEXT NULL,
+  completed_at TEXT NULL
+);
+
 CREATE INDEX IF NOT EXISTS idx_event_logs_user_ts ON event_logs (user_id, ts DESC, id DESC);
 CREATE INDEX IF NOT EXISTS idx_event_logs_ts ON event_logs (ts DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_catalog_topology_changes_changed_at ON catalog_topology_changes (changed_at);
 CREATE INDEX IF NOT EXISTS idx_notification_records_user_created ON notification_records (user_id, created_at DESC, id DESC);
 CREATE INDEX IF NOT EXISTS idx_notification_record_items_record_position ON notification_record_items (record_id, position ASC);
 CREATE INDEX IF NOT EXISTS idx_notification_record_deliveries_record_channel ON notification_record_deliveries (record_id, channel, position ASC, id ASC);
@@ -667,6 +829,9 @@ CREATE INDEX IF NOT EXISTS idx_ops_task_runs_ended_at ON ops_task_runs (ended_at
 CREATE INDEX IF NOT EXISTS idx_ops_task_runs_key ON ops_task_runs (fid, gid, ended_at DESC);
 CREATE INDEX IF NOT EXISTS idx_ops_notify_runs_task_run_id ON ops_notify_runs (task_run_id);
 CREATE INDEX IF NOT EXISTS idx_ops_notify_runs_channel_ts ON ops_notify_runs (channel, ts DESC);
+CREATE INDEX IF NOT EXISTS idx_upstream_outages_provider_started ON upstream_outages (provider_id, started_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_ops_health_alerts_rule_open ON ops_health_alerts (rule, resolved_at);
+CREATE INDEX IF NOT EXISTS idx_monitoring_rules_user_scope ON monitoring_rules (user_id, scope_kind, scope_key);
 "#,
     )
     .execute(db)
@@ -729,6 +894,20 @@ CREATE INDEX IF NOT EXISTS idx_ops_notify_runs_channel_ts ON ops_notify_runs (ch
     add_column_if_missing(db, "catalog_configs", "source_pid", "TEXT NULL").await?;
     add_column_if_missing(db, "catalog_configs", "source_fid", "TEXT NULL").await?;
     add_column_if_missing(db, "catalog_configs", "source_gid", "TEXT NULL").await?;
+    add_column_if_missing(
+        db,
+        "catalog_configs",
+        "provider_id",
+        "TEXT NOT NULL DEFAULT 'lazycat'",
+    )
+    .await?;
+    add_column_if_missing(
+        db,
+        "ops_task_runs",
+        "provider_id",
+        "TEXT NOT NULL DEFAULT 'lazycat'",
+    )
+    .await?;
 
     add_column_if_missing(db, "ops_task_runs", "reason_counts_json", "TEXT NULL").await?;
     add_column_if_missing(
@@ -861,6 +1040,50 @@ WHERE monitoring_events_site_listed_enabled != 0
     )
     .await?;
 
+    add_column_if_missing(db, "catalog_configs", "spec_cpu_cores", "REAL NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_memory_mb", "INTEGER NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_disk_gb", "REAL NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_disk_type", "TEXT NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_traffic_gb", "REAL NULL").await?;
+    add_column_if_missing(
+        db,
+        "catalog_configs",
+        "spec_traffic_unlimited",
+        "INTEGER NOT NULL DEFAULT 0",
+    )
+    .await?;
+    add_column_if_missing(db, "catalog_configs", "spec_bandwidth_mbps", "REAL NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_ipv4_count", "INTEGER NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_ipv6", "INTEGER NULL").await?;
+    add_column_if_missing(db, "catalog_configs", "spec_network_type", "TEXT NULL").await?;
+    add_column_if_missing(
+        db,
+        "catalog_configs",
+        "spec_normalized_version",
+        "INTEGER NOT NULL DEFAULT 0",
+    )
+    .await?;
+    add_column_if_missing(
+        db,
+        "catalog_configs",
+        "billing_cycles_checked_at",
+        "TEXT NULL",
+    )
+    .await?;
+    add_column_if_missing(db, "catalog_url_cache", "etag", "TEXT NULL").await?;
+    add_column_if_missing(db, "catalog_url_cache", "last_modified", "TEXT NULL").await?;
+    add_column_if_missing(db, "catalog_url_cache", "body_hash", "TEXT NULL").await?;
+    add_column_if_missing(db, "ops_task_runs", "attempt", "INTEGER NOT NULL DEFAULT 1").await?;
+    add_column_if_missing(
+        db,
+        "ops_queue_tasks",
+        "attempt",
+        "INTEGER NOT NULL DEFAULT 1",
+    )
+    .await?;
+    add_column_if_missing(db, "ops_queue_tasks", "retry_at", "TEXT NULL").await?;
+    add_column_if_missing(db, "notification_records", "matched_rule_json", "TEXT NULL").await?;
+
     // Backfill lifecycle timestamps for existing rows (idempotent).
     sqlx::query(
         r#"
@@ -895,9 +1118,88 @@ WHERE COALESCE(NULLIF(lifecycle_state, ''), 'active') = 'active'
         .execute(db)
         .await?;
     }
+
+    backfill_normalized_specs(db).await?;
+    Ok(())
+}
+
+async fn backfill_normalized_specs(db: &SqlitePool) -> anyhow::Result<()> {
+    let rows = sqlx::query(
+        "SELECT id, name, specs_json FROM catalog_configs WHERE spec_normalized_version < ?",
+    )
+    .bind(crate::specs::SPEC_NORMALIZATION_VERSION)
+    .fetch_all(db)
+    .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let mut tx = db.begin().await?;
+    for row in rows {
+        let id = row.get::<String, _>(0);
+        let name = row.get::<String, _>(1);
+        let specs = parse_specs_json(&row.get::<String, _>(2));
+        write_normalized_specs(&mut tx, &id, &name, &specs).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn write_normalized_specs(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    id: &str,
+    name: &str,
+    specs: &[Spec],
+) -> anyhow::Result<()> {
+    let n = crate::specs::normalize_specs(name, specs);
+    sqlx::query(
+        r#"
+UPDATE catalog_configs SET
+  spec_cpu_cores = ?,
+  spec_memory_mb = ?,
+  spec_disk_gb = ?,
+  spec_disk_type = ?,
+  spec_traffic_gb = ?,
+  spec_traffic_unlimited = ?,
+  spec_bandwidth_mbps = ?,
+  spec_ipv4_count = ?,
+  spec_ipv6 = ?,
+  spec_network_type = ?,
+  spec_normalized_version = ?
+WHERE id = ?
+"#,
+    )
+    .bind(n.cpu_cores)
+    .bind(n.memory_mb)
+    .bind(n.disk_gb)
+    .bind(n.disk_type.as_deref())
+    .bind(n.traffic_gb)
+    .bind(if n.traffic_unlimited { 1 } else { 0 })
+    .bind(n.bandwidth_mbps)
+    .bind(n.ipv4_count)
+    .bind(n.ipv6.map(|v| if v { 1 } else { 0 }))
+    .bind(n.network_type.as_deref())
+    .bind(crate::specs::SPEC_NORMALIZATION_VERSION)
+    .bind(id)
+    .execute(&mut **tx)
+    .await?;
     Ok(())
 }
 
+fn normalized_specs_from_row(row: &sqlx::sqlite::SqliteRow) -> NormalizedSpecs {
+    NormalizedSpecs {
+        cpu_cores: row.get::<Option<f64>, _>("spec_cpu_cores"),
+        memory_mb: row.get::<Option<i64>, _>("spec_memory_mb"),
+        disk_gb: row.get::<Option<f64>, _>("spec_disk_gb"),
+        disk_type: row.get::<Option<String>, _>("spec_disk_type"),
+        traffic_gb: row.get::<Option<f64>, _>("spec_traffic_gb"),
+        traffic_unlimited: row.get::<i64, _>("spec_traffic_unlimited") != 0,
+        bandwidth_mbps: row.get::<Option<f64>, _>("spec_bandwidth_mbps"),
+        ipv4_count: row.get::<Option<i64>, _>("spec_ipv4_count"),
+        ipv6: row.get::<Option<i64>, _>("spec_ipv6").map(|v| v != 0),
+        network_type: row.get::<Option<String>, _>("spec_network_type"),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CatalogTopologyStateRow {
     pub source_url: String,
@@ -1211,6 +1513,7 @@ SELECT COALESCE(
     };
 
     Ok(crate::upstream::CatalogSnapshot {
+        provider_id: crate::storefront::DEFAULT_PROVIDER_ID.to_string(),
         countries,
         regions,
         region_notices,
@@ -2086,6 +2389,40 @@ pub async fn list_lazycat_machines(
         .collect())
 }
 
+/// Current-cycle traffic of one LazyCat machine, for the metrics endpoint.
+#[derive(Debug, Clone)]
+pub struct LazycatMachineTraffic {
+    pub user_id: String,
+    pub service_id: i64,
+    pub service_name: String,
+    pub used_gb: Option<f64>,
+    pub limit_gb: Option<f64>,
+}
+
+pub async fn list_lazycat_machine_traffic(
+    db: &SqlitePool,
+) -> anyhow::Result<Vec<LazycatMachineTraffic>> {
+    let rows = sqlx::query(
+        r#"SELECT user_id, service_id, service_name, traffic_used_gb, traffic_limit_gb
+        FROM lazycat_machines
+        WHERE traffic_used_gb IS NOT NULL OR traffic_limit_gb IS NOT NULL
+        ORDER BY user_id ASC, service_id ASC"#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| LazycatMachineTraffic {
+            user_id: row.get::<String, _>(0),
+            service_id: row.get::<i64, _>(1),
+            service_name: row.get::<String, _>(2),
+            used_gb: row.get::<Option<f64>, _>(3),
+            limit_gb: row.get::<Option<f64>, _>(4),
+        })
+        .collect())
+}
+
 pub async fn get_lazycat_machine(
     db: &SqlitePool,
     user_id: &str,
@@ -2574,1723 +2911,3470 @@ LIMIT 1
     Ok(row.is_some())
 }
 
-pub async fn catalog_named_region_exists(
-    db: &SqlitePool,
-    country_id: &str,
-    region_id: &str,
-) -> anyhow::Result<bool> {
-    let country_id = country_id.trim();
-    let region_id = region_id.trim();
-    if country_id.is_empty() || region_id.is_empty() {
-        return Ok(false);
-    }
-
-    let row = sqlx::query(
-        r#"
-SELECT 1
-FROM catalog_regions
-WHERE country_id = ? AND id = ?
-LIMIT 1
-"#,
-    )
-    .bind(country_id)
-    .bind(region_id)
-    .fetch_optional(db)
-    .await?;
-    Ok(row.is_some())
+#[derive(Debug, Clone)]
+pub struct PollScheduleRow {
+    /// `partition` (key from [`monitoring_partition_key`]) or `config` (key is the config id).
+    pub scope_kind: String,
+    pub scope_key: String,
+    pub utc_offset: String,
+    pub rules_json: String,
+    pub updated_at: String,
 }
 
-pub async fn list_enabled_monitoring_partitions(
+pub async fn list_poll_schedules(
     db: &SqlitePool,
     user_id: &str,
-) -> anyhow::Result<Vec<MonitoringPartitionView>> {
+) -> anyhow::Result<Vec<PollScheduleRow>> {
     let rows = sqlx::query(
         r#"
-SELECT country_id, region_id
-FROM monitoring_partitions
-WHERE user_id = ? AND enabled = 1
-ORDER BY country_id ASC, region_id ASC, partition_key ASC
+SELECT scope_kind, scope_key, utc_offset, rules_json, updated_at
+FROM poll_schedules
+WHERE user_id = ?
+ORDER BY scope_kind DESC, scope_key ASC
 "#,
     )
     .bind(user_id)
     .fetch_all(db)
     .await?;
-
     Ok(rows
         .into_iter()
-        .filter_map(|row| {
-            normalize_monitoring_partition(
-                &row.get::<String, _>(0),
-                row.get::<Option<String>, _>(1).as_deref(),
-            )
+        .map(|r| PollScheduleRow {
+            scope_kind: r.get(0),
+            scope_key: r.get(1),
+            utc_offset: r.get(2),
+            rules_json: r.get(3),
+            updated_at: r.get(4),
         })
         .collect())
 }
 
-pub async fn set_monitoring_partition_enabled(
+pub async fn upsert_poll_schedule(
     db: &SqlitePool,
     user_id: &str,
-    country_id: &str,
-    region_id: Option<&str>,
-    enabled: bool,
-) -> anyhow::Result<MonitoringPartitionView> {
-    let partition = normalize_monitoring_partition(country_id, region_id)
-        .ok_or_else(|| anyhow::anyhow!("invalid monitoring partition"))?;
-    let now = now_rfc3339();
-    let partition_key =
-        monitoring_partition_key(&partition.country_id, partition.region_id.as_deref());
+    scope_kind: &str,
+    scope_key: &str,
+    utc_offset: &str,
+    rules_json: &str,
+) -> anyhow::Result<()> {
     sqlx::query(
         r#"
-INSERT INTO monitoring_partitions (
-  user_id,
-  partition_key,
-  country_id,
-  region_id,
-  enabled,
-  created_at,
-  updated_at
-)
-VALUES (?, ?, ?, ?, ?, ?, ?)
-ON CONFLICT(user_id, partition_key) DO UPDATE SET
-  enabled = excluded.enabled,
-  country_id = excluded.country_id,
-  region_id = excluded.region_id,
+INSERT INTO poll_schedules (user_id, scope_kind, scope_key, utc_offset, rules_json, updated_at)
+VALUES (?, ?, ?, ?, ?, ?)
+ON CONFLICT(user_id, scope_kind, scope_key) DO UPDATE SET
+  utc_offset = excluded.utc_offset,
+  rules_json = excluded.rules_json,
   updated_at = excluded.updated_at
 "#,
     )
     .bind(user_id)
-    .bind(partition_key)
-    .bind(&partition.country_id)
-    .bind(partition.region_id.as_deref())
-    .bind(if enabled { 1 } else { 0 })
-    .bind(&now)
-    .bind(&now)
+    .bind(scope_kind)
+    .bind(scope_key)
+    .bind(utc_offset)
+    .bind(rules_json)
+    .bind(now_rfc3339())
     .execute(db)
     .await?;
+    Ok(())
+}
 
-    Ok(MonitoringPartitionView {
-        country_id: partition.country_id,
-        region_id: partition.region_id,
-    })
+pub async fn delete_poll_schedule(
+    db: &SqlitePool,
+    user_id: &str,
+    scope_kind: &str,
+    scope_key: &str,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "DELETE FROM poll_schedules WHERE user_id = ? AND scope_kind = ? AND scope_key = ?",
+    )
+    .bind(user_id)
+    .bind(scope_kind)
+    .bind(scope_key)
+    .execute(db)
+    .await?;
+    Ok(())
 }
 
-fn monitor_supported_for_country(country_id: &str) -> bool {
-    country_id.trim() != "2"
+#[derive(Debug, Clone)]
+pub struct MonitoringRuleRow {
+    pub id: i64,
+    pub scope_kind: String,
+    pub scope_key: String,
+    pub name: String,
+    pub conditions_json: String,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
 }
 
-fn parse_specs_json(specs_json: &str) -> Vec<Spec> {
-    serde_json::from_str(specs_json).unwrap_or_default()
+fn monitoring_rule_from_row(row: sqlx::sqlite::SqliteRow) -> MonitoringRuleRow {
+    MonitoringRuleRow {
+        id: row.get(0),
+        scope_kind: row.get(1),
+        scope_key: row.get(2),
+        name: row.get(3),
+        conditions_json: row.get(4),
+        enabled: row.get::<i64, _>(5) != 0,
+        created_at: row.get(6),
+        updated_at: row.get(7),
+    }
 }
 
-fn config_view_from_row(row: &sqlx::sqlite::SqliteRow) -> anyhow::Result<ConfigView> {
-    let specs_json = row.get::<String, _>("specs_json");
-    let specs = parse_specs_json(&specs_json);
+pub async fn list_monitoring_rules(
+    db: &SqlitePool,
+    user_id: &str,
+) -> anyhow::Result<Vec<MonitoringRuleRow>> {
+    let rows = sqlx::query(
+        r#"
+SELECT id, scope_kind, scope_key, name, conditions_json, enabled, created_at, updated_at
+FROM monitoring_rules
+WHERE user_id = ?
+ORDER BY id ASC
+"#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(monitoring_rule_from_row).collect())
+}
 
-    let country_id = row.get::<String, _>("country_id");
-    let region_id = row.get::<Option<String>, _>("region_id");
-    let lifecycle_state = row.get::<String, _>("lifecycle_state").trim().to_string();
-    let listed_at = row.get::<String, _>("lifecycle_listed_at");
-    let delisted_at = row.get::<Option<String>, _>("lifecycle_delisted_at");
-    let cleanup_at = row.get::<Option<String>, _>("cleanup_at");
+/// Enabled rules on `config_id` or on one of `partition_keys`, oldest first.
+pub async fn list_applicable_monitoring_rules(
+    db: &SqlitePool,
+    user_id: &str,
+    config_id: &str,
+    partition_keys: &[String],
+) -> anyhow::Result<Vec<MonitoringRuleRow>> {
+    let placeholders = std::iter::repeat_n("?", partition_keys.len().max(1))
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        r#"
+SELECT id, scope_kind, scope_key, name, conditions_json, enabled, created_at, updated_at
+FROM monitoring_rules
+WHERE user_id = ?
+  AND enabled = 1
+  AND (
+    (scope_kind = 'config' AND scope_key = ?)
+    OR (scope_kind = 'partition' AND scope_key IN ({placeholders}))
+  )
+ORDER BY id ASC
+"#
+    );
+    let mut q = sqlx::query(&sql).bind(user_id).bind(config_id);
+    if partition_keys.is_empty() {
+        q = q.bind(Option::<String>::None);
+    }
+    for key in partition_keys {
+        q = q.bind(key);
+    }
+    let rows = q.fetch_all(db).await?;
+    Ok(rows.into_iter().map(monitoring_rule_from_row).collect())
+}
 
-    Ok(ConfigView {
-        id: row.get::<String, _>("id"),
-        country_id: country_id.clone(),
-        region_id,
-        name: row.get::<String, _>("name"),
-        specs,
-        price: Money {
-            amount: row.get::<f64, _>("price_amount"),
-            currency: row.get::<String, _>("price_currency"),
-            period: row.get::<String, _>("price_period"),
-        },
-        inventory: Inventory {
-            status: row.get::<String, _>("inventory_status"),
-            quantity: row.get::<i64, _>("inventory_quantity"),
-            checked_at: row.get::<String, _>("checked_at"),
-        },
-        digest: row.get::<String, _>("config_digest"),
-        lifecycle: ConfigLifecycleView {
-            state: lifecycle_state,
-            listed_at,
-            delisted_at,
-            cleanup_at,
-        },
-        monitor_supported: monitor_supported_for_country(&country_id),
-        monitor_enabled: row.get::<i64, _>("monitor_enabled") != 0,
-        source_pid: row.get::<Option<String>, _>("source_pid"),
-        source_fid: row.get::<Option<String>, _>("source_fid"),
-        source_gid: row.get::<Option<String>, _>("source_gid"),
-    })
+pub async fn insert_monitoring_rule(
+    db: &SqlitePool,
+    user_id: &str,
+    scope_kind: &str,
+    scope_key: &str,
+    name: &str,
+    conditions_json: &str,
+    enabled: bool,
+) -> anyhow::Result<i64> {
+    let now = now_rfc3339();
+    let res = sqlx::query(
+        r#"
+INSERT INTO monitoring_rules (
+  user_id, scope_kind, scope_key, name, conditions_json, enabled, created_at, updated_at
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+"#,
+    )
+    .bind(user_id)
+    .bind(scope_kind)
+    .bind(scope_key)
+    .bind(name)
+    .bind(conditions_json)
+    .bind(if enabled { 1 } else { 0 })
+    .bind(&now)
+    .bind(&now)
+    .execute(db)
+    .await?;
+    Ok(res.last_insert_rowid())
 }
 
-fn build_partition_label(country_name: &str, region_name: Option<&str>) -> Option<String> {
-    let country_name = country_name.trim();
-    if country_name.is_empty() {
-        return None;
-    }
-    let region_name = region_name.map(str::trim).filter(|value| !value.is_empty());
-    Some(match region_name {
-        Some(region_name) => format!("{country_name} / {region_name}"),
-        None => country_name.to_string(),
-    })
+/// Replaces name, conditions and enabled flag of one of the user's rules; `false` when the user
+/// has no such rule.
+pub async fn update_monitoring_rule(
+    db: &SqlitePool,
+    user_id: &str,
+    id: i64,
+    name: &str,
+    conditions_json: &str,
+    enabled: bool,
+) -> anyhow::Result<bool> {
+    let now = now_rfc3339();
+    let res = sqlx::query(
+        r#"
+UPDATE monitoring_rules
+SET name = ?, conditions_json = ?, enabled = ?, updated_at = ?
+WHERE user_id = ? AND id = ?
+"#,
+    )
+    .bind(name)
+    .bind(conditions_json)
+    .bind(if enabled { 1 } else { 0 })
+    .bind(&now)
+    .bind(user_id)
+    .bind(id)
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected() > 0)
 }
 
-fn notification_record_item_view_from_row(
-    row: &sqlx::sqlite::SqliteRow,
-) -> NotificationRecordItemView {
-    let specs_json = row.get::<String, _>("specs_json");
-    let country_name = row.get::<String, _>("country_name");
-    let region_name = row.get::<Option<String>, _>("region_name");
-    NotificationRecordItemView {
-        config_id: row.get::<Option<String>, _>("config_id"),
-        country_name: country_name.clone(),
-        region_name: region_name.clone(),
-        partition_label: build_partition_label(&country_name, region_name.as_deref()),
-        name: row.get::<String, _>("name"),
-        specs: parse_specs_json(&specs_json),
-        price: Money {
-            amount: row.get::<f64, _>("price_amount"),
-            currency: row.get::<String, _>("price_currency"),
-            period: row.get::<String, _>("price_period"),
-        },
-        inventory: Inventory {
-            status: row.get::<String, _>("inventory_status"),
-            quantity: row.get::<i64, _>("inventory_quantity"),
-            checked_at: row.get::<String, _>("checked_at"),
-        },
-        lifecycle: ConfigLifecycleView {
-            state: row.get::<String, _>("lifecycle_state"),
-            listed_at: row.get::<String, _>("lifecycle_listed_at"),
-            delisted_at: row.get::<Option<String>, _>("lifecycle_delisted_at"),
-            cleanup_at: None,
-        },
-    }
+pub async fn delete_monitoring_rule(
+    db: &SqlitePool,
+    user_id: &str,
+    id: i64,
+) -> anyhow::Result<bool> {
+    let res = sqlx::query("DELETE FROM monitoring_rules WHERE user_id = ? AND id = ?")
+        .bind(user_id)
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(res.rows_affected() > 0)
 }
 
-pub async fn load_notification_record_item_snapshot(
+/// `(config id, country id, region id)` of every config the user monitors.
+pub async fn list_enabled_monitoring_targets(
     db: &SqlitePool,
-    config_id: &str,
-) -> anyhow::Result<Option<NotificationRecordItemView>> {
-    let mut items = load_notification_record_item_snapshots(db, &[config_id.to_string()]).await?;
-    Ok(items.pop())
+    user_id: &str,
+) -> anyhow::Result<Vec<(String, String, Option<String>)>> {
+    let rows = sqlx::query(
+        r#"
+SELECT c.id, c.country_id, c.region_id
+FROM monitoring_configs m
+JOIN catalog_configs c ON c.id = m.config_id
+WHERE m.user_id = ? AND m.enabled = 1
+ORDER BY c.country_id ASC, c.region_id ASC, c.id ASC
+"#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.get(0), r.get(1), r.get(2)))
+        .collect())
 }
 
-pub async fn load_notification_record_item_snapshots(
+/// The subset of `config_ids` at least one user monitors.
+pub async fn list_monitored_config_ids(
     db: &SqlitePool,
     config_ids: &[String],
-) -> anyhow::Result<Vec<NotificationRecordItemView>> {
+) -> anyhow::Result<Vec<String>> {
     if config_ids.is_empty() {
         return Ok(Vec::new());
     }
-
     let placeholders = std::iter::repeat_n("?", config_ids.len())
         .collect::<Vec<_>>()
         .join(",");
     let sql = format!(
-        r#"
-SELECT
-  c.id AS config_id,
-  c.name,
-  COALESCE(cc.name, c.country_id) AS country_name,
-  COALESCE(cr.name, c.region_id) AS region_name,
-  c.specs_json,
-  c.price_amount,
-  c.price_currency,
-  c.price_period,
-  c.inventory_status,
-  c.inventory_quantity,
-  c.checked_at,
-  c.lifecycle_state,
-  c.lifecycle_listed_at,
-  c.lifecycle_delisted_at
-FROM catalog_configs c
-LEFT JOIN catalog_countries cc
-  ON cc.id = c.country_id
-LEFT JOIN catalog_regions cr
-  ON cr.country_id = c.country_id AND cr.id = c.region_id
-WHERE c.id IN ({placeholders})
-"#
+        "SELECT DISTINCT config_id FROM monitoring_configs WHERE enabled = 1 AND config_id IN ({placeholders}) ORDER BY config_id"
     );
-    let mut query = sqlx::query(&sql);
-    for config_id in config_ids {
-        query = query.bind(config_id);
-    }
-    let rows = query.fetch_all(db).await?;
-    let mut by_id = std::collections::HashMap::new();
-    for row in rows {
-        let config_id = row.get::<Option<String>, _>("config_id");
-        if let Some(config_id) = config_id {
-            by_id.insert(config_id, notification_record_item_view_from_row(&row));
-        }
+    let mut q = sqlx::query(&sql);
+    for id in config_ids {
+        q = q.bind(id);
     }
+    Ok(q.fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|r| r.get::<String, _>(0))
+        .collect())
+}
 
-    let mut items = Vec::with_capacity(config_ids.len());
-    for config_id in config_ids {
-        if let Some(item) = by_id.remove(config_id) {
-            items.push(item);
-        }
-    }
-    Ok(items)
+/// Whether at least one user monitors the partition for catalog changes.
+pub async fn monitoring_partition_watched(
+    db: &SqlitePool,
+    partition_key: &str,
+) -> anyhow::Result<bool> {
+    let row = sqlx::query(
+        "SELECT 1 FROM monitoring_partitions WHERE partition_key = ? AND enabled = 1 LIMIT 1",
+    )
+    .bind(partition_key)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.is_some())
 }
 
-pub async fn list_catalog_configs_view(
+/// Last poll per partition key, as `(last_polled_at, jitter_seconds)`.
+pub async fn list_monitoring_poll_state(
     db: &SqlitePool,
     user_id: &str,
-    country_id: Option<&str>,
-    region_id: Option<&str>,
-) -> anyhow::Result<Vec<ConfigView>> {
-    let mut sql = r#"
-SELECT
-  c.id,
-  c.country_id,
-  c.region_id,
-  c.name,
-  c.specs_json,
-  c.price_amount,
-  c.price_currency,
-  c.price_period,
-  c.inventory_status,
-  c.inventory_quantity,
-  c.checked_at,
-  c.config_digest,
-  c.lifecycle_state,
-  c.lifecycle_listed_at,
-  c.lifecycle_delisted_at,
-  a.cleaned_at AS cleanup_at,
-  c.source_pid,
-  c.source_fid,
-  c.source_gid,
-  COALESCE(m.enabled, 0) AS monitor_enabled
-FROM catalog_configs c
-LEFT JOIN monitoring_configs m
-  ON m.user_id = ? AND m.config_id = c.id
-LEFT JOIN user_config_archives a
-  ON a.user_id = ? AND a.config_id = c.id
-WHERE 1 = 1
-"#
-    .to_string();
-
-    if country_id.is_some() {
-        sql.push_str(" AND c.country_id = ?\n");
-    }
-    if region_id.is_some() {
-        sql.push_str(" AND c.region_id = ?\n");
-    }
-
-    sql.push_str(" ORDER BY c.country_id ASC, c.region_id ASC, c.price_amount ASC, c.id ASC");
-
-    let mut q = sqlx::query(&sql).bind(user_id).bind(user_id);
-    if let Some(v) = country_id {
-        q = q.bind(v);
-    }
-    if let Some(v) = region_id {
-        q = q.bind(v);
-    }
-
-    let rows = q.fetch_all(db).await?;
-    rows.iter().map(config_view_from_row).collect()
+) -> anyhow::Result<HashMap<String, (String, i64)>> {
+    let rows = sqlx::query(
+        "SELECT partition_key, last_polled_at, jitter_seconds FROM monitoring_poll_state WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.get(0), (r.get(1), r.get(2))))
+        .collect())
 }
 
-pub async fn list_monitoring_configs_view(
+pub async fn record_monitoring_poll(
     db: &SqlitePool,
     user_id: &str,
-) -> anyhow::Result<Vec<ConfigView>> {
-    let rows = sqlx::query(
+    partition_key: &str,
+    polled_at: &str,
+    jitter_seconds: i64,
+) -> anyhow::Result<()> {
+    sqlx::query(
         r#"
-SELECT
-  c.id,
-  c.country_id,
-  c.region_id,
-  c.name,
-  c.specs_json,
-  c.price_amount,
-  c.price_currency,
-  c.price_period,
-  c.inventory_status,
-  c.inventory_quantity,
-  c.checked_at,
-  c.config_digest,
-  c.lifecycle_state,
-  c.lifecycle_listed_at,
-  c.lifecycle_delisted_at,
-  a.cleaned_at AS cleanup_at,
-  c.source_pid,
-  c.source_fid,
-  c.source_gid,
-  COALESCE(m.enabled, 0) AS monitor_enabled
-FROM catalog_configs c
-JOIN monitoring_configs m
-  ON m.user_id = ? AND m.config_id = c.id AND m.enabled = 1
-LEFT JOIN user_config_archives a
-  ON a.user_id = ? AND a.config_id = c.id
-ORDER BY c.country_id ASC, c.region_id ASC, c.price_amount ASC, c.id ASC
+INSERT INTO monitoring_poll_state (user_id, partition_key, last_polled_at, jitter_seconds)
+VALUES (?, ?, ?, ?)
+ON CONFLICT(user_id, partition_key) DO UPDATE SET
+  last_polled_at = excluded.last_polled_at,
+  jitter_seconds = excluded.jitter_seconds
 "#,
     )
     .bind(user_id)
-    .bind(user_id)
-    .fetch_all(db)
+    .bind(partition_key)
+    .bind(polled_at)
+    .bind(jitter_seconds)
+    .execute(db)
     .await?;
-    rows.iter().map(config_view_from_row).collect()
+    Ok(())
 }
 
-pub async fn list_recent_listed_24h_view(
+pub async fn catalog_named_region_exists(
     db: &SqlitePool,
-    user_id: &str,
-) -> anyhow::Result<Vec<ConfigView>> {
-    let cutoff = OffsetDateTime::now_utc()
-        .saturating_sub(time::Duration::hours(24))
-        .format(&Rfc3339)
-        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
-    let rows = sqlx::query(
+    country_id: &str,
+    region_id: &str,
+) -> anyhow::Result<bool> {
+    let country_id = country_id.trim();
+    let region_id = region_id.trim();
+    if country_id.is_empty() || region_id.is_empty() {
+        return Ok(false);
+    }
+
+    let row = sqlx::query(
         r#"
-SELECT
-  c.id,
-  c.country_id,
-  c.region_id,
-  c.name,
-  c.specs_json,
-  c.price_amount,
-  c.price_currency,
-  c.price_period,
-  c.inventory_status,
-  c.inventory_quantity,
-  c.checked_at,
-  c.config_digest,
-  c.lifecycle_state,
-  c.lifecycle_listed_at,
-  c.lifecycle_delisted_at,
-  a.cleaned_at AS cleanup_at,
-  c.source_pid,
-  c.source_fid,
-  c.source_gid,
-  COALESCE(m.enabled, 0) AS monitor_enabled
-FROM catalog_configs c
-LEFT JOIN monitoring_configs m
-  ON m.user_id = ? AND m.config_id = c.id
-LEFT JOIN user_config_archives a
-  ON a.user_id = ? AND a.config_id = c.id
-WHERE c.lifecycle_listed_at >= ?
-ORDER BY c.lifecycle_listed_at DESC, c.id DESC
-LIMIT 200
+SELECT 1
+FROM catalog_regions
+WHERE country_id = ? AND id = ?
+LIMIT 1
 "#,
     )
-    .bind(user_id)
-    .bind(user_id)
-    .bind(cutoff)
-    .fetch_all(db)
+    .bind(country_id)
+    .bind(region_id)
+    .fetch_optional(db)
     .await?;
-    rows.iter().map(config_view_from_row).collect()
-}
-
-pub async fn get_catalog_latest_checked_at(db: &SqlitePool) -> anyhow::Result<Option<String>> {
-    let row = sqlx::query("SELECT MAX(checked_at) FROM catalog_configs")
-        .fetch_one(db)
-        .await?;
-    Ok(row.get::<Option<String>, _>(0))
+    Ok(row.is_some())
 }
 
-pub async fn list_known_catalog_targets(
+pub async fn list_enabled_monitoring_partitions(
     db: &SqlitePool,
-) -> anyhow::Result<Vec<(String, Option<String>)>> {
+    user_id: &str,
+) -> anyhow::Result<Vec<MonitoringPartitionView>> {
     let rows = sqlx::query(
         r#"
-SELECT DISTINCT source_fid, source_gid
-FROM catalog_configs
-WHERE source_fid IS NOT NULL
-  AND TRIM(source_fid) != ''
-  AND lifecycle_state = 'active'
-ORDER BY source_fid ASC, source_gid ASC
+SELECT country_id, region_id
+FROM monitoring_partitions
+WHERE user_id = ? AND enabled = 1
+ORDER BY country_id ASC, region_id ASC, partition_key ASC
 "#,
     )
+    .bind(user_id)
     .fetch_all(db)
     .await?;
+
     Ok(rows
         .into_iter()
-        .map(|row| {
-            let fid = row.get::<String, _>(0).trim().to_string();
-            let gid = row
-                .get::<Option<String>, _>(1)
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty());
-            (fid, gid)
+        .filter_map(|row| {
+            normalize_monitoring_partition(
+                &row.get::<String, _>(0),
+                row.get::<Option<String>, _>(1).as_deref(),
+            )
         })
-        .filter(|(fid, _)| !fid.is_empty())
         .collect())
 }
 
-pub async fn retire_catalog_targets(
+pub async fn set_monitoring_partition_enabled(
     db: &SqlitePool,
-    targets: &[(String, Option<String>)],
-) -> anyhow::Result<Vec<String>> {
-    if targets.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let retired_at = now_rfc3339();
-    let mut tx = db.begin().await?;
-    let mut retired_ids = Vec::new();
-
-    for (fid, gid) in targets {
-        let gid = gid.as_deref();
-        let rows = sqlx::query(
-            r#"
-SELECT id
-FROM catalog_configs
-WHERE source_fid = ?
-  AND (
-    (? IS NULL AND source_gid IS NULL)
-    OR (? IS NOT NULL AND source_gid = ?)
-  )
-  AND lifecycle_state != 'delisted'
-"#,
-        )
-        .bind(fid)
-        .bind(gid)
-        .bind(gid)
-        .bind(gid)
-        .fetch_all(&mut *tx)
-        .await?;
-        retired_ids.extend(rows.into_iter().map(|row| row.get::<String, _>(0)));
-
-        sqlx::query(
-            r#"
-UPDATE catalog_configs
-SET lifecycle_state = 'delisted',
-    lifecycle_delisted_at = ?
-WHERE source_fid = ?
-  AND (
-    (? IS NULL AND source_gid IS NULL)
-    OR (? IS NOT NULL AND source_gid = ?)
-  )
-  AND lifecycle_state != 'delisted'
+    user_id: &str,
+    country_id: &str,
+    region_id: Option<&str>,
+    enabled: bool,
+) -> anyhow::Result<MonitoringPartitionView> {
+    let partition = normalize_monitoring_partition(country_id, region_id)
+        .ok_or_else(|| anyhow::anyhow!("invalid monitoring partition"))?;
+    let now = now_rfc3339();
+    let partition_key =
+        monitoring_partition_key(&partition.country_id, partition.region_id.as_deref());
+    sqlx::query(
+        r#"
+INSERT INTO monitoring_partitions (
+  user_id,
+  partition_key,
+  country_id,
+  region_id,
+  enabled,
+  created_at,
+  updated_at
+)
+VALUES (?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(user_id, partition_key) DO UPDATE SET
+  enabled = excluded.enabled,
+  country_id = excluded.country_id,
+  region_id = excluded.region_id,
+  updated_at = excluded.updated_at
 "#,
-        )
-        .bind(&retired_at)
-        .bind(fid)
-        .bind(gid)
-        .bind(gid)
-        .bind(gid)
-        .execute(&mut *tx)
-        .await?;
+    )
+    .bind(user_id)
+    .bind(partition_key)
+    .bind(&partition.country_id)
+    .bind(partition.region_id.as_deref())
+    .bind(if enabled { 1 } else { 0 })
+    .bind(&now)
+    .bind(&now)
+    .execute(db)
+    .await?;
 
-        sqlx::query("DELETE FROM catalog_url_cache WHERE url_key = ?")
-            .bind(crate::upstream::catalog_region_key(fid, gid))
-            .execute(&mut *tx)
-            .await?;
-    }
+    Ok(MonitoringPartitionView {
+        country_id: partition.country_id,
+        region_id: partition.region_id,
+    })
+}
 
-    tx.commit().await?;
-    Ok(retired_ids)
+fn monitor_supported_for_country(country_id: &str) -> bool {
+    country_id.trim() != "2"
 }
 
-pub async fn get_global_catalog_refresh_interval_hours(
-    db: &SqlitePool,
-) -> anyhow::Result<Option<i64>> {
-    let row = sqlx::query(
-        r#"
-SELECT MIN(catalog_refresh_auto_interval_hours)
-FROM settings
-WHERE catalog_refresh_auto_interval_hours IS NOT NULL
-  AND catalog_refresh_auto_interval_hours > 0
-"#,
-    )
-    .fetch_one(db)
-    .await?;
-    Ok(row.get::<Option<i64>, _>(0))
+fn parse_specs_json(specs_json: &str) -> Vec<Spec> {
+    serde_json::from_str(specs_json).unwrap_or_default()
 }
 
-#[derive(Debug, Clone)]
-pub struct CatalogUrlCacheRow {
-    pub url_key: String,
-    pub url: String,
-    pub config_ids_json: String,
-    pub last_success_at: String,
+fn money_from_row(row: &sqlx::sqlite::SqliteRow) -> Money {
+    Money {
+        amount: row.get::<f64, _>("price_amount"),
+        currency: row.get::<String, _>("price_currency"),
+        period: row.get::<String, _>("price_period"),
+    }
 }
 
-pub async fn get_catalog_url_cache(
-    db: &SqlitePool,
-    url_key: &str,
-) -> anyhow::Result<Option<CatalogUrlCacheRow>> {
-    let row = sqlx::query(
-        r#"
-SELECT url_key, url, config_ids_json, last_success_at
-FROM catalog_url_cache
-WHERE url_key = ?
-"#,
-    )
-    .bind(url_key)
-    .fetch_optional(db)
-    .await?;
-    Ok(row.map(|row| CatalogUrlCacheRow {
-        url_key: row.get::<String, _>(0),
-        url: row.get::<String, _>(1),
-        config_ids_json: row.get::<String, _>(2),
-        last_success_at: row.get::<String, _>(3),
-    }))
-}
+fn config_view_from_row(
+    row: &sqlx::sqlite::SqliteRow,
+    rates: &crate::pricing::ExchangeRates,
+) -> anyhow::Result<ConfigView> {
+    let specs_json = row.get::<String, _>("specs_json");
+    let specs = parse_specs_json(&specs_json);
+    let price = money_from_row(row);
 
-#[derive(Debug, Clone)]
-pub struct ApplyCatalogUrlResult {
-    pub listed_ids: Vec<String>,
-    pub listed_event_ids: Vec<String>,
-    pub listed_pending_zero_stock_ids: Vec<String>,
-    pub delisted_ids: Vec<String>,
-    pub fetched_at: String,
-}
+    let country_id = row.get::<String, _>("country_id");
+    let region_id = row.get::<Option<String>, _>("region_id");
+    let lifecycle_state = row.get::<String, _>("lifecycle_state").trim().to_string();
+    let listed_at = row.get::<String, _>("lifecycle_listed_at");
+    let delisted_at = row.get::<Option<String>, _>("lifecycle_delisted_at");
+    let cleanup_at = row.get::<Option<String>, _>("cleanup_at");
 
-#[derive(Debug, Clone, Copy, Default)]
-pub struct CatalogUrlFetchHints<'a> {
-    pub region_notice: Option<&'a str>,
-    pub empty_result_authoritative: bool,
+    Ok(ConfigView {
+        id: row.get::<String, _>("id"),
+        provider_id: row.get::<String, _>("provider_id"),
+        country_id: country_id.clone(),
+        region_id,
+        name: row.get::<String, _>("name"),
+        specs,
+        normalized_specs: normalized_specs_from_row(row),
+        monthly_price: rates.monthly_equivalent(&price),
+        price,
+        billing_cycles: Vec::new(),
+        inventory: Inventory {
+            status: row.get::<String, _>("inventory_status"),
+            quantity: row.get::<i64, _>("inventory_quantity"),
+            checked_at: row.get::<String, _>("checked_at"),
+        },
+        digest: row.get::<String, _>("config_digest"),
+        lifecycle: ConfigLifecycleView {
+            state: lifecycle_state,
+            listed_at,
+            delisted_at,
+            cleanup_at,
+        },
+        monitor_supported: monitor_supported_for_country(&country_id),
+        monitor_enabled: row.get::<i64, _>("monitor_enabled") != 0,
+        source_pid: row.get::<Option<String>, _>("source_pid"),
+        source_fid: row.get::<Option<String>, _>("source_fid"),
+        source_gid: row.get::<Option<String>, _>("source_gid"),
+    })
 }
 
-#[derive(Debug, Clone)]
-pub struct ArchiveDelistedResult {
-    pub archived_count: i64,
-    pub archived_at: Option<String>,
-    pub archived_ids: Vec<String>,
+async fn attach_billing_cycles(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    views: &mut [ConfigView],
+) -> anyhow::Result<()> {
+    if views.is_empty() {
+        return Ok(());
+    }
+    let ids = views.iter().map(|view| view.id.clone()).collect::<Vec<_>>();
+    let mut by_id = list_billing_cycles_for_configs(db, rates, &ids).await?;
+    for view in views {
+        if let Some(cycles) = by_id.remove(&view.id) {
+            view.billing_cycles = cycles;
+        }
+    }
+    Ok(())
 }
 
-pub async fn archive_all_delisted_configs(
+/// Stored billing-cycle options keyed by config id, in page order.
+pub async fn list_billing_cycles_for_configs(
     db: &SqlitePool,
-    user_id: &str,
-) -> anyhow::Result<ArchiveDelistedResult> {
-    let archived_at = now_rfc3339();
-    let mut tx = db.begin().await?;
-    let archived_ids = sqlx::query(
+    rates: &crate::pricing::ExchangeRates,
+    config_ids: &[String],
+) -> anyhow::Result<HashMap<String, Vec<BillingCycleOption>>> {
+    let mut out: HashMap<String, Vec<BillingCycleOption>> = HashMap::new();
+    if config_ids.is_empty() {
+        return Ok(out);
+    }
+    let placeholders = std::iter::repeat_n("?", config_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
         r#"
-INSERT INTO user_config_archives (user_id, config_id, cleaned_at)
-SELECT ?, c.id, ?
-FROM catalog_configs c
-LEFT JOIN user_config_archives a
-  ON a.user_id = ? AND a.config_id = c.id
-WHERE c.lifecycle_state = 'delisted'
-  AND a.config_id IS NULL
-ORDER BY c.id ASC
-RETURNING config_id
-"#,
-    )
-    .bind(user_id)
-    .bind(&archived_at)
-    .bind(user_id)
-    .fetch_all(&mut *tx)
-    .await?
-    .into_iter()
-    .map(|r| r.get::<String, _>(0))
-    .collect::<Vec<_>>();
-    tx.commit().await?;
-
-    if archived_ids.is_empty() {
-        return Ok(ArchiveDelistedResult {
-            archived_count: 0,
-            archived_at: None,
-            archived_ids,
-        });
+SELECT config_id, cycle, price_amount, price_currency, setup_fee
+FROM catalog_config_billing_cycles
+WHERE config_id IN ({placeholders})
+ORDER BY config_id ASC, sort_index ASC
+"#
+    );
+    let mut q = sqlx::query(&sql);
+    for id in config_ids {
+        q = q.bind(id);
     }
-
-    Ok(ArchiveDelistedResult {
-        archived_count: archived_ids.len() as i64,
-        archived_at: Some(archived_at),
-        archived_ids,
-    })
+    for row in q.fetch_all(db).await? {
+        let price = Money {
+            amount: row.get::<f64, _>(2),
+            currency: row.get::<String, _>(3),
+            period: row.get::<String, _>(1),
+        };
+        out.entry(row.get::<String, _>(0))
+            .or_default()
+            .push(BillingCycleOption {
+                monthly_price: rates.monthly_equivalent(&price),
+                price,
+                setup_fee: row.get::<Option<f64>, _>(4),
+            });
+    }
+    Ok(out)
 }
 
-pub async fn apply_catalog_url_fetch_success(
+/// `(config_id, source_pid)` for the given configs that know their configureproduct pid.
+pub async fn list_config_source_pids(
     db: &SqlitePool,
-    fid: &str,
-    gid: Option<&str>,
-    url_key: &str,
-    url: &str,
-    mut configs: Vec<crate::upstream::ConfigBase>,
-    hints: CatalogUrlFetchHints<'_>,
-) -> anyhow::Result<ApplyCatalogUrlResult> {
-    let fetched_at = now_rfc3339();
-    for c in configs.iter_mut() {
-        c.inventory.checked_at = fetched_at.clone();
+    config_ids: &[String],
+) -> anyhow::Result<Vec<(String, String)>> {
+    if config_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = std::iter::repeat_n("?", config_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        "SELECT id, source_pid FROM catalog_configs WHERE source_pid IS NOT NULL AND id IN ({placeholders}) ORDER BY id"
+    );
+    let mut q = sqlx::query(&sql);
+    for id in config_ids {
+        q = q.bind(id);
     }
+    Ok(q.fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.get::<String, _>(0), row.get::<String, _>(1)))
+        .collect())
+}
 
-    let mut tx = db.begin().await?;
+/// When the billing cycles of `config_id` were last scraped, if ever.
+pub async fn get_billing_cycles_checked_at(
+    db: &SqlitePool,
+    config_id: &str,
+) -> anyhow::Result<Option<String>> {
+    let row = sqlx::query("SELECT billing_cycles_checked_at FROM catalog_configs WHERE id = ?")
+        .bind(config_id)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.and_then(|row| row.get::<Option<String>, _>(0)))
+}
 
-    let prev_ids: std::collections::HashSet<String> = if let Some(row) =
-        sqlx::query("SELECT config_ids_json FROM catalog_url_cache WHERE url_key = ?")
-            .bind(url_key)
-            .fetch_optional(&mut *tx)
-            .await?
-    {
-        serde_json::from_str::<Vec<String>>(&row.get::<String, _>(0))
-            .unwrap_or_default()
-            .into_iter()
-            .collect()
-    } else {
-        let q = sqlx::query(
+/// Replaces the scraped billing cycles of one config and stamps `billing_cycles_checked_at`.
+pub async fn replace_config_billing_cycles(
+    db: &SqlitePool,
+    config_id: &str,
+    cycles: &[BillingCycleOption],
+) -> anyhow::Result<()> {
+    let now = now_rfc3339();
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM catalog_config_billing_cycles WHERE config_id = ?")
+        .bind(config_id)
+        .execute(&mut *tx)
+        .await?;
+    for (idx, cycle) in cycles.iter().enumerate() {
+        sqlx::query(
             r#"
-SELECT id
-FROM catalog_configs
-WHERE source_fid = ?
-  AND lifecycle_state = 'active'
-  AND (
-    (? IS NULL AND source_gid IS NULL)
-    OR (? IS NOT NULL AND source_gid = ?)
-  )
+INSERT INTO catalog_config_billing_cycles (
+  config_id, cycle, sort_index, price_amount, price_currency, setup_fee, updated_at
+) VALUES (?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(config_id, cycle) DO NOTHING
 "#,
         )
-        .bind(fid)
-        .bind(gid)
-        .bind(gid)
-        .bind(gid);
-        let rows = q.fetch_all(&mut *tx).await?;
-        rows.into_iter().map(|r| r.get::<String, _>(0)).collect()
-    };
+        .bind(config_id)
+        .bind(&cycle.price.period)
+        .bind(idx as i64)
+        .bind(cycle.price.amount)
+        .bind(&cycle.price.currency)
+        .bind(cycle.setup_fee)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query("UPDATE catalog_configs SET billing_cycles_checked_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(config_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
 
-    // An empty parse is usually ambiguous: it could mean the upstream cart truly has no items, or
-    // it could be an upstream HTML change/error page that our parser didn't catch. Only apply an
-    // empty result when the fetch path could prove the upstream page structure still matches a
-    // valid "no direct packages" state.
-    if configs.is_empty() && !prev_ids.is_empty() && !hints.empty_result_authoritative {
-        anyhow::bail!(
-            "refusing to apply empty catalog config list for {url_key} (would delist {} ids)",
-            prev_ids.len()
-        );
+fn build_partition_label(country_name: &str, region_name: Option<&str>) -> Option<String> {
+    let country_name = country_name.trim();
+    if country_name.is_empty() {
+        return None;
     }
+    let region_name = region_name.map(str::trim).filter(|value| !value.is_empty());
+    Some(match region_name {
+        Some(region_name) => format!("{country_name} / {region_name}"),
+        None => country_name.to_string(),
+    })
+}
 
-    let fetched_ids: std::collections::HashSet<String> =
-        configs.iter().map(|c| c.id.clone()).collect();
+fn notification_record_item_view_from_row(
+    row: &sqlx::sqlite::SqliteRow,
+    rates: &crate::pricing::ExchangeRates,
+) -> NotificationRecordItemView {
+    let specs_json = row.get::<String, _>("specs_json");
+    let country_name = row.get::<String, _>("country_name");
+    let region_name = row.get::<Option<String>, _>("region_name");
+    let price = money_from_row(row);
+    NotificationRecordItemView {
+        config_id: row.get::<Option<String>, _>("config_id"),
+        country_name: country_name.clone(),
+        region_name: region_name.clone(),
+        partition_label: build_partition_label(&country_name, region_name.as_deref()),
+        name: row.get::<String, _>("name"),
+        specs: parse_specs_json(&specs_json),
+        monthly_price: rates.monthly_equivalent(&price),
+        price,
+        inventory: Inventory {
+            status: row.get::<String, _>("inventory_status"),
+            quantity: row.get::<i64, _>("inventory_quantity"),
+            checked_at: row.get::<String, _>("checked_at"),
+        },
+        lifecycle: ConfigLifecycleView {
+            state: row.get::<String, _>("lifecycle_state"),
+            listed_at: row.get::<String, _>("lifecycle_listed_at"),
+            delisted_at: row.get::<Option<String>, _>("lifecycle_delisted_at"),
+            cleanup_at: None,
+        },
+    }
+}
 
-    let existing_by_id: std::collections::HashMap<String, (String, Option<String>)> =
-        if fetched_ids.is_empty() {
-            std::collections::HashMap::new()
-        } else {
-            let placeholders = std::iter::repeat_n("?", fetched_ids.len())
-                .collect::<Vec<_>>()
-                .join(",");
-            let sql = format!(
-                r#"
-SELECT id, lifecycle_state, lifecycle_listed_event_at
-FROM catalog_configs
-WHERE id IN ({placeholders})
+pub async fn load_notification_record_item_snapshot(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    config_id: &str,
+) -> anyhow::Result<Option<NotificationRecordItemView>> {
+    let mut items =
+        load_notification_record_item_snapshots(db, rates, &[config_id.to_string()]).await?;
+    Ok(items.pop())
+}
+
+pub async fn load_notification_record_item_snapshots(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    config_ids: &[String],
+) -> anyhow::Result<Vec<NotificationRecordItemView>> {
+    if config_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = std::iter::repeat_n("?", config_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        r#"
+SELECT
+  c.id AS config_id,
+  c.name,
+  COALESCE(cc.name, c.country_id) AS country_name,
+  COALESCE(cr.name, c.region_id) AS region_name,
+  c.specs_json,
+  c.price_amount,
+  c.price_currency,
+  c.price_period,
+  c.inventory_status,
+  c.inventory_quantity,
+  c.checked_at,
+  c.lifecycle_state,
+  c.lifecycle_listed_at,
+  c.lifecycle_delisted_at
+FROM catalog_configs c
+LEFT JOIN catalog_countries cc
+  ON cc.id = c.country_id
+LEFT JOIN catalog_regions cr
+  ON cr.country_id = c.country_id AND cr.id = c.region_id
+WHERE c.id IN ({placeholders})
 "#
+    );
+    let mut query = sqlx::query(&sql);
+    for config_id in config_ids {
+        query = query.bind(config_id);
+    }
+    let rows = query.fetch_all(db).await?;
+    let mut by_id = std::collections::HashMap::new();
+    for row in rows {
+        let config_id = row.get::<Option<String>, _>("config_id");
+        if let Some(config_id) = config_id {
+            by_id.insert(
+                config_id,
+                notification_record_item_view_from_row(&row, rates),
             );
-            let mut q = sqlx::query(&sql);
-            for id in &fetched_ids {
-                q = q.bind(id);
-            }
-            q.fetch_all(&mut *tx)
-                .await?
-                .into_iter()
-                .map(|row| {
-                    (
-                        row.get::<String, _>(0),
-                        (
-                            row.get::<String, _>(1),
-                            row.get::<Option<String>, _>(2)
-                                .filter(|v| !v.trim().is_empty()),
-                        ),
-                    )
-                })
-                .collect()
-        };
+        }
+    }
+
+    let mut items = Vec::with_capacity(config_ids.len());
+    for config_id in config_ids {
+        if let Some(item) = by_id.remove(config_id) {
+            items.push(item);
+        }
+    }
+    Ok(items)
+}
+
+pub async fn list_catalog_configs_view(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    user_id: &str,
+    country_id: Option<&str>,
+    region_id: Option<&str>,
+) -> anyhow::Result<Vec<ConfigView>> {
+    let mut sql = r#"
+SELECT
+  c.id,
+  c.provider_id,
+  c.country_id,
+  c.region_id,
+  c.name,
+  c.specs_json,
+  c.spec_cpu_cores,
+  c.spec_memory_mb,
+  c.spec_disk_gb,
+  c.spec_disk_type,
+  c.spec_traffic_gb,
+  c.spec_traffic_unlimited,
+  c.spec_bandwidth_mbps,
+  c.spec_ipv4_count,
+  c.spec_ipv6,
+  c.spec_network_type,
+  c.price_amount,
+  c.price_currency,
+  c.price_period,
+  c.inventory_status,
+  c.inventory_quantity,
+  c.checked_at,
+  c.config_digest,
+  c.lifecycle_state,
+  c.lifecycle_listed_at,
+  c.lifecycle_delisted_at,
+  a.cleaned_at AS cleanup_at,
+  c.source_pid,
+  c.source_fid,
+  c.source_gid,
+  COALESCE(m.enabled, 0) AS monitor_enabled
+FROM catalog_configs c
+LEFT JOIN monitoring_configs m
+  ON m.user_id = ? AND m.config_id = c.id
+LEFT JOIN user_config_archives a
+  ON a.user_id = ? AND a.config_id = c.id
+WHERE 1 = 1
+"#
+    .to_string();
+
+    if country_id.is_some() {
+        sql.push_str(" AND c.country_id = ?\n");
+    }
+    if region_id.is_some() {
+        sql.push_str(" AND c.region_id = ?\n");
+    }
+
+    sql.push_str(" ORDER BY c.country_id ASC, c.region_id ASC, c.price_amount ASC, c.id ASC");
+
+    let mut q = sqlx::query(&sql).bind(user_id).bind(user_id);
+    if let Some(v) = country_id {
+        q = q.bind(v);
+    }
+    if let Some(v) = region_id {
+        q = q.bind(v);
+    }
+
+    let rows = q.fetch_all(db).await?;
+    let mut views = rows
+        .iter()
+        .map(|row| config_view_from_row(row, rates))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    attach_billing_cycles(db, rates, &mut views).await?;
+    Ok(views)
+}
+
+pub async fn list_monitoring_configs_view(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    user_id: &str,
+) -> anyhow::Result<Vec<ConfigView>> {
+    let rows = sqlx::query(
+        r#"
+SELECT
+  c.id,
+  c.provider_id,
+  c.country_id,
+  c.region_id,
+  c.name,
+  c.specs_json,
+  c.spec_cpu_cores,
+  c.spec_memory_mb,
+  c.spec_disk_gb,
+  c.spec_disk_type,
+  c.spec_traffic_gb,
+  c.spec_traffic_unlimited,
+  c.spec_bandwidth_mbps,
+  c.spec_ipv4_count,
+  c.spec_ipv6,
+  c.spec_network_type,
+  c.price_amount,
+  c.price_currency,
+  c.price_period,
+  c.inventory_status,
+  c.inventory_quantity,
+  c.checked_at,
+  c.config_digest,
+  c.lifecycle_state,
+  c.lifecycle_listed_at,
+  c.lifecycle_delisted_at,
+  a.cleaned_at AS cleanup_at,
+  c.source_pid,
+  c.source_fid,
+  c.source_gid,
+  COALESCE(m.enabled, 0) AS monitor_enabled
+FROM catalog_configs c
+JOIN monitoring_configs m
+  ON m.user_id = ? AND m.config_id = c.id AND m.enabled = 1
+LEFT JOIN user_config_archives a
+  ON a.user_id = ? AND a.config_id = c.id
+ORDER BY c.country_id ASC, c.region_id ASC, c.price_amount ASC, c.id ASC
+"#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+    let mut views = rows
+        .iter()
+        .map(|row| config_view_from_row(row, rates))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    attach_billing_cycles(db, rates, &mut views).await?;
+    Ok(views)
+}
+
+pub async fn list_recent_listed_24h_view(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    user_id: &str,
+) -> anyhow::Result<Vec<ConfigView>> {
+    let cutoff = OffsetDateTime::now_utc()
+        .saturating_sub(time::Duration::hours(24))
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
+    let rows = sqlx::query(
+        r#"
+SELECT
+  c.id,
+  c.provider_id,
+  c.country_id,
+  c.region_id,
+  c.name,
+  c.specs_json,
+  c.spec_cpu_cores,
+  c.spec_memory_mb,
+  c.spec_disk_gb,
+  c.spec_disk_type,
+  c.spec_traffic_gb,
+  c.spec_traffic_unlimited,
+  c.spec_bandwidth_mbps,
+  c.spec_ipv4_count,
+  c.spec_ipv6,
+  c.spec_network_type,
+  c.price_amount,
+  c.price_currency,
+  c.price_period,
+  c.inventory_status,
+  c.inventory_quantity,
+  c.checked_at,
+  c.config_digest,
+  c.lifecycle_state,
+  c.lifecycle_listed_at,
+  c.lifecycle_delisted_at,
+  a.cleaned_at AS cleanup_at,
+  c.source_pid,
+  c.source_fid,
+  c.source_gid,
+  COALESCE(m.enabled, 0) AS monitor_enabled
+FROM catalog_configs c
+LEFT JOIN monitoring_configs m
+  ON m.user_id = ? AND m.config_id = c.id
+LEFT JOIN user_config_archives a
+  ON a.user_id = ? AND a.config_id = c.id
+WHERE c.lifecycle_listed_at >= ?
+ORDER BY c.lifecycle_listed_at DESC, c.id DESC
+LIMIT 200
+"#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(cutoff)
+    .fetch_all(db)
+    .await?;
+    let mut views = rows
+        .iter()
+        .map(|row| config_view_from_row(row, rates))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    attach_billing_cycles(db, rates, &mut views).await?;
+    Ok(views)
+}
+
+pub async fn get_catalog_latest_checked_at(db: &SqlitePool) -> anyhow::Result<Option<String>> {
+    let row = sqlx::query("SELECT MAX(checked_at) FROM catalog_configs")
+        .fetch_one(db)
+        .await?;
+    Ok(row.get::<Option<String>, _>(0))
+}
+
+pub async fn list_known_catalog_targets(
+    db: &SqlitePool,
+) -> anyhow::Result<Vec<(String, Option<String>)>> {
+    list_known_provider_catalog_targets(db, crate::storefront::DEFAULT_PROVIDER_ID).await
+}
+
+/// Active `(fid, gid)` targets of one storefront, using the shop's raw (unscoped) ids.
+pub async fn list_known_provider_catalog_targets(
+    db: &SqlitePool,
+    provider_id: &str,
+) -> anyhow::Result<Vec<(String, Option<String>)>> {
+    let rows = sqlx::query(
+        r#"
+SELECT DISTINCT source_fid, source_gid
+FROM catalog_configs
+WHERE provider_id = ?
+  AND source_fid IS NOT NULL
+  AND TRIM(source_fid) != ''
+  AND lifecycle_state = 'active'
+ORDER BY source_fid ASC, source_gid ASC
+"#,
+    )
+    .bind(provider_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let fid = row.get::<String, _>(0).trim().to_string();
+            let gid = row
+                .get::<Option<String>, _>(1)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            (fid, gid)
+        })
+        .filter(|(fid, _)| !fid.is_empty())
+        .collect())
+}
+
+/// Delists the configs of `provider_id` found under the given `(fid, gid)` targets, which use
+/// the provider's raw ids.
+pub async fn retire_catalog_targets(
+    db: &SqlitePool,
+    provider_id: &str,
+    targets: &[(String, Option<String>)],
+) -> anyhow::Result<Vec<String>> {
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let retired_at = now_rfc3339();
+    let mut tx = db.begin().await?;
+    let mut retired_ids = Vec::new();
+
+    for (fid, gid) in targets {
+        let gid = gid.as_deref();
+        let rows = sqlx::query(
+            r#"
+SELECT id
+FROM catalog_configs
+WHERE provider_id = ?
+  AND source_fid = ?
+  AND (
+    (? IS NULL AND source_gid IS NULL)
+    OR (? IS NOT NULL AND source_gid = ?)
+  )
+  AND lifecycle_state != 'delisted'
+"#,
+        )
+        .bind(provider_id)
+        .bind(fid)
+        .bind(gid)
+        .bind(gid)
+        .bind(gid)
+        .fetch_all(&mut *tx)
+        .await?;
+        retired_ids.extend(rows.into_iter().map(|row| row.get::<String, _>(0)));
+
+        sqlx::query(
+            r#"
+UPDATE catalog_configs
+SET lifecycle_state = 'delisted',
+    lifecycle_delisted_at = ?
+WHERE provider_id = ?
+  AND source_fid = ?
+  AND (
+    (? IS NULL AND source_gid IS NULL)
+    OR (? IS NOT NULL AND source_gid = ?)
+  )
+  AND lifecycle_state != 'delisted'
+"#,
+        )
+        .bind(&retired_at)
+        .bind(provider_id)
+        .bind(fid)
+        .bind(gid)
+        .bind(gid)
+        .bind(gid)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM catalog_url_cache WHERE url_key = ?")
+            .bind(crate::storefront::provider_region_key(
+                provider_id,
+                fid,
+                gid,
+            ))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(retired_ids)
+}
+
+pub async fn get_global_catalog_refresh_interval_hours(
+    db: &SqlitePool,
+) -> anyhow::Result<Option<i64>> {
+    let row = sqlx::query(
+        r#"
+SELECT MIN(catalog_refresh_auto_interval_hours)
+FROM settings
+WHERE catalog_refresh_auto_interval_hours IS NOT NULL
+  AND catalog_refresh_auto_interval_hours > 0
+"#,
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(row.get::<Option<i64>, _>(0))
+}
+
+#[derive(Debug, Clone)]
+pub struct CatalogUrlCacheRow {
+    pub url_key: String,
+    pub url: String,
+    pub config_ids_json: String,
+    pub last_success_at: String,
+    pub validators: crate::upstream::PageValidators,
+}
+
+pub async fn get_catalog_url_cache(
+    db: &SqlitePool,
+    url_key: &str,
+) -> anyhow::Result<Option<CatalogUrlCacheRow>> {
+    let row = sqlx::query(
+        r#"
+SELECT url_key, url, config_ids_json, last_success_at, etag, last_modified, body_hash
+FROM catalog_url_cache
+WHERE url_key = ?
+"#,
+    )
+    .bind(url_key)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.map(|row| CatalogUrlCacheRow {
+        url_key: row.get::<String, _>(0),
+        url: row.get::<String, _>(1),
+        config_ids_json: row.get::<String, _>(2),
+        last_success_at: row.get::<String, _>(3),
+        validators: crate::upstream::PageValidators {
+            etag: row.get::<Option<String>, _>(4),
+            last_modified: row.get::<Option<String>, _>(5),
+            body_hash: row.get::<Option<String>, _>(6),
+        },
+    }))
+}
+
+/// Stores the validators of the page just applied for `url_key`; `None` clears them.
+pub async fn set_catalog_url_validators(
+    db: &SqlitePool,
+    url_key: &str,
+    validators: Option<&crate::upstream::PageValidators>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "UPDATE catalog_url_cache SET etag = ?, last_modified = ?, body_hash = ? WHERE url_key = ?",
+    )
+    .bind(validators.and_then(|v| v.etag.as_deref()))
+    .bind(validators.and_then(|v| v.last_modified.as_deref()))
+    .bind(validators.and_then(|v| v.body_hash.as_deref()))
+    .bind(url_key)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Records an unchanged page: only the cache row's success time and validators move, the
+/// catalog rows are left exactly as the previous fetch wrote them. Returns the new timestamp.
+pub async fn touch_catalog_url_not_modified(
+    db: &SqlitePool,
+    url_key: &str,
+    validators: &crate::upstream::PageValidators,
+) -> anyhow::Result<String> {
+    let now = now_rfc3339();
+    sqlx::query(
+        r#"
+UPDATE catalog_url_cache SET
+  last_success_at = ?,
+  updated_at = ?,
+  etag = ?,
+  last_modified = ?,
+  body_hash = ?
+WHERE url_key = ?
+"#,
+    )
+    .bind(&now)
+    .bind(&now)
+    .bind(validators.etag.as_deref())
+    .bind(validators.last_modified.as_deref())
+    .bind(validators.body_hash.as_deref())
+    .bind(url_key)
+    .execute(db)
+    .await?;
+    Ok(now)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ApplyCatalogUrlResult {
+    pub listed_ids: Vec<String>,
+    pub listed_event_ids: Vec<String>,
+    pub listed_pending_zero_stock_ids: Vec<String>,
+    pub delisted_ids: Vec<String>,
+    /// Configs that were already listed with zero stock and now have some.
+    pub restocked_ids: Vec<String>,
+    pub fetched_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CatalogUrlFetchHints<'a> {
+    pub region_notice: Option<&'a str>,
+    pub empty_result_authoritative: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveDelistedResult {
+    pub archived_count: i64,
+    pub archived_at: Option<String>,
+    pub archived_ids: Vec<String>,
+}
+
+pub async fn archive_all_delisted_configs(
+    db: &SqlitePool,
+    user_id: &str,
+) -> anyhow::Result<ArchiveDelistedResult> {
+    let archived_at = now_rfc3339();
+    let mut tx = db.begin().await?;
+    let archived_ids = sqlx::query(
+        r#"
+INSERT INTO user_config_archives (user_id, config_id, cleaned_at)
+SELECT ?, c.id, ?
+FROM catalog_configs c
+LEFT JOIN user_config_archives a
+  ON a.user_id = ? AND a.config_id = c.id
+WHERE c.lifecycle_state = 'delisted'
+  AND a.config_id IS NULL
+ORDER BY c.id ASC
+RETURNING config_id
+"#,
+    )
+    .bind(user_id)
+    .bind(&archived_at)
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|r| r.get::<String, _>(0))
+    .collect::<Vec<_>>();
+    tx.commit().await?;
+
+    if archived_ids.is_empty() {
+        return Ok(ArchiveDelistedResult {
+            archived_count: 0,
+            archived_at: None,
+            archived_ids,
+        });
+    }
+
+    Ok(ArchiveDelistedResult {
+        archived_count: archived_ids.len() as i64,
+        archived_at: Some(archived_at),
+        archived_ids,
+    })
+}
+
+pub async fn apply_catalog_url_fetch_success(
+    db: &SqlitePool,
+    fid: &str,
+    gid: Option<&str>,
+    url_key: &str,
+    url: &str,
+    mut configs: Vec<crate::upstream::ConfigBase>,
+    hints: CatalogUrlFetchHints<'_>,
+) -> anyhow::Result<ApplyCatalogUrlResult> {
+    let fetched_at = now_rfc3339();
+    for c in configs.iter_mut() {
+        c.inventory.checked_at = fetched_at.clone();
+    }
+
+    let mut tx = db.begin().await?;
+
+    let prev_ids: std::collections::HashSet<String> = if let Some(row) =
+        sqlx::query("SELECT config_ids_json FROM catalog_url_cache WHERE url_key = ?")
+            .bind(url_key)
+            .fetch_optional(&mut *tx)
+            .await?
+    {
+        serde_json::from_str::<Vec<String>>(&row.get::<String, _>(0))
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    } else {
+        let (provider_id, source_fid) = crate::storefront::split_country_id(fid);
+        let q = sqlx::query(
+            r#"
+SELECT id
+FROM catalog_configs
+WHERE provider_id = ?
+  AND source_fid = ?
+  AND lifecycle_state = 'active'
+  AND (
+    (? IS NULL AND source_gid IS NULL)
+    OR (? IS NOT NULL AND source_gid = ?)
+  )
+"#,
+        )
+        .bind(provider_id)
+        .bind(source_fid)
+        .bind(gid)
+        .bind(gid)
+        .bind(gid);
+        let rows = q.fetch_all(&mut *tx).await?;
+        rows.into_iter().map(|r| r.get::<String, _>(0)).collect()
+    };
+
+    // An empty parse is usually ambiguous: it could mean the upstream cart truly has no items, or
+    // it could be an upstream HTML change/error page that our parser didn't catch. Only apply an
+    // empty result when the fetch path could prove the upstream page structure still matches a
+    // valid "no direct packages" state.
+    if configs.is_empty() && !prev_ids.is_empty() && !hints.empty_result_authoritative {
+        anyhow::bail!(
+            "refusing to apply empty catalog config list for {url_key} (would delist {} ids)",
+            prev_ids.len()
+        );
+    }
+
+    let fetched_ids: std::collections::HashSet<String> =
+        configs.iter().map(|c| c.id.clone()).collect();
+
+    let existing_by_id: std::collections::HashMap<String, (String, Option<String>, i64)> =
+        if fetched_ids.is_empty() {
+            std::collections::HashMap::new()
+        } else {
+            let placeholders = std::iter::repeat_n("?", fetched_ids.len())
+                .collect::<Vec<_>>()
+                .join(",");
+            let sql = format!(
+                r#"
+SELECT id, lifecycle_state, lifecycle_listed_event_at, inventory_quantity
+FROM catalog_configs
+WHERE id IN ({placeholders})
+"#
+            );
+            let mut q = sqlx::query(&sql);
+            for id in &fetched_ids {
+                q = q.bind(id);
+            }
+            q.fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(|row| {
+                    (
+                        row.get::<String, _>(0),
+                        (
+                            row.get::<String, _>(1),
+                            row.get::<Option<String>, _>(2)
+                                .filter(|v| !v.trim().is_empty()),
+                            row.get::<i64, _>(3),
+                        ),
+                    )
+                })
+                .collect()
+        };
+
+    let listed_ids = fetched_ids
+        .difference(&prev_ids)
+        .cloned()
+        .collect::<Vec<_>>();
+    let listed_id_set = listed_ids
+        .iter()
+        .cloned()
+        .collect::<std::collections::HashSet<_>>();
+    let delisted_ids = prev_ids
+        .difference(&fetched_ids)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut listed_event_ids = Vec::new();
+    let mut listed_pending_zero_stock_ids = Vec::new();
+    let mut restocked_ids = Vec::new();
+    for c in &configs {
+        let is_new_lifecycle = listed_id_set.contains(&c.id);
+        let existing = existing_by_id.get(&c.id);
+        let should_emit_listed_event = c.inventory.quantity > 0
+            && (is_new_lifecycle
+                || existing.is_some_and(|(state, listed_event_at, _)| {
+                    state == "active" && listed_event_at.is_none()
+                }));
+
+        if c.inventory.quantity > 0
+            && !is_new_lifecycle
+            && existing.is_some_and(|(state, _, quantity)| state == "active" && *quantity == 0)
+        {
+            restocked_ids.push(c.id.clone());
+        }
+
+        if should_emit_listed_event {
+            listed_event_ids.push(c.id.clone());
+        }
+
+        if is_new_lifecycle && c.inventory.quantity == 0 {
+            listed_pending_zero_stock_ids.push(c.id.clone());
+        }
+    }
+
+    if !configs.is_empty() {
+        // Upsert all fetched configs and mark as active.
+        for c in configs.iter() {
+            sqlx::query(
+                r#"
+INSERT INTO catalog_configs (
+  id, country_id, region_id, name, specs_json,
+  price_amount, price_currency, price_period,
+  inventory_status, inventory_quantity, checked_at,
+  config_digest,
+  lifecycle_state, lifecycle_listed_at, lifecycle_delisted_at, lifecycle_last_seen_at,
+  lifecycle_listed_event_at,
+  source_pid, source_fid, source_gid, provider_id
+) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?, NULL, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(id) DO UPDATE SET
+  country_id = excluded.country_id,
+  region_id = excluded.region_id,
+  name = excluded.name,
+  specs_json = excluded.specs_json,
+  price_amount = excluded.price_amount,
+  price_currency = excluded.price_currency,
+  price_period = excluded.price_period,
+  inventory_status = excluded.inventory_status,
+  inventory_quantity = excluded.inventory_quantity,
+  checked_at = excluded.checked_at,
+  config_digest = excluded.config_digest,
+  lifecycle_state = 'active',
+  lifecycle_delisted_at = NULL,
+  lifecycle_last_seen_at = excluded.lifecycle_last_seen_at,
+  lifecycle_listed_at = CASE
+    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.lifecycle_listed_at
+    WHEN catalog_configs.lifecycle_listed_at IS NULL OR catalog_configs.lifecycle_listed_at = '1970-01-01T00:00:00Z' THEN excluded.lifecycle_listed_at
+    ELSE catalog_configs.lifecycle_listed_at
+  END,
+  lifecycle_listed_event_at = CASE
+    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.lifecycle_listed_event_at
+    WHEN catalog_configs.lifecycle_listed_event_at IS NULL AND excluded.inventory_quantity > 0 THEN excluded.lifecycle_listed_event_at
+    ELSE catalog_configs.lifecycle_listed_event_at
+  END,
+  source_pid = COALESCE(excluded.source_pid, catalog_configs.source_pid),
+  source_fid = excluded.source_fid,
+  source_gid = excluded.source_gid,
+  provider_id = excluded.provider_id
+"#,
+            )
+            .bind(&c.id)
+            .bind(&c.country_id)
+            .bind(c.region_id.as_deref())
+            .bind(&c.name)
+            .bind(serde_json::to_string(&c.specs)?)
+            .bind(c.price.amount)
+            .bind(&c.price.currency)
+            .bind(&c.price.period)
+            .bind(&c.inventory.status)
+            .bind(c.inventory.quantity)
+            .bind(&c.inventory.checked_at)
+            .bind(&c.digest)
+            .bind(&fetched_at)
+            .bind(&fetched_at)
+            .bind(if c.inventory.quantity > 0 {
+                Some(fetched_at.as_str())
+            } else {
+                None
+            })
+            .bind(c.source_pid.as_deref())
+            .bind(c.source_fid.as_deref())
+            .bind(c.source_gid.as_deref())
+            .bind(&c.provider_id)
+            .execute(&mut *tx)
+            .await?;
+            write_normalized_specs(&mut tx, &c.id, &c.name, &c.specs).await?;
+
+            // Best-effort: write minute history samples without affecting current inventory availability.
+            if let Some(ts_minute) = floor_rfc3339_to_minute_utc(&c.inventory.checked_at) {
+                let _ = sqlx::query(
+                    r#"
+INSERT INTO inventory_samples_1m (config_id, ts_minute, inventory_quantity)
+VALUES (?, ?, ?)
+ON CONFLICT(config_id, ts_minute) DO UPDATE SET
+  inventory_quantity = excluded.inventory_quantity
+"#,
+                )
+                .bind(&c.id)
+                .bind(ts_minute)
+                .bind(c.inventory.quantity.max(0))
+                .execute(&mut *tx)
+                .await;
+            }
+        }
+    }
+
+    if !fetched_ids.is_empty() {
+        let placeholders = std::iter::repeat_n("?", fetched_ids.len())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!("DELETE FROM user_config_archives WHERE config_id IN ({placeholders})");
+        let mut q = sqlx::query(&sql);
+        for id in &fetched_ids {
+            q = q.bind(id);
+        }
+        q.execute(&mut *tx).await?;
+    }
+
+    if !delisted_ids.is_empty() {
+        // Mark configs as delisted (one success-miss = delist).
+        let placeholders = std::iter::repeat_n("?", delisted_ids.len())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            r#"
+UPDATE catalog_configs
+SET lifecycle_state = 'delisted',
+    lifecycle_delisted_at = ?,
+    lifecycle_listed_event_at = NULL
+WHERE id IN ({placeholders})
+  AND lifecycle_state != 'delisted'
+"#
+        );
+        let mut q = sqlx::query(&sql).bind(&fetched_at);
+        for id in &delisted_ids {
+            q = q.bind(id);
+        }
+        q.execute(&mut *tx).await?;
+    }
+
+    let ids_json =
+        serde_json::to_string(&configs.iter().map(|c| c.id.clone()).collect::<Vec<_>>())?;
+    sqlx::query(
+        r#"
+INSERT INTO catalog_url_cache (url_key, url, config_ids_json, last_success_at, updated_at)
+VALUES (?, ?, ?, ?, ?)
+ON CONFLICT(url_key) DO UPDATE SET
+  url = excluded.url,
+  config_ids_json = excluded.config_ids_json,
+  last_success_at = excluded.last_success_at,
+  updated_at = excluded.updated_at,
+  etag = NULL,
+  last_modified = NULL,
+  body_hash = NULL
+"#,
+    )
+    .bind(url_key)
+    .bind(url)
+    .bind(ids_json)
+    .bind(&fetched_at)
+    .bind(&fetched_at)
+    .execute(&mut *tx)
+    .await?;
+
+    let notice = hints
+        .region_notice
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if let Some(notice) = notice {
+        sqlx::query(
+            r#"
+INSERT INTO catalog_region_notices (url_key, country_id, region_id, text, updated_at)
+VALUES (?, ?, ?, ?, ?)
+ON CONFLICT(url_key) DO UPDATE SET
+  country_id = excluded.country_id,
+  region_id = excluded.region_id,
+  text = excluded.text,
+  updated_at = excluded.updated_at
+"#,
+        )
+        .bind(url_key)
+        .bind(fid)
+        .bind(gid)
+        .bind(notice)
+        .bind(&fetched_at)
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        sqlx::query("DELETE FROM catalog_region_notices WHERE url_key = ?")
+            .bind(url_key)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(ApplyCatalogUrlResult {
+        listed_ids,
+        listed_event_ids,
+        listed_pending_zero_stock_ids,
+        delisted_ids,
+        restocked_ids,
+        fetched_at,
+    })
+}
+
+pub async fn upsert_catalog_configs(
+    db: &SqlitePool,
+    configs: &[crate::upstream::ConfigBase],
+) -> anyhow::Result<()> {
+    let mut tx = db.begin().await?;
+    let mut active_ids: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for c in configs {
+        active_ids.insert(c.id.clone());
+        sqlx::query(
+            r#"
+INSERT INTO catalog_configs (
+  id, country_id, region_id, name, specs_json,
+  price_amount, price_currency, price_period,
+  inventory_status, inventory_quantity, checked_at,
+  config_digest,
+  lifecycle_state, lifecycle_listed_at, lifecycle_delisted_at, lifecycle_last_seen_at,
+  lifecycle_listed_event_at,
+  source_pid, source_fid, source_gid, provider_id
+) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?, NULL, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(id) DO UPDATE SET
+  country_id = excluded.country_id,
+  region_id = excluded.region_id,
+  name = excluded.name,
+  specs_json = excluded.specs_json,
+  price_amount = excluded.price_amount,
+  price_currency = excluded.price_currency,
+  price_period = excluded.price_period,
+  inventory_status = excluded.inventory_status,
+  inventory_quantity = excluded.inventory_quantity,
+  checked_at = excluded.checked_at,
+  config_digest = excluded.config_digest,
+  lifecycle_state = 'active',
+  lifecycle_delisted_at = NULL,
+  lifecycle_last_seen_at = excluded.checked_at,
+  lifecycle_listed_at = CASE
+    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.checked_at
+    WHEN catalog_configs.lifecycle_listed_at IS NULL OR catalog_configs.lifecycle_listed_at = '1970-01-01T00:00:00Z' THEN excluded.checked_at
+    ELSE catalog_configs.lifecycle_listed_at
+  END,
+  lifecycle_listed_event_at = CASE
+    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.lifecycle_listed_event_at
+    ELSE COALESCE(catalog_configs.lifecycle_listed_event_at, excluded.lifecycle_listed_event_at)
+  END,
+  source_pid = COALESCE(excluded.source_pid, catalog_configs.source_pid),
+  source_fid = excluded.source_fid,
+  source_gid = excluded.source_gid,
+  provider_id = excluded.provider_id
+"#,
+        )
+        .bind(&c.id)
+        .bind(&c.country_id)
+        .bind(c.region_id.as_deref())
+        .bind(&c.name)
+        .bind(serde_json::to_string(&c.specs)?)
+        .bind(c.price.amount)
+        .bind(&c.price.currency)
+        .bind(&c.price.period)
+        .bind(&c.inventory.status)
+        .bind(c.inventory.quantity)
+        .bind(&c.inventory.checked_at)
+        .bind(&c.digest)
+        .bind(&c.inventory.checked_at)
+        .bind(&c.inventory.checked_at)
+        .bind(&c.inventory.checked_at)
+        .bind(c.source_pid.as_deref())
+        .bind(c.source_fid.as_deref())
+        .bind(c.source_gid.as_deref())
+        .bind(&c.provider_id)
+        .execute(&mut *tx)
+        .await?;
+        write_normalized_specs(&mut tx, &c.id, &c.name, &c.specs).await?;
+
+        // Best-effort: write minute history samples without affecting current inventory availability.
+        if let Some(ts_minute) = floor_rfc3339_to_minute_utc(&c.inventory.checked_at) {
+            let _ = sqlx::query(
+                r#"
+INSERT INTO inventory_samples_1m (config_id, ts_minute, inventory_quantity)
+VALUES (?, ?, ?)
+ON CONFLICT(config_id, ts_minute) DO UPDATE SET
+  inventory_quantity = excluded.inventory_quantity
+"#,
+            )
+            .bind(&c.id)
+            .bind(ts_minute)
+            .bind(c.inventory.quantity.max(0))
+            .execute(&mut *tx)
+            .await;
+        }
+    }
+
+    if !active_ids.is_empty() {
+        let placeholders = std::iter::repeat_n("?", active_ids.len())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!("DELETE FROM user_config_archives WHERE config_id IN ({placeholders})");
+        let mut q = sqlx::query(&sql);
+        for id in &active_ids {
+            q = q.bind(id);
+        }
+        q.execute(&mut *tx).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct InventorySample1mRow {
+    pub config_id: String,
+    pub ts_minute: String,
+    pub inventory_quantity: i64,
+}
+
+pub async fn list_inventory_samples_1m(
+    db: &SqlitePool,
+    config_ids: &[String],
+    window_from: &str,
+    window_to: &str,
+) -> anyhow::Result<Vec<InventorySample1mRow>> {
+    if config_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = std::iter::repeat_n("?", config_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        r#"SELECT config_id, ts_minute, inventory_quantity
+           FROM inventory_samples_1m
+           WHERE config_id IN ({placeholders})
+             AND ts_minute >= ?
+             AND ts_minute <= ?
+           ORDER BY config_id ASC, ts_minute ASC"#
+    );
+
+    let mut query = sqlx::query(&sql);
+    for id in config_ids {
+        query = query.bind(id);
+    }
+    query = query.bind(window_from).bind(window_to);
+
+    let rows = query.fetch_all(db).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| InventorySample1mRow {
+            config_id: row.get::<String, _>(0),
+            ts_minute: row.get::<String, _>(1),
+            inventory_quantity: row.get::<i64, _>(2),
+        })
+        .collect())
+}
+
+pub async fn insert_log(
+    db: &SqlitePool,
+    user_id: Option<&str>,
+    level: &str,
+    scope: &str,
+    message: &str,
+    meta: Option<serde_json::Value>,
+) -> anyhow::Result<()> {
+    let id = Uuid::new_v4().to_string();
+    let ts = now_rfc3339();
+    sqlx::query(
+        "INSERT INTO event_logs (id, user_id, ts, level, scope, message, meta_json) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(ts)
+    .bind(level)
+    .bind(scope)
+    .bind(message)
+    .bind(meta.map(|v| v.to_string()))
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_logs(
+    db: &SqlitePool,
+    user_id: &str,
+    level: Option<&str>,
+    cursor: Option<&str>,
+    limit: i64,
+) -> anyhow::Result<(Vec<LogEntryView>, Option<String>)> {
+    let (cursor_ts, cursor_id) = cursor
+        .and_then(|c| {
+            // Cursor format: "<RFC3339 ts>:<id>".
+            // RFC3339 timestamps contain `:` (e.g. "2026-01-19T00:00:00Z"), so we must split
+            // from the right to preserve the timestamp portion.
+            let mut parts = c.rsplitn(2, ':');
+            let id = parts.next()?.to_string();
+            let ts = parts.next()?.to_string();
+            Some((ts, id))
+        })
+        .unwrap_or(("9999-12-31T23:59:59Z".to_string(), "zzzz".to_string()));
+
+    let mut q = String::from(
+        r#"SELECT id, ts, level, scope, message, meta_json
+           FROM event_logs
+           WHERE user_id = ?
+             AND (ts < ? OR (ts = ? AND id < ?))"#,
+    );
+    if level.is_some() {
+        q.push_str(" AND level = ?");
+    }
+    q.push_str(" ORDER BY ts DESC, id DESC LIMIT ?");
+
+    let mut query = sqlx::query(&q)
+        .bind(user_id)
+        .bind(&cursor_ts)
+        .bind(&cursor_ts)
+        .bind(&cursor_id);
+    if let Some(level) = level {
+        query = query.bind(level);
+    }
+    query = query.bind(limit + 1);
+
+    let rows = query.fetch_all(db).await?;
+    let mut items = Vec::new();
+    for row in rows.iter().take(limit as usize) {
+        let meta_json = row.get::<Option<String>, _>(5);
+        let meta = meta_json.and_then(|v| serde_json::from_str(&v).ok());
+        items.push(LogEntryView {
+            id: row.get::<String, _>(0),
+            ts: row.get::<String, _>(1),
+            level: row.get::<String, _>(2),
+            scope: row.get::<String, _>(3),
+            message: row.get::<String, _>(4),
+            meta,
+        });
+    }
+
+    let next_cursor = if rows.len() as i64 > limit {
+        let last = items.last().unwrap();
+        Some(format!("{}:{}", last.ts, last.id))
+    } else {
+        None
+    };
+
+    Ok((items, next_cursor))
+}
+
+fn notification_record_view_from_row(
+    row: &sqlx::sqlite::SqliteRow,
+    telegram_deliveries: Vec<NotificationRecordDeliveryView>,
+    items: Vec<NotificationRecordItemView>,
+) -> NotificationRecordView {
+    NotificationRecordView {
+        id: row.get::<String, _>("id"),
+        created_at: row.get::<String, _>("created_at"),
+        kind: row.get::<String, _>("kind"),
+        title: row.get::<String, _>("title"),
+        summary: row.get::<String, _>("summary"),
+        partition_label: row.get::<Option<String>, _>("partition_label"),
+        telegram_status: row.get::<String, _>("telegram_status"),
+        web_push_status: row.get::<String, _>("web_push_status"),
+        telegram_deliveries,
+        items,
+        matched_rule: row
+            .get::<Option<String>, _>("matched_rule_json")
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
+    }
+}
+
+async fn load_notification_record_items_by_record_ids(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    record_ids: &[String],
+) -> anyhow::Result<std::collections::HashMap<String, Vec<NotificationRecordItemView>>> {
+    if record_ids.is_empty() {
+        return Ok(std::collections::HashMap::new());
+    }
+
+    let placeholders = std::iter::repeat_n("?", record_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        r#"
+SELECT
+  record_id,
+  config_id,
+  name,
+  country_name,
+  region_name,
+  specs_json,
+  price_amount,
+  price_currency,
+  price_period,
+  inventory_status,
+  inventory_quantity,
+  checked_at,
+  lifecycle_state,
+  lifecycle_listed_at,
+  lifecycle_delisted_at
+FROM notification_record_items
+WHERE record_id IN ({placeholders})
+ORDER BY record_id ASC, position ASC, id ASC
+"#
+    );
+    let mut query = sqlx::query(&sql);
+    for record_id in record_ids {
+        query = query.bind(record_id);
+    }
+
+    let rows = query.fetch_all(db).await?;
+    let mut out = std::collections::HashMap::new();
+    for row in rows {
+        let record_id = row.get::<String, _>("record_id");
+        out.entry(record_id)
+            .or_insert_with(Vec::new)
+            .push(notification_record_item_view_from_row(&row, rates));
+    }
+    Ok(out)
+}
+
+pub async fn insert_notification_record(
+    db: &SqlitePool,
+    user_id: &str,
+    draft: &NotificationRecordDraft,
+) -> anyhow::Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let created_at = notification_record_now_rfc3339();
+    let mut tx = db.begin().await?;
+
+    sqlx::query(
+        r#"
+INSERT INTO notification_records (
+  id,
+  user_id,
+  created_at,
+  kind,
+  title,
+  summary,
+  partition_label,
+  telegram_status,
+  web_push_status,
+  matched_rule_json
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#,
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(&created_at)
+    .bind(&draft.kind)
+    .bind(&draft.title)
+    .bind(&draft.summary)
+    .bind(draft.partition_label.as_deref())
+    .bind(&draft.telegram_status)
+    .bind(&draft.web_push_status)
+    .bind(
+        draft
+            .matched_rule
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for (position, item) in draft.items.iter().enumerate() {
+        let item_id = Uuid::new_v4().to_string();
+        let specs_json = serde_json::to_string(&item.specs)?;
+        sqlx::query(
+            r#"
+INSERT INTO notification_record_items (
+  id,
+  record_id,
+  position,
+  config_id,
+  name,
+  country_name,
+  region_name,
+  specs_json,
+  price_amount,
+  price_currency,
+  price_period,
+  inventory_status,
+  inventory_quantity,
+  checked_at,
+  lifecycle_state,
+  lifecycle_listed_at,
+  lifecycle_delisted_at
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#,
+        )
+        .bind(&item_id)
+        .bind(&id)
+        .bind(position as i64)
+        .bind(item.config_id.as_deref())
+        .bind(&item.name)
+        .bind(&item.country_name)
+        .bind(item.region_name.as_deref())
+        .bind(&specs_json)
+        .bind(item.price.amount)
+        .bind(&item.price.currency)
+        .bind(&item.price.period)
+        .bind(&item.inventory.status)
+        .bind(item.inventory.quantity)
+        .bind(&item.inventory.checked_at)
+        .bind(&item.lifecycle.state)
+        .bind(&item.lifecycle.listed_at)
+        .bind(item.lifecycle.delisted_at.as_deref())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+pub async fn replace_notification_record_deliveries(
+    db: &SqlitePool,
+    record_id: &str,
+    channel: &str,
+    deliveries: &[NotificationRecordDeliveryView],
+) -> anyhow::Result<()> {
+    let now = now_rfc3339();
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM notification_record_deliveries WHERE record_id = ? AND channel = ?")
+        .bind(record_id)
+        .bind(channel)
+        .execute(&mut *tx)
+        .await?;
+
+    for (position, delivery) in deliveries.iter().enumerate() {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+INSERT INTO notification_record_deliveries (
+  id,
+  record_id,
+  channel,
+  position,
+  target,
+  status,
+  error_message,
+  created_at,
+  updated_at
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#,
+        )
+        .bind(&id)
+        .bind(record_id)
+        .bind(channel)
+        .bind(position as i64)
+        .bind(&delivery.target)
+        .bind(&delivery.status)
+        .bind(delivery.error.as_deref())
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn update_notification_record_channel_status(
+    db: &SqlitePool,
+    record_id: &str,
+    channel: &str,
+    status: &str,
+) -> anyhow::Result<()> {
+    let sql = match channel {
+        "telegram" => "UPDATE notification_records SET telegram_status = ? WHERE id = ?",
+        "webPush" => "UPDATE notification_records SET web_push_status = ? WHERE id = ?",
+        other => anyhow::bail!("unknown notification channel: {other}"),
+    };
+    sqlx::query(sql)
+        .bind(status)
+        .bind(record_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+async fn load_notification_record_deliveries_by_record_ids(
+    db: &SqlitePool,
+    record_ids: &[String],
+) -> anyhow::Result<std::collections::HashMap<String, Vec<NotificationRecordDeliveryView>>> {
+    if record_ids.is_empty() {
+        return Ok(std::collections::HashMap::new());
+    }
+
+    let placeholders = std::iter::repeat_n("?", record_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        r#"
+SELECT record_id, channel, target, status, error_message
+FROM notification_record_deliveries
+WHERE record_id IN ({placeholders})
+  AND channel = 'telegram'
+ORDER BY record_id ASC, position ASC, created_at ASC, id ASC
+"#
+    );
+
+    let mut query = sqlx::query(&sql);
+    for record_id in record_ids {
+        query = query.bind(record_id);
+    }
+
+    let rows = query.fetch_all(db).await?;
+    let mut out = std::collections::HashMap::new();
+    for row in rows {
+        let record_id = row.get::<String, _>("record_id");
+        out.entry(record_id)
+            .or_insert_with(Vec::new)
+            .push(NotificationRecordDeliveryView {
+                channel: row.get::<String, _>("channel"),
+                target: row.get::<String, _>("target"),
+                status: row.get::<String, _>("status"),
+                error: row.get::<Option<String>, _>("error_message"),
+            });
+    }
+    Ok(out)
+}
+
+pub async fn list_notification_records(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    user_id: &str,
+    cursor: Option<&str>,
+    limit: i64,
+) -> anyhow::Result<(Vec<NotificationRecordView>, Option<String>)> {
+    let (cursor_ts, cursor_id) = cursor
+        .and_then(|c| {
+            let mut parts = c.rsplitn(2, ':');
+            let id = parts.next()?.trim().to_string();
+            let ts = parts.next()?.trim();
+            let parsed = OffsetDateTime::parse(ts, &Rfc3339).ok()?;
+            Some((format_notification_record_rfc3339(parsed), id))
+        })
+        .unwrap_or((
+            NOTIFICATION_RECORD_CURSOR_MAX_TS.to_string(),
+            "zzzz".to_string(),
+        ));
+
+    let rows = sqlx::query(
+        r#"
+SELECT id, created_at, kind, title, summary, partition_label, telegram_status, web_push_status,
+  matched_rule_json
+FROM notification_records
+WHERE user_id = ?
+  AND (created_at < ? OR (created_at = ? AND id < ?))
+ORDER BY created_at DESC, id DESC
+LIMIT ?
+"#,
+    )
+    .bind(user_id)
+    .bind(&cursor_ts)
+    .bind(&cursor_ts)
+    .bind(&cursor_id)
+    .bind(limit + 1)
+    .fetch_all(db)
+    .await?;
+
+    let visible_rows = rows.iter().take(limit as usize).collect::<Vec<_>>();
+    let record_ids = visible_rows
+        .iter()
+        .map(|row| row.get::<String, _>("id"))
+        .collect::<Vec<_>>();
+    let mut items_by_record =
+        load_notification_record_items_by_record_ids(db, rates, &record_ids).await?;
+    let mut deliveries_by_record =
+        load_notification_record_deliveries_by_record_ids(db, &record_ids).await?;
+
+    let mut items = Vec::with_capacity(visible_rows.len());
+    for row in visible_rows {
+        let record_id = row.get::<String, _>("id");
+        items.push(notification_record_view_from_row(
+            row,
+            deliveries_by_record.remove(&record_id).unwrap_or_default(),
+            items_by_record.remove(&record_id).unwrap_or_default(),
+        ));
+    }
+
+    let next_cursor = if rows.len() as i64 > limit {
+        let last = items
+            .last()
+            .expect("visible items exist when next cursor exists");
+        Some(format!("{}:{}", last.created_at, last.id))
+    } else {
+        None
+    };
+
+    Ok((items, next_cursor))
+}
+
+pub async fn get_notification_record(
+    db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
+    user_id: &str,
+    record_id: &str,
+) -> anyhow::Result<Option<NotificationRecordView>> {
+    let row = sqlx::query(
+        r#"
+SELECT id, created_at, kind, title, summary, partition_label, telegram_status, web_push_status,
+  matched_rule_json
+FROM notification_records
+WHERE user_id = ? AND id = ?
+"#,
+    )
+    .bind(user_id)
+    .bind(record_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let mut items_by_record =
+        load_notification_record_items_by_record_ids(db, rates, &[record_id.to_string()]).await?;
+    let mut deliveries_by_record =
+        load_notification_record_deliveries_by_record_ids(db, &[record_id.to_string()]).await?;
+    Ok(Some(notification_record_view_from_row(
+        &row,
+        deliveries_by_record.remove(record_id).unwrap_or_default(),
+        items_by_record.remove(record_id).unwrap_or_default(),
+    )))
+}
+
+pub async fn cleanup_notification_records(
+    db: &SqlitePool,
+    retention_days: i64,
+    max_rows: i64,
+) -> anyhow::Result<()> {
+    let mut tx = db.begin().await?;
+
+    if retention_days > 0 {
+        let cutoff = OffsetDateTime::now_utc().saturating_sub(time::Duration::days(retention_days));
+        sqlx::query("DELETE FROM notification_records WHERE created_at < ?")
+            .bind(format_notification_record_rfc3339(cutoff))
+            .execute(&mut *tx)
+            .await?;
+    }
+    if max_rows > 0 {
+        sqlx::query(
+            r#"
+DELETE FROM notification_records
+WHERE id IN (
+  SELECT id
+  FROM (
+    SELECT
+      id,
+      ROW_NUMBER() OVER (
+        PARTITION BY user_id
+        ORDER BY created_at DESC, id DESC
+      ) AS row_num
+    FROM notification_records
+  ) ranked
+  WHERE row_num > ?
+)"#,
+        )
+        .bind(max_rows)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+DELETE FROM notification_record_items
+WHERE NOT EXISTS (
+  SELECT 1 FROM notification_records
+  WHERE notification_records.id = notification_record_items.record_id
+)"#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+DELETE FROM notification_record_deliveries
+WHERE NOT EXISTS (
+  SELECT 1 FROM notification_records
+  WHERE notification_records.id = notification_record_deliveries.record_id
+)"#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn insert_web_push_subscription(
+    db: &SqlitePool,
+    user_id: &str,
+    req: WebPushSubscribeRequest,
+) -> anyhow::Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = now_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO web_push_subscriptions (id, user_id, endpoint, p256dh, auth, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(req.subscription.endpoint)
+    .bind(req.subscription.keys.p256dh)
+    .bind(req.subscription.keys.auth)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(id)
+}
 
-    let listed_ids = fetched_ids
-        .difference(&prev_ids)
-        .cloned()
-        .collect::<Vec<_>>();
-    let listed_id_set = listed_ids
-        .iter()
-        .cloned()
-        .collect::<std::collections::HashSet<_>>();
-    let delisted_ids = prev_ids
-        .difference(&fetched_ids)
-        .cloned()
-        .collect::<Vec<_>>();
+pub async fn get_latest_web_push_subscription(
+    db: &SqlitePool,
+    user_id: &str,
+) -> anyhow::Result<Option<WebPushSubscription>> {
+    let row = sqlx::query(
+        r#"SELECT endpoint, p256dh, auth
+           FROM web_push_subscriptions
+           WHERE user_id = ?
+           ORDER BY created_at DESC, id DESC
+           LIMIT 1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?;
 
-    let mut listed_event_ids = Vec::new();
-    let mut listed_pending_zero_stock_ids = Vec::new();
-    for c in &configs {
-        let is_new_lifecycle = listed_id_set.contains(&c.id);
-        let existing = existing_by_id.get(&c.id);
-        let should_emit_listed_event = c.inventory.quantity > 0
-            && (is_new_lifecycle
-                || existing.is_some_and(|(state, listed_event_at)| {
-                    state == "active" && listed_event_at.is_none()
-                }));
+    Ok(row.map(|row| WebPushSubscription {
+        endpoint: row.get::<String, _>(0),
+        keys: WebPushKeys {
+            p256dh: row.get::<String, _>(1),
+            auth: row.get::<String, _>(2),
+        },
+    }))
+}
 
-        if should_emit_listed_event {
-            listed_event_ids.push(c.id.clone());
-        }
+pub async fn cleanup_logs(
+    db: &SqlitePool,
+    retention_days: i64,
+    max_rows: i64,
+) -> anyhow::Result<()> {
+    if retention_days > 0 {
+        let cutoff = OffsetDateTime::now_utc()
+            .saturating_sub(time::Duration::days(retention_days))
+            .format(&Rfc3339)
+            .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
+        sqlx::query("DELETE FROM event_logs WHERE ts < ?")
+            .bind(cutoff)
+            .execute(db)
+            .await?;
+    }
+    if max_rows > 0 {
+        // Delete oldest rows beyond max_rows.
+        sqlx::query(
+            r#"
+DELETE FROM event_logs
+WHERE id IN (
+  SELECT id FROM event_logs
+  ORDER BY ts DESC, id DESC
+  LIMIT -1 OFFSET ?
+)"#,
+        )
+        .bind(max_rows)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
 
-        if is_new_lifecycle && c.inventory.quantity == 0 {
-            listed_pending_zero_stock_ids.push(c.id.clone());
-        }
+pub async fn cleanup_ops(db: &SqlitePool, retention_days: i64) -> anyhow::Result<()> {
+    if retention_days <= 0 {
+        return Ok(());
     }
 
-    if !configs.is_empty() {
-        // Upsert all fetched configs and mark as active.
-        for c in configs.iter() {
-            sqlx::query(
-                r#"
-INSERT INTO catalog_configs (
-  id, country_id, region_id, name, specs_json,
-  price_amount, price_currency, price_period,
-  inventory_status, inventory_quantity, checked_at,
-  config_digest,
-  lifecycle_state, lifecycle_listed_at, lifecycle_delisted_at, lifecycle_last_seen_at,
-  lifecycle_listed_event_at,
-  source_pid, source_fid, source_gid
-) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?, NULL, ?, ?, ?, ?, ?)
-ON CONFLICT(id) DO UPDATE SET
-  country_id = excluded.country_id,
-  region_id = excluded.region_id,
-  name = excluded.name,
-  specs_json = excluded.specs_json,
-  price_amount = excluded.price_amount,
-  price_currency = excluded.price_currency,
-  price_period = excluded.price_period,
-  inventory_status = excluded.inventory_status,
-  inventory_quantity = excluded.inventory_quantity,
-  checked_at = excluded.checked_at,
-  config_digest = excluded.config_digest,
-  lifecycle_state = 'active',
-  lifecycle_delisted_at = NULL,
-  lifecycle_last_seen_at = excluded.lifecycle_last_seen_at,
-  lifecycle_listed_at = CASE
-    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.lifecycle_listed_at
-    WHEN catalog_configs.lifecycle_listed_at IS NULL OR catalog_configs.lifecycle_listed_at = '1970-01-01T00:00:00Z' THEN excluded.lifecycle_listed_at
-    ELSE catalog_configs.lifecycle_listed_at
-  END,
-  lifecycle_listed_event_at = CASE
-    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.lifecycle_listed_event_at
-    WHEN catalog_configs.lifecycle_listed_event_at IS NULL AND excluded.inventory_quantity > 0 THEN excluded.lifecycle_listed_event_at
-    ELSE catalog_configs.lifecycle_listed_event_at
-  END,
-  source_pid = COALESCE(excluded.source_pid, catalog_configs.source_pid),
-  source_fid = excluded.source_fid,
-  source_gid = excluded.source_gid
-"#,
-            )
-            .bind(&c.id)
-            .bind(&c.country_id)
-            .bind(c.region_id.as_deref())
-            .bind(&c.name)
-            .bind(serde_json::to_string(&c.specs)?)
-            .bind(c.price.amount)
-            .bind(&c.price.currency)
-            .bind(&c.price.period)
-            .bind(&c.inventory.status)
-            .bind(c.inventory.quantity)
-            .bind(&c.inventory.checked_at)
-            .bind(&c.digest)
-            .bind(&fetched_at)
-            .bind(&fetched_at)
-            .bind(if c.inventory.quantity > 0 {
-                Some(fetched_at.as_str())
-            } else {
-                None
-            })
-            .bind(c.source_pid.as_deref())
-            .bind(c.source_fid.as_deref())
-            .bind(c.source_gid.as_deref())
-            .execute(&mut *tx)
-            .await?;
+    let cutoff = OffsetDateTime::now_utc()
+        .saturating_sub(time::Duration::days(retention_days))
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
 
-            // Best-effort: write minute history samples without affecting current inventory availability.
-            if let Some(ts_minute) = floor_rfc3339_to_minute_utc(&c.inventory.checked_at) {
-                let _ = sqlx::query(
-                    r#"
-INSERT INTO inventory_samples_1m (config_id, ts_minute, inventory_quantity)
-VALUES (?, ?, ?)
-ON CONFLICT(config_id, ts_minute) DO UPDATE SET
-  inventory_quantity = excluded.inventory_quantity
+    sqlx::query("DELETE FROM ops_events WHERE ts < ?")
+        .bind(&cutoff)
+        .execute(db)
+        .await?;
+    sqlx::query("DELETE FROM ops_notify_runs WHERE ts < ?")
+        .bind(&cutoff)
+        .execute(db)
+        .await?;
+    sqlx::query("DELETE FROM catalog_topology_changes WHERE changed_at < ?")
+        .bind(&cutoff)
+        .execute(db)
+        .await?;
+    sqlx::query(
+        r#"
+DELETE FROM ops_task_runs
+WHERE (
+  ended_at IS NOT NULL AND ended_at < ?
+) OR (
+  ended_at IS NULL AND started_at < ?
+)
 "#,
-                )
-                .bind(&c.id)
-                .bind(ts_minute)
-                .bind(c.inventory.quantity.max(0))
-                .execute(&mut *tx)
-                .await;
-            }
-        }
-    }
+    )
+    .bind(&cutoff)
+    .bind(&cutoff)
+    .execute(db)
+    .await?;
 
-    if !fetched_ids.is_empty() {
-        let placeholders = std::iter::repeat_n("?", fetched_ids.len())
-            .collect::<Vec<_>>()
-            .join(",");
-        let sql = format!("DELETE FROM user_config_archives WHERE config_id IN ({placeholders})");
-        let mut q = sqlx::query(&sql);
-        for id in &fetched_ids {
-            q = q.bind(id);
-        }
-        q.execute(&mut *tx).await?;
+    Ok(())
+}
+
+fn upstream_outage_from_row(row: sqlx::sqlite::SqliteRow) -> UpstreamOutageView {
+    let started_at = row.get::<String, _>(2);
+    let ended_at = row.get::<Option<String>, _>(3);
+    let duration_seconds = ended_at.as_deref().and_then(|ended_at| {
+        let start = OffsetDateTime::parse(&started_at, &Rfc3339).ok()?;
+        let end = OffsetDateTime::parse(ended_at, &Rfc3339).ok()?;
+        Some((end - start).whole_seconds().max(0))
+    });
+    UpstreamOutageView {
+        id: row.get::<i64, _>(0),
+        provider_id: row.get::<String, _>(1),
+        started_at,
+        ended_at,
+        duration_seconds,
+        failure_count: row.get::<i64, _>(4),
+        last_error: row.get::<Option<String>, _>(5),
     }
+}
 
-    if !delisted_ids.is_empty() {
-        // Mark configs as delisted (one success-miss = delist).
-        let placeholders = std::iter::repeat_n("?", delisted_ids.len())
-            .collect::<Vec<_>>()
-            .join(",");
-        let sql = format!(
-            r#"
-UPDATE catalog_configs
-SET lifecycle_state = 'delisted',
-    lifecycle_delisted_at = ?,
-    lifecycle_listed_event_at = NULL
-WHERE id IN ({placeholders})
-  AND lifecycle_state != 'delisted'
-"#
-        );
-        let mut q = sqlx::query(&sql).bind(&fetched_at);
-        for id in &delisted_ids {
-            q = q.bind(id);
-        }
-        q.execute(&mut *tx).await?;
+/// Starts an outage window for `provider_id`, or returns the one still open (e.g. left over from
+/// a restart during an outage).
+pub async fn open_upstream_outage(
+    db: &SqlitePool,
+    provider_id: &str,
+    started_at: &str,
+    failure_count: i64,
+    last_error: Option<&str>,
+) -> anyhow::Result<i64> {
+    if let Some(row) = sqlx::query(
+        "SELECT id FROM upstream_outages WHERE provider_id = ? AND ended_at IS NULL ORDER BY id DESC LIMIT 1",
+    )
+    .bind(provider_id)
+    .fetch_optional(db)
+    .await?
+    {
+        return Ok(row.get::<i64, _>(0));
     }
+    let res = sqlx::query(
+        r#"
+INSERT INTO upstream_outages (provider_id, started_at, ended_at, failure_count, last_error)
+VALUES (?, ?, NULL, ?, ?)
+"#,
+    )
+    .bind(provider_id)
+    .bind(started_at)
+    .bind(failure_count)
+    .bind(last_error)
+    .execute(db)
+    .await?;
+    Ok(res.last_insert_rowid())
+}
 
-    let ids_json =
-        serde_json::to_string(&configs.iter().map(|c| c.id.clone()).collect::<Vec<_>>())?;
+/// Counts another failed fetch (usually a half-open probe) against the open outage.
+pub async fn record_upstream_outage_failure(
+    db: &SqlitePool,
+    provider_id: &str,
+    last_error: Option<&str>,
+) -> anyhow::Result<()> {
     sqlx::query(
         r#"
-INSERT INTO catalog_url_cache (url_key, url, config_ids_json, last_success_at, updated_at)
-VALUES (?, ?, ?, ?, ?)
-ON CONFLICT(url_key) DO UPDATE SET
-  url = excluded.url,
-  config_ids_json = excluded.config_ids_json,
-  last_success_at = excluded.last_success_at,
-  updated_at = excluded.updated_at
+UPDATE upstream_outages
+SET failure_count = failure_count + 1,
+    last_error = COALESCE(?, last_error)
+WHERE provider_id = ? AND ended_at IS NULL
 "#,
     )
-    .bind(url_key)
-    .bind(url)
-    .bind(ids_json)
-    .bind(&fetched_at)
-    .bind(&fetched_at)
-    .execute(&mut *tx)
+    .bind(last_error)
+    .bind(provider_id)
+    .execute(db)
     .await?;
+    Ok(())
+}
 
-    let notice = hints
-        .region_notice
-        .map(str::trim)
-        .filter(|value| !value.is_empty());
-    if let Some(notice) = notice {
-        sqlx::query(
-            r#"
-INSERT INTO catalog_region_notices (url_key, country_id, region_id, text, updated_at)
-VALUES (?, ?, ?, ?, ?)
-ON CONFLICT(url_key) DO UPDATE SET
-  country_id = excluded.country_id,
-  region_id = excluded.region_id,
-  text = excluded.text,
-  updated_at = excluded.updated_at
-"#,
-        )
-        .bind(url_key)
-        .bind(fid)
-        .bind(gid)
-        .bind(notice)
-        .bind(&fetched_at)
-        .execute(&mut *tx)
-        .await?;
-    } else {
-        sqlx::query("DELETE FROM catalog_region_notices WHERE url_key = ?")
-            .bind(url_key)
-            .execute(&mut *tx)
-            .await?;
-    }
+/// Ends the open outage window of `provider_id`, returning it when there was one.
+pub async fn close_upstream_outage(
+    db: &SqlitePool,
+    provider_id: &str,
+    ended_at: &str,
+) -> anyhow::Result<Option<UpstreamOutageView>> {
+    let Some(row) = sqlx::query(
+        "SELECT id FROM upstream_outages WHERE provider_id = ? AND ended_at IS NULL ORDER BY id DESC LIMIT 1",
+    )
+    .bind(provider_id)
+    .fetch_optional(db)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let id = row.get::<i64, _>(0);
+    sqlx::query(
+        "UPDATE upstream_outages SET ended_at = ? WHERE provider_id = ? AND ended_at IS NULL",
+    )
+    .bind(ended_at)
+    .bind(provider_id)
+    .execute(db)
+    .await?;
+    let row = sqlx::query(
+        "SELECT id, provider_id, started_at, ended_at, failure_count, last_error FROM upstream_outages WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(db)
+    .await?;
+    Ok(Some(upstream_outage_from_row(row)))
+}
 
-    tx.commit().await?;
+/// Outage windows, newest first. `before_id` pages backwards through older windows.
+pub async fn list_upstream_outages(
+    db: &SqlitePool,
+    provider_id: Option<&str>,
+    before_id: Option<i64>,
+    limit: i64,
+) -> anyhow::Result<Vec<UpstreamOutageView>> {
+    let rows = sqlx::query(
+        r#"
+SELECT id, provider_id, started_at, ended_at, failure_count, last_error
+FROM upstream_outages
+WHERE (? IS NULL OR provider_id = ?)
+  AND (? IS NULL OR id < ?)
+ORDER BY id DESC
+LIMIT ?
+"#,
+    )
+    .bind(provider_id)
+    .bind(provider_id)
+    .bind(before_id)
+    .bind(before_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(upstream_outage_from_row).collect())
+}
 
-    Ok(ApplyCatalogUrlResult {
-        listed_ids,
-        listed_event_ids,
-        listed_pending_zero_stock_ids,
-        delisted_ids,
-        fetched_at,
-    })
+fn ops_health_alert_from_row(row: sqlx::sqlite::SqliteRow) -> OpsHealthAlertView {
+    OpsHealthAlertView {
+        id: row.get::<i64, _>(0),
+        rule: row.get::<String, _>(1),
+        opened_at: row.get::<String, _>(2),
+        resolved_at: row.get::<Option<String>, _>(3),
+        value: row.get::<f64, _>(4),
+        threshold: row.get::<f64, _>(5),
+        message: row.get::<String, _>(6),
+    }
 }
 
-pub async fn upsert_catalog_configs(
+/// Health alerts not resolved yet, oldest first.
+pub async fn list_open_ops_health_alerts(
     db: &SqlitePool,
-    configs: &[crate::upstream::ConfigBase],
-) -> anyhow::Result<()> {
-    let mut tx = db.begin().await?;
-    let mut active_ids: std::collections::HashSet<String> = std::collections::HashSet::new();
-    for c in configs {
-        active_ids.insert(c.id.clone());
-        sqlx::query(
-            r#"
-INSERT INTO catalog_configs (
-  id, country_id, region_id, name, specs_json,
-  price_amount, price_currency, price_period,
-  inventory_status, inventory_quantity, checked_at,
-  config_digest,
-  lifecycle_state, lifecycle_listed_at, lifecycle_delisted_at, lifecycle_last_seen_at,
-  lifecycle_listed_event_at,
-  source_pid, source_fid, source_gid
-) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?, NULL, ?, ?, ?, ?, ?)
-ON CONFLICT(id) DO UPDATE SET
-  country_id = excluded.country_id,
-  region_id = excluded.region_id,
-  name = excluded.name,
-  specs_json = excluded.specs_json,
-  price_amount = excluded.price_amount,
-  price_currency = excluded.price_currency,
-  price_period = excluded.price_period,
-  inventory_status = excluded.inventory_status,
-  inventory_quantity = excluded.inventory_quantity,
-  checked_at = excluded.checked_at,
-  config_digest = excluded.config_digest,
-  lifecycle_state = 'active',
-  lifecycle_delisted_at = NULL,
-  lifecycle_last_seen_at = excluded.checked_at,
-  lifecycle_listed_at = CASE
-    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.checked_at
-    WHEN catalog_configs.lifecycle_listed_at IS NULL OR catalog_configs.lifecycle_listed_at = '1970-01-01T00:00:00Z' THEN excluded.checked_at
-    ELSE catalog_configs.lifecycle_listed_at
-  END,
-  lifecycle_listed_event_at = CASE
-    WHEN catalog_configs.lifecycle_state = 'delisted' THEN excluded.lifecycle_listed_event_at
-    ELSE COALESCE(catalog_configs.lifecycle_listed_event_at, excluded.lifecycle_listed_event_at)
-  END,
-  source_pid = COALESCE(excluded.source_pid, catalog_configs.source_pid),
-  source_fid = excluded.source_fid,
-  source_gid = excluded.source_gid
+) -> anyhow::Result<Vec<OpsHealthAlertView>> {
+    let rows = sqlx::query(
+        r#"
+SELECT id, rule, opened_at, resolved_at, value, threshold, message
+FROM ops_health_alerts
+WHERE resolved_at IS NULL
+ORDER BY id ASC
 "#,
-        )
-        .bind(&c.id)
-        .bind(&c.country_id)
-        .bind(c.region_id.as_deref())
-        .bind(&c.name)
-        .bind(serde_json::to_string(&c.specs)?)
-        .bind(c.price.amount)
-        .bind(&c.price.currency)
-        .bind(&c.price.period)
-        .bind(&c.inventory.status)
-        .bind(c.inventory.quantity)
-        .bind(&c.inventory.checked_at)
-        .bind(&c.digest)
-        .bind(&c.inventory.checked_at)
-        .bind(&c.inventory.checked_at)
-        .bind(&c.inventory.checked_at)
-        .bind(c.source_pid.as_deref())
-        .bind(c.source_fid.as_deref())
-        .bind(c.source_gid.as_deref())
-        .execute(&mut *tx)
-        .await?;
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(ops_health_alert_from_row).collect())
+}
 
-        // Best-effort: write minute history samples without affecting current inventory availability.
-        if let Some(ts_minute) = floor_rfc3339_to_minute_utc(&c.inventory.checked_at) {
-            let _ = sqlx::query(
-                r#"
-INSERT INTO inventory_samples_1m (config_id, ts_minute, inventory_quantity)
-VALUES (?, ?, ?)
-ON CONFLICT(config_id, ts_minute) DO UPDATE SET
-  inventory_quantity = excluded.inventory_quantity
+pub async fn open_ops_health_alert(
+    db: &SqlitePool,
+    rule: &str,
+    opened_at: &str,
+    value: f64,
+    threshold: f64,
+    message: &str,
+) -> anyhow::Result<i64> {
+    let res = sqlx::query(
+        r#"
+INSERT INTO ops_health_alerts (rule, opened_at, resolved_at, value, threshold, message)
+VALUES (?, ?, NULL, ?, ?, ?)
 "#,
-            )
-            .bind(&c.id)
-            .bind(ts_minute)
-            .bind(c.inventory.quantity.max(0))
-            .execute(&mut *tx)
-            .await;
-        }
-    }
+    )
+    .bind(rule)
+    .bind(opened_at)
+    .bind(value)
+    .bind(threshold)
+    .bind(message)
+    .execute(db)
+    .await?;
+    Ok(res.last_insert_rowid())
+}
 
-    if !active_ids.is_empty() {
-        let placeholders = std::iter::repeat_n("?", active_ids.len())
-            .collect::<Vec<_>>()
-            .join(",");
-        let sql = format!("DELETE FROM user_config_archives WHERE config_id IN ({placeholders})");
-        let mut q = sqlx::query(&sql);
-        for id in &active_ids {
-            q = q.bind(id);
-        }
-        q.execute(&mut *tx).await?;
-    }
+/// Resolves the open alert of `rule`, returning it when there was one.
+pub async fn resolve_ops_health_alert(
+    db: &SqlitePool,
+    rule: &str,
+    resolved_at: &str,
+) -> anyhow::Result<Option<OpsHealthAlertView>> {
+    let Some(row) = sqlx::query(
+        "SELECT id FROM ops_health_alerts WHERE rule = ? AND resolved_at IS NULL ORDER BY id DESC LIMIT 1",
+    )
+    .bind(rule)
+    .fetch_optional(db)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let id = row.get::<i64, _>(0);
+    sqlx::query(
+        "UPDATE ops_health_alerts SET resolved_at = ? WHERE rule = ? AND resolved_at IS NULL",
+    )
+    .bind(resolved_at)
+    .bind(rule)
+    .execute(db)
+    .await?;
+    let row = sqlx::query(
+        "SELECT id, rule, opened_at, resolved_at, value, threshold, message FROM ops_health_alerts WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(db)
+    .await?;
+    Ok(Some(ops_health_alert_from_row(row)))
+}
 
-    tx.commit().await?;
-    Ok(())
+/// Health alerts, newest first. `before_id` pages backwards through older alerts.
+pub async fn list_ops_health_alerts(
+    db: &SqlitePool,
+    open_only: bool,
+    before_id: Option<i64>,
+    limit: i64,
+) -> anyhow::Result<Vec<OpsHealthAlertView>> {
+    let rows = sqlx::query(
+        r#"
+SELECT id, rule, opened_at, resolved_at, value, threshold, message
+FROM ops_health_alerts
+WHERE (? = 0 OR resolved_at IS NULL)
+  AND (? IS NULL OR id < ?)
+ORDER BY id DESC
+LIMIT ?
+"#,
+    )
+    .bind(open_only)
+    .bind(before_id)
+    .bind(before_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(ops_health_alert_from_row).collect())
 }
 
-#[derive(Debug, Clone)]
-pub struct InventorySample1mRow {
-    pub config_id: String,
-    pub ts_minute: String,
-    pub inventory_quantity: i64,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderLeaseRow {
+    pub holder: String,
+    pub acquired_at: String,
+    pub renewed_at: String,
+    pub expires_at: String,
 }
 
-pub async fn list_inventory_samples_1m(
+/// Takes or renews lease `name` for `holder` unless another holder's lease is still unexpired,
+/// then returns whoever holds it. Timestamps must share one RFC 3339 format (whole seconds,
+/// UTC) since expiry is compared as text.
+pub async fn acquire_leader_lease(
     db: &SqlitePool,
-    config_ids: &[String],
-    window_from: &str,
-    window_to: &str,
-) -> anyhow::Result<Vec<InventorySample1mRow>> {
-    if config_ids.is_empty() {
-        return Ok(Vec::new());
-    }
+    name: &str,
+    holder: &str,
+    now: &str,
+    expires_at: &str,
+) -> anyhow::Result<LeaderLeaseRow> {
+    sqlx::query(
+        r#"
+INSERT INTO leader_leases (name, holder, acquired_at, renewed_at, expires_at)
+VALUES (?, ?, ?, ?, ?)
+ON CONFLICT(name) DO UPDATE SET
+  acquired_at = CASE
+    WHEN leader_leases.holder = excluded.holder THEN leader_leases.acquired_at
+    ELSE excluded.acquired_at
+  END,
+  holder = excluded.holder,
+  renewed_at = excluded.renewed_at,
+  expires_at = excluded.expires_at
+WHERE leader_leases.holder = excluded.holder OR leader_leases.expires_at <= excluded.renewed_at
+"#,
+    )
+    .bind(name)
+    .bind(holder)
+    .bind(now)
+    .bind(now)
+    .bind(expires_at)
+    .execute(db)
+    .await?;
+    let row = sqlx::query(
+        "SELECT holder, acquired_at, renewed_at, expires_at FROM leader_leases WHERE name = ?",
+    )
+    .bind(name)
+    .fetch_one(db)
+    .await?;
+    Ok(LeaderLeaseRow {
+        holder: row.get(0),
+        acquired_at: row.get(1),
+        renewed_at: row.get(2),
+        expires_at: row.get(3),
+    })
+}
+
+/// Gives lease `name` up so another instance can take it without waiting for expiry.
+pub async fn release_leader_lease(db: &SqlitePool, name: &str, holder: &str) -> anyhow::Result<()> {
+    sqlx::query("DELETE FROM leader_leases WHERE name = ? AND holder = ?")
+        .bind(name)
+        .bind(holder)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Task a follower instance hands to the leader through `ops_queue_requests`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpsQueueRequest {
+    pub fid: String,
+    pub gid: Option<String>,
+    pub reason: String,
+    pub force_fetch: bool,
+    pub poller_waiter_user_id: Option<String>,
+}
 
-    let placeholders = std::iter::repeat_n("?", config_ids.len())
-        .collect::<Vec<_>>()
-        .join(",");
-    let sql = format!(
-        r#"SELECT config_id, ts_minute, inventory_quantity
-           FROM inventory_samples_1m
-           WHERE config_id IN ({placeholders})
-             AND ts_minute >= ?
-             AND ts_minute <= ?
-           ORDER BY config_id ASC, ts_minute ASC"#
-    );
+/// How the leader settled a forwarded request: the run it joined, or why it was not queued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpsQueueRequestResult {
+    pub run_id: Option<i64>,
+    pub ok: bool,
+    pub error: Option<String>,
+}
 
-    let mut query = sqlx::query(&sql);
-    for id in config_ids {
-        query = query.bind(id);
-    }
-    query = query.bind(window_from).bind(window_to);
+pub async fn insert_ops_queue_request(
+    db: &SqlitePool,
+    requested_by: &str,
+    req: &OpsQueueRequest,
+) -> anyhow::Result<i64> {
+    let res = sqlx::query(
+        r#"
+INSERT INTO ops_queue_requests (
+  fid, gid, reason, force_fetch, poller_waiter_user_id, requested_by, requested_at
+) VALUES (?, ?, ?, ?, ?, ?, ?)
+"#,
+    )
+    .bind(&req.fid)
+    .bind(&req.gid)
+    .bind(&req.reason)
+    .bind(if req.force_fetch { 1 } else { 0 })
+    .bind(&req.poller_waiter_user_id)
+    .bind(requested_by)
+    .bind(now_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(res.last_insert_rowid())
+}
 
-    let rows = query.fetch_all(db).await?;
-    Ok(rows
+/// Marks every unclaimed request as taken by the caller and returns them oldest first.
+pub async fn claim_ops_queue_requests(
+    db: &SqlitePool,
+    now: &str,
+) -> anyhow::Result<Vec<(i64, OpsQueueRequest)>> {
+    let rows = sqlx::query(
+        r#"
+UPDATE ops_queue_requests
+SET claimed_at = ?
+WHERE claimed_at IS NULL
+RETURNING id, fid, gid, reason, force_fetch, poller_waiter_user_id
+"#,
+    )
+    .bind(now)
+    .fetch_all(db)
+    .await?;
+    let mut out = rows
         .into_iter()
-        .map(|row| InventorySample1mRow {
-            config_id: row.get::<String, _>(0),
-            ts_minute: row.get::<String, _>(1),
-            inventory_quantity: row.get::<i64, _>(2),
+        .map(|row| {
+            (
+                row.get::<i64, _>(0),
+                OpsQueueRequest {
+                    fid: row.get(1),
+                    gid: row.get(2),
+                    reason: row.get(3),
+                    force_fetch: row.get::<i64, _>(4) != 0,
+                    poller_waiter_user_id: row.get(5),
+                },
+            )
         })
-        .collect())
+        .collect::<Vec<_>>();
+    out.sort_by_key(|(id, _)| *id);
+    Ok(out)
 }
 
-pub async fn insert_log(
+/// Hands claimed but unfinished requests back, for a new leader whose predecessor may have
+/// taken them and gone away.
+pub async fn release_ops_queue_request_claims(db: &SqlitePool) -> anyhow::Result<u64> {
+    let res = sqlx::query(
+        "UPDATE ops_queue_requests SET claimed_at = NULL WHERE completed_at IS NULL AND claimed_at IS NOT NULL",
+    )
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected())
+}
+
+pub async fn complete_ops_queue_request(
     db: &SqlitePool,
-    user_id: Option<&str>,
-    level: &str,
-    scope: &str,
-    message: &str,
-    meta: Option<serde_json::Value>,
+    id: i64,
+    result: &OpsQueueRequestResult,
 ) -> anyhow::Result<()> {
-    let id = Uuid::new_v4().to_string();
-    let ts = now_rfc3339();
     sqlx::query(
-        "INSERT INTO event_logs (id, user_id, ts, level, scope, message, meta_json) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        "UPDATE ops_queue_requests SET run_id = ?, ok = ?, error = ?, completed_at = ? WHERE id = ?",
     )
+    .bind(result.run_id)
+    .bind(if result.ok { 1 } else { 0 })
+    .bind(&result.error)
+    .bind(now_rfc3339())
     .bind(id)
-    .bind(user_id)
-    .bind(ts)
-    .bind(level)
-    .bind(scope)
-    .bind(message)
-    .bind(meta.map(|v| v.to_string()))
     .execute(db)
     .await?;
     Ok(())
 }
 
-pub async fn list_logs(
+/// Returns and removes the result of request `id` once the leader completed it.
+pub async fn take_ops_queue_request_result(
     db: &SqlitePool,
-    user_id: &str,
-    level: Option<&str>,
-    cursor: Option<&str>,
-    limit: i64,
-) -> anyhow::Result<(Vec<LogEntryView>, Option<String>)> {
-    let (cursor_ts, cursor_id) = cursor
-        .and_then(|c| {
-            // Cursor format: "<RFC3339 ts>:<id>".
-            // RFC3339 timestamps contain `:` (e.g. "2026-01-19T00:00:00Z"), so we must split
-            // from the right to preserve the timestamp portion.
-            let mut parts = c.rsplitn(2, ':');
-            let id = parts.next()?.to_string();
-            let ts = parts.next()?.to_string();
-            Some((ts, id))
-        })
-        .unwrap_or(("9999-12-31T23:59:59Z".to_string(), "zzzz".to_string()));
-
-    let mut q = String::from(
-        r#"SELECT id, ts, level, scope, message, meta_json
-           FROM event_logs
-           WHERE user_id = ?
-             AND (ts < ? OR (ts = ? AND id < ?))"#,
-    );
-    if level.is_some() {
-        q.push_str(" AND level = ?");
-    }
-    q.push_str(" ORDER BY ts DESC, id DESC LIMIT ?");
+    id: i64,
+) -> anyhow::Result<Option<OpsQueueRequestResult>> {
+    let row = sqlx::query(
+        r#"
+DELETE FROM ops_queue_requests
+WHERE id = ? AND completed_at IS NOT NULL
+RETURNING run_id, ok, error
+"#,
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.map(|row| OpsQueueRequestResult {
+        run_id: row.get(0),
+        ok: row.get::<Option<i64>, _>(1).unwrap_or(0) != 0,
+        error: row.get(2),
+    }))
+}
 
-    let mut query = sqlx::query(&q)
-        .bind(user_id)
-        .bind(&cursor_ts)
-        .bind(&cursor_ts)
-        .bind(&cursor_id);
-    if let Some(level) = level {
-        query = query.bind(level);
-    }
-    query = query.bind(limit + 1);
+/// Whether request `id` is still there, i.e. neither collected nor cleaned up.
+pub async fn ops_queue_request_exists(db: &SqlitePool, id: i64) -> anyhow::Result<bool> {
+    let row = sqlx::query("SELECT 1 FROM ops_queue_requests WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.is_some())
+}
 
-    let rows = query.fetch_all(db).await?;
-    let mut items = Vec::new();
-    for row in rows.iter().take(limit as usize) {
-        let meta_json = row.get::<Option<String>, _>(5);
-        let meta = meta_json.and_then(|v| serde_json::from_str(&v).ok());
-        items.push(LogEntryView {
-            id: row.get::<String, _>(0),
-            ts: row.get::<String, _>(1),
-            level: row.get::<String, _>(2),
-            scope: row.get::<String, _>(3),
-            message: row.get::<String, _>(4),
-            meta,
-        });
-    }
+/// Withdraws request `id`, e.g. because its requester gave up waiting.
+pub async fn delete_ops_queue_request(db: &SqlitePool, id: i64) -> anyhow::Result<()> {
+    sqlx::query("DELETE FROM ops_queue_requests WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
 
-    let next_cursor = if rows.len() as i64 > limit {
-        let last = items.last().unwrap();
-        Some(format!("{}:{}", last.ts, last.id))
-    } else {
-        None
-    };
+/// Drops results nobody collected, e.g. because the requesting instance stopped waiting.
+pub async fn delete_stale_ops_queue_requests(
+    db: &SqlitePool,
+    completed_before: &str,
+) -> anyhow::Result<u64> {
+    let res = sqlx::query(
+        "DELETE FROM ops_queue_requests WHERE completed_at IS NOT NULL AND completed_at < ?",
+    )
+    .bind(completed_before)
+    .execute(db)
+    .await?;
+    let admin = sqlx::query(
+        "DELETE FROM ops_admin_requests WHERE completed_at IS NOT NULL AND completed_at < ?",
+    )
+    .bind(completed_before)
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected() + admin.rows_affected())
+}
 
-    Ok((items, next_cursor))
+/// Queue admin action (`pause`, `resume`, `drain`, `cancel`, `clear`) a follower hands to the
+/// leader through `ops_admin_requests`; `keys` are the `(fid, gid)` pairs of a `cancel`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpsAdminRequest {
+    pub action: String,
+    pub actor: String,
+    pub keys: Vec<(String, Option<String>)>,
 }
 
-fn notification_record_view_from_row(
-    row: &sqlx::sqlite::SqliteRow,
-    telegram_deliveries: Vec<NotificationRecordDeliveryView>,
-    items: Vec<NotificationRecordItemView>,
-) -> NotificationRecordView {
-    NotificationRecordView {
-        id: row.get::<String, _>("id"),
-        created_at: row.get::<String, _>("created_at"),
-        kind: row.get::<String, _>("kind"),
-        title: row.get::<String, _>("title"),
-        summary: row.get::<String, _>("summary"),
-        partition_label: row.get::<Option<String>, _>("partition_label"),
-        telegram_status: row.get::<String, _>("telegram_status"),
-        web_push_status: row.get::<String, _>("web_push_status"),
-        telegram_deliveries,
-        items,
-    }
+/// The leader's answer to a forwarded admin action: the JSON view it returned, or its error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpsAdminRequestResult {
+    pub result_json: Option<String>,
+    pub error: Option<String>,
 }
 
-async fn load_notification_record_items_by_record_ids(
+pub async fn insert_ops_admin_request(
     db: &SqlitePool,
-    record_ids: &[String],
-) -> anyhow::Result<std::collections::HashMap<String, Vec<NotificationRecordItemView>>> {
-    if record_ids.is_empty() {
-        return Ok(std::collections::HashMap::new());
-    }
-
-    let placeholders = std::iter::repeat_n("?", record_ids.len())
-        .collect::<Vec<_>>()
-        .join(",");
-    let sql = format!(
+    requested_by: &str,
+    req: &OpsAdminRequest,
+) -> anyhow::Result<i64> {
+    let res = sqlx::query(
         r#"
-SELECT
-  record_id,
-  config_id,
-  name,
-  country_name,
-  region_name,
-  specs_json,
-  price_amount,
-  price_currency,
-  price_period,
-  inventory_status,
-  inventory_quantity,
-  checked_at,
-  lifecycle_state,
-  lifecycle_listed_at,
-  lifecycle_delisted_at
-FROM notification_record_items
-WHERE record_id IN ({placeholders})
-ORDER BY record_id ASC, position ASC, id ASC
-"#
-    );
-    let mut query = sqlx::query(&sql);
-    for record_id in record_ids {
-        query = query.bind(record_id);
-    }
+INSERT INTO ops_admin_requests (action, actor, keys_json, requested_by, requested_at)
+VALUES (?, ?, ?, ?, ?)
+"#,
+    )
+    .bind(&req.action)
+    .bind(&req.actor)
+    .bind(serde_json::to_string(&req.keys)?)
+    .bind(requested_by)
+    .bind(now_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(res.last_insert_rowid())
+}
 
-    let rows = query.fetch_all(db).await?;
-    let mut out = std::collections::HashMap::new();
-    for row in rows {
-        let record_id = row.get::<String, _>("record_id");
-        out.entry(record_id)
-            .or_insert_with(Vec::new)
-            .push(notification_record_item_view_from_row(&row));
-    }
+/// Marks every unclaimed admin request as taken by the caller and returns them oldest first.
+pub async fn claim_ops_admin_requests(
+    db: &SqlitePool,
+    now: &str,
+) -> anyhow::Result<Vec<(i64, OpsAdminRequest)>> {
+    let rows = sqlx::query(
+        r#"
+UPDATE ops_admin_requests
+SET claimed_at = ?
+WHERE claimed_at IS NULL
+RETURNING id, action, actor, keys_json
+"#,
+    )
+    .bind(now)
+    .fetch_all(db)
+    .await?;
+    let mut out = rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get::<i64, _>(0),
+                OpsAdminRequest {
+                    action: row.get(1),
+                    actor: row.get(2),
+                    keys: serde_json::from_str(&row.get::<String, _>(3)).unwrap_or_default(),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    out.sort_by_key(|(id, _)| *id);
     Ok(out)
 }
 
-pub async fn insert_notification_record(
+pub async fn complete_ops_admin_request(
     db: &SqlitePool,
-    user_id: &str,
-    draft: &NotificationRecordDraft,
-) -> anyhow::Result<String> {
-    let id = Uuid::new_v4().to_string();
-    let created_at = notification_record_now_rfc3339();
-    let mut tx = db.begin().await?;
-
+    id: i64,
+    result: &OpsAdminRequestResult,
+) -> anyhow::Result<()> {
     sqlx::query(
-        r#"
-INSERT INTO notification_records (
-  id,
-  user_id,
-  created_at,
-  kind,
-  title,
-  summary,
-  partition_label,
-  telegram_status,
-  web_push_status
-)
-VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-"#,
+        "UPDATE ops_admin_requests SET result_json = ?, error = ?, completed_at = ? WHERE id = ?",
     )
-    .bind(&id)
-    .bind(user_id)
-    .bind(&created_at)
-    .bind(&draft.kind)
-    .bind(&draft.title)
-    .bind(&draft.summary)
-    .bind(draft.partition_label.as_deref())
-    .bind(&draft.telegram_status)
-    .bind(&draft.web_push_status)
-    .execute(&mut *tx)
+    .bind(&result.result_json)
+    .bind(&result.error)
+    .bind(now_rfc3339())
+    .bind(id)
+    .execute(db)
     .await?;
+    Ok(())
+}
 
-    for (position, item) in draft.items.iter().enumerate() {
-        let item_id = Uuid::new_v4().to_string();
-        let specs_json = serde_json::to_string(&item.specs)?;
-        sqlx::query(
-            r#"
-INSERT INTO notification_record_items (
-  id,
-  record_id,
-  position,
-  config_id,
-  name,
-  country_name,
-  region_name,
-  specs_json,
-  price_amount,
-  price_currency,
-  price_period,
-  inventory_status,
-  inventory_quantity,
-  checked_at,
-  lifecycle_state,
-  lifecycle_listed_at,
-  lifecycle_delisted_at
-)
-VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+/// Returns and removes the result of admin request `id` once the leader completed it.
+pub async fn take_ops_admin_request_result(
+    db: &SqlitePool,
+    id: i64,
+) -> anyhow::Result<Option<OpsAdminRequestResult>> {
+    let row = sqlx::query(
+        r#"
+DELETE FROM ops_admin_requests
+WHERE id = ? AND completed_at IS NOT NULL
+RETURNING result_json, error
 "#,
-        )
-        .bind(&item_id)
-        .bind(&id)
-        .bind(position as i64)
-        .bind(item.config_id.as_deref())
-        .bind(&item.name)
-        .bind(&item.country_name)
-        .bind(item.region_name.as_deref())
-        .bind(&specs_json)
-        .bind(item.price.amount)
-        .bind(&item.price.currency)
-        .bind(&item.price.period)
-        .bind(&item.inventory.status)
-        .bind(item.inventory.quantity)
-        .bind(&item.inventory.checked_at)
-        .bind(&item.lifecycle.state)
-        .bind(&item.lifecycle.listed_at)
-        .bind(item.lifecycle.delisted_at.as_deref())
-        .execute(&mut *tx)
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.map(|row| OpsAdminRequestResult {
+        result_json: row.get(0),
+        error: row.get(1),
+    }))
+}
+
+/// Withdraws admin request `id`, e.g. because its requester gave up waiting.
+pub async fn delete_ops_admin_request(db: &SqlitePool, id: i64) -> anyhow::Result<()> {
+    sqlx::query("DELETE FROM ops_admin_requests WHERE id = ?")
+        .bind(id)
+        .execute(db)
         .await?;
-    }
+    Ok(())
+}
 
-    tx.commit().await?;
-    Ok(id)
+/// A country (`region.*`) or region (`partition.*`) appearing in or leaving the storefront.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogTopologyChange<'a> {
+    pub kind: &'static str,
+    pub country_id: &'a str,
+    pub country_name: &'a str,
+    pub region_id: Option<&'a str>,
+    pub region_name: Option<&'a str>,
 }
 
-pub async fn replace_notification_record_deliveries(
+pub async fn record_catalog_topology_changes(
     db: &SqlitePool,
-    record_id: &str,
-    channel: &str,
-    deliveries: &[NotificationRecordDeliveryView],
+    changes: &[CatalogTopologyChange<'_>],
 ) -> anyhow::Result<()> {
     let now = now_rfc3339();
     let mut tx = db.begin().await?;
-    sqlx::query("DELETE FROM notification_record_deliveries WHERE record_id = ? AND channel = ?")
-        .bind(record_id)
-        .bind(channel)
-        .execute(&mut *tx)
-        .await?;
-
-    for (position, delivery) in deliveries.iter().enumerate() {
-        let id = Uuid::new_v4().to_string();
+    for change in changes {
         sqlx::query(
             r#"
-INSERT INTO notification_record_deliveries (
-  id,
-  record_id,
-  channel,
-  position,
-  target,
-  status,
-  error_message,
-  created_at,
-  updated_at
-)
-VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+INSERT INTO catalog_topology_changes (
+  kind, country_id, country_name, region_id, region_name, changed_at
+) VALUES (?, ?, ?, ?, ?, ?)
 "#,
         )
-        .bind(&id)
-        .bind(record_id)
-        .bind(channel)
-        .bind(position as i64)
-        .bind(&delivery.target)
-        .bind(&delivery.status)
-        .bind(delivery.error.as_deref())
-        .bind(&now)
+        .bind(change.kind)
+        .bind(change.country_id)
+        .bind(change.country_name)
+        .bind(change.region_id)
+        .bind(change.region_name)
         .bind(&now)
         .execute(&mut *tx)
         .await?;
     }
-
     tx.commit().await?;
     Ok(())
 }
 
-pub async fn update_notification_record_channel_status(
+/// Aggregates ops runs, notification deliveries and catalog changes over `[from, to)` into the
+/// daily digest. Config changes come from the `catalog_configs` lifecycle columns, topology
+/// changes from `catalog_topology_changes`.
+pub async fn load_ops_digest(
     db: &SqlitePool,
-    record_id: &str,
-    channel: &str,
-    status: &str,
-) -> anyhow::Result<()> {
-    let sql = match channel {
-        "telegram" => "UPDATE notification_records SET telegram_status = ? WHERE id = ?",
-        "webPush" => "UPDATE notification_records SET web_push_status = ? WHERE id = ?",
-        other => anyhow::bail!("unknown notification channel: {other}"),
+    from: &str,
+    to: &str,
+) -> anyhow::Result<OpsDigestView> {
+    let row = sqlx::query(
+        r#"
+SELECT
+  COUNT(*) as total,
+  SUM(CASE WHEN ok = 1 THEN 1 ELSE 0 END) as success,
+  SUM(CASE WHEN fetch_action = 'cache' THEN 1 ELSE 0 END) as cache_hits,
+  SUM(CASE WHEN fetch_action = 'not_modified' THEN 1 ELSE 0 END) as not_modified
+FROM ops_task_runs
+WHERE ended_at IS NOT NULL
+  AND ended_at >= ?
+  AND ended_at < ?
+"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_one(db)
+    .await?;
+    let total = row.get::<i64, _>(0);
+    let success = row.try_get::<i64, _>(1).unwrap_or(0);
+    let tasks = OpsDigestTasksView {
+        total,
+        success,
+        failure: (total - success).max(0),
+        cache_hits: row.try_get::<i64, _>(2).unwrap_or(0),
+        not_modified: row.try_get::<i64, _>(3).unwrap_or(0),
+        success_rate_pct: if total > 0 {
+            (success as f64) * 100.0 / (total as f64)
+        } else {
+            0.0
+        },
     };
-    sqlx::query(sql)
-        .bind(status)
-        .bind(record_id)
-        .execute(db)
-        .await?;
-    Ok(())
-}
 
-async fn load_notification_record_deliveries_by_record_ids(
-    db: &SqlitePool,
-    record_ids: &[String],
-) -> anyhow::Result<std::collections::HashMap<String, Vec<NotificationRecordDeliveryView>>> {
-    if record_ids.is_empty() {
-        return Ok(std::collections::HashMap::new());
-    }
+    let failures = sqlx::query(
+        r#"
+SELECT COALESCE(error_code, 'unknown') as code, COUNT(*) as n
+FROM ops_task_runs
+WHERE ended_at IS NOT NULL
+  AND ended_at >= ?
+  AND ended_at < ?
+  AND ok = 0
+GROUP BY code
+ORDER BY n DESC, code ASC
+"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| OpsDigestCountView {
+        key: row.get::<String, _>(0),
+        count: row.get::<i64, _>(1),
+    })
+    .collect();
 
-    let placeholders = std::iter::repeat_n("?", record_ids.len())
-        .collect::<Vec<_>>()
-        .join(",");
-    let sql = format!(
+    let mut elapsed = sqlx::query(
         r#"
-SELECT record_id, channel, target, status, error_message
-FROM notification_record_deliveries
-WHERE record_id IN ({placeholders})
-  AND channel = 'telegram'
-ORDER BY record_id ASC, position ASC, created_at ASC, id ASC
-"#
-    );
+SELECT fetch_elapsed_ms
+FROM ops_task_runs
+WHERE ended_at IS NOT NULL
+  AND ended_at >= ?
+  AND ended_at < ?
+  AND fetch_action != 'cache'
+  AND fetch_elapsed_ms IS NOT NULL
+"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| row.get::<i64, _>(0))
+    .collect::<Vec<_>>();
+    elapsed.sort_unstable();
+    let latency = OpsDigestLatencyView {
+        samples: elapsed.len() as i64,
+        p50_ms: nearest_rank(&elapsed, 50),
+        p90_ms: nearest_rank(&elapsed, 90),
+        p99_ms: nearest_rank(&elapsed, 99),
+        max_ms: elapsed.last().copied(),
+    };
 
-    let mut query = sqlx::query(&sql);
-    for record_id in record_ids {
-        query = query.bind(record_id);
-    }
+    let notify = sqlx::query(
+        r#"
+SELECT
+  channel,
+  SUM(CASE WHEN result = 'success' THEN 1 ELSE 0 END) as success,
+  SUM(CASE WHEN result = 'error' THEN 1 ELSE 0 END) as error,
+  SUM(CASE WHEN result = 'skipped' THEN 1 ELSE 0 END) as skipped
+FROM ops_notify_runs
+WHERE ts >= ?
+  AND ts < ?
+GROUP BY channel
+ORDER BY channel ASC
+"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| OpsDigestChannelView {
+        channel: row.get::<String, _>(0),
+        success: row.try_get::<i64, _>(1).unwrap_or(0),
+        error: row.try_get::<i64, _>(2).unwrap_or(0),
+        skipped: row.try_get::<i64, _>(3).unwrap_or(0),
+    })
+    .collect();
 
-    let rows = query.fetch_all(db).await?;
-    let mut out = std::collections::HashMap::new();
-    for row in rows {
-        let record_id = row.get::<String, _>("record_id");
-        out.entry(record_id)
-            .or_insert_with(Vec::new)
-            .push(NotificationRecordDeliveryView {
-                channel: row.get::<String, _>("channel"),
-                target: row.get::<String, _>("target"),
-                status: row.get::<String, _>("status"),
-                error: row.get::<Option<String>, _>("error_message"),
-            });
-    }
-    Ok(out)
+    let notification_records = sqlx::query(
+        r#"
+SELECT kind, COUNT(*) as n
+FROM notification_records
+WHERE created_at >= ?
+  AND created_at < ?
+GROUP BY kind
+ORDER BY n DESC, kind ASC
+"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| OpsDigestCountView {
+        key: row.get::<String, _>(0),
+        count: row.get::<i64, _>(1),
+    })
+    .collect();
+
+    let configs_added = load_digest_configs(
+        db,
+        r#"
+SELECT id, name
+FROM catalog_configs
+WHERE lifecycle_state = 'active'
+  AND lifecycle_listed_at >= ?
+  AND lifecycle_listed_at < ?
+ORDER BY id ASC
+"#,
+        from,
+        to,
+    )
+    .await?;
+    let configs_removed = load_digest_configs(
+        db,
+        r#"
+SELECT id, name
+FROM catalog_configs
+WHERE lifecycle_state = 'delisted'
+  AND lifecycle_delisted_at >= ?
+  AND lifecycle_delisted_at < ?
+ORDER BY id ASC
+"#,
+        from,
+        to,
+    )
+    .await?;
+
+    let topology = sqlx::query(
+        r#"
+SELECT DISTINCT kind, country_name, region_name
+FROM catalog_topology_changes
+WHERE changed_at >= ?
+  AND changed_at < ?
+ORDER BY kind ASC, country_name ASC, region_name ASC
+"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| {
+        let country = row.get::<String, _>(1);
+        OpsDigestTopologyView {
+            kind: row.get::<String, _>(0),
+            label: match row.get::<Option<String>, _>(2) {
+                Some(region) => format!("{country} / {region}"),
+                None => country,
+            },
+        }
+    })
+    .collect();
+
+    Ok(OpsDigestView {
+        from: from.to_string(),
+        to: to.to_string(),
+        tasks,
+        failures,
+        latency,
+        notify,
+        notification_records,
+        configs_added,
+        configs_removed,
+        topology,
+    })
 }
 
-pub async fn list_notification_records(
+async fn load_digest_configs(
     db: &SqlitePool,
-    user_id: &str,
-    cursor: Option<&str>,
-    limit: i64,
-) -> anyhow::Result<(Vec<NotificationRecordView>, Option<String>)> {
-    let (cursor_ts, cursor_id) = cursor
-        .and_then(|c| {
-            let mut parts = c.rsplitn(2, ':');
-            let id = parts.next()?.trim().to_string();
-            let ts = parts.next()?.trim();
-            let parsed = OffsetDateTime::parse(ts, &Rfc3339).ok()?;
-            Some((format_notification_record_rfc3339(parsed), id))
+    sql: &str,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<Vec<OpsDigestConfigView>> {
+    Ok(sqlx::query(sql)
+        .bind(from)
+        .bind(to)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| OpsDigestConfigView {
+            id: row.get::<String, _>(0),
+            name: row.get::<String, _>(1),
         })
-        .unwrap_or((
-            NOTIFICATION_RECORD_CURSOR_MAX_TS.to_string(),
-            "zzzz".to_string(),
-        ));
+        .collect())
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn nearest_rank(sorted: &[i64], pct: usize) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Filters of `GET /api/ops/runs`; unset fields match everything. `from`/`to` bound
+/// `started_at` (inclusive/exclusive).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpsTaskRunFilter<'a> {
+    pub from: Option<&'a str>,
+    pub to: Option<&'a str>,
+    pub provider_id: Option<&'a str>,
+    pub fid: Option<&'a str>,
+    pub gid: Option<&'a str>,
+    pub ok: Option<bool>,
+    pub error_code: Option<&'a str>,
+    pub fetch_action: Option<&'a str>,
+}
 
+/// Task runs newest first, each with the notification deliveries it triggered.
+pub async fn list_ops_task_runs(
+    db: &SqlitePool,
+    filter: &OpsTaskRunFilter<'_>,
+    before_id: Option<i64>,
+    limit: i64,
+) -> anyhow::Result<Vec<OpsTaskRunView>> {
+    let ok = filter.ok.map(|ok| if ok { 1 } else { 0 });
     let rows = sqlx::query(
         r#"
-SELECT id, created_at, kind, title, summary, partition_label, telegram_status, web_push_status
-FROM notification_records
-WHERE user_id = ?
-  AND (created_at < ? OR (created_at = ? AND id < ?))
-ORDER BY created_at DESC, id DESC
+SELECT id, provider_id, fid, gid, started_at, ended_at, ok, attempt, fetch_action,
+       freshness_window_seconds, reason_counts_json, fetch_http_status, fetch_bytes,
+       fetch_elapsed_ms, parse_produced_configs, parse_elapsed_ms, error_code, error_message
+FROM ops_task_runs
+WHERE (? IS NULL OR started_at >= ?)
+  AND (? IS NULL OR started_at < ?)
+  AND (? IS NULL OR provider_id = ?)
+  AND (? IS NULL OR fid = ?)
+  AND (? IS NULL OR gid = ?)
+  AND (? IS NULL OR (ended_at IS NOT NULL AND ok = ?))
+  AND (? IS NULL OR error_code = ?)
+  AND (? IS NULL OR fetch_action = ?)
+  AND (? IS NULL OR id < ?)
+ORDER BY id DESC
 LIMIT ?
 "#,
     )
-    .bind(user_id)
-    .bind(&cursor_ts)
-    .bind(&cursor_ts)
-    .bind(&cursor_id)
-    .bind(limit + 1)
+    .bind(filter.from)
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(filter.to)
+    .bind(filter.provider_id)
+    .bind(filter.provider_id)
+    .bind(filter.fid)
+    .bind(filter.fid)
+    .bind(filter.gid)
+    .bind(filter.gid)
+    .bind(ok)
+    .bind(ok)
+    .bind(filter.error_code)
+    .bind(filter.error_code)
+    .bind(filter.fetch_action)
+    .bind(filter.fetch_action)
+    .bind(before_id)
+    .bind(before_id)
+    .bind(limit)
     .fetch_all(db)
     .await?;
 
-    let visible_rows = rows.iter().take(limit as usize).collect::<Vec<_>>();
-    let record_ids = visible_rows
-        .iter()
-        .map(|row| row.get::<String, _>("id"))
+    let mut items = rows
+        .into_iter()
+        .map(|row| {
+            let ended_at = row.get::<Option<String>, _>(5);
+            let ok = ended_at.is_some().then(|| row.get::<i64, _>(6) != 0);
+            OpsTaskRunView {
+                id: row.get::<i64, _>(0),
+                provider_id: row.get::<String, _>(1),
+                fid: row.get::<String, _>(2),
+                gid: row.get::<Option<String>, _>(3),
+                started_at: row.get::<String, _>(4),
+                ended_at,
+                ok,
+                attempt: row.get::<i64, _>(7),
+                fetch_action: row.get::<String, _>(8),
+                freshness_window_seconds: row.get::<Option<i64>, _>(9),
+                reason_counts: row
+                    .get::<Option<String>, _>(10)
+                    .and_then(|raw| serde_json::from_str(&raw).ok())
+                    .unwrap_or_default(),
+                fetch_http_status: row.get::<Option<i64>, _>(11),
+                fetch_bytes: row.get::<Option<i64>, _>(12),
+                fetch_elapsed_ms: row.get::<Option<i64>, _>(13),
+                parse_produced_configs: row.get::<Option<i64>, _>(14),
+                parse_elapsed_ms: row.get::<Option<i64>, _>(15),
+                error_code: row.get::<Option<String>, _>(16),
+                error_message: row.get::<Option<String>, _>(17),
+                notifications: Vec::new(),
+            }
+        })
         .collect::<Vec<_>>();
-    let mut items_by_record = load_notification_record_items_by_record_ids(db, &record_ids).await?;
-    let mut deliveries_by_record =
-        load_notification_record_deliveries_by_record_ids(db, &record_ids).await?;
-
-    let mut items = Vec::with_capacity(visible_rows.len());
-    for row in visible_rows {
-        let record_id = row.get::<String, _>("id");
-        items.push(notification_record_view_from_row(
-            row,
-            deliveries_by_record.remove(&record_id).unwrap_or_default(),
-            items_by_record.remove(&record_id).unwrap_or_default(),
-        ));
+    if items.is_empty() {
+        return Ok(items);
     }
 
-    let next_cursor = if rows.len() as i64 > limit {
-        let last = items
-            .last()
-            .expect("visible items exist when next cursor exists");
-        Some(format!("{}:{}", last.created_at, last.id))
-    } else {
-        None
-    };
+    let placeholders = vec!["?"; items.len()].join(", ");
+    let sql = format!(
+        "SELECT id, task_run_id, ts, channel, result, error_message FROM ops_notify_runs \
+         WHERE task_run_id IN ({placeholders}) ORDER BY id ASC"
+    );
+    let mut query = sqlx::query(&sql);
+    for item in &items {
+        query = query.bind(item.id);
+    }
+    let mut by_run: HashMap<i64, Vec<OpsNotifyRunView>> = HashMap::new();
+    for row in query.fetch_all(db).await? {
+        by_run
+            .entry(row.get::<i64, _>(1))
+            .or_default()
+            .push(OpsNotifyRunView {
+                id: row.get::<i64, _>(0),
+                ts: row.get::<String, _>(2),
+                channel: row.get::<String, _>(3),
+                result: row.get::<String, _>(4),
+                error_message: row.get::<Option<String>, _>(5),
+            });
+    }
+    for item in &mut items {
+        item.notifications = by_run.remove(&item.id).unwrap_or_default();
+    }
+    Ok(items)
+}
 
-    Ok((items, next_cursor))
+#[derive(Debug, Clone)]
+pub struct RestockEventRow {
+    pub config_id: String,
+    pub country_id: String,
+    pub region_id: Option<String>,
+    /// First minute sampled with stock after a zero-stock sample.
+    pub ts_minute: String,
 }
 
-pub async fn get_notification_record(
+/// Zero-to-positive inventory transitions in `inventory_samples_1m` since `since`, per config in
+/// time order.
+pub async fn list_restock_events(
     db: &SqlitePool,
-    user_id: &str,
-    record_id: &str,
-) -> anyhow::Result<Option<NotificationRecordView>> {
-    let row = sqlx::query(
+    since: &str,
+) -> anyhow::Result<Vec<RestockEventRow>> {
+    let rows = sqlx::query(
         r#"
-SELECT id, created_at, kind, title, summary, partition_label, telegram_status, web_push_status
-FROM notification_records
-WHERE user_id = ? AND id = ?
+SELECT s.config_id, c.country_id, c.region_id, s.ts_minute
+FROM (
+  SELECT
+    config_id,
+    ts_minute,
+    inventory_quantity,
+    LAG(inventory_quantity) OVER (PARTITION BY config_id ORDER BY ts_minute) AS prev_quantity
+  FROM inventory_samples_1m
+  WHERE ts_minute >= ?
+) s
+JOIN catalog_configs c ON c.id = s.config_id
+WHERE s.prev_quantity = 0 AND s.inventory_quantity > 0
+ORDER BY s.config_id ASC, s.ts_minute ASC
 "#,
     )
-    .bind(user_id)
-    .bind(record_id)
-    .fetch_optional(db)
+    .bind(since)
+    .fetch_all(db)
     .await?;
-
-    let Some(row) = row else {
-        return Ok(None);
-    };
-
-    let mut items_by_record =
-        load_notification_record_items_by_record_ids(db, &[record_id.to_string()]).await?;
-    let mut deliveries_by_record =
-        load_notification_record_deliveries_by_record_ids(db, &[record_id.to_string()]).await?;
-    Ok(Some(notification_record_view_from_row(
-        &row,
-        deliveries_by_record.remove(record_id).unwrap_or_default(),
-        items_by_record.remove(record_id).unwrap_or_default(),
-    )))
+    Ok(rows
+        .into_iter()
+        .map(|r| RestockEventRow {
+            config_id: r.get(0),
+            country_id: r.get(1),
+            region_id: r.get(2),
+            ts_minute: r.get(3),
+        })
+        .collect())
 }
 
-pub async fn cleanup_notification_records(
+/// Replaces every stored restock profile with `profiles` (`(scope kind, scope key, json)`).
+pub async fn replace_restock_profiles(
     db: &SqlitePool,
-    retention_days: i64,
-    max_rows: i64,
+    profiles: &[(&str, String, String)],
+    computed_at: &str,
 ) -> anyhow::Result<()> {
     let mut tx = db.begin().await?;
-
-    if retention_days > 0 {
-        let cutoff = OffsetDateTime::now_utc().saturating_sub(time::Duration::days(retention_days));
-        sqlx::query("DELETE FROM notification_records WHERE created_at < ?")
-            .bind(format_notification_record_rfc3339(cutoff))
-            .execute(&mut *tx)
-            .await?;
-    }
-    if max_rows > 0 {
+    sqlx::query("DELETE FROM restock_profiles")
+        .execute(&mut *tx)
+        .await?;
+    for (scope_kind, scope_key, profile_json) in profiles {
         sqlx::query(
-            r#"
-DELETE FROM notification_records
-WHERE id IN (
-  SELECT id
-  FROM (
-    SELECT
-      id,
-      ROW_NUMBER() OVER (
-        PARTITION BY user_id
-        ORDER BY created_at DESC, id DESC
-      ) AS row_num
-    FROM notification_records
-  ) ranked
-  WHERE row_num > ?
-)"#,
+            "INSERT INTO restock_profiles (scope_kind, scope_key, profile_json, computed_at) VALUES (?, ?, ?, ?)",
         )
-        .bind(max_rows)
+        .bind(scope_kind)
+        .bind(scope_key)
+        .bind(profile_json)
+        .bind(computed_at)
         .execute(&mut *tx)
         .await?;
     }
-
-    sqlx::query(
-        r#"
-DELETE FROM notification_record_items
-WHERE NOT EXISTS (
-  SELECT 1 FROM notification_records
-  WHERE notification_records.id = notification_record_items.record_id
-)"#,
-    )
-    .execute(&mut *tx)
-    .await?;
-
-    sqlx::query(
-        r#"
-DELETE FROM notification_record_deliveries
-WHERE NOT EXISTS (
-  SELECT 1 FROM notification_records
-  WHERE notification_records.id = notification_record_deliveries.record_id
-)"#,
-    )
-    .execute(&mut *tx)
-    .await?;
-
     tx.commit().await?;
     Ok(())
 }
 
-pub async fn insert_web_push_subscription(
-    db: &SqlitePool,
-    user_id: &str,
-    req: WebPushSubscribeRequest,
-) -> anyhow::Result<String> {
-    let id = Uuid::new_v4().to_string();
-    let now = now_rfc3339();
-    sqlx::query(
-        r#"INSERT INTO web_push_subscriptions (id, user_id, endpoint, p256dh, auth, created_at)
-           VALUES (?, ?, ?, ?, ?, ?)"#,
-    )
-    .bind(&id)
-    .bind(user_id)
-    .bind(req.subscription.endpoint)
-    .bind(req.subscription.keys.p256dh)
-    .bind(req.subscription.keys.auth)
-    .bind(now)
-    .execute(db)
-    .await?;
-    Ok(id)
-}
-
-pub async fn get_latest_web_push_subscription(
-    db: &SqlitePool,
-    user_id: &str,
-) -> anyhow::Result<Option<WebPushSubscription>> {
-    let row = sqlx::query(
-        r#"SELECT endpoint, p256dh, auth
-           FROM web_push_subscriptions
-           WHERE user_id = ?
-           ORDER BY created_at DESC, id DESC
-           LIMIT 1"#,
-    )
-    .bind(user_id)
-    .fetch_optional(db)
-    .await?;
-
-    Ok(row.map(|row| WebPushSubscription {
-        endpoint: row.get::<String, _>(0),
-        keys: WebPushKeys {
-            p256dh: row.get::<String, _>(1),
-            auth: row.get::<String, _>(2),
-        },
-    }))
-}
-
-pub async fn cleanup_logs(
+/// Stored profiles of one scope kind by key, as `(profile_json, computed_at)`.
+pub async fn list_restock_profiles(
     db: &SqlitePool,
-    retention_days: i64,
-    max_rows: i64,
-) -> anyhow::Result<()> {
-    if retention_days > 0 {
-        let cutoff = OffsetDateTime::now_utc()
-            .saturating_sub(time::Duration::days(retention_days))
-            .format(&Rfc3339)
-            .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
-        sqlx::query("DELETE FROM event_logs WHERE ts < ?")
-            .bind(cutoff)
-            .execute(db)
-            .await?;
+    scope_kind: &str,
+    scope_keys: &[String],
+) -> anyhow::Result<HashMap<String, (String, String)>> {
+    if scope_keys.is_empty() {
+        return Ok(HashMap::new());
     }
-    if max_rows > 0 {
-        // Delete oldest rows beyond max_rows.
-        sqlx::query(
-            r#"
-DELETE FROM event_logs
-WHERE id IN (
-  SELECT id FROM event_logs
-  ORDER BY ts DESC, id DESC
-  LIMIT -1 OFFSET ?
-)"#,
-        )
-        .bind(max_rows)
-        .execute(db)
-        .await?;
-    }
-    Ok(())
-}
-
-pub async fn cleanup_ops(db: &SqlitePool, retention_days: i64) -> anyhow::Result<()> {
-    if retention_days <= 0 {
-        return Ok(());
+    let placeholders = std::iter::repeat_n("?", scope_keys.len())
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        "SELECT scope_key, profile_json, computed_at FROM restock_profiles WHERE scope_kind = ? AND scope_key IN ({placeholders})"
+    );
+    let mut q = sqlx::query(&sql).bind(scope_kind);
+    for key in scope_keys {
+        q = q.bind(key);
     }
-
-    let cutoff = OffsetDateTime::now_utc()
-        .saturating_sub(time::Duration::days(retention_days))
-        .format(&Rfc3339)
-        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
-
-    sqlx::query("DELETE FROM ops_events WHERE ts < ?")
-        .bind(&cutoff)
-        .execute(db)
-        .await?;
-    sqlx::query("DELETE FROM ops_notify_runs WHERE ts < ?")
-        .bind(&cutoff)
-        .execute(db)
-        .await?;
-    sqlx::query(
-        r#"
-DELETE FROM ops_task_runs
-WHERE (
-  ended_at IS NOT NULL AND ended_at < ?
-) OR (
-  ended_at IS NULL AND started_at < ?
-)
-"#,
-    )
-    .bind(&cutoff)
-    .bind(&cutoff)
-    .execute(db)
-    .await?;
-
-    Ok(())
+    Ok(q.fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.get(0), (r.get(1), r.get(2))))
+        .collect())
 }
 
 pub async fn cleanup_inventory_samples_1m(
@@ -4370,6 +6454,7 @@ mod tests {
             &db,
             &[crate::upstream::ConfigBase {
                 id: "cfg-1".to_string(),
+                provider_id: "lazycat".to_string(),
                 country_id: "7".to_string(),
                 region_id: Some("40".to_string()),
                 name: "JP test".to_string(),
@@ -4433,6 +6518,7 @@ mod tests {
             &db,
             &[crate::upstream::ConfigBase {
                 id: "cfg-1".to_string(),
+                provider_id: "lazycat".to_string(),
                 country_id: "7".to_string(),
                 region_id: Some("40".to_string()),
                 name: "JP test".to_string(),
@@ -4460,9 +6546,13 @@ mod tests {
         replace_catalog_topology(&db, "https://example.invalid/cart", &[], &[])
             .await
             .unwrap();
-        retire_catalog_targets(&db, &[("7".to_string(), Some("40".to_string()))])
-            .await
-            .unwrap();
+        retire_catalog_targets(
+            &db,
+            crate::storefront::DEFAULT_PROVIDER_ID,
+            &[("7".to_string(), Some("40".to_string()))],
+        )
+        .await
+        .unwrap();
 
         assert!(!catalog_partition_exists(&db, "7", None).await.unwrap());
         assert!(!catalog_named_region_exists(&db, "7", "40").await.unwrap());
@@ -8,6 +8,7 @@ use crate::models::{
     LazycatAccountView, LazycatMachineAccessUrlResponse, LazycatMachinesResponse,
     LazycatTrafficSampleView, LazycatTrafficView,
 };
+use crate::proxy::{self, ProxyClass};
 use anyhow::{anyhow, Context};
 use futures_util::stream::{self, StreamExt};
 use reqwest::header::{COOKIE, LOCATION, SET_COOKIE};
@@ -213,6 +214,7 @@ struct LazycatService {
     site_client: reqwest::Client,
     panel_timeout_ms: i64,
     allow_invalid_tls: bool,
+    proxy: Option<String>,
 }
 
 impl LazycatService {
@@ -226,11 +228,17 @@ impl LazycatService {
         let site_client = reqwest::Client::builder()
             .user_agent("catnap/0.1 (+https://example.invalid)")
             .redirect(reqwest::redirect::Policy::none())
-            .timeout(std::time::Duration::from_millis(SITE_REQUEST_TIMEOUT_MS))
-            .build()?;
+            .timeout(std::time::Duration::from_millis(SITE_REQUEST_TIMEOUT_MS));
+        let site_client = proxy::apply_proxy(
+            site_client,
+            ProxyClass::Lazycat,
+            config.lazycat_proxy.as_deref(),
+        )?
+        .build()?;
         Ok(Self {
             base_url,
             site_client,
+            proxy: config.lazycat_proxy.clone(),
             panel_timeout_ms: config.lazycat_panel_timeout_ms,
             allow_invalid_tls: config.lazycat_allow_invalid_tls,
         })
@@ -698,7 +706,7 @@ impl LazycatService {
             if let Some(form_data) = form.clone() {
                 request = request.form(&form_data);
             }
-            let response = request.send().await?;
+            let response = request.send().await.map_err(|err| self.proxy_error(err))?;
             session.cookies.update_from_headers(response.headers());
 
             if response.status().is_redirection() {
@@ -756,21 +764,15 @@ impl LazycatService {
         panel_hash: &str,
         insecure: bool,
     ) -> anyhow::Result<Value> {
-        let client = reqwest::Client::builder()
-            .user_agent("catnap/0.1 (+https://example.invalid)")
-            .timeout(std::time::Duration::from_millis(
-                self.panel_timeout_ms.max(500) as u64,
-            ))
-            .danger_accept_invalid_certs(insecure)
-            .danger_accept_invalid_hostnames(insecure)
-            .build()?;
+        let client = self.panel_client(insecure)?;
 
         let response = client
             .get(endpoint_url.clone())
             .header("x-container-hash", panel_hash)
             .header("x-requested-with", "XMLHttpRequest")
             .send()
-            .await?;
+            .await
+            .map_err(|err| self.proxy_error(err))?;
         let body = response.text().await?;
         serde_json::from_str::<Value>(&body)
             .with_context(|| format!("invalid panel json response from {endpoint_url}"))
@@ -807,14 +809,7 @@ impl LazycatService {
         body: &Value,
         insecure: bool,
     ) -> anyhow::Result<Value> {
-        let client = reqwest::Client::builder()
-            .user_agent("catnap/0.1 (+https://example.invalid)")
-            .timeout(std::time::Duration::from_millis(
-                self.panel_timeout_ms.max(500) as u64,
-            ))
-            .danger_accept_invalid_certs(insecure)
-            .danger_accept_invalid_hostnames(insecure)
-            .build()?;
+        let client = self.panel_client(insecure)?;
 
         let response = client
             .post(endpoint_url.clone())
@@ -822,7 +817,8 @@ impl LazycatService {
             .header("x-requested-with", "XMLHttpRequest")
             .json(body)
             .send()
-            .await?;
+            .await
+            .map_err(|err| self.proxy_error(err))?;
         let body = response.text().await?;
         serde_json::from_str::<Value>(&body)
             .with_context(|| format!("invalid panel json response from {endpoint_url}"))
@@ -886,20 +882,29 @@ impl LazycatService {
         }
     }
 
-    async fn panel_request_text(&self, url: &Url, insecure: bool) -> anyhow::Result<String> {
-        let client = reqwest::Client::builder()
+    fn panel_client(&self, insecure: bool) -> anyhow::Result<reqwest::Client> {
+        let builder = reqwest::Client::builder()
             .user_agent("catnap/0.1 (+https://example.invalid)")
             .timeout(std::time::Duration::from_millis(
                 self.panel_timeout_ms.max(500) as u64,
             ))
             .danger_accept_invalid_certs(insecure)
-            .danger_accept_invalid_hostnames(insecure)
-            .build()?;
+            .danger_accept_invalid_hostnames(insecure);
+        Ok(proxy::apply_proxy(builder, ProxyClass::Lazycat, self.proxy.as_deref())?.build()?)
+    }
+
+    fn proxy_error(&self, err: reqwest::Error) -> anyhow::Error {
+        proxy::map_send_error(ProxyClass::Lazycat, self.proxy.as_deref(), err)
+    }
+
+    async fn panel_request_text(&self, url: &Url, insecure: bool) -> anyhow::Result<String> {
+        let client = self.panel_client(insecure)?;
 
         client
             .get(url.clone())
             .send()
-            .await?
+            .await
+            .map_err(|err| self.proxy_error(err))?
             .text()
             .await
             .map_err(Into::into)
@@ -1603,7 +1608,13 @@ pub async fn spawn_sync(state: AppState, user_id: String) -> anyhow::Result<bool
 fn spawn_sync_task(state: AppState, user_id: String, guard: SyncActiveGuard) {
     tokio::spawn(async move {
         let _guard = guard;
-        if let Err(err) = sync_user_inner(&state, &user_id).await {
+        let result = sync_user_inner(&state, &user_id).await;
+        crate::metrics::shared().record_lazycat_sync(
+            "account",
+            if result.is_ok() { "ok" } else { "error" },
+            1,
+        );
+        if let Err(err) = result {
             warn!(user_id, error = %err, "lazycat sync failed");
         }
     });
@@ -1802,6 +1813,9 @@ async fn sync_user_inner(state: &AppState, user_id: &str) -> anyhow::Result<()>
     if !sync_account_is_current(&state.db, user_id, &sync_generation).await? {
         return Ok(());
     }
+    let metrics = crate::metrics::shared();
+    metrics.record_lazycat_sync("panel", "ok", panel_summary.successful as u64);
+    metrics.record_lazycat_sync("panel", "error", panel_summary.failed as u64);
     apply_panel_sync_summary(&mut account, panel_summary, &current_timestamp_rfc3339());
     account.updated_at = current_timestamp_rfc3339();
     db::put_lazycat_account(&state.db, &account).await?;
@@ -3262,6 +3276,7 @@ mod tests {
                 .unwrap(),
             panel_timeout_ms: 5_000,
             allow_invalid_tls: true,
+            proxy: None,
         };
         let mut account = LazycatAccountRow {
             user_id: "u_1".to_string(),
@@ -3311,6 +3326,7 @@ mod tests {
                 .unwrap(),
             panel_timeout_ms: 5_000,
             allow_invalid_tls: true,
+            proxy: None,
         };
         let mut account = LazycatAccountRow {
             user_id: "u_1".to_string(),
@@ -3360,6 +3376,7 @@ mod tests {
                 .unwrap(),
             panel_timeout_ms: 5_000,
             allow_invalid_tls: true,
+            proxy: None,
         };
         let mut account = LazycatAccountRow {
             user_id: "u_1".to_string(),
@@ -3455,6 +3472,7 @@ mod tests {
                 .unwrap(),
             panel_timeout_ms: 5_000,
             allow_invalid_tls: true,
+            proxy: None,
         };
         let mut account = LazycatAccountRow {
             user_id: "u_1".to_string(),
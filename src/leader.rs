@@ -0,0 +1,303 @@
+use crate::config::RuntimeConfig;
+use crate::db;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::sync::watch;
+
+/// Lease guarding the poller, ops workers, LazyCat sync and cleanup.
+pub const LEASE_NAME: &str = "workers";
+
+/// Leadership as shown on the `#ops` page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderView {
+    /// Off when `CATNAP_LEADER_LEASE_SECONDS=0`; the instance then always leads.
+    pub enabled: bool,
+    pub instance_id: String,
+    pub leader: bool,
+    /// Instance holding the lease as of the last heartbeat.
+    pub holder: Option<String>,
+    pub acquired_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub lease_seconds: u64,
+    pub last_heartbeat_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeadershipChange {
+    Gained,
+    /// Renewal failed or another instance took over; `holder` is that instance when known.
+    Lost {
+        holder: Option<String>,
+    },
+}
+
+#[derive(Debug, Default)]
+struct LeaseState {
+    /// Local deadline of the lease, measured from before the renewal that granted it.
+    valid_until: Option<Instant>,
+    holder: Option<String>,
+    acquired_at: Option<String>,
+    expires_at: Option<String>,
+    last_heartbeat_at: Option<String>,
+    last_error: Option<String>,
+}
+
+struct Inner {
+    db: SqlitePool,
+    instance_id: String,
+    lease: Option<Duration>,
+    state: Mutex<LeaseState>,
+    leader_tx: watch::Sender<bool>,
+}
+
+/// This instance's claim on the database-wide leader lease. Followers keep serving the API and
+/// take over once the leader stops renewing.
+#[derive(Clone)]
+pub struct LeaderLease {
+    inner: Arc<Inner>,
+}
+
+impl LeaderLease {
+    pub fn new(cfg: &RuntimeConfig, db: SqlitePool) -> Self {
+        Self::with_lease(
+            db,
+            cfg.instance_id.clone().unwrap_or_else(default_instance_id),
+            (cfg.leader_lease_seconds > 0).then(|| Duration::from_secs(cfg.leader_lease_seconds)),
+        )
+    }
+
+    fn with_lease(db: SqlitePool, instance_id: String, lease: Option<Duration>) -> Self {
+        let (leader_tx, _) = watch::channel(lease.is_none());
+        Self {
+            inner: Arc::new(Inner {
+                db,
+                instance_id,
+                lease,
+                state: Mutex::new(LeaseState::default()),
+                leader_tx,
+            }),
+        }
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.inner.instance_id
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.inner.lease.is_some()
+    }
+
+    /// How often the lease is renewed: a third of its validity, so two renewals may fail in a
+    /// row before it lapses.
+    pub fn heartbeat_interval(&self) -> Duration {
+        self.inner
+            .lease
+            .map(|lease| (lease / 3).max(Duration::from_secs(1)))
+            .unwrap_or(Duration::from_secs(60))
+    }
+
+    /// Whether this instance may run background work right now. A leader that cannot renew
+    /// stops trusting its lease once it would have expired, even before a heartbeat notices.
+    pub fn is_leader(&self) -> bool {
+        if !self.enabled() {
+            return true;
+        }
+        let st = self
+            .inner
+            .state
+            .lock()
+            .expect("leader lease state poisoned");
+        st.valid_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Resolves once this instance leads.
+    pub async fn wait_leader(&self) {
+        let mut rx = self.inner.leader_tx.subscribe();
+        let _ = rx.wait_for(|leader| *leader).await;
+    }
+
+    /// Takes or renews the lease and reports whether leadership changed.
+    pub async fn heartbeat(&self, now: OffsetDateTime) -> Option<LeadershipChange> {
+        let lease = self.inner.lease?;
+        let was_leader = *self.inner.leader_tx.borrow();
+        let started = Instant::now();
+        let now = now.replace_nanosecond(0).unwrap_or(now);
+        let result = async {
+            let expires_at = (now + lease).format(&Rfc3339)?;
+            db::acquire_leader_lease(
+                &self.inner.db,
+                LEASE_NAME,
+                &self.inner.instance_id,
+                &now.format(&Rfc3339)?,
+                &expires_at,
+            )
+            .await
+        }
+        .await;
+
+        let (leader, holder) = {
+            let mut st = self
+                .inner
+                .state
+                .lock()
+                .expect("leader lease state poisoned");
+            st.last_heartbeat_at = now.format(&Rfc3339).ok();
+            match result {
+                Ok(row) => {
+                    let leader = row.holder == self.inner.instance_id;
+                    st.valid_until = leader.then_some(started + lease);
+                    st.holder = Some(row.holder);
+                    st.acquired_at = Some(row.acquired_at);
+                    st.expires_at = Some(row.expires_at);
+                    st.last_error = None;
+                }
+                Err(err) => {
+                    st.last_error = Some(err.to_string());
+                }
+            }
+            let leader = st.valid_until.is_some_and(|until| Instant::now() < until);
+            if !leader {
+                st.valid_until = None;
+            }
+            (leader, st.holder.clone())
+        };
+
+        if leader == was_leader {
+            return None;
+        }
+        self.inner.leader_tx.send_replace(leader);
+        Some(if leader {
+            LeadershipChange::Gained
+        } else {
+            LeadershipChange::Lost {
+                holder: holder.filter(|h| *h != self.inner.instance_id),
+            }
+        })
+    }
+
+    /// Gives the lease up on shutdown so a follower takes over right away.
+    pub async fn release(&self) -> anyhow::Result<()> {
+        if !self.enabled() {
+            return Ok(());
+        }
+        {
+            let mut st = self
+                .inner
+                .state
+                .lock()
+                .expect("leader lease state poisoned");
+            st.valid_until = None;
+        }
+        self.inner.leader_tx.send_replace(false);
+        db::release_leader_lease(&self.inner.db, LEASE_NAME, &self.inner.instance_id).await
+    }
+
+    pub fn view(&self) -> LeaderView {
+        let leader = self.is_leader();
+        let st = self
+            .inner
+            .state
+            .lock()
+            .expect("leader lease state poisoned");
+        LeaderView {
+            enabled: self.enabled(),
+            instance_id: self.inner.instance_id.clone(),
+            leader,
+            holder: if self.enabled() {
+                st.holder.clone()
+            } else {
+                Some(self.inner.instance_id.clone())
+            },
+            acquired_at: st.acquired_at.clone(),
+            expires_at: st.expires_at.clone(),
+            lease_seconds: self.inner.lease.map(|d| d.as_secs()).unwrap_or(0),
+            last_heartbeat_at: st.last_heartbeat_at.clone(),
+            last_error: st.last_error.clone(),
+        }
+    }
+}
+
+fn default_instance_id() -> String {
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "catnap".to_string());
+    format!("{host}-{}-{:04x}", std::process::id(), fastrand::u16(..))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use time::macros::datetime;
+
+    async fn test_db() -> SqlitePool {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        db::init_db(&db).await.unwrap();
+        db
+    }
+
+    fn lease(db: &SqlitePool, instance_id: &str) -> LeaderLease {
+        LeaderLease::with_lease(
+            db.clone(),
+            instance_id.to_string(),
+            Some(Duration::from_secs(30)),
+        )
+    }
+
+    #[tokio::test]
+    async fn one_instance_leads_until_it_releases_or_its_lease_expires() {
+        let db = test_db().await;
+        let (a, b) = (lease(&db, "a"), lease(&db, "b"));
+        let now = datetime!(2026-10-18 12:00 UTC);
+        assert!(!a.is_leader());
+        assert_eq!(a.heartbeat(now).await, Some(LeadershipChange::Gained));
+        assert!(a.is_leader());
+        assert_eq!(b.heartbeat(now).await, None);
+        assert!(!b.is_leader());
+        assert_eq!(b.view().holder.as_deref(), Some("a"));
+
+        // Renewing keeps the original acquisition time.
+        let renewed = now + time::Duration::seconds(10);
+        assert_eq!(a.heartbeat(renewed).await, None);
+        assert_eq!(
+            a.view().acquired_at.as_deref(),
+            Some("2026-10-18T12:00:00Z")
+        );
+        assert_eq!(a.view().expires_at.as_deref(), Some("2026-10-18T12:00:40Z"));
+
+        // `a` stopped renewing: `b` takes over once the lease has expired.
+        let expired = renewed + time::Duration::seconds(30);
+        assert_eq!(b.heartbeat(expired).await, Some(LeadershipChange::Gained));
+        assert_eq!(
+            a.heartbeat(expired).await,
+            Some(LeadershipChange::Lost {
+                holder: Some("b".to_string())
+            })
+        );
+        assert!(!a.is_leader());
+
+        b.release().await.unwrap();
+        assert!(!b.is_leader());
+        assert_eq!(a.heartbeat(expired).await, Some(LeadershipChange::Gained));
+    }
+
+    #[tokio::test]
+    async fn disabled_lease_always_leads() {
+        let solo = LeaderLease::with_lease(test_db().await, "solo".to_string(), None);
+        assert!(solo.is_leader());
+        assert_eq!(solo.heartbeat(OffsetDateTime::now_utc()).await, None);
+        solo.wait_leader().await;
+        assert!(solo.view().leader);
+    }
+}
@@ -5,13 +5,29 @@ pub mod config;
 pub mod db;
 pub mod defaults;
 pub mod lazycat;
+pub mod leader;
+pub mod metrics;
 pub mod models;
+pub mod monitoring_rules;
 mod notification_content;
 pub mod notifications;
 pub mod ops;
+pub mod ops_concurrency;
+pub mod ops_health;
+pub mod ops_retry;
+pub mod poll_schedule;
 pub mod poller;
+pub mod pricing;
+pub mod proxy;
+pub mod restock_forecast;
+pub mod specs;
+pub mod storefront;
+pub mod telemetry;
 pub mod update_check;
 pub mod upstream;
+pub mod upstream_archive;
+pub mod upstream_breaker;
+pub mod upstream_limiter;
 
 pub use app::{build_app, AppState};
 pub use config::RuntimeConfig;
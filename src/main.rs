@@ -1,18 +1,23 @@
 use catnap::{build_app, RuntimeConfig};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use std::{collections::HashSet, net::SocketAddr, str::FromStr, sync::Arc};
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    let mut config = RuntimeConfig::from_env();
+    let telemetry = catnap::telemetry::init(&config)?;
 
-    let config = RuntimeConfig::from_env();
+    if let Some(path) = config.exchange_rates_file.clone() {
+        let mut exchange_rates = catnap::pricing::ExchangeRates::from_config(&config);
+        match exchange_rates.load_file(&path) {
+            Ok(()) => config.exchange_rates = exchange_rates.rates,
+            Err(err) => warn!(error = %err, "exchange rate file ignored"),
+        }
+    }
+    catnap::upstream_limiter::shared()
+        .configure(config.upstream_rate_per_second, config.upstream_rate_burst);
+    catnap::proxy::ProxyConfig::from_config(&config).validate()?;
 
     let db_opts = SqliteConnectOptions::from_str(&config.db_url)?.create_if_missing(true);
     let db = SqlitePoolOptions::new()
@@ -40,6 +45,7 @@ async fn main() -> anyhow::Result<()> {
 
     catnap::poller::spawn(state.clone()).await;
 
+    let leader = state.ops.leader().clone();
     let app = build_app(state);
     let addr: SocketAddr = catnap::app::parse_socket_addr(&config.bind_addr)?;
 
@@ -59,6 +65,10 @@ async fn main() -> anyhow::Result<()> {
         .with_graceful_shutdown(shutdown_signal())
         .await?;
 
+    if let Err(err) = leader.release().await {
+        warn!(error = %err, "leader lease release failed");
+    }
+    telemetry.shutdown();
     Ok(())
 }
 
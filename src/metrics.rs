@@ -0,0 +1,407 @@
+use crate::app::AppState;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Response, StatusCode},
+};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds (seconds) of the task duration histogram.
+const TASK_DURATION_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+struct Counter {
+    name: &'static str,
+    help: &'static str,
+}
+
+const OPS_TASK_RUNS: Counter = Counter {
+    name: "catnap_ops_task_runs_total",
+    help: "Finished ops task runs by fetch action and result (ok or error code).",
+};
+const UPSTREAM_RESPONSES: Counter = Counter {
+    name: "catnap_upstream_http_responses_total",
+    help: "HTTP responses received from storefront cart pages by status code.",
+};
+const NOTIFICATION_DELIVERIES: Counter = Counter {
+    name: "catnap_notification_deliveries_total",
+    help: "Notification deliveries by channel and result.",
+};
+const LAZYCAT_SYNCS: Counter = Counter {
+    name: "catnap_lazycat_syncs_total",
+    help:
+        "LazyCat account syncs (kind=account) and per-machine panel syncs (kind=panel) by result.",
+};
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; TASK_DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Metric name -> rendered label set -> value.
+    counters: BTreeMap<&'static str, BTreeMap<String, u64>>,
+    /// `fetch_action` -> durations of finished runs.
+    task_durations: BTreeMap<String, Histogram>,
+}
+
+/// Counters for events that leave no cheaply queryable row behind. Gauges (queue depth, worker
+/// states, traffic usage) are read from live state at scrape time instead.
+#[derive(Default)]
+pub struct Metrics {
+    registry: Mutex<Registry>,
+}
+
+/// Registry shared by the ops workers, upstream clients, notification delivery and LazyCat sync.
+pub fn shared() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+impl Metrics {
+    fn inc(&self, counter: &Counter, labels: &[(&str, &str)], by: u64) {
+        let mut registry = self.registry.lock().unwrap_or_else(|err| err.into_inner());
+        *registry
+            .counters
+            .entry(counter.name)
+            .or_default()
+            .entry(render_labels(labels))
+            .or_insert(0) += by;
+    }
+
+    pub fn record_task_run(&self, fetch_action: &str, error_code: Option<&str>, elapsed: Duration) {
+        self.inc(
+            &OPS_TASK_RUNS,
+            &[
+                ("fetch_action", fetch_action),
+                ("result", error_code.unwrap_or("ok")),
+            ],
+            1,
+        );
+        let secs = elapsed.as_secs_f64();
+        let mut registry = self.registry.lock().unwrap_or_else(|err| err.into_inner());
+        let histogram = registry
+            .task_durations
+            .entry(fetch_action.to_string())
+            .or_default();
+        for (idx, bound) in TASK_DURATION_BUCKETS.iter().enumerate() {
+            if secs <= *bound {
+                histogram.buckets[idx] += 1;
+            }
+        }
+        histogram.sum += secs;
+        histogram.count += 1;
+    }
+
+    pub fn record_upstream_response(&self, provider_id: &str, status: u16) {
+        self.inc(
+            &UPSTREAM_RESPONSES,
+            &[("provider", provider_id), ("status", &status.to_string())],
+            1,
+        );
+    }
+
+    pub fn record_notification(&self, channel: &str, result: &str) {
+        self.inc(
+            &NOTIFICATION_DELIVERIES,
+            &[("channel", channel), ("result", result)],
+            1,
+        );
+    }
+
+    pub fn record_lazycat_sync(&self, kind: &str, result: &str, count: u64) {
+        if count > 0 {
+            self.inc(&LAZYCAT_SYNCS, &[("kind", kind), ("result", result)], count);
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        let registry = self.registry.lock().unwrap_or_else(|err| err.into_inner());
+        for counter in [
+            &OPS_TASK_RUNS,
+            &UPSTREAM_RESPONSES,
+            &NOTIFICATION_DELIVERIES,
+            &LAZYCAT_SYNCS,
+        ] {
+            write_header(out, counter.name, counter.help, "counter");
+            for (labels, value) in registry.counters.get(counter.name).into_iter().flatten() {
+                let _ = writeln!(out, "{}{labels} {value}", counter.name);
+            }
+        }
+
+        let name = "catnap_ops_task_duration_seconds";
+        write_header(
+            out,
+            name,
+            "Duration of finished ops task runs by fetch action.",
+            "histogram",
+        );
+        for (action, histogram) in &registry.task_durations {
+            for (bound, count) in TASK_DURATION_BUCKETS.iter().zip(histogram.buckets) {
+                let labels = render_labels(&[("fetch_action", action), ("le", &bound.to_string())]);
+                let _ = writeln!(out, "{name}_bucket{labels} {count}");
+            }
+            let labels = render_labels(&[("fetch_action", action), ("le", "+Inf")]);
+            let _ = writeln!(out, "{name}_bucket{labels} {}", histogram.count);
+            let labels = render_labels(&[("fetch_action", action)]);
+            let _ = writeln!(out, "{name}_sum{labels} {}", histogram.sum);
+            let _ = writeln!(out, "{name}_count{labels} {}", histogram.count);
+        }
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn write_sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    let _ = writeln!(out, "{name}{} {value}", render_labels(labels));
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let body = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{key}=\"{value}\"")
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{body}}}")
+}
+
+/// Compares in time independent of where the first mismatch is.
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// `GET /metrics` in the Prometheus text format. Authenticated by `CATNAP_METRICS_TOKEN` as a
+/// bearer token rather than the user header; answers 404 while no token is configured.
+pub async fn serve_metrics(State(state): State<AppState>, headers: HeaderMap) -> Response<Body> {
+    let Some(expected) = state.config.metrics_token.as_deref() else {
+        return plain(StatusCode::NOT_FOUND, "metrics disabled".to_string());
+    };
+    if !bearer_token(&headers).is_some_and(|provided| token_matches(expected, provided)) {
+        return plain(StatusCode::UNAUTHORIZED, "unauthorized".to_string());
+    }
+    match render_metrics(&state).await {
+        Ok(body) => plain(StatusCode::OK, body),
+        Err(err) => plain(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("render metrics failed: {err}"),
+        ),
+    }
+}
+
+fn plain(status: StatusCode, body: String) -> Response<Body> {
+    let mut res = Response::new(Body::from(body));
+    *res.status_mut() = status;
+    res.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+    );
+    res
+}
+
+async fn render_metrics(state: &AppState) -> anyhow::Result<String> {
+    let mut out = String::new();
+    let runtime = state.ops.runtime_view().await;
+
+    write_header(
+        &mut out,
+        "catnap_ops_queue_tasks",
+        "Tasks in the ops queue by priority lane and state.",
+        "gauge",
+    );
+    for lane in &runtime.queue.lanes {
+        for (task_state, value) in [("pending", lane.pending), ("running", lane.running)] {
+            write_sample(
+                &mut out,
+                "catnap_ops_queue_tasks",
+                &[("lane", &lane.lane), ("state", task_state)],
+                value as f64,
+            );
+        }
+    }
+    write_sample(
+        &mut out,
+        "catnap_ops_queue_tasks",
+        &[("lane", "all"), ("state", "retry_scheduled")],
+        runtime.queue.retry_scheduled as f64,
+    );
+    write_header(
+        &mut out,
+        "catnap_ops_queue_oldest_wait_seconds",
+        "Wait of the oldest queued task.",
+        "gauge",
+    );
+    write_sample(
+        &mut out,
+        "catnap_ops_queue_oldest_wait_seconds",
+        &[],
+        runtime.queue.oldest_wait_seconds.unwrap_or(0) as f64,
+    );
+    write_header(
+        &mut out,
+        "catnap_ops_queue_deduped_total",
+        "Enqueues merged into an already queued task.",
+        "counter",
+    );
+    write_sample(
+        &mut out,
+        "catnap_ops_queue_deduped_total",
+        &[],
+        runtime.queue.deduped as f64,
+    );
+
+    write_header(
+        &mut out,
+        "catnap_ops_workers",
+        "Ops workers by state.",
+        "gauge",
+    );
+    for worker_state in ["idle", "running", "error", "parked"] {
+        let count = runtime
+            .workers
+            .iter()
+            .filter(|worker| worker.state == worker_state)
+            .count();
+        write_sample(
+            &mut out,
+            "catnap_ops_workers",
+            &[("state", worker_state)],
+            count as f64,
+        );
+    }
+
+    let stats = state
+        .ops
+        .stats(crate::ops::OpsRange::H24, time::OffsetDateTime::now_utc())
+        .await?;
+    write_header(
+        &mut out,
+        "catnap_ops_cache_hit_ratio",
+        "Share of ops task runs answered from the URL cache over the last 24h.",
+        "gauge",
+    );
+    let collection = &stats.collection;
+    write_sample(
+        &mut out,
+        "catnap_ops_cache_hit_ratio",
+        &[],
+        if collection.total > 0 {
+            collection.cache_hits as f64 / collection.total as f64
+        } else {
+            0.0
+        },
+    );
+
+    shared().render(&mut out);
+
+    let traffic = crate::db::list_lazycat_machine_traffic(&state.db).await?;
+    for (name, help) in [
+        (
+            "catnap_lazycat_traffic_used_gb",
+            "Traffic used in the current cycle per LazyCat machine.",
+        ),
+        (
+            "catnap_lazycat_traffic_limit_gb",
+            "Traffic allowance per LazyCat machine.",
+        ),
+    ] {
+        write_header(&mut out, name, help, "gauge");
+        for machine in &traffic {
+            let value = if name.ends_with("used_gb") {
+                machine.used_gb
+            } else {
+                machine.limit_gb
+            };
+            let Some(value) = value else {
+                continue;
+            };
+            write_sample(
+                &mut out,
+                name,
+                &[
+                    ("user_id", &machine.user_id),
+                    ("service_id", &machine.service_id.to_string()),
+                    ("service_name", &machine.service_name),
+                ],
+                value,
+            );
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_histograms_in_text_format() {
+        let metrics = Metrics::default();
+        metrics.record_task_run("fetch", None, Duration::from_millis(300));
+        metrics.record_task_run("fetch", Some("upstream_5xx"), Duration::from_secs(3));
+        metrics.record_notification("telegram", "success");
+        metrics.record_lazycat_sync("panel", "error", 0);
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        assert!(out.contains("# TYPE catnap_ops_task_runs_total counter\n"));
+        assert!(
+            out.contains("catnap_ops_task_runs_total{fetch_action=\"fetch\",result=\"ok\"} 1\n")
+        );
+        assert!(out.contains(
+            "catnap_ops_task_runs_total{fetch_action=\"fetch\",result=\"upstream_5xx\"} 1\n"
+        ));
+        assert!(out.contains(
+            "catnap_ops_task_duration_seconds_bucket{fetch_action=\"fetch\",le=\"0.5\"} 1\n"
+        ));
+        assert!(out.contains(
+            "catnap_ops_task_duration_seconds_bucket{fetch_action=\"fetch\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(out.contains(
+            "catnap_notification_deliveries_total{channel=\"telegram\",result=\"success\"} 1\n"
+        ));
+        assert!(!out.contains("catnap_lazycat_syncs_total{"));
+        assert_eq!(
+            render_labels(&[("name", "a\"b\\c")]),
+            "{name=\"a\\\"b\\\\c\"}"
+        );
+    }
+
+    #[test]
+    fn token_comparison_requires_exact_match() {
+        assert!(token_matches("s3cret", "s3cret"));
+        assert!(!token_matches("s3cret", "s3cre"));
+        assert!(!token_matches("s3cret", "s3creT"));
+    }
+}
@@ -53,6 +53,26 @@ pub struct Spec {
     pub value: String,
 }
 
+/// Typed reading of the known `specs` keys (see `crate::specs`). Fields stay `None` when the
+/// shop does not list the spec or its value cannot be understood.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedSpecs {
+    pub cpu_cores: Option<f64>,
+    pub memory_mb: Option<i64>,
+    pub disk_gb: Option<f64>,
+    /// `ssd`, `nvme` or `hdd`.
+    pub disk_type: Option<String>,
+    /// Monthly traffic allowance; `None` together with `traffic_unlimited` means no cap.
+    pub traffic_gb: Option<f64>,
+    pub traffic_unlimited: bool,
+    pub bandwidth_mbps: Option<f64>,
+    pub ipv4_count: Option<i64>,
+    pub ipv6: Option<bool>,
+    /// `nat` or `dedicated`.
+    pub network_type: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Money {
@@ -61,6 +81,18 @@ pub struct Money {
     pub period: String,
 }
 
+/// One billing cycle offered on a config's configureproduct page; `price.period` is the cycle
+/// (`month`, `quarter`, `semiannual`, `year`, `biennial`, `triennial`).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingCycleOption {
+    pub price: Money,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_fee: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_price: Option<Money>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Inventory {
@@ -73,11 +105,19 @@ pub struct Inventory {
 #[serde(rename_all = "camelCase")]
 pub struct ConfigView {
     pub id: String,
+    pub provider_id: String,
     pub country_id: String,
     pub region_id: Option<String>,
     pub name: String,
     pub specs: Vec<Spec>,
+    pub normalized_specs: NormalizedSpecs,
     pub price: Money,
+    /// `price` per month in the configured base currency (see `crate::pricing`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_price: Option<Money>,
+    /// Scraped billing-cycle options; empty unless `CATNAP_UPSTREAM_BILLING_CYCLES_ENABLED`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub billing_cycles: Vec<BillingCycleOption>,
     pub inventory: Inventory,
     pub digest: String,
     pub lifecycle: ConfigLifecycleView,
@@ -387,6 +427,121 @@ pub struct MonitoringToggleResponse {
     pub enabled: bool,
 }
 
+/// A stored poll schedule (see `crate::poll_schedule`), scoped to a partition or to one config.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollScheduleView {
+    /// `partition` or `config`.
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_id: Option<String>,
+    pub utc_offset: String,
+    pub rules: Vec<crate::poll_schedule::PollScheduleRule>,
+    pub updated_at: String,
+}
+
+/// A monitoring rule (see `crate::monitoring_rules`), scoped to a partition or to one config.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringRuleView {
+    pub id: i64,
+    /// `partition` or `config`.
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_id: Option<String>,
+    pub name: String,
+    pub conditions: Vec<crate::monitoring_rules::RuleCondition>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringRulesResponse {
+    pub rules: Vec<MonitoringRuleView>,
+}
+
+/// Creates a rule on `configId`, or on the partition `countryId`/`regionId` when no config is
+/// given.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringRuleCreateRequest {
+    #[serde(default)]
+    pub config_id: Option<String>,
+    #[serde(default)]
+    pub country_id: Option<String>,
+    #[serde(default)]
+    pub region_id: Option<String>,
+    #[serde(flatten)]
+    pub rule: MonitoringRuleRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringRuleRequest {
+    pub name: String,
+    pub conditions: Vec<crate::monitoring_rules::RuleCondition>,
+    /// Defaults to enabled.
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+/// Planned fetch of one monitored partition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartitionPollPlanView {
+    pub country_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<String>,
+    pub config_ids: Vec<String>,
+    /// Configs compared on the next fetch; the rest are quiet then.
+    pub active_config_ids: Vec<String>,
+    pub last_polled_at: Option<String>,
+    /// Unset when every config stays quiet for the next week.
+    pub next_fetch_at: Option<String>,
+    pub every_minutes: Option<i64>,
+    /// Schedules that apply, e.g. `config:<id>`, `partition:<country>::<region>` or
+    /// `forecast:<country>::<region>`; `default` when some config follows the global interval.
+    pub schedule_scopes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollSchedulesResponse {
+    pub default_interval_minutes: i64,
+    pub schedules: Vec<PollScheduleView>,
+    pub partitions: Vec<PartitionPollPlanView>,
+}
+
+/// Replaces a partition schedule; a region-less partition covers the whole country. Empty `rules`
+/// removes the schedule.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollSchedulePartitionRequest {
+    pub country_id: String,
+    pub region_id: Option<String>,
+    #[serde(flatten)]
+    pub schedule: PollScheduleRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollScheduleRequest {
+    #[serde(default)]
+    pub utc_offset: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<crate::poll_schedule::PollScheduleRule>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitoringPartitionToggleRequest {
@@ -560,6 +715,47 @@ pub struct InventoryHistoryPoint {
     pub quantity: i64,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestockForecastRequest {
+    pub config_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestockWindowView {
+    pub start: String,
+    pub end: String,
+    /// Peak likelihood relative to a uniform spread over the week.
+    pub score: f64,
+}
+
+/// Learned restock pattern of one config or partition (see `crate::restock_forecast`). `profile`
+/// is unset until a restock has been seen; `windows` stay empty until there are enough.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestockForecastView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_id: Option<String>,
+    pub country_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<String>,
+    pub computed_at: Option<String>,
+    pub profile: Option<crate::restock_forecast::RestockProfile>,
+    pub windows: Vec<RestockWindowView>,
+    /// Last restock plus the median gap between restocks, when still ahead.
+    pub expected_by_gap_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestockForecastResponse {
+    pub generated_at: String,
+    pub horizon_hours: i64,
+    pub configs: Vec<RestockForecastView>,
+    pub partitions: Vec<RestockForecastView>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshStatusResponse {
@@ -597,6 +793,173 @@ pub struct LogsResponse {
     pub next_cursor: Option<String>,
 }
 
+/// A window during which a storefront's circuit breaker was open.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamOutageView {
+    pub id: i64,
+    pub provider_id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_seconds: Option<i64>,
+    pub failure_count: i64,
+    pub last_error: Option<String>,
+}
+
+/// An operator health rule breach (see `crate::ops_health`); open while `resolved_at` is unset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsHealthAlertView {
+    pub id: i64,
+    pub rule: String,
+    pub opened_at: String,
+    pub resolved_at: Option<String>,
+    pub value: f64,
+    pub threshold: f64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsHealthAlertsResponse {
+    pub items: Vec<OpsHealthAlertView>,
+    pub next_cursor: Option<String>,
+}
+
+/// Daily operations digest over `[from, to)`, as sent to the operator channels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestView {
+    pub from: String,
+    pub to: String,
+    pub tasks: OpsDigestTasksView,
+    /// Failed runs per error code, most frequent first.
+    pub failures: Vec<OpsDigestCountView>,
+    pub latency: OpsDigestLatencyView,
+    /// Delivery attempts per notification channel (`ops_notify_runs`).
+    pub notify: Vec<OpsDigestChannelView>,
+    /// Notification records created per kind.
+    pub notification_records: Vec<OpsDigestCountView>,
+    pub configs_added: Vec<OpsDigestConfigView>,
+    pub configs_removed: Vec<OpsDigestConfigView>,
+    pub topology: Vec<OpsDigestTopologyView>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestTasksView {
+    pub total: i64,
+    pub success: i64,
+    pub failure: i64,
+    pub cache_hits: i64,
+    pub not_modified: i64,
+    pub success_rate_pct: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestCountView {
+    pub key: String,
+    pub count: i64,
+}
+
+/// Upstream fetch latency, nearest-rank percentiles over runs that went to the upstream.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestLatencyView {
+    pub samples: i64,
+    pub p50_ms: Option<i64>,
+    pub p90_ms: Option<i64>,
+    pub p99_ms: Option<i64>,
+    pub max_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestChannelView {
+    pub channel: String,
+    pub success: i64,
+    pub error: i64,
+    pub skipped: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestConfigView {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsDigestTopologyView {
+    /// `region.added`, `region.removed`, `partition.added` or `partition.removed`.
+    pub kind: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsTaskRunView {
+    pub id: i64,
+    pub provider_id: String,
+    pub fid: String,
+    pub gid: Option<String>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    /// `None` while the run is still in progress.
+    pub ok: Option<bool>,
+    pub attempt: i64,
+    pub fetch_action: String,
+    pub freshness_window_seconds: Option<i64>,
+    pub reason_counts: std::collections::HashMap<String, i64>,
+    pub fetch_http_status: Option<i64>,
+    pub fetch_bytes: Option<i64>,
+    pub fetch_elapsed_ms: Option<i64>,
+    pub parse_produced_configs: Option<i64>,
+    pub parse_elapsed_ms: Option<i64>,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    pub notifications: Vec<OpsNotifyRunView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsNotifyRunView {
+    pub id: i64,
+    pub ts: String,
+    pub channel: String,
+    pub result: String,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsTaskRunsResponse {
+    pub items: Vec<OpsTaskRunView>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsCancelRequest {
+    pub keys: Vec<OpsCancelKey>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsCancelKey {
+    pub fid: String,
+    pub gid: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamOutagesResponse {
+    pub items: Vec<UpstreamOutageView>,
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotificationRecordItemView {
@@ -610,6 +973,8 @@ pub struct NotificationRecordItemView {
     pub name: String,
     pub specs: Vec<Spec>,
     pub price: Money,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_price: Option<Money>,
     pub inventory: Inventory,
     pub lifecycle: ConfigLifecycleView,
 }
@@ -629,6 +994,19 @@ pub struct NotificationRecordView {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub telegram_deliveries: Vec<NotificationRecordDeliveryView>,
     pub items: Vec<NotificationRecordItemView>,
+    /// Monitoring rule that let this alert through, as it read when it fired.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<NotificationMatchedRuleView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationMatchedRuleView {
+    pub rule_id: i64,
+    pub name: String,
+    pub scope: String,
+    pub scope_key: String,
+    pub conditions: Vec<crate::monitoring_rules::RuleCondition>,
 }
 
 #[derive(Debug, Clone)]
@@ -640,6 +1018,7 @@ pub struct NotificationRecordDraft {
     pub telegram_status: String,
     pub web_push_status: String,
     pub items: Vec<NotificationRecordItemView>,
+    pub matched_rule: Option<NotificationMatchedRuleView>,
 }
 
 #[derive(Debug, Serialize)]
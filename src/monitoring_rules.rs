@@ -0,0 +1,343 @@
+use crate::db;
+use crate::models::{Money, NotificationMatchedRuleView, Spec};
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+const MAX_CONDITIONS: usize = 8;
+const EPSILON: f64 = 1e-9;
+
+/// One condition of a monitoring rule as stored and exchanged over the API, tagged by `kind`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RuleCondition {
+    /// At least `min` in stock.
+    QuantityAtLeast { min: i64 },
+    /// Price of at most `amount`. `currency` and `period` default to the config's own; when
+    /// they differ both sides are compared per month in the base currency.
+    PriceAtMost {
+        amount: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        currency: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        period: Option<String>,
+    },
+    /// Price fell by at least `pct` percent since the previous check.
+    PriceDropPct { pct: f64 },
+    /// Some spec key or value contains `text`, ignoring case.
+    SpecsContain { text: String },
+}
+
+/// What a rule is checked against: one config at one point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleState<'a> {
+    pub quantity: i64,
+    pub price: &'a Money,
+    pub specs: &'a [Spec],
+    /// Table for comparing prices quoted in different currencies or periods.
+    pub rates: &'a crate::pricing::ExchangeRates,
+}
+
+impl RuleCondition {
+    fn validate(&self) -> anyhow::Result<()> {
+        match self {
+            Self::QuantityAtLeast { min } if *min < 0 => bail!("min must not be negative"),
+            Self::PriceAtMost {
+                amount,
+                currency,
+                period,
+            } => {
+                if !amount.is_finite() || *amount < 0.0 {
+                    bail!("amount must be a non-negative number");
+                }
+                if currency.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                    bail!("currency must not be empty");
+                }
+                if period
+                    .as_deref()
+                    .is_some_and(|v| crate::pricing::period_months(v).is_none())
+                {
+                    bail!("unknown period");
+                }
+            }
+            Self::PriceDropPct { pct } if !(pct.is_finite() && *pct > 0.0 && *pct <= 100.0) => {
+                bail!("pct must be in (0, 100]")
+            }
+            Self::SpecsContain { text } if text.trim().is_empty() => {
+                bail!("text must not be empty")
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// `previous` is the price at the check before `state`, when known.
+    fn holds(&self, previous: Option<&Money>, state: &RuleState<'_>) -> bool {
+        match self {
+            Self::QuantityAtLeast { min } => state.quantity >= *min,
+            Self::PriceAtMost {
+                amount,
+                currency,
+                period,
+            } => {
+                let threshold = Money {
+                    amount: *amount,
+                    currency: currency
+                        .clone()
+                        .unwrap_or_else(|| state.price.currency.clone()),
+                    period: period.clone().unwrap_or_else(|| state.price.period.clone()),
+                };
+                if same_unit(&threshold, state.price) {
+                    return state.price.amount <= threshold.amount + EPSILON;
+                }
+                match (
+                    state.rates.monthly_equivalent(state.price),
+                    state.rates.monthly_equivalent(&threshold),
+                ) {
+                    (Some(price), Some(threshold)) => price.amount <= threshold.amount + EPSILON,
+                    _ => false,
+                }
+            }
+            Self::PriceDropPct { pct } => previous.is_some_and(|old| {
+                same_unit(old, state.price)
+                    && old.amount > 0.0
+                    && (old.amount - state.price.amount) * 100.0 / old.amount >= pct - EPSILON
+            }),
+            Self::SpecsContain { text } => {
+                let needle = text.trim().to_lowercase();
+                state.specs.iter().any(|spec| {
+                    spec.key.to_lowercase().contains(&needle)
+                        || spec.value.to_lowercase().contains(&needle)
+                })
+            }
+        }
+    }
+}
+
+fn same_unit(a: &Money, b: &Money) -> bool {
+    a.currency.eq_ignore_ascii_case(&b.currency) && a.period == b.period
+}
+
+pub fn validate_conditions(conditions: &[RuleCondition]) -> anyhow::Result<()> {
+    if conditions.is_empty() || conditions.len() > MAX_CONDITIONS {
+        bail!("a rule needs 1 to {MAX_CONDITIONS} conditions");
+    }
+    for (idx, condition) in conditions.iter().enumerate() {
+        condition
+            .validate()
+            .map_err(|err| anyhow::anyhow!("condition {}: {err}", idx + 1))?;
+    }
+    Ok(())
+}
+
+/// A user's rule attached to one config (`scope` = `config`) or to a partition, where a
+/// region-less partition covers the whole country. All conditions must hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitoringRule {
+    pub id: i64,
+    pub scope: String,
+    pub scope_key: String,
+    pub name: String,
+    pub conditions: Vec<RuleCondition>,
+}
+
+impl MonitoringRule {
+    pub fn holds(&self, previous: Option<&Money>, state: &RuleState<'_>) -> bool {
+        !self.conditions.is_empty()
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.holds(previous, state))
+    }
+
+    /// Whether the change from `old` to `new` should alert: the rule holds now but did not
+    /// before, so a standing match alerts once rather than on every later change. Price drops
+    /// only exist across a change, which makes rules using them alert whenever they hold. A
+    /// config without a previous state (just listed) alerts when the rule holds.
+    pub fn fires(&self, old: Option<&RuleState<'_>>, new: &RuleState<'_>) -> bool {
+        match old {
+            Some(old) => self.holds(Some(old.price), new) && !self.holds(None, old),
+            None => self.holds(None, new),
+        }
+    }
+
+    pub fn matched_view(&self) -> NotificationMatchedRuleView {
+        NotificationMatchedRuleView {
+            rule_id: self.id,
+            name: self.name.clone(),
+            scope: self.scope.clone(),
+            scope_key: self.scope_key.clone(),
+            conditions: self.conditions.clone(),
+        }
+    }
+}
+
+/// What a user's rules make of one change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleVerdict<'a> {
+    /// No rules cover the config; every monitored change alerts as before.
+    NoRules,
+    /// First rule, in creation order, that fires.
+    Matched(&'a MonitoringRule),
+    /// Rules cover the config but none fires, so the change stays silent.
+    Suppressed,
+}
+
+pub fn evaluate<'a>(
+    rules: &'a [MonitoringRule],
+    old: Option<&RuleState<'_>>,
+    new: &RuleState<'_>,
+) -> RuleVerdict<'a> {
+    if rules.is_empty() {
+        return RuleVerdict::NoRules;
+    }
+    rules
+        .iter()
+        .find(|rule| rule.fires(old, new))
+        .map_or(RuleVerdict::Suppressed, RuleVerdict::Matched)
+}
+
+/// Enabled rules of `user_id` that cover `config_id`: its own, its region's and its country's.
+/// An empty result means the user alerts on every monitored change as before.
+pub async fn load_rules_for_config(
+    db: &SqlitePool,
+    user_id: &str,
+    config_id: &str,
+    country_id: &str,
+    region_id: Option<&str>,
+) -> anyhow::Result<Vec<MonitoringRule>> {
+    let mut partition_keys = vec![db::monitoring_partition_key(country_id, None)];
+    if region_id.is_some() {
+        partition_keys.push(db::monitoring_partition_key(country_id, region_id));
+    }
+    Ok(
+        db::list_applicable_monitoring_rules(db, user_id, config_id, &partition_keys)
+            .await?
+            .into_iter()
+            .filter_map(|row| {
+                let conditions = serde_json::from_str(&row.conditions_json).ok()?;
+                Some(MonitoringRule {
+                    id: row.id,
+                    scope: row.scope_kind,
+                    scope_key: row.scope_key,
+                    name: row.name,
+                    conditions,
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(amount: f64, period: &str) -> Money {
+        Money {
+            amount,
+            currency: "CNY".to_string(),
+            period: period.to_string(),
+        }
+    }
+
+    fn rule(conditions: Vec<RuleCondition>) -> MonitoringRule {
+        MonitoringRule {
+            id: 1,
+            scope: "config".to_string(),
+            scope_key: "lc:1".to_string(),
+            name: "test".to_string(),
+            conditions,
+        }
+    }
+
+    #[test]
+    fn conditions_parse_from_tagged_json_and_validate() {
+        let conditions: Vec<RuleCondition> = serde_json::from_value(serde_json::json!([
+            { "kind": "quantityAtLeast", "min": 3 },
+            { "kind": "priceAtMost", "amount": 25, "period": "month" },
+            { "kind": "priceDropPct", "pct": 10 },
+            { "kind": "specsContain", "text": "IPv4" }
+        ]))
+        .unwrap();
+        assert_eq!(conditions[0], RuleCondition::QuantityAtLeast { min: 3 });
+        assert!(validate_conditions(&conditions).is_ok());
+        assert!(validate_conditions(&[]).is_err());
+        assert!(validate_conditions(&[RuleCondition::PriceDropPct { pct: 0.0 }]).is_err());
+        assert!(validate_conditions(&[RuleCondition::PriceAtMost {
+            amount: 25.0,
+            currency: None,
+            period: Some("fortnight".to_string()),
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn rules_fire_on_the_change_that_makes_them_hold() {
+        let specs = vec![Spec {
+            key: "网络".to_string(),
+            value: "1 IPv4 / 1 IPv6".to_string(),
+        }];
+        let price = money(30.0, "month");
+        let rates = crate::pricing::ExchangeRates::default();
+        let state = |quantity| RuleState {
+            quantity,
+            price: &price,
+            specs: &specs,
+            rates: &rates,
+        };
+        let stock = rule(vec![
+            RuleCondition::QuantityAtLeast { min: 3 },
+            RuleCondition::SpecsContain {
+                text: "ipv4".to_string(),
+            },
+        ]);
+        assert!(stock.fires(Some(&state(1)), &state(3)));
+        assert!(!stock.fires(Some(&state(3)), &state(5)));
+        assert!(!stock.fires(Some(&state(5)), &state(2)));
+        assert!(stock.fires(None, &state(4)));
+
+        let cheaper = money(26.0, "month");
+        let dropped = RuleState {
+            quantity: 2,
+            price: &cheaper,
+            specs: &specs,
+            rates: &rates,
+        };
+        let drop = rule(vec![RuleCondition::PriceDropPct { pct: 10.0 }]);
+        assert!(drop.fires(Some(&state(2)), &dropped));
+        assert!(!drop.fires(Some(&dropped), &state(2)));
+        assert!(!drop.fires(None, &dropped));
+
+        let cap = rule(vec![RuleCondition::PriceAtMost {
+            amount: 27.0,
+            currency: None,
+            period: None,
+        }]);
+        assert!(cap.fires(Some(&state(2)), &dropped));
+        let yearly = money(300.0, "year");
+        let yearly_state = RuleState {
+            quantity: 2,
+            price: &yearly,
+            specs: &specs,
+            rates: &rates,
+        };
+        let monthly_cap = rule(vec![RuleCondition::PriceAtMost {
+            amount: 25.0,
+            currency: Some("CNY".to_string()),
+            period: Some("month".to_string()),
+        }]);
+        assert!(monthly_cap.holds(None, &yearly_state));
+        assert!(!monthly_cap.holds(None, &state(2)));
+
+        let rules = vec![drop.clone(), cap.clone()];
+        assert_eq!(evaluate(&[], None, &state(1)), RuleVerdict::NoRules);
+        assert_eq!(
+            evaluate(&rules, Some(&state(2)), &dropped),
+            RuleVerdict::Matched(&rules[0])
+        );
+        assert_eq!(
+            evaluate(&rules, Some(&dropped), &dropped),
+            RuleVerdict::Suppressed
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::Money;
+use crate::models::{BillingCycleOption, Money, NotificationMatchedRuleView, OpsDigestView};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,6 +17,8 @@ pub enum MonitorEventKind {
     Restock,
     Price,
     Config,
+    /// A monitoring rule fired on a change none of the above covers, e.g. stock rising 1 → 3.
+    Rule,
 }
 
 impl MonitorEventKind {
@@ -25,6 +27,7 @@ impl MonitorEventKind {
             Self::Restock => "restock",
             Self::Price => "price",
             Self::Config => "config",
+            Self::Rule => "rule",
         }
     }
 
@@ -33,6 +36,7 @@ impl MonitorEventKind {
             Self::Restock => "补货",
             Self::Price => "价格变动",
             Self::Config => "配置更新",
+            Self::Rule => "规则命中",
         }
     }
 }
@@ -99,6 +103,8 @@ pub struct MonitoringSnapshot<'a> {
     pub inventory_quantity: i64,
     pub price: &'a Money,
     pub digest: &'a str,
+    /// Scraped billing-cycle options; a changed amount on any cycle counts as a price change.
+    pub billing_cycles: &'a [BillingCycleOption],
 }
 
 pub struct MonitoringChangeNotification {
@@ -109,6 +115,7 @@ pub struct MonitoringChangeNotification {
     pub web_push_title: String,
     pub web_push_body: String,
     pub web_push_url: String,
+    pub matched_rule: Option<NotificationMatchedRuleView>,
 }
 
 pub fn build_monitoring_change_notification(
@@ -121,7 +128,12 @@ pub fn build_monitoring_change_notification(
     if old.inventory_quantity == 0 && new.inventory_quantity > 0 {
         events.push(MonitorEventKind::Restock);
     }
-    if (old.price.amount - new.price.amount).abs() > f64::EPSILON {
+    let main_price_changed = (old.price.amount - new.price.amount).abs() > f64::EPSILON;
+    let cycle_changes = changed_billing_cycles(old.billing_cycles, new.billing_cycles)
+        .into_iter()
+        .filter(|(_, cycle)| !(main_price_changed && cycle.period == new.price.period))
+        .collect::<Vec<_>>();
+    if main_price_changed || !cycle_changes.is_empty() {
         events.push(MonitorEventKind::Price);
     }
     if old.digest != new.digest {
@@ -149,10 +161,16 @@ pub fn build_monitoring_change_notification(
     }
 
     if events.contains(&MonitorEventKind::Price) {
-        summary.push(format!(
-            "价格 {}",
-            format_price_change(old.price, new.price)
-        ));
+        let mut changes = Vec::new();
+        if main_price_changed {
+            changes.push(format_price_change(old.price, new.price));
+        }
+        changes.extend(
+            cycle_changes
+                .iter()
+                .map(|(old_cycle, new_cycle)| format_price_change(old_cycle, new_cycle)),
+        );
+        summary.push(format!("价格 {}", changes.join("，")));
     } else if events.contains(&MonitorEventKind::Restock) {
         summary.push(format_money(new.price));
     }
@@ -209,9 +227,75 @@ pub fn build_monitoring_change_notification(
         web_push_title: format!("Catnap · {title}"),
         web_push_body,
         web_push_url: "/monitoring".to_string(),
+        matched_rule: None,
     })
 }
 
+/// Alert for a change only a monitoring rule asked for; the rule itself is added with
+/// [`attach_matched_rule`].
+pub fn build_rule_match_notification(
+    name: &str,
+    old: &MonitoringSnapshot<'_>,
+    new: &MonitoringSnapshot<'_>,
+    site_base_url: Option<&str>,
+) -> MonitoringChangeNotification {
+    let title = MonitorEventKind::Rule.label();
+    let quantity = if old.inventory_quantity == new.inventory_quantity {
+        format!("库存 {}", new.inventory_quantity)
+    } else {
+        format!(
+            "库存 {} → {}",
+            old.inventory_quantity, new.inventory_quantity
+        )
+    };
+    let detail = format!("{quantity}｜{}", format_money(new.price));
+    let mut lines = vec![format!("【{title}】{name}"), detail.clone()];
+    if let Some(url) = monitoring_url(site_base_url) {
+        lines.push(format!("查看监控：{url}"));
+    }
+    MonitoringChangeNotification {
+        events: vec![MonitorEventKind::Rule],
+        title: title.to_string(),
+        summary: format!("{name} · {detail}"),
+        telegram_text: lines.join("\n"),
+        web_push_title: format!("Catnap · {title}"),
+        web_push_body: format!("{name}｜{detail}"),
+        web_push_url: "/monitoring".to_string(),
+        matched_rule: None,
+    }
+}
+
+/// Records the rule that let `notification` through and names it in every channel.
+pub fn attach_matched_rule(
+    notification: &mut MonitoringChangeNotification,
+    rule: NotificationMatchedRuleView,
+) {
+    let line = format!("规则：{}", rule.name);
+    notification.telegram_text = insert_before_link(&notification.telegram_text, &line);
+    notification.summary = format!("{}｜{line}", notification.summary);
+    notification.web_push_body = format!("{}｜{line}", notification.web_push_body);
+    notification.matched_rule = Some(rule);
+}
+
+/// [`attach_matched_rule`] for lifecycle notifications, which keep the rule on the delivery.
+pub fn name_matched_rule(notification: &mut OutboundNotification, rule_name: &str) {
+    let line = format!("规则：{rule_name}");
+    notification.telegram_text = insert_before_link(&notification.telegram_text, &line);
+    notification.summary = format!("{}｜{line}", notification.summary);
+    notification.web_push_body = format!("{}｜{line}", notification.web_push_body);
+}
+
+/// Adds `line` above the trailing `查看…：` link line, if there is one.
+fn insert_before_link(text: &str, line: &str) -> String {
+    let mut lines = text.lines().collect::<Vec<_>>();
+    let at = match lines.last() {
+        Some(last) if last.starts_with("查看") && last.contains('：') => lines.len() - 1,
+        _ => lines.len(),
+    };
+    lines.insert(at, line);
+    lines.join("\n")
+}
+
 pub fn build_config_lifecycle_notification(
     kind: ConfigLifecycleNotificationKind,
     name: &str,
@@ -336,6 +420,137 @@ pub fn build_topology_notification(
     }
 }
 
+/// Names listed per digest section before the rest is summarized as a count.
+const DIGEST_LIST_LIMIT: usize = 10;
+
+pub fn build_ops_digest_notification(digest: &OpsDigestView) -> OutboundNotification {
+    let period = format!(
+        "{} → {}",
+        format_digest_time(&digest.from),
+        format_digest_time(&digest.to)
+    );
+    let tasks = &digest.tasks;
+    let summary = format!(
+        "任务 {} 次，成功率 {:.1}%，失败 {}",
+        tasks.total, tasks.success_rate_pct, tasks.failure
+    );
+
+    let mut telegram_lines = vec![format!("【每日运维摘要】{period}")];
+    telegram_lines.push(format!(
+        "任务：{} 次，成功 {}（{:.1}%），缓存命中 {}，未变更 {}",
+        tasks.total, tasks.success, tasks.success_rate_pct, tasks.cache_hits, tasks.not_modified
+    ));
+    if !digest.failures.is_empty() {
+        telegram_lines.push(format!(
+            "失败：{}",
+            digest
+                .failures
+                .iter()
+                .map(|f| format!("{} {}", f.key, f.count))
+                .collect::<Vec<_>>()
+                .join(" • ")
+        ));
+    }
+    let latency = &digest.latency;
+    if let (Some(p50), Some(p90), Some(p99)) = (latency.p50_ms, latency.p90_ms, latency.p99_ms) {
+        telegram_lines.push(format!(
+            "上游耗时：p50 {p50}ms • p90 {p90}ms • p99 {p99}ms（{} 次抓取）",
+            latency.samples
+        ));
+    }
+    if !digest.notify.is_empty() {
+        telegram_lines.push(format!(
+            "通知：{}",
+            digest
+                .notify
+                .iter()
+                .map(|c| format!(
+                    "{} 成功 {} / 失败 {} / 跳过 {}",
+                    c.channel, c.success, c.error, c.skipped
+                ))
+                .collect::<Vec<_>>()
+                .join(" • ")
+        ));
+    }
+    for (label, configs) in [
+        ("新增套餐", &digest.configs_added),
+        ("删除套餐", &digest.configs_removed),
+    ] {
+        if configs.is_empty() {
+            continue;
+        }
+        telegram_lines.push(format!(
+            "{label}（{}）：{}",
+            configs.len(),
+            digest_list(configs.iter().map(|c| c.name.as_str()), configs.len())
+        ));
+    }
+    if !digest.topology.is_empty() {
+        telegram_lines.push(format!(
+            "拓扑变化：{}",
+            digest_list(
+                digest.topology.iter().map(|t| format!(
+                    "{} {}",
+                    topology_kind_label(&t.kind),
+                    t.label
+                )),
+                digest.topology.len()
+            )
+        ));
+    }
+
+    let mut web_push_body = summary.clone();
+    let added = digest.configs_added.len();
+    let removed = digest.configs_removed.len();
+    if added + removed > 0 {
+        web_push_body.push_str(&format!("｜套餐 +{added} / -{removed}"));
+    }
+
+    OutboundNotification {
+        title: "每日运维摘要".to_string(),
+        summary,
+        partition_label: None,
+        telegram_text: telegram_lines.join("\n"),
+        web_push_title: "Catnap · 每日运维摘要".to_string(),
+        web_push_body,
+        web_push_url: "/#ops".to_string(),
+    }
+}
+
+fn format_digest_time(ts: &str) -> String {
+    OffsetDateTime::parse(ts, &Rfc3339)
+        .ok()
+        .and_then(|dt| {
+            dt.format(time::macros::format_description!(
+                "[year]-[month]-[day] [hour]:[minute] UTC"
+            ))
+            .ok()
+        })
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn digest_list<S: AsRef<str>>(items: impl Iterator<Item = S>, total: usize) -> String {
+    let mut shown = items
+        .take(DIGEST_LIST_LIMIT)
+        .map(|item| item.as_ref().to_string())
+        .collect::<Vec<_>>()
+        .join("、");
+    if total > DIGEST_LIST_LIMIT {
+        shown.push_str(&format!(" 等 {total} 项"));
+    }
+    shown
+}
+
+fn topology_kind_label(kind: &str) -> &str {
+    match kind {
+        "region.added" => TopologyNotificationKind::RegionAdded.label(),
+        "region.removed" => TopologyNotificationKind::RegionRemoved.label(),
+        "partition.added" => TopologyNotificationKind::PartitionAdded.label(),
+        "partition.removed" => TopologyNotificationKind::PartitionRemoved.label(),
+        other => other,
+    }
+}
+
 pub fn build_telegram_test_text(text_override: Option<&str>, now: OffsetDateTime) -> String {
     if let Some(text) = text_override.filter(|value| !value.trim().is_empty()) {
         return text.to_string();
@@ -395,12 +610,37 @@ pub fn append_notification_record_link(
     )
 }
 
+/// Cycles present on both sides whose price moved, as `(old, new)` in the new page order.
+fn changed_billing_cycles<'a>(
+    old: &'a [BillingCycleOption],
+    new: &'a [BillingCycleOption],
+) -> Vec<(&'a Money, &'a Money)> {
+    new.iter()
+        .filter_map(|new_cycle| {
+            let old_cycle = old
+                .iter()
+                .find(|old_cycle| old_cycle.price.period == new_cycle.price.period)?;
+            let changed = (old_cycle.price.amount - new_cycle.price.amount).abs() > f64::EPSILON
+                || old_cycle.price.currency != new_cycle.price.currency;
+            changed.then_some((&old_cycle.price, &new_cycle.price))
+        })
+        .collect()
+}
+
+fn period_label(period: &str) -> &str {
+    match period {
+        "month" => "月",
+        "quarter" => "季",
+        "semiannual" => "半年",
+        "year" => "年",
+        "biennial" => "两年",
+        "triennial" => "三年",
+        other => other,
+    }
+}
+
 pub fn format_money(money: &Money) -> String {
-    let period_label = match money.period.as_str() {
-        "month" => "月".to_string(),
-        "year" => "年".to_string(),
-        _ => money.period.clone(),
-    };
+    let period_label = period_label(&money.period);
 
     if money.currency == "CNY" {
         return format!("¥{:.2} / {period_label}", money.amount);
@@ -412,11 +652,7 @@ pub fn format_money(money: &Money) -> String {
 fn format_price_change(old: &Money, new: &Money) -> String {
     if old.currency == new.currency && old.period == new.period {
         if old.currency == "CNY" {
-            let period_label = match new.period.as_str() {
-                "month" => "月",
-                "year" => "年",
-                other => other,
-            };
+            let period_label = period_label(&new.period);
             return format!("¥{:.2} → ¥{:.2} / {period_label}", old.amount, new.amount);
         }
 
@@ -471,6 +707,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn builds_ops_digest_with_each_section() {
+        use crate::models::*;
+        let digest = OpsDigestView {
+            from: "2026-03-10T01:00:00Z".to_string(),
+            to: "2026-03-11T01:00:00Z".to_string(),
+            tasks: OpsDigestTasksView {
+                total: 40,
+                success: 36,
+                failure: 4,
+                cache_hits: 10,
+                not_modified: 5,
+                success_rate_pct: 90.0,
+            },
+            failures: vec![OpsDigestCountView {
+                key: "upstream_timeout".to_string(),
+                count: 4,
+            }],
+            latency: OpsDigestLatencyView {
+                samples: 25,
+                p50_ms: Some(800),
+                p90_ms: Some(2100),
+                p99_ms: Some(4000),
+                max_ms: Some(4200),
+            },
+            notify: vec![OpsDigestChannelView {
+                channel: "telegram".to_string(),
+                success: 3,
+                error: 1,
+                skipped: 0,
+            }],
+            notification_records: Vec::new(),
+            configs_added: vec![OpsDigestConfigView {
+                id: "cfg_1".to_string(),
+                name: "芬兰 Mini".to_string(),
+            }],
+            configs_removed: Vec::new(),
+            topology: vec![OpsDigestTopologyView {
+                kind: "partition.added".to_string(),
+                label: "日本 / 东京".to_string(),
+            }],
+        };
+
+        let notification = build_ops_digest_notification(&digest);
+        assert_eq!(
+            notification.telegram_text,
+            "【每日运维摘要】2026-03-10 01:00 UTC → 2026-03-11 01:00 UTC\n任务：40 次，成功 36（90.0%），缓存命中 10，未变更 5\n失败：upstream_timeout 4\n上游耗时：p50 800ms • p90 2100ms • p99 4000ms（25 次抓取）\n通知：telegram 成功 3 / 失败 1 / 跳过 0\n新增套餐（1）：芬兰 Mini\n拓扑变化：新可用区 日本 / 东京"
+        );
+        assert_eq!(
+            notification.web_push_body,
+            "任务 40 次，成功率 90.0%，失败 4｜套餐 +1 / -0"
+        );
+    }
+
     #[test]
     fn formats_cny_period_labels_like_ui() {
         assert_eq!(format_money(&money(4.99, "CNY", "year")), "¥4.99 / 年");
@@ -485,11 +775,13 @@ mod tests {
             inventory_quantity: 0,
             price: &old_price,
             digest: "old",
+            billing_cycles: &[],
         };
         let new = MonitoringSnapshot {
             inventory_quantity: 0,
             price: &new_price,
             digest: "new",
+            billing_cycles: &[],
         };
 
         let notification = build_monitoring_change_notification(
@@ -515,11 +807,13 @@ mod tests {
             inventory_quantity: 0,
             price: &old_price,
             digest: "same",
+            billing_cycles: &[],
         };
         let new = MonitoringSnapshot {
             inventory_quantity: 3,
             price: &new_price,
             digest: "same",
+            billing_cycles: &[],
         };
 
         let notification =
@@ -536,6 +830,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn billing_cycle_price_change_triggers_price_event() {
+        let price = money(9.34, "CNY", "month");
+        let cycle = |amount: f64, period: &str| BillingCycleOption {
+            price: money(amount, "CNY", period),
+            setup_fee: None,
+            monthly_price: None,
+        };
+        let old_cycles = [cycle(9.34, "month"), cycle(27.0, "quarter")];
+        let new_cycles = [cycle(9.34, "month"), cycle(25.0, "quarter")];
+        let old = MonitoringSnapshot {
+            inventory_quantity: 2,
+            price: &price,
+            digest: "same",
+            billing_cycles: &old_cycles,
+        };
+        let new = MonitoringSnapshot {
+            inventory_quantity: 2,
+            price: &price,
+            digest: "same",
+            billing_cycles: &new_cycles,
+        };
+
+        let notification = build_monitoring_change_notification("芬兰 Mini", &old, &new, None)
+            .expect("notification should exist");
+
+        assert_eq!(notification.events, vec![MonitorEventKind::Price]);
+        assert_eq!(
+            notification.telegram_text,
+            "【价格变动】芬兰 Mini\n价格 ¥27.00 → ¥25.00 / 季｜库存 2"
+        );
+
+        let unchanged = MonitoringSnapshot {
+            billing_cycles: &old_cycles[..1],
+            ..new
+        };
+        assert!(
+            build_monitoring_change_notification("芬兰 Mini", &old, &unchanged, None).is_none()
+        );
+    }
+
+    #[test]
+    fn rule_match_notification_names_the_rule_above_the_link() {
+        let price = money(9.34, "CNY", "month");
+        let old = MonitoringSnapshot {
+            inventory_quantity: 1,
+            price: &price,
+            digest: "same",
+            billing_cycles: &[],
+        };
+        let new = MonitoringSnapshot {
+            inventory_quantity: 3,
+            ..old
+        };
+        let mut notification =
+            build_rule_match_notification("芬兰 Mini", &old, &new, Some("https://catnap.example"));
+        attach_matched_rule(
+            &mut notification,
+            NotificationMatchedRuleView {
+                rule_id: 7,
+                name: "至少 3 台".to_string(),
+                scope: "config".to_string(),
+                scope_key: "lc:1".to_string(),
+                conditions: Vec::new(),
+            },
+        );
+
+        assert_eq!(notification.events, vec![MonitorEventKind::Rule]);
+        assert_eq!(
+            notification.telegram_text,
+            "【规则命中】芬兰 Mini\n库存 1 → 3｜¥9.34 / 月\n规则：至少 3 台\n查看监控：https://catnap.example/monitoring"
+        );
+        assert_eq!(
+            notification.summary,
+            "芬兰 Mini · 库存 1 → 3｜¥9.34 / 月｜规则：至少 3 台"
+        );
+        assert_eq!(notification.matched_rule.map(|rule| rule.rule_id), Some(7));
+    }
+
     #[test]
     fn builds_config_added_notification_for_products_page() {
         let notification = build_config_lifecycle_notification(
@@ -1,4 +1,6 @@
+use crate::config::RuntimeConfig;
 use crate::models::NotificationRecordDeliveryView;
+use crate::proxy::{self, ProxyClass};
 use anyhow::anyhow;
 use serde::Deserialize;
 use std::net::IpAddr;
@@ -19,17 +21,19 @@ struct TelegramErrorParameters {
     retry_after: Option<i64>,
 }
 
+/// Sends through `cfg.telegram_api_base_url`, via `CATNAP_TELEGRAM_PROXY` when set.
 pub async fn send_telegram(
-    api_base_url: &str,
+    cfg: &RuntimeConfig,
     token: &str,
     chat_id: &str,
     text: &str,
 ) -> anyhow::Result<()> {
-    let api_base_url = api_base_url.trim_end_matches('/');
+    let api_base_url = cfg.telegram_api_base_url.trim_end_matches('/');
     let url = format!("{api_base_url}/bot{token}/sendMessage");
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
+    let proxy_url = cfg.telegram_proxy.clone();
+    let builder = reqwest::Client::builder().timeout(Duration::from_secs(10));
+    let client = proxy::apply_proxy(builder, ProxyClass::Telegram, proxy_url.as_deref())?
         .build()
         .map_err(|_| anyhow!("telegram client init failed"))?;
     let res = client
@@ -41,7 +45,16 @@ pub async fn send_telegram(
         }))
         .send()
         .await
-        .map_err(|_| anyhow!("telegram request failed"))?;
+        .map_err(|err| {
+            // The request URL carries the bot token, so only proxy failures keep their detail.
+            let err = proxy::map_send_error(ProxyClass::Telegram, proxy_url.as_deref(), err);
+            if err.is::<proxy::ProxyError>() {
+                err
+            } else {
+                anyhow!("telegram request failed")
+            }
+        })?;
+    proxy::check_proxy_status(ProxyClass::Telegram, proxy_url.as_deref(), res.status())?;
 
     if !res.status().is_success() {
         let status = res.status();
@@ -54,14 +67,14 @@ pub async fn send_telegram(
 }
 
 pub async fn send_telegram_to_targets(
-    api_base_url: &str,
+    cfg: &RuntimeConfig,
     token: &str,
     targets: &[String],
     text: &str,
 ) -> Vec<NotificationRecordDeliveryView> {
     let mut deliveries = Vec::with_capacity(targets.len());
     for target in targets {
-        let result = match send_telegram(api_base_url, token, target, text).await {
+        let result = match send_telegram(cfg, token, target, text).await {
             Ok(()) => NotificationRecordDeliveryView {
                 channel: "telegram".to_string(),
                 target: target.clone(),
@@ -1,22 +1,44 @@
 use crate::config::RuntimeConfig;
-use crate::models::{Money, NotificationRecordDeliveryView, NotificationRecordItemView};
+use crate::leader::{LeaderLease, LeadershipChange};
+use crate::models::{
+    Money, NotificationRecordDeliveryView, NotificationRecordItemView, OpsDigestView,
+    OpsHealthAlertView,
+};
+use crate::monitoring_rules::{MonitoringRule, RuleState, RuleVerdict};
 use crate::notification_content::{
     self, ConfigLifecycleNotificationKind, TopologyNotificationKind,
 };
 use crate::notifications;
+use crate::ops_concurrency::{ConcurrencyChange, ConcurrencyController, FetchSample};
+use crate::ops_health::{plan_transitions, HealthRules, HealthSample, HealthTransition};
+use crate::ops_retry::OpsRetryPolicies;
 use crate::upstream::{catalog_region_key, CatalogSnapshot, UpstreamClient};
+use crate::upstream_breaker::{
+    Admission, CircuitState, FetchOutcome, Transition, UpstreamCircuitBreaker,
+};
 use serde::{Deserialize, Serialize};
 use sqlx::{Row, SqlitePool};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
-use tokio::sync::{broadcast, oneshot, Mutex, Notify, RwLock};
-use tracing::warn;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, Notify, RwLock};
+use tracing::{warn, Instrument};
 
 const POLLER_FRESHNESS_WINDOW_SECONDS: i64 = 45;
 const DISCOVERY_FRESHNESS_WINDOW_SECONDS: i64 = 150;
 const MANUAL_REFRESH_FRESHNESS_WINDOW_SECONDS: i64 = 5 * 60;
 const AUTO_REFRESH_FRESHNESS_WINDOW_SECONDS: i64 = 5 * 60;
+/// Hot-mode runs exist to see fresh stock, so they cap whatever window other callers allow.
+const HOT_MODE_FRESHNESS_WINDOW_SECONDS: i64 = 5;
+const OPERATOR_ALERT_COOLDOWN_SECONDS: i64 = 60 * 60;
+/// Callers beyond this many share a task run without adding more span links.
+const MAX_TRACE_ORIGINS: usize = 16;
+/// How often the leader takes tasks followers forwarded, and followers check for the outcome.
+const OPS_REQUEST_POLL: std::time::Duration = std::time::Duration::from_millis(500);
+/// How long a follower waits for the outcome of a task it forwarded before giving up on it.
+const OPS_FORWARDED_TASK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+/// How long a follower waits for the leader to carry out a forwarded admin action.
+const OPS_ADMIN_FORWARD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 fn reason_freshness_window_seconds(reason: &str) -> Option<i64> {
     match reason {
@@ -29,17 +51,31 @@ fn reason_freshness_window_seconds(reason: &str) -> Option<i64> {
 }
 
 fn task_freshness_window_seconds(reason_counts: &HashMap<String, i64>) -> Option<i64> {
-    reason_counts
+    let window = reason_counts
         .keys()
         .filter_map(|reason| reason_freshness_window_seconds(reason))
-        .max()
+        .max();
+    if reason_counts.contains_key("hot_mode") {
+        return Some(window.map_or(HOT_MODE_FRESHNESS_WINDOW_SECONDS, |w| {
+            w.min(HOT_MODE_FRESHNESS_WINDOW_SECONDS)
+        }));
+    }
+    window
+}
+
+/// Poller-originated work that the upstream circuit breaker may hold back during an outage.
+fn pausable_by_breaker(reason_counts: &HashMap<String, i64>) -> bool {
+    !reason_counts.is_empty()
+        && reason_counts
+            .keys()
+            .all(|reason| matches!(reason.as_str(), "poller_due" | "discovery_due" | "hot_mode"))
 }
 
 fn should_emit_lifecycle_notify(reason_counts: &HashMap<String, i64>) -> bool {
     reason_counts.keys().any(|reason| {
         matches!(
             reason.as_str(),
-            "manual_refresh" | "auto_refresh" | "poller_due" | "discovery_due"
+            "manual_refresh" | "auto_refresh" | "poller_due" | "discovery_due" | "hot_mode"
         )
     })
 }
@@ -106,35 +142,110 @@ impl OpsRange {
     }
 }
 
+/// `fid` is the provider-scoped country id (see [`crate::storefront::scoped_country_id`]) of a
+/// partition of the `provider_id` storefront.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct TaskKey {
+    provider_id: String,
     fid: String,
     gid: Option<String>,
 }
 
 impl TaskKey {
+    /// Key of the partition behind a provider-scoped country id.
+    fn scoped(fid: &str, gid: Option<String>) -> Self {
+        Self {
+            provider_id: crate::storefront::split_country_id(fid).0.to_string(),
+            fid: fid.to_string(),
+            gid,
+        }
+    }
+
+    fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    fn source_fid(&self) -> &str {
+        crate::storefront::split_country_id(&self.fid).1
+    }
+
     fn to_view(&self) -> OpsTaskKeyView {
         OpsTaskKeyView {
+            provider_id: self.provider_id().to_string(),
             fid: self.fid.clone(),
             gid: self.gid.clone(),
         }
     }
 }
 
+/// Priority lanes of the task queue, highest first. The lane comes from the `reason` a task was
+/// enqueued with; a deduped enqueue with a more urgent reason moves a pending task up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum OpsLane {
+    Interactive,
+    Poller,
+    Discovery,
+}
+
+impl OpsLane {
+    const ALL: [Self; 3] = [Self::Interactive, Self::Poller, Self::Discovery];
+
+    fn for_reason(reason: &str) -> Self {
+        match reason {
+            "poller_due" | "hot_mode" => Self::Poller,
+            "discovery_due" => Self::Discovery,
+            _ => Self::Interactive,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Poller => "poller",
+            Self::Discovery => "discovery",
+        }
+    }
+}
+
 #[derive(Debug)]
 struct TaskEntry {
     state: TaskEntryState,
+    lane: OpsLane,
+    queued_at: std::time::Instant,
     enqueued_at: String,
     reason_counts: HashMap<String, i64>,
     force_fetch: bool,
     joiners: Vec<oneshot::Sender<OpsRunOutcome>>,
     poller_waiter_user_ids: HashSet<String>,
+    /// 1-based number of the next (or current) run; bumped for each scheduled retry.
+    attempt: u32,
+    /// Span contexts of the callers that enqueued this task; the run span is parented on the
+    /// first and linked to the rest.
+    trace_origins: Vec<opentelemetry::trace::SpanContext>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum TaskEntryState {
     Pending,
-    Running { run_id: i64, started_at: String },
+    Running {
+        run_id: i64,
+        started_at: String,
+    },
+    /// Failed with a retryable error; kept out of the lanes until `due`.
+    RetryScheduled {
+        due: std::time::Instant,
+        retry_at: String,
+    },
+}
+
+impl TaskEntryState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running { .. } => "running",
+            Self::RetryScheduled { .. } => "retry_scheduled",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -151,6 +262,19 @@ enum WorkerState {
     Idle,
     Running,
     Error,
+    /// Above the current concurrency limit; takes no tasks until the limit grows.
+    Parked,
+}
+
+impl WorkerState {
+    fn as_str(self) -> &'static str {
+        match self {
+            WorkerState::Idle => "idle",
+            WorkerState::Running => "running",
+            WorkerState::Error => "error",
+            WorkerState::Parked => "parked",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -162,9 +286,219 @@ struct WorkerError {
 #[derive(Debug)]
 struct RuntimeState {
     deduped: i64,
-    pending: VecDeque<TaskKey>,
+    /// Pending keys per [`OpsLane`], FIFO within a lane.
+    lanes: [VecDeque<TaskKey>; 3],
     tasks: HashMap<TaskKey, TaskEntry>,
     workers: Vec<WorkerRuntime>,
+    /// Whether changes are mirrored into `ops_queue_tasks`. Off until the queue a previous
+    /// process left there has been restored, so early enqueues cannot overwrite it.
+    persisting: bool,
+    /// Operator pause/drain state, mirrored into `ops_control_state`.
+    control: OpsControlView,
+    /// Workers with an index at or above `concurrency.limit()` park.
+    concurrency: ConcurrencyController,
+    last_concurrency_change: Option<OpsConcurrencyChangeView>,
+    /// Partitions in hot mode, re-fetched every `hot_mode_interval_seconds` until they expire.
+    hot: HashMap<TaskKey, HotWindow>,
+}
+
+#[derive(Debug, Clone)]
+struct HotWindow {
+    until: OffsetDateTime,
+    view: OpsHotModeView,
+}
+
+impl RuntimeState {
+    /// Takes the next pending task. Each full `starvation` period a task has waited lifts it one
+    /// lane, so the pick is the lane head with the best aged lane, oldest first on ties.
+    fn pop_next(
+        &mut self,
+        now: std::time::Instant,
+        starvation: std::time::Duration,
+    ) -> Option<TaskKey> {
+        self.promote_due_retries(now);
+        let starvation_secs = starvation.as_secs().max(1);
+        let (lane_idx, _) = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, lane)| {
+                let queued_at = self.tasks.get(lane.front()?)?.queued_at;
+                let waited = now.saturating_duration_since(queued_at);
+                let aged = idx.saturating_sub((waited.as_secs() / starvation_secs) as usize);
+                Some((idx, (aged, std::cmp::Reverse(waited), idx)))
+            })
+            .min_by_key(|(_, rank)| *rank)?;
+        self.lanes[lane_idx].pop_front()
+    }
+
+    /// Moves scheduled retries whose backoff has elapsed back into their lane.
+    fn promote_due_retries(&mut self, now: std::time::Instant) {
+        let mut due = self
+            .tasks
+            .iter_mut()
+            .filter_map(|(key, entry)| match entry.state {
+                TaskEntryState::RetryScheduled { due, .. } if due <= now => {
+                    entry.state = TaskEntryState::Pending;
+                    entry.queued_at = due;
+                    Some((due, entry.lane, key.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(due, _, _)| *due);
+        for (_, lane, key) in due {
+            self.lanes[lane as usize].push_back(key);
+        }
+    }
+
+    fn next_retry_due(&self) -> Option<std::time::Instant> {
+        self.tasks
+            .values()
+            .filter_map(|entry| match entry.state {
+                TaskEntryState::RetryScheduled { due, .. } => Some(due),
+                _ => None,
+            })
+            .min()
+    }
+
+    fn workers_view(&self) -> Vec<OpsWorkerView> {
+        self.workers
+            .iter()
+            .map(|w| OpsWorkerView {
+                worker_id: w.worker_id.clone(),
+                state: w.state.as_str().to_string(),
+                task: w.task.as_ref().map(|k| k.to_view()),
+                started_at: w.started_at.clone(),
+                last_error: w.last_error.as_ref().map(|e| OpsWorkerErrorView {
+                    ts: e.ts.clone(),
+                    message: e.message.clone(),
+                }),
+            })
+            .collect()
+    }
+
+    fn concurrency_view(&self) -> OpsConcurrencyView {
+        OpsConcurrencyView {
+            adaptive: self.concurrency.adaptive(),
+            limit: self.concurrency.limit(),
+            min: self.concurrency.min(),
+            max: self.concurrency.max(),
+            last_change: self.last_concurrency_change.clone(),
+        }
+    }
+
+    fn queue_view(&self, now: OffsetDateTime) -> OpsQueueView {
+        build_queue_view(&self.tasks, self.deduped, &self.control, now)
+    }
+
+    fn task_views(&self) -> Vec<OpsTaskView> {
+        build_task_views(&self.tasks, &self.hot)
+    }
+
+    /// Drops a task that is not running and returns its entry; its joiners see the task as
+    /// canceled once the entry is dropped.
+    fn cancel_task(&mut self, key: &TaskKey) -> Result<TaskEntry, OpsCancelSkip> {
+        match self.tasks.get(key).map(|entry| &entry.state) {
+            None => return Err(OpsCancelSkip::NotFound),
+            Some(TaskEntryState::Running { .. }) => return Err(OpsCancelSkip::Running),
+            Some(TaskEntryState::Pending | TaskEntryState::RetryScheduled { .. }) => {}
+        }
+        let entry = self.tasks.remove(key).expect("task checked above");
+        self.lanes[entry.lane as usize].retain(|pending| pending != key);
+        Ok(entry)
+    }
+}
+
+fn build_queue_view(
+    tasks: &HashMap<TaskKey, TaskEntry>,
+    deduped: i64,
+    control: &OpsControlView,
+    now: OffsetDateTime,
+) -> OpsQueueView {
+    let wait_seconds = |task: &TaskEntry| {
+        OffsetDateTime::parse(&task.enqueued_at, &Rfc3339)
+            .ok()
+            .map(|enqueued_at| (now - enqueued_at).whole_seconds().max(0))
+    };
+    let pending = tasks
+        .values()
+        .filter(|t| matches!(t.state, TaskEntryState::Pending))
+        .count() as i64;
+    let running = tasks
+        .values()
+        .filter(|t| matches!(t.state, TaskEntryState::Running { .. }))
+        .count() as i64;
+    let retry_scheduled = tasks
+        .values()
+        .filter(|t| matches!(t.state, TaskEntryState::RetryScheduled { .. }))
+        .count() as i64;
+    let oldest_wait_seconds = tasks.values().filter_map(wait_seconds).max();
+    let mut reason_counts = HashMap::new();
+    for task in tasks.values() {
+        for (reason, count) in &task.reason_counts {
+            *reason_counts.entry(reason.clone()).or_insert(0) += *count;
+        }
+    }
+    let lanes = OpsLane::ALL
+        .into_iter()
+        .map(|lane| {
+            let in_lane = || tasks.values().filter(move |t| t.lane == lane);
+            OpsLaneView {
+                lane: lane.as_str().to_string(),
+                pending: in_lane()
+                    .filter(|t| matches!(t.state, TaskEntryState::Pending))
+                    .count() as i64,
+                running: in_lane()
+                    .filter(|t| matches!(t.state, TaskEntryState::Running { .. }))
+                    .count() as i64,
+                oldest_wait_seconds: in_lane()
+                    .filter(|t| matches!(t.state, TaskEntryState::Pending))
+                    .filter_map(wait_seconds)
+                    .max(),
+            }
+        })
+        .collect();
+    OpsQueueView {
+        pending,
+        running,
+        retry_scheduled,
+        deduped,
+        oldest_wait_seconds,
+        reason_counts,
+        lanes,
+        control: control.clone(),
+    }
+}
+
+fn build_task_views(
+    tasks: &HashMap<TaskKey, TaskEntry>,
+    hot: &HashMap<TaskKey, HotWindow>,
+) -> Vec<OpsTaskView> {
+    let mut views = tasks
+        .iter()
+        .map(|(k, t)| OpsTaskView {
+            key: k.to_view(),
+            state: t.state.as_str().to_string(),
+            enqueued_at: t.enqueued_at.clone(),
+            reason_counts: t.reason_counts.clone(),
+            last_run: None,
+            attempt: t.attempt,
+            retry_at: match &t.state {
+                TaskEntryState::RetryScheduled { retry_at, .. } => Some(retry_at.clone()),
+                _ => None,
+            },
+            hot: hot.get(k).map(|w| w.view.clone()),
+        })
+        .collect::<Vec<_>>();
+    views.sort_by(|a, b| a.enqueued_at.cmp(&b.enqueued_at));
+    views
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpsCancelSkip {
+    NotFound,
+    Running,
 }
 
 #[derive(Debug, Clone)]
@@ -175,6 +509,105 @@ pub struct StoredOpsEvent {
     pub ts: String,
 }
 
+/// Subscriber-side filter of the ops event stream (`/api/ops/stream?events=&fid=&gid=&level=`).
+/// A key filter drops events that carry no task key (queue and worker snapshots); the level
+/// filter only applies to events that have a level (`ops.log`, `ops.alert`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpsEventFilter {
+    pub events: Option<HashSet<String>>,
+    pub fid: Option<String>,
+    pub gid: Option<String>,
+    pub min_level: Option<u8>,
+}
+
+impl OpsEventFilter {
+    /// `events` is a comma-separated list of event names; returns `None` for an unknown level.
+    pub fn parse(
+        events: Option<&str>,
+        fid: Option<&str>,
+        gid: Option<&str>,
+        level: Option<&str>,
+    ) -> Option<Self> {
+        let non_empty = |v: Option<&str>| {
+            v.map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(ToOwned::to_owned)
+        };
+        let min_level = match non_empty(level) {
+            Some(level) => Some(log_level_rank(&level)?),
+            None => None,
+        };
+        Some(Self {
+            events: non_empty(events).map(|events| {
+                events
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            }),
+            fid: non_empty(fid),
+            gid: non_empty(gid),
+            min_level,
+        })
+    }
+
+    pub fn wants_event(&self, event: &str) -> bool {
+        self.events
+            .as_ref()
+            .is_none_or(|events| events.contains(event))
+    }
+
+    pub fn matches(&self, event: &StoredOpsEvent) -> bool {
+        if !self.wants_event(&event.event) {
+            return false;
+        }
+        if self.fid.is_none() && self.gid.is_none() && self.min_level.is_none() {
+            return true;
+        }
+        let Ok(data) = serde_json::from_str::<serde_json::Value>(&event.data_json) else {
+            return false;
+        };
+        if let Some(min_level) = self.min_level {
+            let level = data
+                .get("level")
+                .and_then(|v| v.as_str())
+                .and_then(log_level_rank);
+            if level.is_some_and(|level| level < min_level) {
+                return false;
+            }
+        }
+        if self.fid.is_some() || self.gid.is_some() {
+            let key = data
+                .get("key")
+                .filter(|key| key.is_object())
+                .or_else(|| data.get("meta"));
+            let field = |name: &str| {
+                key.and_then(|key| key.get(name))
+                    .and_then(|v| v.as_str())
+                    .map(ToOwned::to_owned)
+            };
+            if self.fid.is_some() && field("fid") != self.fid {
+                return false;
+            }
+            if self.gid.is_some() && field("gid") != self.gid {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn log_level_rank(level: &str) -> Option<u8> {
+    match level {
+        "debug" => Some(0),
+        "info" => Some(1),
+        "warn" => Some(2),
+        "error" => Some(3),
+        _ => None,
+    }
+}
+
 #[derive(Clone)]
 pub struct OpsManager {
     inner: Arc<Inner>,
@@ -187,7 +620,17 @@ struct Inner {
     tx: broadcast::Sender<StoredOpsEvent>,
     publish_lock: Mutex<()>,
     state: Mutex<RuntimeState>,
+    /// `ops_queue_tasks` writes staged under the state lock, applied in order after it is released.
+    queue_writes: mpsc::UnboundedSender<QueueWrite>,
+    queue_write_rx: Mutex<mpsc::UnboundedReceiver<QueueWrite>>,
     notify: Notify,
+    operator_alerts: Mutex<HashMap<String, OffsetDateTime>>,
+    breaker: UpstreamCircuitBreaker,
+    retry: OpsRetryPolicies,
+    health: HealthRules,
+    /// Baseline of the `no_success` health rule until the first successful fetch.
+    started_at: OffsetDateTime,
+    leader: LeaderLease,
 }
 
 #[derive(Debug, Clone)]
@@ -309,13 +752,16 @@ WHERE id IN ({placeholders})
 
 async fn load_notification_record_item_map(
     db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
     ids: &[String],
 ) -> anyhow::Result<HashMap<String, NotificationRecordItemView>> {
-    Ok(crate::db::load_notification_record_item_snapshots(db, ids)
-        .await?
-        .into_iter()
-        .filter_map(|item| item.config_id.clone().map(|id| (id, item)))
-        .collect())
+    Ok(
+        crate::db::load_notification_record_item_snapshots(db, rates, ids)
+            .await?
+            .into_iter()
+            .filter_map(|item| item.config_id.clone().map(|id| (id, item)))
+            .collect(),
+    )
 }
 
 async fn load_country_catalog_summary(
@@ -367,6 +813,7 @@ LIMIT 10
 
 async fn load_country_catalog_notification_items(
     db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
     country_id: &str,
 ) -> anyhow::Result<Vec<NotificationRecordItemView>> {
     let rows = sqlx::query(
@@ -386,7 +833,7 @@ LIMIT 10
         .into_iter()
         .map(|row| row.get::<String, _>(0))
         .collect::<Vec<_>>();
-    crate::db::load_notification_record_item_snapshots(db, &ids).await
+    crate::db::load_notification_record_item_snapshots(db, rates, &ids).await
 }
 
 async fn load_region_catalog_summary(
@@ -443,6 +890,7 @@ LIMIT 10
 
 async fn load_region_catalog_notification_items(
     db: &SqlitePool,
+    rates: &crate::pricing::ExchangeRates,
     country_id: &str,
     region_id: &str,
 ) -> anyhow::Result<Vec<NotificationRecordItemView>> {
@@ -465,7 +913,7 @@ LIMIT 10
         .into_iter()
         .map(|row| row.get::<String, _>(0))
         .collect::<Vec<_>>();
-    crate::db::load_notification_record_item_snapshots(db, &ids).await
+    crate::db::load_notification_record_item_snapshots(db, rates, &ids).await
 }
 
 fn notification_partition_label_from_items(items: &[NotificationRecordItemView]) -> Option<String> {
@@ -503,7 +951,7 @@ async fn deliver_telegram_channel(
         .filter(|value| !value.is_empty());
     if let Some(token) = tg_bot_token.filter(|_| !request.targets.is_empty()) {
         let deliveries = notifications::send_telegram_to_targets(
-            &manager.inner.cfg.telegram_api_base_url,
+            &manager.inner.cfg,
             token,
             request.targets,
             request.text,
@@ -580,6 +1028,17 @@ async fn deliver_telegram_channel(
     Ok(())
 }
 
+#[tracing::instrument(
+    name = "notify.deliver",
+    skip_all,
+    fields(
+        run_id = ?run_id,
+        user_id = %target.user_id,
+        kind = %payload.record_kind,
+        telegram = tracing::field::Empty,
+        web_push = tracing::field::Empty,
+    )
+)]
 async fn deliver_outbound_notification(
     manager: &OpsManager,
     run_id: Option<i64>,
@@ -610,6 +1069,7 @@ async fn deliver_outbound_notification(
                 "skipped".to_string()
             },
             items: payload.items.to_vec(),
+            matched_rule: payload.matched_rule.clone(),
         },
     )
     .await?;
@@ -736,6 +1196,7 @@ struct OutboundDeliveryPayload<'a> {
     record_kind: &'a str,
     notification: &'a notification_content::OutboundNotification,
     items: &'a [NotificationRecordItemView],
+    matched_rule: Option<crate::models::NotificationMatchedRuleView>,
 }
 
 async fn load_enabled_monitoring_ids(
@@ -843,6 +1304,7 @@ async fn deliver_monitoring_change_notification(
                 "skipped".to_string()
             },
             items: items.to_vec(),
+            matched_rule: notification.matched_rule.clone(),
         },
     )
     .await?;
@@ -971,8 +1433,15 @@ pub struct OpsRunOutcome {
 impl OpsManager {
     pub fn new(cfg: RuntimeConfig, db: SqlitePool, catalog: Arc<RwLock<CatalogSnapshot>>) -> Self {
         let (tx, _) = broadcast::channel(512);
-        let concurrency = cfg.ops_worker_concurrency.max(1);
-        let workers = (0..concurrency)
+        let breaker = UpstreamCircuitBreaker::new(
+            cfg.upstream_breaker_failure_threshold,
+            std::time::Duration::from_secs(cfg.upstream_breaker_open_seconds),
+        );
+        let retry = OpsRetryPolicies::from_config(&cfg);
+        let concurrency = ConcurrencyController::from_config(&cfg);
+        let health = HealthRules::from_config(&cfg);
+        let leader = LeaderLease::new(&cfg, db.clone());
+        let workers = (0..concurrency.max())
             .map(|i| WorkerRuntime {
                 worker_id: format!("w{}", i + 1),
                 state: WorkerState::Idle,
@@ -981,6 +1450,7 @@ impl OpsManager {
                 last_error: None,
             })
             .collect::<Vec<_>>();
+        let (queue_writes, queue_write_rx) = mpsc::unbounded_channel();
         Self {
             inner: Arc::new(Inner {
                 cfg,
@@ -990,21 +1460,316 @@ impl OpsManager {
                 publish_lock: Mutex::new(()),
                 state: Mutex::new(RuntimeState {
                     deduped: 0,
-                    pending: VecDeque::new(),
+                    lanes: Default::default(),
                     tasks: HashMap::new(),
                     workers,
+                    persisting: false,
+                    control: OpsControlView::default(),
+                    concurrency,
+                    last_concurrency_change: None,
+                    hot: HashMap::new(),
                 }),
+                queue_writes,
+                queue_write_rx: Mutex::new(queue_write_rx),
                 notify: Notify::new(),
+                operator_alerts: Mutex::new(HashMap::new()),
+                breaker,
+                retry,
+                health,
+                started_at: OffsetDateTime::now_utc(),
+                leader,
             }),
         }
     }
 
+    /// Lease deciding which instance sharing the database runs background work.
+    pub fn leader(&self) -> &LeaderLease {
+        &self.inner.leader
+    }
+
+    /// Once this instance holds the leader lease, reloads the persisted queue, then spawns the
+    /// workers and the health check loop. Workers and loops idle while leadership is lost.
     pub fn start(&self) {
-        let concurrency = self.inner.cfg.ops_worker_concurrency.max(1);
-        for worker_idx in 0..concurrency {
-            let this = self.clone();
-            tokio::spawn(async move { this.worker_loop(worker_idx).await });
+        if self.inner.leader.enabled() {
+            let lease = self.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(lease.inner.leader.heartbeat_interval());
+                loop {
+                    ticker.tick().await;
+                    lease.renew_leader_lease(OffsetDateTime::now_utc()).await;
+                }
+            });
+
+            let requests = self.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(OPS_REQUEST_POLL);
+                let mut leading = false;
+                loop {
+                    ticker.tick().await;
+                    let leader = requests.inner.leader.is_leader();
+                    if leader && !leading {
+                        // A previous leader may have claimed requests it never finished.
+                        if let Err(err) =
+                            crate::db::release_ops_queue_request_claims(&requests.inner.db).await
+                        {
+                            warn!(error = %err, "releasing ops request claims failed");
+                        }
+                    }
+                    leading = leader;
+                    if leader {
+                        if let Err(err) = requests.take_forwarded_requests().await {
+                            warn!(error = %err, "taking forwarded ops requests failed");
+                        }
+                        if let Err(err) = requests.take_forwarded_admin_requests().await {
+                            warn!(error = %err, "taking forwarded ops admin requests failed");
+                        }
+                    }
+                }
+            });
+        }
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            this.inner.leader.wait_leader().await;
+            if let Err(err) = this.restore_control().await {
+                let _ = this
+                    .log(
+                        "error",
+                        "ops.admin",
+                        &format!("restore ops control state failed: {err}"),
+                        None,
+                    )
+                    .await;
+            }
+            if let Err(err) = this.restore_queue().await {
+                this.inner.state.lock().await.persisting = true;
+                let _ = this
+                    .log(
+                        "error",
+                        "ops.queue",
+                        &format!("restore persisted queue failed: {err}"),
+                        None,
+                    )
+                    .await;
+            }
+            let worker_count = this.inner.state.lock().await.workers.len();
+            for worker_idx in 0..worker_count {
+                let worker = this.clone();
+                tokio::spawn(async move { worker.worker_loop(worker_idx).await });
+            }
+
+            if this.inner.cfg.hot_mode_minutes > 0 {
+                let hot = this.clone();
+                tokio::spawn(async move {
+                    let interval = std::time::Duration::from_secs(
+                        hot.inner.cfg.hot_mode_interval_seconds.max(1),
+                    );
+                    let mut ticker = tokio::time::interval(interval);
+                    loop {
+                        ticker.tick().await;
+                        if !hot.inner.leader.is_leader() {
+                            continue;
+                        }
+                        if let Err(err) = hot.tick_hot_mode(OffsetDateTime::now_utc()).await {
+                            warn!(error = %err, "ops hot mode tick failed");
+                        }
+                    }
+                });
+            }
+
+            if let Some(hour) = this.inner.cfg.ops_digest_hour_utc {
+                let digest = this.clone();
+                tokio::spawn(async move {
+                    loop {
+                        let now = OffsetDateTime::now_utc();
+                        let due = next_digest_at(now, hour);
+                        tokio::time::sleep((due - now).unsigned_abs()).await;
+                        if !digest.inner.leader.is_leader() {
+                            continue;
+                        }
+                        if let Err(err) = digest.send_daily_digest(due).await {
+                            warn!(error = %err, "ops daily digest failed");
+                        }
+                    }
+                });
+            }
+
+            let interval = std::time::Duration::from_secs(
+                this.inner.cfg.ops_health_check_interval_seconds.max(1),
+            );
+            let mut ticker =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            loop {
+                ticker.tick().await;
+                if !this.inner.leader.is_leader() {
+                    continue;
+                }
+                if let Err(err) = this.check_health().await {
+                    warn!(error = %err, "ops health check failed");
+                }
+            }
+        });
+    }
+
+    /// Picks up the queue a previous process left in `ops_queue_tasks`. Runs that never ended are
+    /// closed as `interrupted` and their tasks go back to pending; rows are merged into tasks
+    /// enqueued since startup instead of duplicating them.
+    async fn restore_queue(&self) -> anyhow::Result<()> {
+        let ended_at = now_rfc3339();
+        let interrupted = sqlx::query(
+            "SELECT id, fid, gid FROM ops_task_runs WHERE ended_at IS NULL ORDER BY id",
+        )
+        .fetch_all(&self.inner.db)
+        .await?;
+        sqlx::query(
+            r#"
+UPDATE ops_task_runs
+SET ended_at = ?, ok = 0, error_code = 'interrupted', error_message = 'interrupted by restart'
+WHERE ended_at IS NULL
+"#,
+        )
+        .bind(&ended_at)
+        .execute(&self.inner.db)
+        .await?;
+        for row in &interrupted {
+            let run_id = row.get::<i64, _>(0);
+            let fid = row.get::<String, _>(1);
+            let gid = row.get::<Option<String>, _>(2);
+            let _ = self
+                .log(
+                    "warn",
+                    "ops.task",
+                    &format!(
+                        "task interrupted by restart: fid={fid} gid={}",
+                        gid.clone().unwrap_or_default()
+                    ),
+                    Some(serde_json::json!({ "runId": run_id, "fid": fid, "gid": gid })),
+                )
+                .await;
+        }
+
+        let rows = sqlx::query(
+            r#"
+SELECT fid, gid, enqueued_at, reason_counts_json, force_fetch, poller_waiter_user_ids_json,
+       state, attempt, retry_at, provider_id
+FROM ops_queue_tasks
+ORDER BY enqueued_at ASC
+"#,
+        )
+        .fetch_all(&self.inner.db)
+        .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let now_instant = std::time::Instant::now();
+        let restored = rows.len();
+        {
+            let mut st = self.inner.state.lock().await;
+            for row in rows {
+                let key = TaskKey {
+                    provider_id: row.get::<String, _>(9),
+                    fid: row.get::<String, _>(0),
+                    gid: row.get::<Option<String>, _>(1),
+                };
+                let enqueued_at = row.get::<String, _>(2);
+                let reason_counts =
+                    serde_json::from_str::<HashMap<String, i64>>(&row.get::<String, _>(3))
+                        .unwrap_or_default();
+                let force_fetch = row.get::<i64, _>(4) != 0;
+                let poller_waiter_user_ids =
+                    serde_json::from_str::<HashSet<String>>(&row.get::<String, _>(5))
+                        .unwrap_or_default();
+                let lane = reason_counts
+                    .keys()
+                    .map(|reason| OpsLane::for_reason(reason))
+                    .min()
+                    .unwrap_or(OpsLane::Interactive);
+                let persisted_state = row.get::<String, _>(6);
+                // An interrupted run used up its attempt.
+                let attempt =
+                    (row.get::<i64, _>(7).max(1) as u32) + u32::from(persisted_state == "running");
+                let retry_wait = row
+                    .get::<Option<String>, _>(8)
+                    .filter(|_| persisted_state == "retry_scheduled")
+                    .and_then(|retry_at| {
+                        let wait = OffsetDateTime::parse(&retry_at, &Rfc3339).ok()? - now;
+                        // Negative (already due) waits fail the conversion and run right away.
+                        let wait = std::time::Duration::try_from(wait).ok()?;
+                        Some((wait, retry_at))
+                    });
+
+                if let Some(entry) = st.tasks.get_mut(&key) {
+                    entry.attempt = entry.attempt.max(attempt);
+                    for (reason, count) in reason_counts {
+                        *entry.reason_counts.entry(reason).or_insert(0) += count;
+                    }
+                    entry.force_fetch |= force_fetch;
+                    entry.poller_waiter_user_ids.extend(poller_waiter_user_ids);
+                    if enqueued_at < entry.enqueued_at {
+                        entry.enqueued_at = enqueued_at;
+                    }
+                    if lane < entry.lane && matches!(entry.state, TaskEntryState::Pending) {
+                        let from = entry.lane;
+                        entry.lane = lane;
+                        st.lanes[from as usize].retain(|pending| pending != &key);
+                        st.lanes[lane as usize].push_back(key.clone());
+                    }
+                } else {
+                    let waited = OffsetDateTime::parse(&enqueued_at, &Rfc3339)
+                        .map(|ts| (now - ts).whole_seconds().max(0) as u64)
+                        .unwrap_or(0);
+                    let state = match retry_wait {
+                        Some((wait, retry_at)) => TaskEntryState::RetryScheduled {
+                            due: now_instant + wait,
+                            retry_at,
+                        },
+                        None => {
+                            st.lanes[lane as usize].push_back(key.clone());
+                            TaskEntryState::Pending
+                        }
+                    };
+                    st.tasks.insert(
+                        key.clone(),
+                        TaskEntry {
+                            state,
+                            lane,
+                            queued_at: now_instant
+                                .checked_sub(std::time::Duration::from_secs(waited))
+                                .unwrap_or(now_instant),
+                            enqueued_at,
+                            reason_counts,
+                            force_fetch,
+                            joiners: Vec::new(),
+                            poller_waiter_user_ids,
+                            attempt,
+                            trace_origins: Vec::new(),
+                        },
+                    );
+                }
+            }
+            // Rows now hold the stale "running" state and miss tasks enqueued since startup.
+            for (key, entry) in &st.tasks {
+                self.stage_queue_upsert(key, entry);
+            }
+            st.persisting = true;
+        }
+        self.flush_queue_writes().await;
+        if restored == 0 {
+            return Ok(());
         }
+
+        let _ = self
+            .log(
+                "info",
+                "ops.queue",
+                &format!(
+                    "restored {restored} queued tasks ({} interrupted mid-run)",
+                    interrupted.len()
+                ),
+                None,
+            )
+            .await;
+        let _ = self.publish_queue_snapshot().await;
+        Ok(())
     }
 
     pub fn subscribe(&self) -> broadcast::Receiver<StoredOpsEvent> {
@@ -1075,7 +1840,8 @@ LIMIT 2000
         let rx = self
             .enqueue(fid, gid, "poller_due", false, Some(user_id))
             .await?;
-        rx.await.map_err(|_| anyhow::anyhow!("ops task canceled"))
+        rx.await
+            .map_err(|_| anyhow::anyhow!("ops task canceled or held back by an open circuit"))
     }
 
     pub async fn enqueue_and_wait_force_fetch(
@@ -1098,37 +1864,644 @@ LIMIT 2000
         Ok(())
     }
 
-    pub async fn log(
-        &self,
-        level: &str,
-        scope: &str,
-        message: &str,
-        meta: Option<serde_json::Value>,
-    ) -> anyhow::Result<i64> {
-        let ts = now_rfc3339();
-        let payload_ts = ts.clone();
-        let payload = serde_json::json!({
-            "ts": payload_ts,
-            "level": level,
-            "scope": scope,
-            "message": message,
-            "meta": meta,
-        });
-        self.publish_event_with_ts("ops.log", &ts, payload).await
+    /// Stops workers from taking new tasks; tasks already running finish normally and enqueues
+    /// keep queueing. The poller is not made to wait out the pause: its queued tasks release their
+    /// waiters with an error and it cannot enqueue new ones.
+    pub async fn pause(&self, actor: &str) -> anyhow::Result<OpsControlView> {
+        if self.forwards_to_leader() {
+            return self.forward_admin("pause", actor, &[]).await;
+        }
+        let (control, released) = {
+            let mut st = self.inner.state.lock().await;
+            if !st.control.paused {
+                st.control.paused = true;
+                st.control.paused_at = Some(now_rfc3339());
+                st.control.paused_by = Some(actor.to_string());
+            }
+            let mut released = Vec::new();
+            for entry in st.tasks.values_mut().filter(|entry| {
+                !matches!(entry.state, TaskEntryState::Running { .. })
+                    && !entry.poller_waiter_user_ids.is_empty()
+            }) {
+                released.append(&mut entry.joiners);
+                entry.poller_waiter_user_ids.clear();
+            }
+            (st.control.clone(), released)
+        };
+        drop(released);
+        self.apply_control_change("pause", actor, "ops queue paused", control)
+            .await
     }
 
-    pub async fn record_notify(
-        &self,
-        task_run_id: i64,
-        channel: &str,
-        result: &str,
-        message: Option<&str>,
-    ) -> anyhow::Result<()> {
-        let ts = now_rfc3339();
-        sqlx::query(
-            r#"
-INSERT INTO ops_notify_runs (task_run_id, ts, channel, result, error_message)
-VALUES (?, ?, ?, ?, ?)
+    /// Lifts a pause or an ongoing drain and wakes idle workers.
+    pub async fn resume(&self, actor: &str) -> anyhow::Result<OpsControlView> {
+        if self.forwards_to_leader() {
+            return self.forward_admin("resume", actor, &[]).await;
+        }
+        let control = {
+            let mut st = self.inner.state.lock().await;
+            st.control = OpsControlView::default();
+            st.control.clone()
+        };
+        let control = self
+            .apply_control_change("resume", actor, "ops queue resumed", control)
+            .await?;
+        self.inner.notify.notify_waiters();
+        self.inner.notify.notify_one();
+        Ok(control)
+    }
+
+    /// Refuses new task keys (joining a queued key still works) and lets the queue run empty,
+    /// then pauses it.
+    pub async fn drain(&self, actor: &str) -> anyhow::Result<OpsControlView> {
+        if self.forwards_to_leader() {
+            return self.forward_admin("drain", actor, &[]).await;
+        }
+        let control = {
+            let mut st = self.inner.state.lock().await;
+            if !st.control.draining && !st.control.paused {
+                st.control.draining = true;
+                st.control.draining_since = Some(now_rfc3339());
+                st.control.draining_by = Some(actor.to_string());
+            }
+            st.control.clone()
+        };
+        self.apply_control_change("drain", actor, "ops queue draining", control)
+            .await?;
+        self.finish_drain_if_empty().await;
+        Ok(self.inner.state.lock().await.control.clone())
+    }
+
+    /// Removes the given tasks from the queue unless they are running. Callers waiting on a
+    /// canceled task get an "ops task canceled" error.
+    pub async fn cancel_tasks(
+        &self,
+        actor: &str,
+        keys: &[(String, Option<String>)],
+    ) -> anyhow::Result<OpsCancelView> {
+        if self.forwards_to_leader() {
+            return self.forward_admin("cancel", actor, keys).await;
+        }
+        let keys = keys
+            .iter()
+            .map(|(fid, gid)| {
+                TaskKey::scoped(
+                    fid.trim(),
+                    gid.as_deref()
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .map(ToOwned::to_owned),
+                )
+            })
+            .collect::<Vec<_>>();
+        self.cancel_keys("cancel", actor, keys).await
+    }
+
+    /// Cancels every task that is not running.
+    pub async fn clear_queue(&self, actor: &str) -> anyhow::Result<OpsCancelView> {
+        if self.forwards_to_leader() {
+            return self.forward_admin("clear", actor, &[]).await;
+        }
+        let keys = {
+            let st = self.inner.state.lock().await;
+            st.tasks
+                .iter()
+                .filter(|(_, entry)| !matches!(entry.state, TaskEntryState::Running { .. }))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>()
+        };
+        self.cancel_keys("clear", actor, keys).await
+    }
+
+    /// Whether this instance follows another one's lease: its queue is empty and queue work
+    /// belongs to the leader.
+    fn forwards_to_leader(&self) -> bool {
+        self.inner.leader.enabled() && !self.inner.leader.is_leader()
+    }
+
+    /// Hands an admin action to the leader through `ops_admin_requests` and returns the view the
+    /// leader answered with.
+    async fn forward_admin<T: serde::de::DeserializeOwned>(
+        &self,
+        action: &str,
+        actor: &str,
+        keys: &[(String, Option<String>)],
+    ) -> anyhow::Result<T> {
+        let id = crate::db::insert_ops_admin_request(
+            &self.inner.db,
+            self.inner.leader.instance_id(),
+            &crate::db::OpsAdminRequest {
+                action: action.to_string(),
+                actor: actor.to_string(),
+                keys: keys.to_vec(),
+            },
+        )
+        .await?;
+        let deadline = tokio::time::Instant::now() + OPS_ADMIN_FORWARD_TIMEOUT;
+        let mut ticker = tokio::time::interval(OPS_REQUEST_POLL);
+        loop {
+            ticker.tick().await;
+            if let Some(result) =
+                crate::db::take_ops_admin_request_result(&self.inner.db, id).await?
+            {
+                return match result.result_json {
+                    Some(json) => Ok(serde_json::from_str(&json)?),
+                    None => anyhow::bail!(
+                        "leader refused ops {action}: {}",
+                        result.error.unwrap_or_default()
+                    ),
+                };
+            }
+            if tokio::time::Instant::now() >= deadline {
+                crate::db::delete_ops_admin_request(&self.inner.db, id).await?;
+                anyhow::bail!("leader did not answer ops {action} in time");
+            }
+        }
+    }
+
+    /// Carries out admin actions followers left in `ops_admin_requests` and reports each answer
+    /// back.
+    async fn take_forwarded_admin_requests(&self) -> anyhow::Result<()> {
+        let now = now_rfc3339();
+        for (id, req) in crate::db::claim_ops_admin_requests(&self.inner.db, &now).await? {
+            let actor = req.actor.as_str();
+            let answer = match req.action.as_str() {
+                "pause" => self.pause(actor).await.and_then(to_json_value),
+                "resume" => self.resume(actor).await.and_then(to_json_value),
+                "drain" => self.drain(actor).await.and_then(to_json_value),
+                "cancel" => self
+                    .cancel_tasks(actor, &req.keys)
+                    .await
+                    .and_then(to_json_value),
+                "clear" => self.clear_queue(actor).await.and_then(to_json_value),
+                other => Err(anyhow::anyhow!("unknown ops admin action: {other}")),
+            };
+            let result = match answer {
+                Ok(view) => crate::db::OpsAdminRequestResult {
+                    result_json: Some(view.to_string()),
+                    error: None,
+                },
+                Err(err) => crate::db::OpsAdminRequestResult {
+                    result_json: None,
+                    error: Some(err.to_string()),
+                },
+            };
+            crate::db::complete_ops_admin_request(&self.inner.db, id, &result).await?;
+        }
+        Ok(())
+    }
+
+    async fn cancel_keys(
+        &self,
+        action: &str,
+        actor: &str,
+        keys: Vec<TaskKey>,
+    ) -> anyhow::Result<OpsCancelView> {
+        let mut canceled = Vec::new();
+        let mut running = Vec::new();
+        let mut not_found = Vec::new();
+        let mut events = Vec::new();
+        {
+            let mut st = self.inner.state.lock().await;
+            for key in keys {
+                match st.cancel_task(&key) {
+                    Ok(entry) => {
+                        if st.persisting {
+                            self.stage_queue_delete(&key);
+                        }
+                        events.push(serde_json::json!({
+                            "phase": "canceled",
+                            "key": key.to_view(),
+                            "reasonCounts": entry.reason_counts,
+                            "run": null,
+                        }));
+                        canceled.push(key.to_view());
+                    }
+                    Err(OpsCancelSkip::Running) => running.push(key.to_view()),
+                    Err(OpsCancelSkip::NotFound) => not_found.push(key.to_view()),
+                }
+            }
+        }
+        self.flush_queue_writes().await;
+
+        for payload in events {
+            let _ = self.publish_event("ops.task", payload).await;
+        }
+        let meta = serde_json::json!({
+            "action": action,
+            "actor": actor,
+            "canceled": canceled,
+            "running": running,
+            "notFound": not_found,
+        });
+        self.publish_event("ops.admin", meta.clone()).await?;
+        let _ = self
+            .log(
+                if canceled.is_empty() { "info" } else { "warn" },
+                "ops.admin",
+                &format!(
+                    "{action} by {actor}: {} task(s) canceled, {} running left alone",
+                    canceled.len(),
+                    running.len()
+                ),
+                Some(meta),
+            )
+            .await;
+        let _ = self.publish_queue_snapshot().await;
+        self.finish_drain_if_empty().await;
+
+        let queue = {
+            let st = self.inner.state.lock().await;
+            st.queue_view(OffsetDateTime::now_utc())
+        };
+        Ok(OpsCancelView {
+            canceled,
+            running,
+            not_found,
+            queue,
+        })
+    }
+
+    /// Persists and announces a control change as an `ops.admin` event plus an ops log entry.
+    async fn apply_control_change(
+        &self,
+        action: &str,
+        actor: &str,
+        message: &str,
+        control: OpsControlView,
+    ) -> anyhow::Result<OpsControlView> {
+        persist_control(&self.inner.db, &control).await?;
+        let meta = serde_json::json!({
+            "action": action,
+            "actor": actor,
+            "control": control,
+        });
+        self.publish_event("ops.admin", meta.clone()).await?;
+        let _ = self
+            .log(
+                "warn",
+                "ops.admin",
+                &format!("{message} by {actor}"),
+                Some(meta),
+            )
+            .await;
+        let _ = self.publish_queue_snapshot().await;
+        Ok(control)
+    }
+
+    /// Completes a drain once no task is left: the queue switches to paused.
+    async fn finish_drain_if_empty(&self) {
+        let control = {
+            let mut st = self.inner.state.lock().await;
+            if !st.control.draining || !st.tasks.is_empty() {
+                return;
+            }
+            st.control = OpsControlView {
+                paused: true,
+                paused_at: Some(now_rfc3339()),
+                paused_by: st.control.draining_by.take(),
+                ..OpsControlView::default()
+            };
+            st.control.clone()
+        };
+        let actor = control.paused_by.clone().unwrap_or_default();
+        if let Err(err) = self
+            .apply_control_change("drained", &actor, "ops queue drained and paused", control)
+            .await
+        {
+            warn!(error = %err, "record drained ops queue failed");
+        }
+    }
+
+    async fn restore_control(&self) -> anyhow::Result<()> {
+        let Some(control) = load_control(&self.inner.db).await? else {
+            return Ok(());
+        };
+        self.inner.state.lock().await.control = control.clone();
+        if control.paused || control.draining {
+            let _ = self
+                .log(
+                    "warn",
+                    "ops.admin",
+                    if control.paused {
+                        "ops queue still paused from before the restart"
+                    } else {
+                        "ops queue still draining from before the restart"
+                    },
+                    Some(serde_json::json!({ "control": control })),
+                )
+                .await;
+        }
+        Ok(())
+    }
+
+    pub async fn log(
+        &self,
+        level: &str,
+        scope: &str,
+        message: &str,
+        meta: Option<serde_json::Value>,
+    ) -> anyhow::Result<i64> {
+        let ts = now_rfc3339();
+        let payload_ts = ts.clone();
+        let payload = serde_json::json!({
+            "ts": payload_ts,
+            "level": level,
+            "scope": scope,
+            "message": message,
+            "meta": meta,
+        });
+        self.publish_event_with_ts("ops.log", &ts, payload).await
+    }
+
+    /// Raises a service-health alert for the operator: an error-level ops log entry, an
+    /// `ops.alert` event and, when configured, a message to the operator Telegram targets.
+    /// Repeats of the same `kind`/`key` are suppressed for an hour; returns whether it was raised.
+    pub async fn alert_operator(
+        &self,
+        kind: &str,
+        key: &str,
+        message: &str,
+        meta: serde_json::Value,
+    ) -> bool {
+        let now = OffsetDateTime::now_utc();
+        let dedupe_key = format!("{kind}:{key}");
+        {
+            let mut alerts = self.inner.operator_alerts.lock().await;
+            if alerts.get(&dedupe_key).is_some_and(|last| {
+                now - *last < time::Duration::seconds(OPERATOR_ALERT_COOLDOWN_SECONDS)
+            }) {
+                return false;
+            }
+            alerts.insert(dedupe_key, now);
+        }
+        self.notify_operator("error", kind, key, message, meta)
+            .await;
+        true
+    }
+
+    /// Sends an operator message without cooldown: an ops log entry at `level`, an `ops.alert`
+    /// event and the operator Telegram targets when configured.
+    async fn notify_operator(
+        &self,
+        level: &str,
+        kind: &str,
+        key: &str,
+        message: &str,
+        meta: serde_json::Value,
+    ) {
+        let _ = self
+            .log(level, &format!("alert.{kind}"), message, Some(meta.clone()))
+            .await;
+        let _ = self
+            .publish_event(
+                "ops.alert",
+                serde_json::json!({
+                    "level": level,
+                    "kind": kind,
+                    "key": key,
+                    "message": message,
+                    "meta": meta,
+                }),
+            )
+            .await;
+
+        self.send_operator_channels(
+            format!("[catnap] {message}"),
+            "Catnap · 运维告警".to_string(),
+            message.to_string(),
+        );
+    }
+
+    /// Sends a message to the operator Telegram targets and the Web Push subscriptions of the
+    /// operator users, in the background; failed deliveries are logged.
+    fn send_operator_channels(&self, telegram_text: String, web_push_title: String, body: String) {
+        let cfg = &self.inner.cfg;
+        if let Some(token) = cfg.operator_telegram_bot_token.clone() {
+            if !cfg.operator_telegram_targets.is_empty() {
+                let this = self.clone();
+                tokio::spawn(async move {
+                    let cfg = &this.inner.cfg;
+                    let deliveries = notifications::send_telegram_to_targets(
+                        cfg,
+                        &token,
+                        &cfg.operator_telegram_targets,
+                        &telegram_text,
+                    )
+                    .await;
+                    for delivery in deliveries.into_iter().filter(|d| d.status != "success") {
+                        let _ = this
+                            .log(
+                                "warn",
+                                "alert.telegram",
+                                &format!(
+                                    "operator alert delivery failed: {}",
+                                    delivery.error.unwrap_or_default()
+                                ),
+                                Some(serde_json::json!({ "target": delivery.target })),
+                            )
+                            .await;
+                    }
+                });
+            }
+        }
+
+        if !cfg.operator_web_push_user_ids.is_empty() {
+            let this = self.clone();
+            tokio::spawn(async move {
+                for user_id in &this.inner.cfg.operator_web_push_user_ids {
+                    let result =
+                        match crate::db::get_latest_web_push_subscription(&this.inner.db, user_id)
+                            .await
+                        {
+                            Ok(Some(sub)) => notifications::send_web_push(
+                                &this.inner.cfg,
+                                &sub,
+                                &web_push_title,
+                                &body,
+                                "/#ops",
+                            )
+                            .await
+                            .map_err(|err| err.to_string()),
+                            Ok(None) => Err("no web push subscription".to_string()),
+                            Err(err) => Err(err.to_string()),
+                        };
+                    if let Err(err) = result {
+                        let _ = this
+                            .log(
+                                "warn",
+                                "alert.webPush",
+                                &format!("operator alert delivery failed: {err}"),
+                                Some(serde_json::json!({ "userId": user_id })),
+                            )
+                            .await;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Builds the digest of the 24 hours before `to` and sends it to the operator channels,
+    /// alongside an `ops.digest` event.
+    pub async fn send_daily_digest(&self, to: OffsetDateTime) -> anyhow::Result<OpsDigestView> {
+        let from = (to - time::Duration::days(1)).format(&Rfc3339)?;
+        let to = to.format(&Rfc3339)?;
+        let digest = crate::db::load_ops_digest(&self.inner.db, &from, &to).await?;
+        let notification = notification_content::build_ops_digest_notification(&digest);
+        let _ = self
+            .log(
+                "info",
+                "ops.digest",
+                &format!("daily digest: {}", notification.summary),
+                Some(serde_json::json!({ "from": &digest.from, "to": &digest.to })),
+            )
+            .await;
+        let _ = self
+            .publish_event("ops.digest", serde_json::to_value(&digest)?)
+            .await;
+        self.send_operator_channels(
+            notification.telegram_text,
+            notification.web_push_title,
+            notification.web_push_body,
+        );
+        Ok(digest)
+    }
+
+    /// Evaluates the configured health rules once. A rule that starts failing opens an alert
+    /// (`ops_health_alerts`, an `ops.health` event and an operator message); it is not repeated
+    /// while the breach lasts, and resolves with another message once the rule passes again.
+    pub async fn check_health(&self) -> anyhow::Result<Vec<HealthTransition>> {
+        let open = crate::db::list_open_ops_health_alerts(&self.inner.db).await?;
+        let rules = &self.inner.health;
+        if !rules.any_enabled() && open.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sample = self.health_sample(OffsetDateTime::now_utc()).await?;
+        let open_rules = open.iter().map(|a| a.rule.clone()).collect::<HashSet<_>>();
+        let transitions = plan_transitions(rules.evaluate(&sample), &open_rules);
+        for transition in &transitions {
+            let now = now_rfc3339();
+            match transition {
+                HealthTransition::Opened(breach) => {
+                    let id = crate::db::open_ops_health_alert(
+                        &self.inner.db,
+                        breach.rule,
+                        &now,
+                        breach.value,
+                        breach.threshold,
+                        &breach.message,
+                    )
+                    .await?;
+                    let alert = OpsHealthAlertView {
+                        id,
+                        rule: breach.rule.to_string(),
+                        opened_at: now,
+                        resolved_at: None,
+                        value: breach.value,
+                        threshold: breach.threshold,
+                        message: breach.message.clone(),
+                    };
+                    let _ = self
+                        .publish_event(
+                            "ops.health",
+                            serde_json::json!({ "phase": "opened", "alert": alert }),
+                        )
+                        .await;
+                    self.notify_operator(
+                        "error",
+                        "health",
+                        breach.rule,
+                        &breach.message,
+                        serde_json::json!({ "alertId": id, "rule": breach.rule }),
+                    )
+                    .await;
+                }
+                HealthTransition::Resolved { rule } => {
+                    let Some(alert) =
+                        crate::db::resolve_ops_health_alert(&self.inner.db, rule, &now).await?
+                    else {
+                        continue;
+                    };
+                    let _ = self
+                        .publish_event(
+                            "ops.health",
+                            serde_json::json!({ "phase": "resolved", "alert": alert }),
+                        )
+                        .await;
+                    self.notify_operator(
+                        "info",
+                        "health",
+                        rule,
+                        &format!("resolved: {}", alert.message),
+                        serde_json::json!({ "alertId": alert.id, "rule": rule }),
+                    )
+                    .await;
+                }
+            }
+        }
+        Ok(transitions)
+    }
+
+    async fn health_sample(&self, now: OffsetDateTime) -> anyhow::Result<HealthSample> {
+        let cutoff = now
+            .saturating_sub(time::Duration::minutes(
+                self.inner.health.success_rate_window_minutes,
+            ))
+            .format(&Rfc3339)
+            .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
+        let row = sqlx::query(
+            r#"
+SELECT
+  COUNT(*) as total,
+  SUM(CASE WHEN ok = 1 THEN 1 ELSE 0 END) as success,
+  (SELECT MAX(ended_at) FROM ops_task_runs WHERE ok = 1 AND fetch_action != 'cache') as last_success
+FROM ops_task_runs
+WHERE ended_at IS NOT NULL
+  AND ended_at >= ?
+"#,
+        )
+        .bind(&cutoff)
+        .fetch_one(&self.inner.db)
+        .await?;
+        let last_success = row
+            .try_get::<Option<String>, _>(2)
+            .ok()
+            .flatten()
+            .and_then(|ts| OffsetDateTime::parse(&ts, &Rfc3339).ok())
+            .map_or(self.inner.started_at, |ts| ts.max(self.inner.started_at));
+
+        let st = self.inner.state.lock().await;
+        let queue = st.queue_view(now);
+        let worker_errors = st
+            .workers
+            .iter()
+            .filter(|w| w.state == WorkerState::Error)
+            .filter_map(|w| {
+                let since = OffsetDateTime::parse(&w.last_error.as_ref()?.ts, &Rfc3339).ok()?;
+                Some((w.worker_id.clone(), (now - since).whole_seconds().max(0)))
+            })
+            .collect();
+        Ok(HealthSample {
+            window_total: row.get::<i64, _>(0),
+            window_success: row.try_get::<i64, _>(1).unwrap_or(0),
+            queue_depth: queue.pending + queue.retry_scheduled,
+            worker_errors,
+            seconds_since_success: (now - last_success).whole_seconds().max(0),
+        })
+    }
+
+    pub async fn record_notify(
+        &self,
+        task_run_id: i64,
+        channel: &str,
+        result: &str,
+        message: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let ts = now_rfc3339();
+        sqlx::query(
+            r#"
+INSERT INTO ops_notify_runs (task_run_id, ts, channel, result, error_message)
+VALUES (?, ?, ?, ?, ?)
 "#,
         )
         .bind(task_run_id)
@@ -1138,6 +2511,14 @@ VALUES (?, ?, ?, ?, ?)
         .bind(message)
         .execute(&self.inner.db)
         .await?;
+        crate::metrics::shared().record_notification(channel, result);
+        // Lands on the enclosing `notify.deliver` span; a no-op elsewhere.
+        let span_field = if channel == "webPush" {
+            "web_push"
+        } else {
+            channel
+        };
+        tracing::Span::current().record(span_field, result);
 
         let _ = self
             .publish_event(
@@ -1176,92 +2557,123 @@ VALUES (?, ?, ?, ?, ?)
         Ok(())
     }
 
-    pub async fn snapshot(
-        &self,
-        range: OpsRange,
-        log_limit: Option<i64>,
-        task_limit: Option<i64>,
-    ) -> anyhow::Result<OpsStateSnapshot> {
+    /// Live queue and worker state, without the database-backed parts of [`Self::snapshot`]. A
+    /// follower reports the leader's persisted queue.
+    pub async fn runtime_view(&self) -> OpsRuntimeView {
         let now = OffsetDateTime::now_utc();
-        let server_time = now_rfc3339();
-        let replay_window_seconds = self.inner.cfg.ops_sse_replay_window_seconds;
-
-        let log_limit = log_limit
-            .unwrap_or(self.inner.cfg.ops_log_tail_limit_default)
-            .clamp(1, 500);
-        let task_limit = task_limit
-            .unwrap_or(self.inner.cfg.ops_queue_task_limit_default)
-            .clamp(1, 500);
-
-        let (queue, workers, tasks) = {
-            let st = self.inner.state.lock().await;
-            let pending = st
-                .tasks
-                .values()
-                .filter(|t| matches!(t.state, TaskEntryState::Pending))
-                .count() as i64;
-            let running = st
-                .tasks
-                .values()
-                .filter(|t| matches!(t.state, TaskEntryState::Running { .. }))
-                .count() as i64;
-            let oldest_wait_seconds = st
-                .tasks
-                .values()
-                .filter_map(|task| OffsetDateTime::parse(&task.enqueued_at, &Rfc3339).ok())
-                .map(|enqueued_at| (now - enqueued_at).whole_seconds().max(0))
-                .max();
-            let mut queue_reason_counts = HashMap::new();
-            for task in st.tasks.values() {
-                for (reason, count) in &task.reason_counts {
-                    *queue_reason_counts.entry(reason.clone()).or_insert(0) += *count;
+        let persisted = if self.forwards_to_leader() {
+            match self.persisted_queue().await {
+                Ok((tasks, control)) => Some(build_queue_view(&tasks, 0, &control, now)),
+                Err(err) => {
+                    warn!(error = %err, "loading the leader's ops queue failed");
+                    None
                 }
             }
-            let queue = OpsQueueView {
-                pending,
-                running,
-                deduped: st.deduped,
-                oldest_wait_seconds,
-                reason_counts: queue_reason_counts,
-            };
-
-            let workers = st
-                .workers
-                .iter()
-                .map(|w| OpsWorkerView {
-                    worker_id: w.worker_id.clone(),
-                    state: match w.state {
-                        WorkerState::Idle => "idle".to_string(),
-                        WorkerState::Running => "running".to_string(),
-                        WorkerState::Error => "error".to_string(),
-                    },
-                    task: w.task.as_ref().map(|k| k.to_view()),
-                    started_at: w.started_at.clone(),
-                    last_error: w.last_error.as_ref().map(|e| OpsWorkerErrorView {
-                        ts: e.ts.clone(),
-                        message: e.message.clone(),
-                    }),
-                })
-                .collect::<Vec<_>>();
+        } else {
+            None
+        };
+        let st = self.inner.state.lock().await;
+        OpsRuntimeView {
+            queue: persisted.unwrap_or_else(|| st.queue_view(now)),
+            workers: st.workers_view(),
+        }
+    }
 
-            let mut tasks = st
-                .tasks
-                .iter()
-                .map(|(k, t)| OpsTaskView {
-                    key: k.to_view(),
-                    state: match t.state {
-                        TaskEntryState::Pending => "pending".to_string(),
-                        TaskEntryState::Running { .. } => "running".to_string(),
-                    },
-                    enqueued_at: t.enqueued_at.clone(),
-                    reason_counts: t.reason_counts.clone(),
-                    last_run: None,
-                })
-                .collect::<Vec<_>>();
-            tasks.sort_by(|a, b| a.enqueued_at.cmp(&b.enqueued_at));
-            tasks.truncate(task_limit as usize);
-            (queue, workers, tasks)
+    /// The leader's queue as last mirrored into `ops_queue_tasks`, with its control state.
+    async fn persisted_queue(
+        &self,
+    ) -> anyhow::Result<(HashMap<TaskKey, TaskEntry>, OpsControlView)> {
+        let rows = sqlx::query(
+            r#"
+SELECT fid, gid, provider_id, state, enqueued_at, reason_counts_json, attempt, retry_at,
+       run_id, started_at
+FROM ops_queue_tasks
+"#,
+        )
+        .fetch_all(&self.inner.db)
+        .await?;
+        let now = std::time::Instant::now();
+        let mut tasks = HashMap::new();
+        for row in rows {
+            let reason_counts =
+                serde_json::from_str::<HashMap<String, i64>>(&row.get::<String, _>(5))
+                    .unwrap_or_default();
+            let state = match row.get::<String, _>(3).as_str() {
+                "running" => TaskEntryState::Running {
+                    run_id: row.get::<Option<i64>, _>(8).unwrap_or_default(),
+                    started_at: row.get::<Option<String>, _>(9).unwrap_or_default(),
+                },
+                "retry_scheduled" => TaskEntryState::RetryScheduled {
+                    due: now,
+                    retry_at: row.get::<Option<String>, _>(7).unwrap_or_default(),
+                },
+                _ => TaskEntryState::Pending,
+            };
+            tasks.insert(
+                TaskKey {
+                    provider_id: row.get::<String, _>(2),
+                    fid: row.get::<String, _>(0),
+                    gid: row.get::<Option<String>, _>(1),
+                },
+                TaskEntry {
+                    state,
+                    lane: reason_counts
+                        .keys()
+                        .map(|reason| OpsLane::for_reason(reason))
+                        .min()
+                        .unwrap_or(OpsLane::Interactive),
+                    queued_at: now,
+                    enqueued_at: row.get::<String, _>(4),
+                    reason_counts,
+                    force_fetch: false,
+                    joiners: Vec::new(),
+                    poller_waiter_user_ids: HashSet::new(),
+                    attempt: row.get::<i64, _>(6).max(1) as u32,
+                    trace_origins: Vec::new(),
+                },
+            );
+        }
+        let control = load_control(&self.inner.db).await?.unwrap_or_default();
+        Ok((tasks, control))
+    }
+
+    pub async fn snapshot(
+        &self,
+        range: OpsRange,
+        log_limit: Option<i64>,
+        task_limit: Option<i64>,
+    ) -> anyhow::Result<OpsStateSnapshot> {
+        let now = OffsetDateTime::now_utc();
+        let server_time = now_rfc3339();
+        let replay_window_seconds = self.inner.cfg.ops_sse_replay_window_seconds;
+
+        let log_limit = log_limit
+            .unwrap_or(self.inner.cfg.ops_log_tail_limit_default)
+            .clamp(1, 500);
+        let task_limit = task_limit
+            .unwrap_or(self.inner.cfg.ops_queue_task_limit_default)
+            .clamp(1, 500);
+
+        let (queue, workers, concurrency, tasks) = {
+            let st = self.inner.state.lock().await;
+            let queue = st.queue_view(now);
+
+            let workers = st.workers_view();
+            let concurrency = st.concurrency_view();
+
+            (queue, workers, concurrency, st.task_views())
+        };
+        // A follower's own queue is empty; the leader's lives in `ops_queue_tasks`.
+        let (queue, mut tasks) = if self.forwards_to_leader() {
+            let (persisted, control) = self.persisted_queue().await?;
+            (
+                build_queue_view(&persisted, 0, &control, now),
+                build_task_views(&persisted, &HashMap::new()),
+            )
+        } else {
+            (queue, tasks)
         };
+        tasks.truncate(task_limit as usize);
 
         let stats = self.stats(range, now).await?;
 
@@ -1269,7 +2681,6 @@ VALUES (?, ?, ?, ?, ?)
 
         let log_tail = self.log_tail(log_limit).await?;
 
-        let mut tasks = tasks;
         if !tasks.is_empty() {
             let last_runs = self.last_runs_for_keys(&tasks).await?;
             for t in tasks.iter_mut() {
@@ -1278,6 +2689,8 @@ VALUES (?, ?, ?, ?, ?)
             }
         }
 
+        let health_alerts = crate::db::list_open_ops_health_alerts(&self.inner.db).await?;
+
         let topology = {
             let snap = self.inner.catalog.read().await;
             OpsTopologyView {
@@ -1294,11 +2707,16 @@ VALUES (?, ?, ?, ?, ?)
             replay_window_seconds,
             queue,
             workers,
+            concurrency,
             tasks,
             stats,
             sparks,
             log_tail,
             topology,
+            health_alerts,
+            rate_limits: crate::upstream_limiter::shared().snapshot(),
+            circuits: self.inner.breaker.snapshot(),
+            leader: self.inner.leader.view(),
         })
     }
 
@@ -1317,7 +2735,8 @@ VALUES (?, ?, ?, ?, ?)
 SELECT
   COUNT(*) as total,
   SUM(CASE WHEN ok = 1 THEN 1 ELSE 0 END) as success,
-  SUM(CASE WHEN fetch_action = 'cache' THEN 1 ELSE 0 END) as cache_hits
+  SUM(CASE WHEN fetch_action = 'cache' THEN 1 ELSE 0 END) as cache_hits,
+  SUM(CASE WHEN fetch_action = 'not_modified' THEN 1 ELSE 0 END) as not_modified
 FROM ops_task_runs
 WHERE ended_at IS NOT NULL
   AND ended_at >= ?
@@ -1329,6 +2748,7 @@ WHERE ended_at IS NOT NULL
         let total = row.get::<i64, _>(0);
         let success = row.try_get::<i64, _>(1).unwrap_or(0);
         let cache_hits = row.try_get::<i64, _>(2).unwrap_or(0);
+        let not_modified = row.try_get::<i64, _>(3).unwrap_or(0);
         let failure = (total - success).max(0);
         let success_rate_pct = if total > 0 {
             (success as f64) * 100.0 / (total as f64)
@@ -1370,6 +2790,7 @@ WHERE channel = ?
                 failure,
                 success_rate_pct,
                 cache_hits: 0,
+                not_modified: 0,
             }))
         }
 
@@ -1383,6 +2804,7 @@ WHERE channel = ?
                 failure,
                 success_rate_pct,
                 cache_hits,
+                not_modified,
             },
             notify: OpsNotifyStatsView { telegram, web_push },
         })
@@ -1605,6 +3027,8 @@ LIMIT 1
         Ok(out)
     }
 
+    /// Queues a task here while this instance leads; a follower hands it to the leader through
+    /// `ops_queue_requests` instead and waits for the leader's outcome.
     async fn enqueue(
         &self,
         fid: &str,
@@ -1612,6 +3036,160 @@ LIMIT 1
         reason: &str,
         force_fetch: bool,
         poller_waiter_user_id: Option<&str>,
+    ) -> anyhow::Result<oneshot::Receiver<OpsRunOutcome>> {
+        if !self.forwards_to_leader() {
+            return self
+                .enqueue_local(fid, gid, reason, force_fetch, poller_waiter_user_id)
+                .await;
+        }
+        let fid = fid.trim();
+        let reason = reason.trim();
+        if fid.is_empty() || reason.is_empty() {
+            anyhow::bail!("fid and reason must not be empty");
+        }
+        let id = crate::db::insert_ops_queue_request(
+            &self.inner.db,
+            self.inner.leader.instance_id(),
+            &crate::db::OpsQueueRequest {
+                fid: fid.to_string(),
+                gid: gid
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(ToOwned::to_owned),
+                reason: reason.to_string(),
+                force_fetch,
+                poller_waiter_user_id: poller_waiter_user_id
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(ToOwned::to_owned),
+            },
+        )
+        .await?;
+
+        let (tx, rx) = oneshot::channel();
+        let db = self.inner.db.clone();
+        tokio::spawn(async move {
+            let deadline = tokio::time::Instant::now() + OPS_FORWARDED_TASK_TIMEOUT;
+            let mut ticker = tokio::time::interval(OPS_REQUEST_POLL);
+            loop {
+                ticker.tick().await;
+                // Background enqueues drop the receiver; the leader still runs the task and the
+                // uncollected result is cleaned up later.
+                if tx.is_closed() {
+                    return;
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    warn!(request_id = id, "gave up waiting for forwarded ops task");
+                    if let Err(err) = crate::db::delete_ops_queue_request(&db, id).await {
+                        warn!(request_id = id, error = %err, "withdrawing ops request failed");
+                    }
+                    return;
+                }
+                match crate::db::take_ops_queue_request_result(&db, id).await {
+                    Ok(Some(result)) => {
+                        match result.run_id {
+                            Some(run_id) => {
+                                let _ = tx.send(OpsRunOutcome {
+                                    run_id,
+                                    ok: result.ok,
+                                });
+                            }
+                            None => warn!(
+                                request_id = id,
+                                error = result.error.as_deref().unwrap_or_default(),
+                                "leader did not queue forwarded ops task"
+                            ),
+                        }
+                        return;
+                    }
+                    // The row is gone without a result (a leader that claimed it died before
+                    // the claim was released, or cleanup took it); nothing will answer.
+                    Ok(None) => match crate::db::ops_queue_request_exists(&db, id).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            warn!(request_id = id, "forwarded ops request disappeared");
+                            return;
+                        }
+                        Err(err) => warn!(request_id = id, error = %err, "ops request poll failed"),
+                    },
+                    Err(err) => warn!(request_id = id, error = %err, "ops request poll failed"),
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    /// Queues requests followers left in `ops_queue_requests` and reports each outcome back.
+    async fn take_forwarded_requests(&self) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        crate::db::delete_stale_ops_queue_requests(
+            &self.inner.db,
+            &(now - time::Duration::hours(1)).format(&Rfc3339)?,
+        )
+        .await?;
+        for (id, req) in
+            crate::db::claim_ops_queue_requests(&self.inner.db, &now.format(&Rfc3339)?).await?
+        {
+            let queued = self
+                .enqueue_local(
+                    &req.fid,
+                    req.gid.as_deref(),
+                    &req.reason,
+                    req.force_fetch,
+                    req.poller_waiter_user_id.as_deref(),
+                )
+                .await;
+            let db = self.inner.db.clone();
+            tokio::spawn(async move {
+                let result = match queued {
+                    Ok(rx) => match rx.await {
+                        Ok(outcome) => crate::db::OpsQueueRequestResult {
+                            run_id: Some(outcome.run_id),
+                            ok: outcome.ok,
+                            error: None,
+                        },
+                        Err(_) => crate::db::OpsQueueRequestResult {
+                            run_id: None,
+                            ok: false,
+                            error: Some("ops task canceled".to_string()),
+                        },
+                    },
+                    Err(err) => crate::db::OpsQueueRequestResult {
+                        run_id: None,
+                        ok: false,
+                        error: Some(err.to_string()),
+                    },
+                };
+                if let Err(err) = crate::db::complete_ops_queue_request(&db, id, &result).await {
+                    warn!(request_id = id, error = %err, "ops request completion failed");
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Replaces the in-memory catalog with the database's. Followers never fetch, so this is how
+    /// they see what the leader applied.
+    pub async fn reload_catalog_snapshot(&self) -> anyhow::Result<()> {
+        let snapshot =
+            crate::db::load_catalog_snapshot(&self.inner.db, &self.inner.cfg.upstream_cart_url)
+                .await?;
+        *self.inner.catalog.write().await = snapshot;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "ops.enqueue",
+        skip(self, force_fetch, poller_waiter_user_id),
+        fields(fid = %fid, gid = gid.unwrap_or_default(), reason = %reason)
+    )]
+    async fn enqueue_local(
+        &self,
+        fid: &str,
+        gid: Option<&str>,
+        reason: &str,
+        force_fetch: bool,
+        poller_waiter_user_id: Option<&str>,
     ) -> anyhow::Result<oneshot::Receiver<OpsRunOutcome>> {
         let fid = fid.trim();
         if fid.is_empty() {
@@ -1627,47 +3205,84 @@ LIMIT 1
             .filter(|value| !value.is_empty())
             .map(ToOwned::to_owned);
 
-        let key = TaskKey {
-            fid: fid.to_string(),
-            gid: gid.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()),
-        };
+        let key = TaskKey::scoped(
+            fid,
+            gid.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()),
+        );
         let now = now_rfc3339();
+        let trace_origin = crate::telemetry::current_span_context();
 
         let (tx, rx) = oneshot::channel();
 
         let (should_notify, task_event_payload) = {
             let mut st = self.inner.state.lock().await;
-            if st.tasks.contains_key(&key) {
+            if st.control.draining && !st.tasks.contains_key(&key) {
+                anyhow::bail!("ops queue is draining");
+            }
+            // The poller waits on its tasks inline; a paused queue would stall its whole loop.
+            if st.control.paused && poller_waiter_user_id.is_some() {
+                anyhow::bail!("ops queue is paused");
+            }
+            let lane = OpsLane::for_reason(reason);
+            let enqueued = if st.tasks.contains_key(&key) {
                 st.deduped += 1;
                 let entry = st.tasks.get_mut(&key).unwrap();
+                let promoted_from = (lane < entry.lane
+                    && matches!(entry.state, TaskEntryState::Pending))
+                .then_some(entry.lane);
+                if lane < entry.lane {
+                    entry.lane = lane;
+                }
+                // A fresh request does not wait out the backoff of a scheduled retry.
+                let retry_released = matches!(entry.state, TaskEntryState::RetryScheduled { .. });
+                if retry_released {
+                    entry.state = TaskEntryState::Pending;
+                    entry.queued_at = std::time::Instant::now();
+                }
+                let entry_lane = entry.lane;
                 *entry.reason_counts.entry(reason.to_string()).or_insert(0) += 1;
                 entry.force_fetch |= force_fetch;
                 entry.joiners.push(tx);
+                if entry.trace_origins.len() < MAX_TRACE_ORIGINS {
+                    entry.trace_origins.extend(trace_origin);
+                }
                 if let Some(user_id) = poller_waiter_user_id.as_ref() {
                     entry.poller_waiter_user_ids.insert(user_id.clone());
                 }
+                let reason_counts = entry.reason_counts.clone();
+                if let Some(from) = promoted_from {
+                    st.lanes[from as usize].retain(|pending| pending != &key);
+                    st.lanes[lane as usize].push_back(key.clone());
+                }
+                if retry_released {
+                    st.lanes[entry_lane as usize].push_back(key.clone());
+                }
                 (
-                    false,
+                    retry_released,
                     serde_json::json!({
                         "phase": "enqueued",
                         "key": key.to_view(),
-                        "reasonCounts": entry.reason_counts.clone(),
+                        "reasonCounts": reason_counts,
                         "run": null,
                     }),
                 )
             } else {
                 let mut reason_counts = HashMap::new();
                 reason_counts.insert(reason.to_string(), 1);
-                st.pending.push_back(key.clone());
+                st.lanes[lane as usize].push_back(key.clone());
                 st.tasks.insert(
                     key.clone(),
                     TaskEntry {
                         state: TaskEntryState::Pending,
+                        lane,
+                        queued_at: std::time::Instant::now(),
                         enqueued_at: now.clone(),
                         reason_counts,
                         force_fetch,
                         joiners: vec![tx],
                         poller_waiter_user_ids: poller_waiter_user_id.into_iter().collect(),
+                        attempt: 1,
+                        trace_origins: trace_origin.into_iter().collect(),
                     },
                 );
                 (
@@ -1679,8 +3294,13 @@ LIMIT 1
                         "run": null,
                     }),
                 )
+            };
+            if let Some(entry) = st.tasks.get(&key).filter(|_| st.persisting) {
+                self.stage_queue_upsert(&key, entry);
             }
+            enqueued
         };
+        self.flush_queue_writes().await;
 
         let _ = self.publish_event("ops.task", task_event_payload).await;
 
@@ -1694,47 +3314,165 @@ LIMIT 1
     }
 
     async fn worker_loop(&self, worker_idx: usize) {
-        let upstream = match UpstreamClient::new(self.inner.cfg.upstream_cart_url.clone()) {
+        let archive = match crate::upstream_archive::UpstreamArchive::shared(&self.inner.cfg) {
             Ok(v) => v,
             Err(err) => {
-                let msg = format!("upstream client init failed: {err}");
+                let msg = format!("upstream archive init failed: {err}");
                 let _ = self.set_worker_error(worker_idx, msg).await;
                 return;
             }
         };
+        let registry = crate::storefront::StorefrontRegistry::from_config(&self.inner.cfg);
+        let mut upstreams = HashMap::new();
+        // An additional storefront that cannot build its client only fails its own tasks.
+        let mut upstream_init_errors = HashMap::new();
+        for provider in registry.providers() {
+            match UpstreamClient::for_provider(provider.clone(), &self.inner.cfg.upstream_proxies) {
+                Ok(v) => {
+                    upstreams.insert(provider.id().to_string(), v.with_archive(archive.clone()));
+                }
+                Err(err) if provider.id() == crate::storefront::DEFAULT_PROVIDER_ID => {
+                    let msg = format!("upstream client init failed ({}): {err}", provider.id());
+                    let _ = self.set_worker_error(worker_idx, msg).await;
+                    return;
+                }
+                Err(err) => {
+                    warn!(
+                        worker_idx,
+                        provider = provider.id(),
+                        error = %err,
+                        "upstream client init failed, provider skipped"
+                    );
+                    upstream_init_errors.insert(provider.id().to_string(), err.to_string());
+                }
+            }
+        }
 
         loop {
-            let key = {
+            let (key, parking_changed) = {
                 let mut st = self.inner.state.lock().await;
-                st.pending.pop_front()
+                let parked = worker_idx >= st.concurrency.limit();
+                let parking_changed = st.workers.get_mut(worker_idx).is_some_and(|w| {
+                    let next = match (parked, w.state) {
+                        (true, _) => WorkerState::Parked,
+                        (false, WorkerState::Parked) => WorkerState::Idle,
+                        (false, state) => state,
+                    };
+                    std::mem::replace(&mut w.state, next) != next
+                });
+                let leader = self.inner.leader.is_leader();
+                let key = if st.control.paused || parked || !leader {
+                    None
+                } else {
+                    st.pop_next(
+                        std::time::Instant::now(),
+                        std::time::Duration::from_secs(self.inner.cfg.ops_lane_starvation_seconds),
+                    )
+                };
+                (key, parking_changed)
             };
+            if parking_changed {
+                let _ = self.publish_workers_snapshot().await;
+            }
 
             let Some(key) = key else {
-                self.inner.notify.notified().await;
+                let next_retry = {
+                    let st = self.inner.state.lock().await;
+                    let leader = self.inner.leader.is_leader();
+                    (!st.control.paused && leader && worker_idx < st.concurrency.limit())
+                        .then(|| st.next_retry_due())
+                        .flatten()
+                };
+                match next_retry {
+                    Some(due) => {
+                        tokio::select! {
+                            _ = self.inner.notify.notified() => {}
+                            _ = tokio::time::sleep_until(due.into()) => {}
+                        }
+                    }
+                    None => self.inner.notify.notified().await,
+                }
                 continue;
             };
 
+            if self.park_while_circuit_open(worker_idx, &key, None).await {
+                continue;
+            }
+
             let started_at = now_rfc3339();
-            let run_id = match self.start_task(worker_idx, &key, &started_at).await {
-                Ok(id) => id,
-                Err(err) => {
-                    let _ = self
-                        .set_worker_error(worker_idx, format!("start task failed: {err}"))
-                        .await;
-                    continue;
-                }
-            };
+            let (run_id, attempt, trace_origins) =
+                match self.start_task(worker_idx, &key, &started_at).await {
+                    Ok(started) => started,
+                    Err(err) => {
+                        let _ = self
+                            .set_worker_error(worker_idx, format!("start task failed: {err}"))
+                            .await;
+                        continue;
+                    }
+                };
 
-            let (res, completion) = loop {
-                let res = self.run_task(&upstream, &key, run_id).await;
-                if matches!(&res, Ok(task_ok) if task_ok.fetch.action == "cache") {
-                    if let Some(completion) = self.complete_or_retry_cache_hit(&key).await {
-                        break (res, completion);
+            let task_span = tracing::info_span!(
+                "ops.task",
+                fid = %key.fid,
+                gid = key.gid.as_deref().unwrap_or_default(),
+                provider = key.provider_id(),
+                run_id,
+                attempt,
+                ok = tracing::field::Empty,
+                error_code = tracing::field::Empty,
+            );
+            crate::telemetry::link_origins(&task_span, &trace_origins);
+            let run_started = std::time::Instant::now();
+            let (res, completion) = async {
+                loop {
+                    let res = match upstreams.get(key.provider_id()) {
+                        Some(upstream) => self.run_task(upstream, &key, run_id).await,
+                        None => match upstream_init_errors.get(key.provider_id()) {
+                            Some(err) => Err(TaskErr {
+                                code: "upstream_init_failed".to_string(),
+                                message: format!(
+                                    "upstream client init failed ({}): {err}",
+                                    key.provider_id()
+                                ),
+                                fetch: None,
+                                parse: None,
+                            }),
+                            None => Err(TaskErr {
+                                code: "unknown_provider".to_string(),
+                                message: format!(
+                                    "storefront provider not configured: {}",
+                                    key.provider_id()
+                                ),
+                                fetch: None,
+                                parse: None,
+                            }),
+                        },
+                    };
+                    if matches!(&res, Err(err) if err.code == "circuit_open") {
+                        break (res, None);
                     }
-                    continue;
+                    if matches!(&res, Ok(task_ok) if task_ok.fetch.action == "cache") {
+                        if let Some(completion) = self.complete_or_retry_cache_hit(&key).await {
+                            break (res, Some(completion));
+                        }
+                        continue;
+                    }
+                    let completion = self
+                        .seal_task_for_completion(
+                            &key,
+                            res.as_ref().err().map(|err| err.code.as_str()),
+                        )
+                        .await;
+                    break (res, Some(completion));
                 }
-                let completion = self.seal_task_for_completion(&key).await;
-                break (res, completion);
+            }
+            .instrument(task_span.clone())
+            .await;
+            let Some(completion) = completion else {
+                // Another task took the half-open probe between the check above and this run.
+                self.park_while_circuit_open(worker_idx, &key, Some(run_id))
+                    .await;
+                continue;
             };
             let ended_at = now_rfc3339();
 
@@ -1749,6 +3487,10 @@ LIMIT 1
                 ),
             };
 
+            task_span.record("ok", ok);
+            if let Some(code) = error_code.as_deref() {
+                task_span.record("error_code", code);
+            }
             let reason_counts_json = completion.reason_counts_json;
 
             let _ = sqlx::query(
@@ -1790,6 +3532,16 @@ WHERE id = ?
             .bind(run_id)
             .execute(&self.inner.db)
             .await;
+            crate::metrics::shared().record_task_run(
+                fetch.as_ref().map(|f| f.action.as_str()).unwrap_or("fetch"),
+                error_code.as_deref(),
+                run_started.elapsed(),
+            );
+            if let Some(sample) =
+                fetch_sample(fetch.as_ref(), error_code.as_deref(), run_started.elapsed())
+            {
+                self.observe_fetch(sample).await;
+            }
 
             let _ = self
                 .publish_event(
@@ -1798,8 +3550,10 @@ WHERE id = ?
                         "phase": "finished",
                         "key": key.to_view(),
                         "reasonCounts": null,
+                        "retryAt": completion.retry.as_ref().map(|retry| retry.retry_at.clone()),
                         "run": {
                             "runId": run_id,
+                            "attempt": completion.attempt,
                             "startedAt": started_at,
                             "endedAt": ended_at,
                             "ok": ok,
@@ -1848,6 +3602,33 @@ WHERE id = ?
                         })),
                     )
                     .await;
+            } else if let Some(retry) = completion.retry.as_ref() {
+                let _ = self
+                    .log(
+                        "warn",
+                        "ops.task",
+                        &format!(
+                            "task failed, retry scheduled: fid={} gid={} attempt {}/{} at {} ({})",
+                            key.fid,
+                            key.gid.clone().unwrap_or_default(),
+                            completion.attempt,
+                            retry.max_attempts,
+                            retry.retry_at,
+                            error_message
+                                .clone()
+                                .unwrap_or_else(|| "unknown".to_string())
+                        ),
+                        Some(serde_json::json!({
+                            "runId": run_id,
+                            "fid": key.fid.clone(),
+                            "gid": key.gid.clone(),
+                            "attempt": completion.attempt,
+                            "maxAttempts": retry.max_attempts,
+                            "retryAt": retry.retry_at.clone(),
+                            "errorCode": error_code.clone(),
+                        })),
+                    )
+                    .await;
             } else {
                 let _ = self
                     .log(
@@ -1857,9 +3638,16 @@ WHERE id = ?
                             "task failed: fid={} gid={} ({})",
                             key.fid,
                             key.gid.clone().unwrap_or_default(),
-                            error_message.clone().unwrap_or_else(|| "unknown".to_string())
+                            error_message
+                                .clone()
+                                .unwrap_or_else(|| "unknown".to_string())
                         ),
-                        Some(serde_json::json!({ "runId": run_id, "fid": key.fid.clone(), "gid": key.gid.clone() })),
+                        Some(serde_json::json!({
+                            "runId": run_id,
+                            "fid": key.fid.clone(),
+                            "gid": key.gid.clone(),
+                            "attempt": completion.attempt,
+                        })),
                     )
                     .await;
             }
@@ -1868,6 +3656,7 @@ WHERE id = ?
                 .finish_task(worker_idx, run_id, ok, completion.joiners)
                 .await;
             let _ = self.publish_queue_snapshot().await;
+            self.finish_drain_if_empty().await;
         }
     }
 
@@ -1876,8 +3665,8 @@ WHERE id = ?
         worker_idx: usize,
         key: &TaskKey,
         started_at: &str,
-    ) -> anyhow::Result<i64> {
-        let (run_id, do_lifecycle_notify, reason_counts) = {
+    ) -> anyhow::Result<(i64, u32, Vec<opentelemetry::trace::SpanContext>)> {
+        let (run_id, do_lifecycle_notify, reason_counts, attempt, trace_origins) = {
             let mut st = self.inner.state.lock().await;
             let w = st
                 .workers
@@ -1887,28 +3676,32 @@ WHERE id = ?
             w.task = Some(key.clone());
             w.started_at = Some(started_at.to_string());
 
+            let persisting = st.persisting;
             let entry = st
                 .tasks
                 .get_mut(key)
                 .ok_or_else(|| anyhow::anyhow!("task missing"))?;
             let do_lifecycle_notify = should_emit_lifecycle_notify(&entry.reason_counts);
             let reason_counts = entry.reason_counts.clone();
+            let attempt = entry.attempt;
 
             let reason_counts_json =
                 serde_json::to_string(&reason_counts).unwrap_or_else(|_| "{}".to_string());
             let res = sqlx::query(
                 r#"
 INSERT INTO ops_task_runs (
-  fid, gid, started_at, ended_at, ok,
-  fetch_action, freshness_window_seconds, reason_counts_json, cache_hit
+  provider_id, fid, gid, started_at, ended_at, ok,
+  fetch_action, freshness_window_seconds, reason_counts_json, cache_hit, attempt
 )
-VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
+VALUES (?, ?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0, ?)
 "#,
             )
+            .bind(key.provider_id())
             .bind(&key.fid)
             .bind(key.gid.as_deref())
             .bind(started_at)
             .bind(&reason_counts_json)
+            .bind(attempt as i64)
             .execute(&self.inner.db)
             .await?;
             let run_id = res.last_insert_rowid();
@@ -1916,9 +3709,20 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
                 run_id,
                 started_at: started_at.to_string(),
             };
+            if persisting {
+                self.stage_queue_upsert(key, entry);
+            }
 
-            (run_id, do_lifecycle_notify, reason_counts)
+            let trace_origins = entry.trace_origins.clone();
+            (
+                run_id,
+                do_lifecycle_notify,
+                reason_counts,
+                attempt,
+                trace_origins,
+            )
         };
+        self.flush_queue_writes().await;
 
         let _ = self.publish_workers_snapshot().await;
         let _ = self.publish_queue_snapshot().await;
@@ -1932,6 +3736,7 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
                     "reasonCounts": reason_counts,
                     "run": {
                         "runId": run_id,
+                        "attempt": attempt,
                         "startedAt": started_at,
                         "endedAt": null,
                         "ok": null,
@@ -1954,7 +3759,7 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
                 .await;
         }
 
-        Ok(run_id)
+        Ok((run_id, attempt, trace_origins))
     }
 
     async fn finish_task(
@@ -2009,17 +3814,191 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
         Ok(())
     }
 
+    /// Holds a pausable task back while its storefront's circuit is open: it waits like a
+    /// scheduled retry, without a run or a used attempt, so an outage does not show up as a
+    /// stream of failed runs. Its waiters (the poller) are released with an error right away
+    /// rather than blocked for the whole outage. `rejected_run` is a run the breaker turned away
+    /// after it started; it is removed again.
+    async fn park_while_circuit_open(
+        &self,
+        worker_idx: usize,
+        key: &TaskKey,
+        rejected_run: Option<i64>,
+    ) -> bool {
+        let now = std::time::Instant::now();
+        let (retry_at, released) = {
+            let mut st = self.inner.state.lock().await;
+            let persisting = st.persisting;
+            let Some(entry) = st
+                .tasks
+                .get_mut(key)
+                .filter(|entry| pausable_by_breaker(&entry.reason_counts))
+            else {
+                return false;
+            };
+            let paused = self
+                .inner
+                .breaker
+                .paused_until(key.provider_id(), now)
+                .or_else(|| rejected_run.map(|_| (now, now_rfc3339())));
+            let Some((due, retry_at)) = paused else {
+                return false;
+            };
+            entry.state = TaskEntryState::RetryScheduled {
+                due,
+                retry_at: retry_at.clone(),
+            };
+            // Released pollers no longer see this run's changes, so lifecycle notifications
+            // must not count on them.
+            let released = std::mem::take(&mut entry.joiners);
+            entry.poller_waiter_user_ids.clear();
+            if persisting {
+                self.stage_queue_upsert(key, entry);
+            }
+            if let Some(w) = st
+                .workers
+                .get_mut(worker_idx)
+                .filter(|_| rejected_run.is_some())
+            {
+                w.state = WorkerState::Idle;
+                w.task = None;
+                w.started_at = None;
+            }
+            (retry_at, released)
+        };
+        self.flush_queue_writes().await;
+        drop(released);
+        if let Some(run_id) = rejected_run {
+            let _ = sqlx::query("DELETE FROM ops_task_runs WHERE id = ?")
+                .bind(run_id)
+                .execute(&self.inner.db)
+                .await;
+            let _ = self.publish_workers_snapshot().await;
+        }
+        let _ = self
+            .log(
+                "warn",
+                "ops.task",
+                &format!(
+                    "task paused: fid={} gid={} (upstream {} unreachable, until {retry_at})",
+                    key.fid,
+                    key.gid.clone().unwrap_or_default(),
+                    key.provider_id()
+                ),
+                Some(serde_json::json!({
+                    "fid": key.fid.clone(),
+                    "gid": key.gid.clone(),
+                    "retryAt": retry_at,
+                })),
+            )
+            .await;
+        let _ = self.publish_queue_snapshot().await;
+        true
+    }
+
     async fn complete_or_retry_cache_hit(&self, key: &TaskKey) -> Option<TaskCompletion> {
-        let mut st = self.inner.state.lock().await;
-        if st.tasks.get(key).is_some_and(|entry| entry.force_fetch) {
-            return None;
+        let completion = {
+            let mut st = self.inner.state.lock().await;
+            if st.tasks.get(key).is_some_and(|entry| entry.force_fetch) {
+                return None;
+            }
+            if st.persisting {
+                self.stage_queue_delete(key);
+            }
+            remove_task_completion(&mut st, key)
+        };
+        self.flush_queue_writes().await;
+        Some(completion)
+    }
+
+    /// Ends the current run of `key`. A failure the task class retries keeps the entry as a
+    /// scheduled retry (handing this run's outcome to the current joiners, so the poller moves
+    /// on while the retry runs in the background); anything else removes it from the queue.
+    async fn seal_task_for_completion(
+        &self,
+        key: &TaskKey,
+        error_code: Option<&str>,
+    ) -> TaskCompletion {
+        let completion = {
+            let mut st = self.inner.state.lock().await;
+            self.seal_task_locked(&mut st, key, error_code)
+        };
+        self.flush_queue_writes().await;
+        completion
+    }
+
+    fn seal_task_locked(
+        &self,
+        st: &mut RuntimeState,
+        key: &TaskKey,
+        error_code: Option<&str>,
+    ) -> TaskCompletion {
+        let persisting = st.persisting;
+        if let (Some(code), Some(entry)) = (error_code, st.tasks.get_mut(key)) {
+            let policy = self.inner.retry.for_class(entry.lane.as_str());
+            if policy.should_retry(entry.attempt, code) {
+                let attempt = entry.attempt;
+                let max_attempts = policy.max_attempts;
+                let delay = policy.backoff(attempt, fastrand::f64());
+                let retry_at = (OffsetDateTime::now_utc() + delay)
+                    .format(&Rfc3339)
+                    .unwrap_or_else(|_| now_rfc3339());
+                entry.state = TaskEntryState::RetryScheduled {
+                    due: std::time::Instant::now() + delay,
+                    retry_at: retry_at.clone(),
+                };
+                entry.attempt += 1;
+                // The poller got this failure and will not look at what the retry changes.
+                entry.poller_waiter_user_ids.clear();
+                let completion = TaskCompletion {
+                    reason_counts_json: serde_json::to_string(&entry.reason_counts)
+                        .unwrap_or_else(|_| "{}".to_string()),
+                    joiners: std::mem::take(&mut entry.joiners),
+                    attempt,
+                    retry: Some(ScheduledRetry {
+                        retry_at,
+                        max_attempts,
+                    }),
+                };
+                if persisting {
+                    self.stage_queue_upsert(key, entry);
+                }
+                return completion;
+            }
         }
-        Some(remove_task_completion(&mut st, key))
+        if persisting {
+            self.stage_queue_delete(key);
+        }
+        remove_task_completion(st, key)
+    }
+
+    fn stage_queue_upsert(&self, key: &TaskKey, entry: &TaskEntry) {
+        let _ = self
+            .inner
+            .queue_writes
+            .send(QueueWrite::Upsert(key.clone(), QueueRow::new(entry)));
     }
 
-    async fn seal_task_for_completion(&self, key: &TaskKey) -> TaskCompletion {
-        let mut st = self.inner.state.lock().await;
-        remove_task_completion(&mut st, key)
+    fn stage_queue_delete(&self, key: &TaskKey) {
+        let _ = self
+            .inner
+            .queue_writes
+            .send(QueueWrite::Delete(key.clone()));
+    }
+
+    /// Applies the staged queue writes. Callers flush right after releasing the state lock;
+    /// draining under `queue_write_rx` keeps the writes in the order they were staged, and
+    /// once this returns the caller's own writes have landed.
+    async fn flush_queue_writes(&self) {
+        let mut rx = self.inner.queue_write_rx.lock().await;
+        while let Ok(write) = rx.try_recv() {
+            match write {
+                QueueWrite::Upsert(key, row) => {
+                    persist_queue_entry(&self.inner.db, &key, &row).await
+                }
+                QueueWrite::Delete(key) => delete_queue_entry(&self.inner.db, &key).await,
+            }
+        }
     }
 
     async fn current_lifecycle_notify_state(
@@ -2048,7 +4027,7 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
         run_id: i64,
     ) -> Result<TaskOk, TaskErr> {
         let gid = key.gid.as_deref();
-        let url_key = format!("{}:{}", key.fid, gid.unwrap_or("0"));
+        let url_key = catalog_region_key(&key.fid, gid);
         let (initial_reason_counts, force_fetch, initial_poller_waiter_user_ids) = {
             let st = self.inner.state.lock().await;
             st.tasks
@@ -2064,62 +4043,175 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
         };
         let freshness_window_seconds = task_freshness_window_seconds(&initial_reason_counts);
 
-        if !force_fetch {
-            if let Some(window) = freshness_window_seconds {
-                if let Ok(Some(cache)) =
-                    crate::db::get_catalog_url_cache(&self.inner.db, &url_key).await
-                {
-                    if let Ok(last_success_at) =
-                        OffsetDateTime::parse(&cache.last_success_at, &Rfc3339)
-                    {
-                        let age = OffsetDateTime::now_utc() - last_success_at;
-                        if age <= time::Duration::seconds(window) {
-                            let produced_configs =
-                                serde_json::from_str::<Vec<String>>(&cache.config_ids_json)
-                                    .map(|ids| ids.len() as i64)
-                                    .unwrap_or(0);
-                            #[cfg(test)]
-                            pause_before_cache_hit_return().await;
-                            return Ok(TaskOk {
-                                fetch: TaskFetchMeta {
-                                    url: cache.url,
-                                    http_status: 0,
-                                    bytes: 0,
-                                    elapsed_ms: 0,
-                                    action: "cache".to_string(),
-                                    freshness_window_seconds: Some(window),
-                                },
-                                parse: TaskParseMeta {
-                                    ok: true,
-                                    produced_configs,
-                                    elapsed_ms: 0,
-                                },
-                            });
-                        }
-                    }
-                }
-            }
+        // A forced fetch always re-parses, so it neither sends nor trusts the stored validators.
+        let previous_cache = if force_fetch {
+            None
+        } else {
+            crate::db::get_catalog_url_cache(&self.inner.db, &url_key)
+                .await
+                .ok()
+                .flatten()
+        };
+
+        if let (Some(cache), Some(window)) = (previous_cache.as_ref(), freshness_window_seconds) {
+            if let Ok(last_success_at) = OffsetDateTime::parse(&cache.last_success_at, &Rfc3339) {
+                let age = OffsetDateTime::now_utc() - last_success_at;
+                if age <= time::Duration::seconds(window) {
+                    let produced_configs =
+                        serde_json::from_str::<Vec<String>>(&cache.config_ids_json)
+                            .map(|ids| ids.len() as i64)
+                            .unwrap_or(0);
+                    #[cfg(test)]
+                    pause_before_cache_hit_return().await;
+                    return Ok(TaskOk {
+                        fetch: TaskFetchMeta {
+                            url: cache.url.clone(),
+                            http_status: 0,
+                            bytes: 0,
+                            elapsed_ms: 0,
+                            action: "cache".to_string(),
+                            freshness_window_seconds: Some(window),
+                        },
+                        parse: TaskParseMeta {
+                            ok: true,
+                            produced_configs,
+                            elapsed_ms: 0,
+                        },
+                    });
+                }
+            }
+        }
+
+        if let Admission::Rejected { retry_at } = self.inner.breaker.admit(
+            key.provider_id(),
+            pausable_by_breaker(&initial_reason_counts),
+            std::time::Instant::now(),
+        ) {
+            return Err(TaskErr {
+                code: "circuit_open".to_string(),
+                message: format!(
+                    "upstream {} unreachable, poller task paused until {retry_at}",
+                    key.provider_id()
+                ),
+                fetch: None,
+                parse: None,
+            });
         }
 
-        let fetch = match upstream.fetch_region_configs_detailed(&key.fid, gid).await {
+        let fetch = upstream
+            .fetch_region_configs_conditional(
+                key.source_fid(),
+                gid,
+                previous_cache.as_ref().map(|cache| &cache.validators),
+            )
+            .instrument(tracing::info_span!(
+                "upstream.fetch",
+                provider = key.provider_id(),
+                conditional = previous_cache.is_some(),
+            ))
+            .await;
+        let outcome = match &fetch {
+            Ok(_) => FetchOutcome::Reachable,
+            Err(err) => fetch_outcome(err),
+        };
+        self.record_upstream_outcome(
+            key.provider_id(),
+            outcome,
+            fetch.as_ref().err().map(|err| err.to_string()),
+        )
+        .await;
+
+        let fetch = match fetch {
             Ok(v) => v,
             Err(err) => {
+                if let Some(drift) = err.downcast_ref::<crate::upstream::ParserDriftError>() {
+                    self.alert_operator(
+                        "parser_drift",
+                        &url_key,
+                        &format!("parser drift, catalog update skipped: {drift}"),
+                        serde_json::json!({
+                            "fid": key.fid,
+                            "gid": key.gid,
+                            "url": drift.url,
+                            "reasons": drift.reasons,
+                        }),
+                    )
+                    .await;
+                    return Err(TaskErr {
+                        code: "parser_drift".to_string(),
+                        message: drift.to_string(),
+                        fetch: Some(TaskFetchMeta {
+                            url: drift.url.clone(),
+                            http_status: drift.http_status,
+                            bytes: drift.bytes,
+                            elapsed_ms: drift.elapsed_ms,
+                            action: "fetch".to_string(),
+                            freshness_window_seconds,
+                        }),
+                        parse: Some(TaskParseMeta {
+                            ok: false,
+                            produced_configs: 0,
+                            elapsed_ms: 0,
+                        }),
+                    });
+                }
                 return Err(TaskErr {
-                    code: "upstream_fetch".to_string(),
+                    code: fetch_error_code(&err).to_string(),
                     message: err.to_string(),
                     fetch: None,
                     parse: None,
-                })
+                });
             }
         };
 
+        if fetch.not_modified {
+            let cached_config_ids = previous_cache
+                .as_ref()
+                .and_then(|cache| serde_json::from_str::<Vec<String>>(&cache.config_ids_json).ok())
+                .unwrap_or_default();
+            let ok = self
+                .finish_not_modified(&url_key, fetch, previous_cache, freshness_window_seconds)
+                .await?;
+            // The cart page being unchanged says nothing about configureproduct prices, which
+            // keep their own `upstream_billing_cycles_refresh_seconds` cadence.
+            if self.inner.cfg.upstream_billing_cycles_enabled {
+                match crate::db::list_config_source_pids(&self.inner.db, &cached_config_ids).await {
+                    Ok(targets) => self.refresh_billing_cycles(upstream, key, &targets).await,
+                    Err(err) => warn!(error = %err, url_key, "list billing cycle targets failed"),
+                }
+            }
+            if self.inner.cfg.hot_mode_minutes > 0 {
+                let unchanged = crate::db::ApplyCatalogUrlResult::default();
+                if let Err(err) = self.maybe_enter_hot_mode(key, &unchanged).await {
+                    warn!(error = %err, "hot mode check failed");
+                }
+            }
+            return Ok(ok);
+        }
+
         let parse = TaskParseMeta {
             ok: true,
             produced_configs: fetch.configs.len() as i64,
             elapsed_ms: fetch.parse_elapsed_ms,
         };
         let region_notice = fetch.region_notice.clone();
+        let validators = fetch.validators.clone();
+        let billing_cycle_targets = if self.inner.cfg.upstream_billing_cycles_enabled {
+            fetch
+                .configs
+                .iter()
+                .filter_map(|c| Some((c.id.clone(), c.source_pid.clone()?)))
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
 
+        let apply_span = tracing::info_span!(
+            "catalog.apply",
+            url_key = %url_key,
+            listed_config_ids = tracing::field::Empty,
+            delisted_config_ids = tracing::field::Empty,
+        );
         let applied = match crate::db::apply_catalog_url_fetch_success(
             &self.inner.db,
             &key.fid,
@@ -2132,6 +4224,7 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
                 empty_result_authoritative: fetch.empty_result_authoritative,
             },
         )
+        .instrument(apply_span.clone())
         .await
         {
             Ok(v) => v,
@@ -2156,12 +4249,33 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
             }
         };
 
+        apply_span.record("listed_config_ids", applied.listed_event_ids.join(","));
+        apply_span.record("delisted_config_ids", applied.delisted_ids.join(","));
+
+        if let Err(err) =
+            crate::db::set_catalog_url_validators(&self.inner.db, &url_key, validators.as_ref())
+                .await
+        {
+            warn!(error = %err, url_key, "store catalog url validators failed");
+        }
+
         {
             let mut snap = self.inner.catalog.write().await;
             snap.fetched_at = applied.fetched_at.clone();
-            upsert_region_notice_in_snapshot(&mut snap, &key.fid, gid, region_notice.as_deref());
+            // The shared snapshot only carries the default storefront's topology.
+            if key.provider_id() == crate::storefront::DEFAULT_PROVIDER_ID {
+                upsert_region_notice_in_snapshot(
+                    &mut snap,
+                    &key.fid,
+                    gid,
+                    region_notice.as_deref(),
+                );
+            }
         }
 
+        self.refresh_billing_cycles(upstream, key, &billing_cycle_targets)
+            .await;
+
         let notify_state = self
             .current_lifecycle_notify_state(
                 key,
@@ -2188,6 +4302,12 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
             }
         }
 
+        if self.inner.cfg.hot_mode_minutes > 0 {
+            if let Err(err) = self.maybe_enter_hot_mode(key, &applied).await {
+                warn!(error = %err, "hot mode check failed");
+            }
+        }
+
         Ok(TaskOk {
             fetch: TaskFetchMeta {
                 url: fetch.url,
@@ -2201,6 +4321,474 @@ VALUES (?, ?, ?, NULL, 0, 'fetch', NULL, ?, 0)
         })
     }
 
+    /// Opens (or extends) the hot-mode window of `key` when the fetch restocked a monitored
+    /// config or listed something new in a monitored partition.
+    async fn maybe_enter_hot_mode(
+        &self,
+        key: &TaskKey,
+        applied: &crate::db::ApplyCatalogUrlResult,
+    ) -> anyhow::Result<()> {
+        let restocked =
+            crate::db::list_monitored_config_ids(&self.inner.db, &applied.restocked_ids).await?;
+        let (trigger, mut config_ids) = if !restocked.is_empty() {
+            ("restock", restocked)
+        } else if !applied.listed_ids.is_empty()
+            && crate::db::monitoring_partition_watched(
+                &self.inner.db,
+                &crate::db::monitoring_partition_key(&key.fid, key.gid.as_deref()),
+            )
+            .await?
+        {
+            ("listed", applied.listed_ids.clone())
+        } else {
+            return Ok(());
+        };
+        config_ids.sort();
+
+        let now = OffsetDateTime::now_utc();
+        let until = now + time::Duration::minutes(self.inner.cfg.hot_mode_minutes);
+        let until_str = until.format(&Rfc3339)?;
+        let entered = {
+            let mut st = self.inner.state.lock().await;
+            if let Some(window) = st.hot.get_mut(key) {
+                window.until = until;
+                window.view.until = until_str;
+                window.view.trigger = trigger.to_string();
+                for id in config_ids {
+                    if !window.view.config_ids.contains(&id) {
+                        window.view.config_ids.push(id);
+                    }
+                }
+                Some(("extended", window.view.clone()))
+            } else if st.hot.len() < self.inner.cfg.hot_mode_max_partitions {
+                let view = OpsHotModeView {
+                    trigger: trigger.to_string(),
+                    config_ids,
+                    started_at: now.format(&Rfc3339)?,
+                    until: until_str,
+                    interval_seconds: self.inner.cfg.hot_mode_interval_seconds,
+                };
+                st.hot.insert(
+                    key.clone(),
+                    HotWindow {
+                        until,
+                        view: view.clone(),
+                    },
+                );
+                Some(("started", view))
+            } else {
+                None
+            }
+        };
+
+        let Some((phase, view)) = entered else {
+            let _ = self
+                .log(
+                    "info",
+                    "hot_mode",
+                    &format!(
+                        "hot mode skipped for {}:{}: {} partitions already hot",
+                        key.fid,
+                        key.gid.as_deref().unwrap_or(""),
+                        self.inner.cfg.hot_mode_max_partitions
+                    ),
+                    Some(serde_json::json!({ "fid": key.fid, "gid": key.gid, "trigger": trigger })),
+                )
+                .await;
+            return Ok(());
+        };
+        self.publish_hot_mode(key, phase, &view).await;
+        Ok(())
+    }
+
+    /// Ends expired hot-mode windows and queues a `hot_mode` run for each one still open. Bursts
+    /// pause while an upstream host is backing off or the storefront circuit is not closed.
+    pub async fn tick_hot_mode(&self, now: OffsetDateTime) -> anyhow::Result<()> {
+        let (ended, active) = {
+            let mut st = self.inner.state.lock().await;
+            let mut ended = Vec::new();
+            st.hot.retain(|key, window| {
+                let open = window.until > now;
+                if !open {
+                    ended.push((key.clone(), window.view.clone()));
+                }
+                open
+            });
+            let mut active = st.hot.keys().cloned().collect::<Vec<_>>();
+            active.sort_by(|a, b| (&a.fid, &a.gid).cmp(&(&b.fid, &b.gid)));
+            (ended, active)
+        };
+        for (key, view) in ended {
+            self.publish_hot_mode(&key, "ended", &view).await;
+        }
+        if active.is_empty() {
+            return Ok(());
+        }
+
+        if crate::upstream_limiter::shared()
+            .snapshot()
+            .iter()
+            .any(|host| host.backoff_until.is_some())
+        {
+            return Ok(());
+        }
+        let open_circuits = self
+            .inner
+            .breaker
+            .snapshot()
+            .into_iter()
+            .filter(|circuit| circuit.state != CircuitState::Closed.as_str())
+            .map(|circuit| circuit.provider_id)
+            .collect::<HashSet<_>>();
+        for key in active {
+            if open_circuits.contains(key.provider_id()) {
+                continue;
+            }
+            self.enqueue_background(&key.fid, key.gid.as_deref(), "hot_mode")
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Renews the leader lease and, on a follower, reloads the catalog the leader keeps applying.
+    /// On a change wakes the workers and records it in the ops log.
+    pub async fn renew_leader_lease(&self, now: OffsetDateTime) -> Option<LeadershipChange> {
+        let change = self.inner.leader.heartbeat(now).await;
+        if !self.inner.leader.is_leader() {
+            if let Err(err) = self.reload_catalog_snapshot().await {
+                warn!(error = %err, "follower catalog reload failed");
+            }
+        }
+        let change = change?;
+        let lease = self.inner.leader.view();
+        let (level, message) = match &change {
+            LeadershipChange::Gained => (
+                "info",
+                format!("instance {} became leader", lease.instance_id),
+            ),
+            LeadershipChange::Lost { holder } => (
+                "warn",
+                format!(
+                    "instance {} lost leadership to {}",
+                    lease.instance_id,
+                    holder.as_deref().unwrap_or("(unknown)")
+                ),
+            ),
+        };
+        if change == LeadershipChange::Gained {
+            self.inner.notify.notify_waiters();
+        }
+        let _ = self
+            .log(
+                level,
+                "ops.leader",
+                &message,
+                Some(serde_json::json!({
+                    "instanceId": lease.instance_id,
+                    "holder": lease.holder,
+                    "lastError": lease.last_error,
+                })),
+            )
+            .await;
+        let _ = self
+            .publish_event("ops.leader", serde_json::json!(lease))
+            .await;
+        Some(change)
+    }
+
+    async fn publish_hot_mode(&self, key: &TaskKey, phase: &str, view: &OpsHotModeView) {
+        let message = match phase {
+            "ended" => format!(
+                "hot mode ended for {}:{}",
+                key.fid,
+                key.gid.as_deref().unwrap_or("")
+            ),
+            _ => format!(
+                "hot mode {phase} for {}:{} ({}: {}) until {}, polling every {}s",
+                key.fid,
+                key.gid.as_deref().unwrap_or(""),
+                view.trigger,
+                view.config_ids.join(", "),
+                view.until,
+                view.interval_seconds
+            ),
+        };
+        let _ = self
+            .log(
+                "info",
+                "hot_mode",
+                &message,
+                Some(serde_json::json!({ "fid": key.fid, "gid": key.gid, "phase": phase })),
+            )
+            .await;
+        let _ = self
+            .publish_event(
+                "ops.hot",
+                serde_json::json!({ "phase": phase, "key": key.to_view(), "hot": view }),
+            )
+            .await;
+    }
+
+    /// Fetches the topology of a storefront from `CATNAP_UPSTREAM_PROVIDERS` for discovery. Only
+    /// the leader fetches, and the fetch shares the circuit breaker of the provider's tasks.
+    pub async fn fetch_provider_topology(
+        &self,
+        provider: Arc<dyn crate::storefront::StorefrontProvider>,
+    ) -> anyhow::Result<crate::upstream::CatalogTopologySnapshot> {
+        if !self.inner.leader.is_leader() {
+            anyhow::bail!("provider topology is only fetched by the leader");
+        }
+        let provider_id = provider.id().to_string();
+        if let Admission::Rejected { retry_at } =
+            self.inner
+                .breaker
+                .admit(&provider_id, true, std::time::Instant::now())
+        {
+            anyhow::bail!(
+                "upstream {provider_id} unreachable, topology fetch paused until {retry_at}"
+            );
+        }
+        let upstream = UpstreamClient::for_provider(provider, &self.inner.cfg.upstream_proxies)?
+            .with_archive(crate::upstream_archive::UpstreamArchive::shared(
+                &self.inner.cfg,
+            )?);
+        let topology = upstream.fetch_topology().await;
+        let outcome = match &topology {
+            Ok(_) => FetchOutcome::Reachable,
+            Err(err) => fetch_outcome(err),
+        };
+        self.record_upstream_outcome(
+            &provider_id,
+            outcome,
+            topology.as_ref().err().map(|err| err.to_string()),
+        )
+        .await;
+        topology
+    }
+
+    /// Feeds a fetch result to the circuit breaker and acts on its transitions: outage windows
+    /// are written to `upstream_outages`, and opening/closing emits `ops.outage` plus an operator
+    /// message.
+    async fn record_upstream_outcome(
+        &self,
+        provider_id: &str,
+        outcome: FetchOutcome,
+        error: Option<String>,
+    ) {
+        let transition = self.inner.breaker.record(
+            provider_id,
+            outcome,
+            error.as_deref(),
+            std::time::Instant::now(),
+        );
+        match transition {
+            None => {}
+            Some(Transition::Opened {
+                opened_at,
+                consecutive_failures,
+            }) => {
+                let outage_id = match crate::db::open_upstream_outage(
+                    &self.inner.db,
+                    provider_id,
+                    &opened_at,
+                    i64::from(consecutive_failures),
+                    error.as_deref(),
+                )
+                .await
+                {
+                    Ok(id) => Some(id),
+                    Err(err) => {
+                        warn!(provider_id, error = %err, "record upstream outage failed");
+                        None
+                    }
+                };
+                let _ = self
+                    .publish_event(
+                        "ops.outage",
+                        serde_json::json!({
+                            "phase": "opened",
+                            "providerId": provider_id,
+                            "outageId": outage_id,
+                            "startedAt": opened_at,
+                            "consecutiveFailures": consecutive_failures,
+                            "lastError": error,
+                        }),
+                    )
+                    .await;
+                self.notify_operator(
+                    "error",
+                    "upstream_outage",
+                    provider_id,
+                    &format!(
+                        "upstream {provider_id} unreachable after {consecutive_failures} failed fetches, poller tasks paused: {}",
+                        error.as_deref().unwrap_or("unknown error")
+                    ),
+                    serde_json::json!({ "providerId": provider_id, "outageId": outage_id }),
+                )
+                .await;
+            }
+            Some(Transition::StillOpen { retry_at }) => {
+                let _ = crate::db::record_upstream_outage_failure(
+                    &self.inner.db,
+                    provider_id,
+                    error.as_deref(),
+                )
+                .await;
+                let _ = self
+                    .log(
+                        "warn",
+                        "ops.circuit",
+                        &format!(
+                            "upstream {provider_id} still unreachable, next probe at {retry_at}"
+                        ),
+                        Some(serde_json::json!({ "providerId": provider_id, "retryAt": retry_at })),
+                    )
+                    .await;
+            }
+            Some(Transition::Closed { opened_at }) => {
+                let outage = match crate::db::close_upstream_outage(
+                    &self.inner.db,
+                    provider_id,
+                    &now_rfc3339(),
+                )
+                .await
+                {
+                    Ok(v) => v,
+                    Err(err) => {
+                        warn!(provider_id, error = %err, "close upstream outage failed");
+                        None
+                    }
+                };
+                let _ = self
+                    .publish_event(
+                        "ops.outage",
+                        serde_json::json!({
+                            "phase": "closed",
+                            "providerId": provider_id,
+                            "outageId": outage.as_ref().map(|o| o.id),
+                            "startedAt": outage.as_ref().map(|o| o.started_at.clone()).or(opened_at),
+                            "endedAt": outage.as_ref().and_then(|o| o.ended_at.clone()),
+                            "durationSeconds": outage.as_ref().and_then(|o| o.duration_seconds),
+                        }),
+                    )
+                    .await;
+                let duration = outage
+                    .as_ref()
+                    .and_then(|o| o.duration_seconds)
+                    .map(|secs| format!(" after {}", format_outage_duration(secs)))
+                    .unwrap_or_default();
+                self.notify_operator(
+                    "info",
+                    "upstream_recovered",
+                    provider_id,
+                    &format!("upstream {provider_id} recovered{duration}, poller tasks resumed"),
+                    serde_json::json!({
+                        "providerId": provider_id,
+                        "outageId": outage.as_ref().map(|o| o.id),
+                    }),
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Unchanged page: keep every catalog row as it is and only move the cache row forward.
+    async fn finish_not_modified(
+        &self,
+        url_key: &str,
+        fetch: crate::upstream::RegionFetchDetailed,
+        previous_cache: Option<crate::db::CatalogUrlCacheRow>,
+        freshness_window_seconds: Option<i64>,
+    ) -> Result<TaskOk, TaskErr> {
+        let fetch_meta = TaskFetchMeta {
+            url: fetch.url,
+            http_status: fetch.http_status,
+            bytes: fetch.bytes,
+            elapsed_ms: fetch.elapsed_ms,
+            action: "not_modified".to_string(),
+            freshness_window_seconds,
+        };
+        let produced_configs = previous_cache
+            .and_then(|cache| serde_json::from_str::<Vec<String>>(&cache.config_ids_json).ok())
+            .map(|ids| ids.len() as i64)
+            .unwrap_or(0);
+        let validators = fetch.validators.unwrap_or_default();
+        match crate::db::touch_catalog_url_not_modified(&self.inner.db, url_key, &validators).await
+        {
+            Ok(fetched_at) => {
+                self.inner.catalog.write().await.fetched_at = fetched_at;
+                Ok(TaskOk {
+                    fetch: fetch_meta,
+                    parse: TaskParseMeta {
+                        ok: true,
+                        produced_configs,
+                        elapsed_ms: 0,
+                    },
+                })
+            }
+            Err(err) => Err(TaskErr {
+                code: "apply_failed".to_string(),
+                message: err.to_string(),
+                fetch: Some(fetch_meta),
+                parse: None,
+            }),
+        }
+    }
+
+    /// Scrapes configureproduct pages for configs whose billing cycles are older than
+    /// `upstream_billing_cycles_refresh_seconds`. Failures only skip that config until next run.
+    async fn refresh_billing_cycles(
+        &self,
+        upstream: &UpstreamClient,
+        key: &TaskKey,
+        targets: &[(String, String)],
+    ) {
+        let max_age =
+            time::Duration::seconds(self.inner.cfg.upstream_billing_cycles_refresh_seconds);
+        for (config_id, pid) in targets {
+            let checked_at = crate::db::get_billing_cycles_checked_at(&self.inner.db, config_id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|ts| OffsetDateTime::parse(&ts, &Rfc3339).ok());
+            if checked_at.is_some_and(|ts| OffsetDateTime::now_utc() - ts < max_age) {
+                continue;
+            }
+            let result = match upstream.fetch_billing_cycles(pid).await {
+                Ok(cycles) => {
+                    crate::db::replace_config_billing_cycles(&self.inner.db, config_id, &cycles)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                let _ = self
+                    .log(
+                        "warn",
+                        "catalog.billing_cycles",
+                        &format!("billing cycle scrape failed for {config_id}: {err}"),
+                        Some(serde_json::json!({
+                            "fid": key.fid.clone(),
+                            "gid": key.gid.clone(),
+                            "configId": config_id,
+                            "pid": pid,
+                        })),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    #[tracing::instrument(
+        name = "ops.notify_lifecycle",
+        skip_all,
+        fields(
+            run_id,
+            listed = applied.listed_event_ids.len(),
+            pending_zero_stock = applied.listed_pending_zero_stock_ids.len(),
+            delisted = applied.delisted_ids.len(),
+        )
+    )]
     async fn notify_lifecycle_events(
         &self,
         run_id: i64,
@@ -2249,8 +4837,9 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
         let mut ids = applied.listed_event_ids.clone();
         ids.extend(applied.listed_pending_zero_stock_ids.iter().cloned());
         ids.extend(applied.delisted_ids.iter().cloned());
+        let rates = crate::pricing::ExchangeRates::from_config(&self.inner.cfg);
         let config_by_id = load_config_lifecycle_records(&self.inner.db, &ids).await?;
-        let item_by_id = load_notification_record_item_map(&self.inner.db, &ids).await?;
+        let item_by_id = load_notification_record_item_map(&self.inner.db, &rates, &ids).await?;
 
         let listed_records = applied
             .listed_event_ids
@@ -2301,6 +4890,7 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
             key: &'a TaskKey,
             poller_waiter_user_ids: &'a HashSet<String>,
             listed_id_set: &'a HashSet<String>,
+            rates: &'a crate::pricing::ExchangeRates,
         }
 
         async fn deliver_monitored_restock_fallbacks(
@@ -2325,21 +4915,55 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
                 let Some(item) = ctx.listed_items.get(&record.id) else {
                     continue;
                 };
-                let notification = notification_content::build_monitoring_change_notification(
+                let rules = crate::monitoring_rules::load_rules_for_config(
+                    &manager.inner.db,
+                    &target.user_id,
+                    &record.id,
+                    &ctx.key.fid,
+                    ctx.key.gid.as_deref(),
+                )
+                .await?;
+                let verdict = crate::monitoring_rules::evaluate(
+                    &rules,
+                    Some(&RuleState {
+                        quantity: 0,
+                        price: &item.price,
+                        specs: &item.specs,
+                        rates: ctx.rates,
+                    }),
+                    &RuleState {
+                        quantity: item.inventory.quantity,
+                        price: &item.price,
+                        specs: &item.specs,
+                        rates: ctx.rates,
+                    },
+                );
+                if verdict == RuleVerdict::Suppressed {
+                    continue;
+                }
+                let mut notification = notification_content::build_monitoring_change_notification(
                     &item.name,
                     &notification_content::MonitoringSnapshot {
                         inventory_quantity: 0,
                         price: &item.price,
                         digest: "lifecycle-listed-pending",
+                        billing_cycles: &[],
                     },
                     &notification_content::MonitoringSnapshot {
                         inventory_quantity: item.inventory.quantity,
                         price: &item.price,
                         digest: "lifecycle-listed-pending",
+                        billing_cycles: &[],
                     },
                     target.site_base_url.as_deref(),
                 )
                 .expect("synthetic restock notification should exist");
+                if let RuleVerdict::Matched(rule) = verdict {
+                    notification_content::attach_matched_rule(
+                        &mut notification,
+                        rule.matched_view(),
+                    );
+                }
                 let msg = format!(
                     "[restock] {} ({}) qty={} price={} {}",
                     item.name,
@@ -2359,6 +4983,7 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
                         "configId": &record.id,
                         "events": ["restock"],
                         "lifecycleFallback": true,
+                        "ruleId": notification.matched_rule.as_ref().map(|rule| rule.rule_id),
                     }),
                     &notification,
                     std::slice::from_ref(item),
@@ -2376,6 +5001,7 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
             key,
             poller_waiter_user_ids,
             listed_id_set: &listed_id_set,
+            rates: &rates,
         };
 
         let mut scope_target_user_ids = HashSet::new();
@@ -2432,11 +5058,33 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
                     .cloned()
                     .into_iter()
                     .collect::<Vec<_>>();
+                let rules = crate::monitoring_rules::load_rules_for_config(
+                    &self.inner.db,
+                    &target.user_id,
+                    &record.id,
+                    &key.fid,
+                    key.gid.as_deref(),
+                )
+                .await?;
+                let matched_rule = match crate::monitoring_rules::evaluate(
+                    &rules,
+                    None,
+                    &RuleState {
+                        quantity: record.quantity,
+                        price: &record.price,
+                        specs: items.first().map_or(&[], |item| item.specs.as_slice()),
+                        rates: &rates,
+                    },
+                ) {
+                    RuleVerdict::NoRules => None,
+                    RuleVerdict::Matched(rule) => Some(rule),
+                    RuleVerdict::Suppressed => continue,
+                };
                 let msg = format!(
                     "[config_added] {} ({}) qty={} price={}",
                     record.name, record.id, record.quantity, record.price.amount
                 );
-                let notification = notification_content::build_config_lifecycle_notification(
+                let mut notification = notification_content::build_config_lifecycle_notification(
                     ConfigLifecycleNotificationKind::Added,
                     &record.name,
                     partition_label.as_deref(),
@@ -2444,6 +5092,9 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
                     &record.price,
                     target.site_base_url.as_deref(),
                 );
+                if let Some(rule) = matched_rule {
+                    notification_content::name_matched_rule(&mut notification, &rule.name);
+                }
                 deliver_outbound_notification(
                     self,
                     Some(run_id),
@@ -2458,10 +5109,12 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
                             "partitionKey": &partition_key,
                             "fetchedAt": &applied.fetched_at,
                             "changeKind": "added",
+                            "ruleId": matched_rule.map(|rule| rule.id),
                         }),
                         record_kind: "catalog.config.added",
                         notification: &notification,
                         items: &items,
+                        matched_rule: matched_rule.map(MonitoringRule::matched_view),
                     },
                 )
                 .await?;
@@ -2503,6 +5156,7 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
                         record_kind: "catalog.config.removed",
                         notification: &notification,
                         items: &items,
+                        matched_rule: None,
                     },
                 )
                 .await?;
@@ -2551,6 +5205,42 @@ WHERE s.monitoring_events_partition_catalog_change_enabled = 1
             return Ok(());
         }
 
+        let countries = [
+            ("region.added", added_countries),
+            ("region.removed", removed_countries),
+        ];
+        let regions = [
+            ("partition.added", added_regions),
+            ("partition.removed", removed_regions),
+        ];
+        let changes = countries
+            .iter()
+            .flat_map(|(kind, changes)| {
+                changes
+                    .iter()
+                    .map(|change| crate::db::CatalogTopologyChange {
+                        kind,
+                        country_id: &change.id,
+                        country_name: &change.name,
+                        region_id: None,
+                        region_name: None,
+                    })
+            })
+            .chain(regions.iter().flat_map(|(kind, changes)| {
+                changes
+                    .iter()
+                    .map(|change| crate::db::CatalogTopologyChange {
+                        kind,
+                        country_id: &change.country_id,
+                        country_name: &change.country_name,
+                        region_id: Some(&change.region_id),
+                        region_name: Some(&change.region_name),
+                    })
+            }))
+            .collect::<Vec<_>>();
+        crate::db::record_catalog_topology_changes(&self.inner.db, &changes).await?;
+        let rates = crate::pricing::ExchangeRates::from_config(&self.inner.cfg);
+
         let site_targets = if added_countries.is_empty() && removed_countries.is_empty() {
             Vec::new()
         } else {
@@ -2582,7 +5272,7 @@ WHERE monitoring_events_site_region_change_enabled = 1
             let (catalog_items, total_catalog_count) =
                 load_country_catalog_summary(&self.inner.db, &change.id).await?;
             let notification_items =
-                load_country_catalog_notification_items(&self.inner.db, &change.id).await?;
+                load_country_catalog_notification_items(&self.inner.db, &rates, &change.id).await?;
             let country_key = catalog_region_key(&change.id, None);
             let summary_fetch_incomplete = added_catalog_fetch_failures.contains(&country_key)
                 || added_regions.iter().any(|region| {
@@ -2625,6 +5315,7 @@ WHERE monitoring_events_site_region_change_enabled = 1
                         record_kind: "catalog.region.added",
                         notification: &notification,
                         items: &notification_items,
+                        matched_rule: None,
                     },
                 )
                 .await?;
@@ -2658,6 +5349,7 @@ WHERE monitoring_events_site_region_change_enabled = 1
                         record_kind: "catalog.region.removed",
                         notification: &notification,
                         items: &[],
+                        matched_rule: None,
                     },
                 )
                 .await?;
@@ -2711,6 +5403,7 @@ WHERE s.monitoring_events_region_partition_change_enabled = 1
                     .await?;
             let notification_items = load_region_catalog_notification_items(
                 &self.inner.db,
+                &rates,
                 &change.country_id,
                 &change.region_id,
             )
@@ -2753,6 +5446,7 @@ WHERE s.monitoring_events_region_partition_change_enabled = 1
                         record_kind: "catalog.partition.added",
                         notification: &notification,
                         items: &notification_items,
+                        matched_rule: None,
                     },
                 )
                 .await?;
@@ -2826,6 +5520,7 @@ WHERE s.monitoring_events_region_partition_change_enabled = 1
                         record_kind: "catalog.partition.removed",
                         notification: &notification,
                         items: &[],
+                        matched_rule: None,
                     },
                 )
                 .await?;
@@ -2836,77 +5531,67 @@ WHERE s.monitoring_events_region_partition_change_enabled = 1
     }
 
     async fn publish_queue_snapshot(&self) -> anyhow::Result<i64> {
-        let now = OffsetDateTime::now_utc();
-        let (pending, running, deduped, oldest_wait_seconds, reason_counts) = {
+        let queue = {
             let st = self.inner.state.lock().await;
-            let pending = st
-                .tasks
-                .values()
-                .filter(|t| matches!(t.state, TaskEntryState::Pending))
-                .count() as i64;
-            let running = st
-                .tasks
-                .values()
-                .filter(|t| matches!(t.state, TaskEntryState::Running { .. }))
-                .count() as i64;
-            let oldest_wait_seconds = st
-                .tasks
-                .values()
-                .filter_map(|task| OffsetDateTime::parse(&task.enqueued_at, &Rfc3339).ok())
-                .map(|enqueued_at| (now - enqueued_at).whole_seconds().max(0))
-                .max();
-            let mut reason_counts = HashMap::new();
-            for task in st.tasks.values() {
-                for (reason, count) in &task.reason_counts {
-                    *reason_counts.entry(reason.clone()).or_insert(0) += *count;
-                }
-            }
-            (
-                pending,
-                running,
-                st.deduped,
-                oldest_wait_seconds,
-                reason_counts,
-            )
+            st.queue_view(OffsetDateTime::now_utc())
+        };
+        self.publish_event("ops.queue", serde_json::json!({ "queue": queue }))
+            .await
+    }
+
+    async fn publish_workers_snapshot(&self) -> anyhow::Result<i64> {
+        let (workers, concurrency) = {
+            let st = self.inner.state.lock().await;
+            (st.workers_view(), st.concurrency_view())
         };
         self.publish_event(
-            "ops.queue",
-            serde_json::json!({
-                "queue": {
-                    "pending": pending,
-                    "running": running,
-                    "deduped": deduped,
-                    "oldestWaitSeconds": oldest_wait_seconds,
-                    "reasonCounts": reason_counts,
-                },
-            }),
+            "ops.worker",
+            serde_json::json!({ "workers": workers, "concurrency": concurrency }),
         )
         .await
     }
 
-    async fn publish_workers_snapshot(&self) -> anyhow::Result<i64> {
-        let workers = {
-            let st = self.inner.state.lock().await;
-            st.workers
-                .iter()
-                .map(|w| OpsWorkerView {
-                    worker_id: w.worker_id.clone(),
-                    state: match w.state {
-                        WorkerState::Idle => "idle".to_string(),
-                        WorkerState::Running => "running".to_string(),
-                        WorkerState::Error => "error".to_string(),
-                    },
-                    task: w.task.as_ref().map(|k| k.to_view()),
-                    started_at: w.started_at.clone(),
-                    last_error: w.last_error.as_ref().map(|e| OpsWorkerErrorView {
-                        ts: e.ts.clone(),
-                        message: e.message.clone(),
-                    }),
-                })
-                .collect::<Vec<_>>()
+    /// Feeds one upstream fetch to the concurrency controller and announces limit changes as
+    /// `ops.concurrency` events and ops log entries.
+    async fn observe_fetch(&self, sample: FetchSample) {
+        let change = {
+            let mut st = self.inner.state.lock().await;
+            let queue_depth = st.lanes.iter().map(VecDeque::len).sum();
+            let Some(change) = st.concurrency.observe(sample, queue_depth) else {
+                return;
+            };
+            let view = OpsConcurrencyChangeView {
+                at: now_rfc3339(),
+                change,
+            };
+            st.last_concurrency_change = Some(view.clone());
+            view
         };
-        self.publish_event("ops.worker", serde_json::json!({ "workers": workers }))
-            .await
+        let ConcurrencyChange {
+            from,
+            to,
+            ref reason,
+            avg_fetch_ms,
+            error_rate_pct,
+            queue_depth,
+        } = change.change;
+        if to > from {
+            self.inner.notify.notify_waiters();
+        }
+        let _ = self
+            .publish_event("ops.concurrency", serde_json::json!(change))
+            .await;
+        let _ = self
+            .log(
+                if to < from { "warn" } else { "info" },
+                "ops.concurrency",
+                &format!(
+                    "worker limit {from} -> {to} ({reason}: avg fetch {avg_fetch_ms}ms, errors {error_rate_pct}%, queue {queue_depth})"
+                ),
+                Some(serde_json::json!(change)),
+            )
+            .await;
+        let _ = self.publish_workers_snapshot().await;
     }
 
     async fn publish_event(&self, event: &str, data: serde_json::Value) -> anyhow::Result<i64> {
@@ -2947,6 +5632,190 @@ WHERE s.monitoring_events_region_partition_change_enabled = 1
 struct TaskCompletion {
     reason_counts_json: String,
     joiners: Vec<oneshot::Sender<OpsRunOutcome>>,
+    attempt: u32,
+    retry: Option<ScheduledRetry>,
+}
+
+#[derive(Debug)]
+struct ScheduledRetry {
+    retry_at: String,
+    max_attempts: u32,
+}
+
+/// A change to `ops_queue_tasks`, staged while the state lock is held.
+#[derive(Debug)]
+enum QueueWrite {
+    Upsert(TaskKey, QueueRow),
+    Delete(TaskKey),
+}
+
+/// The persisted columns of a queue entry at the time it was staged.
+#[derive(Debug)]
+struct QueueRow {
+    state: &'static str,
+    enqueued_at: String,
+    reason_counts_json: String,
+    force_fetch: bool,
+    poller_waiter_user_ids_json: String,
+    run_id: Option<i64>,
+    started_at: Option<String>,
+    attempt: u32,
+    retry_at: Option<String>,
+}
+
+impl QueueRow {
+    fn new(entry: &TaskEntry) -> Self {
+        let (run_id, started_at, retry_at) = match &entry.state {
+            TaskEntryState::Pending => (None, None, None),
+            TaskEntryState::Running { run_id, started_at } => {
+                (Some(*run_id), Some(started_at.clone()), None)
+            }
+            TaskEntryState::RetryScheduled { retry_at, .. } => (None, None, Some(retry_at.clone())),
+        };
+        Self {
+            state: entry.state.as_str(),
+            enqueued_at: entry.enqueued_at.clone(),
+            reason_counts_json: serde_json::to_string(&entry.reason_counts)
+                .unwrap_or_else(|_| "{}".to_string()),
+            force_fetch: entry.force_fetch,
+            poller_waiter_user_ids_json: serde_json::to_string(&entry.poller_waiter_user_ids)
+                .unwrap_or_else(|_| "[]".to_string()),
+            run_id,
+            started_at,
+            attempt: entry.attempt,
+            retry_at,
+        }
+    }
+}
+
+/// Mirrors a queue entry into `ops_queue_tasks`.
+async fn persist_queue_entry(db: &SqlitePool, key: &TaskKey, row: &QueueRow) {
+    let res = sqlx::query(
+        r#"
+INSERT INTO ops_queue_tasks (
+  fid, gid_key, gid, provider_id, state, enqueued_at, reason_counts_json, force_fetch,
+  poller_waiter_user_ids_json, run_id, started_at, attempt, retry_at, updated_at
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(fid, gid_key) DO UPDATE SET
+  state = excluded.state,
+  enqueued_at = excluded.enqueued_at,
+  reason_counts_json = excluded.reason_counts_json,
+  force_fetch = excluded.force_fetch,
+  poller_waiter_user_ids_json = excluded.poller_waiter_user_ids_json,
+  run_id = excluded.run_id,
+  started_at = excluded.started_at,
+  attempt = excluded.attempt,
+  retry_at = excluded.retry_at,
+  updated_at = excluded.updated_at
+"#,
+    )
+    .bind(&key.fid)
+    .bind(key.gid.as_deref().unwrap_or_default())
+    .bind(key.gid.as_deref())
+    .bind(key.provider_id())
+    .bind(row.state)
+    .bind(&row.enqueued_at)
+    .bind(&row.reason_counts_json)
+    .bind(if row.force_fetch { 1 } else { 0 })
+    .bind(&row.poller_waiter_user_ids_json)
+    .bind(row.run_id)
+    .bind(row.started_at.as_deref())
+    .bind(row.attempt as i64)
+    .bind(row.retry_at.as_deref())
+    .bind(now_rfc3339())
+    .execute(db)
+    .await;
+    if let Err(err) = res {
+        warn!(fid = key.fid, error = %err, "persist ops queue entry failed");
+    }
+}
+
+/// What a finished run tells the concurrency controller: cache hits and runs that never reached
+/// the upstream say nothing about its health, and only transport/upstream errors count as
+/// failures.
+fn fetch_sample(
+    fetch: Option<&TaskFetchMeta>,
+    error_code: Option<&str>,
+    run_elapsed: std::time::Duration,
+) -> Option<FetchSample> {
+    let upstream_failure = |code: &str| code.starts_with("upstream_") || code == "proxy_error";
+    match (fetch, error_code) {
+        (Some(fetch), _) if fetch.action == "cache" => None,
+        (Some(fetch), code) => Some(FetchSample {
+            elapsed: std::time::Duration::from_millis(fetch.elapsed_ms.max(0) as u64),
+            failed: code.is_some_and(upstream_failure),
+        }),
+        (None, Some(code)) if upstream_failure(code) => Some(FetchSample {
+            elapsed: run_elapsed,
+            failed: true,
+        }),
+        _ => None,
+    }
+}
+
+fn to_json_value<T: Serialize>(view: T) -> anyhow::Result<serde_json::Value> {
+    Ok(serde_json::to_value(view)?)
+}
+
+async fn load_control(db: &SqlitePool) -> anyhow::Result<Option<OpsControlView>> {
+    let row = sqlx::query(
+        r#"
+SELECT paused, paused_at, paused_by, draining, draining_since, draining_by
+FROM ops_control_state
+WHERE id = 1
+"#,
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row.map(|row| OpsControlView {
+        paused: row.get::<i64, _>(0) != 0,
+        paused_at: row.get(1),
+        paused_by: row.get(2),
+        draining: row.get::<i64, _>(3) != 0,
+        draining_since: row.get(4),
+        draining_by: row.get(5),
+    }))
+}
+
+async fn persist_control(db: &SqlitePool, control: &OpsControlView) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+INSERT INTO ops_control_state (
+  id, paused, paused_at, paused_by, draining, draining_since, draining_by, updated_at
+)
+VALUES (1, ?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(id) DO UPDATE SET
+  paused = excluded.paused,
+  paused_at = excluded.paused_at,
+  paused_by = excluded.paused_by,
+  draining = excluded.draining,
+  draining_since = excluded.draining_since,
+  draining_by = excluded.draining_by,
+  updated_at = excluded.updated_at
+"#,
+    )
+    .bind(if control.paused { 1 } else { 0 })
+    .bind(control.paused_at.as_deref())
+    .bind(control.paused_by.as_deref())
+    .bind(if control.draining { 1 } else { 0 })
+    .bind(control.draining_since.as_deref())
+    .bind(control.draining_by.as_deref())
+    .bind(now_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+async fn delete_queue_entry(db: &SqlitePool, key: &TaskKey) {
+    let res = sqlx::query("DELETE FROM ops_queue_tasks WHERE fid = ? AND gid_key = ?")
+        .bind(&key.fid)
+        .bind(key.gid.as_deref().unwrap_or_default())
+        .execute(db)
+        .await;
+    if let Err(err) = res {
+        warn!(fid = key.fid, error = %err, "delete ops queue entry failed");
+    }
 }
 
 fn remove_task_completion(st: &mut RuntimeState, key: &TaskKey) -> TaskCompletion {
@@ -2957,10 +5826,13 @@ fn remove_task_completion(st: &mut RuntimeState, key: &TaskKey) -> TaskCompletio
             serde_json::to_string(&entry.reason_counts).unwrap_or_else(|_| "{}".to_string())
         })
         .unwrap_or_else(|| "{}".to_string());
+    let attempt = task.as_ref().map(|entry| entry.attempt).unwrap_or(1);
     let joiners = task.map(|entry| entry.joiners).unwrap_or_default();
     TaskCompletion {
         reason_counts_json,
         joiners,
+        attempt,
+        retry: None,
     }
 }
 
@@ -3005,6 +5877,76 @@ struct OpsLogPayload {
     meta: Option<serde_json::Value>,
 }
 
+/// Reachability verdict for a failed fetch: transport errors and 5xx answers point at an
+/// outage, markup drift and other answers prove the shop is up, the rest says nothing.
+/// Error code of a failed cart fetch; retry policies match on these.
+fn fetch_error_code(err: &anyhow::Error) -> &'static str {
+    if err
+        .downcast_ref::<crate::upstream_limiter::ThrottledError>()
+        .is_some()
+    {
+        return "upstream_throttled";
+    }
+    if err.downcast_ref::<crate::proxy::ProxyError>().is_some() {
+        return "proxy_error";
+    }
+    if let Some(http) = err.downcast_ref::<crate::upstream::UpstreamHttpError>() {
+        return if http.status >= 500 {
+            "upstream_5xx"
+        } else {
+            "upstream_http"
+        };
+    }
+    if let Some(req) = err.downcast_ref::<reqwest::Error>() {
+        if req.is_timeout() {
+            return "upstream_timeout";
+        }
+        if req.is_connect() {
+            return "upstream_connect";
+        }
+    }
+    "upstream_fetch"
+}
+
+fn fetch_outcome(err: &anyhow::Error) -> FetchOutcome {
+    if err
+        .downcast_ref::<crate::upstream::ParserDriftError>()
+        .is_some()
+    {
+        return FetchOutcome::Reachable;
+    }
+    if let Some(http) = err.downcast_ref::<crate::upstream::UpstreamHttpError>() {
+        return if http.status >= 500 {
+            FetchOutcome::Unreachable
+        } else {
+            FetchOutcome::Reachable
+        };
+    }
+    if err.downcast_ref::<reqwest::Error>().is_some() {
+        return FetchOutcome::Unreachable;
+    }
+    FetchOutcome::Inconclusive
+}
+
+/// Next `hour:00` UTC strictly after `now`.
+fn next_digest_at(now: OffsetDateTime, hour: u8) -> OffsetDateTime {
+    let today =
+        now.replace_time(time::Time::from_hms(hour.min(23), 0, 0).unwrap_or(time::Time::MIDNIGHT));
+    if today > now {
+        today
+    } else {
+        today + time::Duration::days(1)
+    }
+}
+
+fn format_outage_duration(seconds: i64) -> String {
+    match seconds {
+        s if s >= 3600 => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m{}s", s / 60, s % 60),
+        s => format!("{s}s"),
+    }
+}
+
 fn now_rfc3339() -> String {
     OffsetDateTime::now_utc()
         .format(&Rfc3339)
@@ -3065,6 +6007,7 @@ fn upsert_region_notice_in_snapshot(
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpsTaskKeyView {
+    pub provider_id: String,
     pub fid: String,
     pub gid: Option<String>,
 }
@@ -3074,9 +6017,68 @@ pub struct OpsTaskKeyView {
 pub struct OpsQueueView {
     pub pending: i64,
     pub running: i64,
+    /// Failed tasks waiting out their retry backoff.
+    #[serde(default)]
+    pub retry_scheduled: i64,
     pub deduped: i64,
     pub oldest_wait_seconds: Option<i64>,
     pub reason_counts: HashMap<String, i64>,
+    /// Depth and wait per priority lane, highest lane first.
+    #[serde(default)]
+    pub lanes: Vec<OpsLaneView>,
+    #[serde(default)]
+    pub control: OpsControlView,
+}
+
+/// Operator controls of the queue. While paused, workers finish their current task and take no
+/// new ones; while draining, new keys are refused and the queue pauses itself once empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsControlView {
+    pub paused: bool,
+    pub paused_at: Option<String>,
+    pub paused_by: Option<String>,
+    pub draining: bool,
+    pub draining_since: Option<String>,
+    pub draining_by: Option<String>,
+}
+
+/// Active worker limit; workers above it show as `parked`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsConcurrencyView {
+    pub adaptive: bool,
+    pub limit: usize,
+    pub min: usize,
+    pub max: usize,
+    pub last_change: Option<OpsConcurrencyChangeView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsConcurrencyChangeView {
+    pub at: String,
+    #[serde(flatten)]
+    pub change: ConcurrencyChange,
+}
+
+/// Outcome of canceling queued tasks: running tasks are left alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsCancelView {
+    pub canceled: Vec<OpsTaskKeyView>,
+    pub running: Vec<OpsTaskKeyView>,
+    pub not_found: Vec<OpsTaskKeyView>,
+    pub queue: OpsQueueView,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsLaneView {
+    pub lane: String,
+    pub pending: i64,
+    pub running: i64,
+    pub oldest_wait_seconds: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3096,6 +6098,12 @@ pub struct OpsWorkerView {
     pub last_error: Option<OpsWorkerErrorView>,
 }
 
+#[derive(Debug, Clone)]
+pub struct OpsRuntimeView {
+    pub queue: OpsQueueView,
+    pub workers: Vec<OpsWorkerView>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpsTaskLastRunView {
@@ -3111,6 +6119,31 @@ pub struct OpsTaskView {
     pub enqueued_at: String,
     pub reason_counts: HashMap<String, i64>,
     pub last_run: Option<OpsTaskLastRunView>,
+    #[serde(default = "default_attempt")]
+    pub attempt: u32,
+    /// Set while `state` is `retry_scheduled`.
+    #[serde(default)]
+    pub retry_at: Option<String>,
+    /// Set while the partition is in hot mode.
+    #[serde(default)]
+    pub hot: Option<OpsHotModeView>,
+}
+
+/// Burst polling window of one partition, opened by a monitored restock or a new listing in a
+/// monitored partition. Re-triggering extends `until`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsHotModeView {
+    /// `restock` or `listed`.
+    pub trigger: String,
+    pub config_ids: Vec<String>,
+    pub started_at: String,
+    pub until: String,
+    pub interval_seconds: u64,
+}
+
+fn default_attempt() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3121,6 +6154,9 @@ pub struct OpsRateBucketView {
     pub failure: i64,
     pub success_rate_pct: f64,
     pub cache_hits: i64,
+    /// Fetches answered 304 or with an unchanged body, which skipped parsing and catalog writes.
+    #[serde(default)]
+    pub not_modified: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3165,11 +6201,24 @@ pub struct OpsStateSnapshot {
     pub replay_window_seconds: i64,
     pub queue: OpsQueueView,
     pub workers: Vec<OpsWorkerView>,
+    #[serde(default)]
+    pub concurrency: OpsConcurrencyView,
     pub tasks: Vec<OpsTaskView>,
     pub stats: OpsStatsView,
     pub sparks: OpsSparksView,
     pub log_tail: Vec<OpsLogEntryView>,
     pub topology: OpsTopologyView,
+    /// Health rule alerts still open (see [`OpsManager::check_health`]).
+    #[serde(default)]
+    pub health_alerts: Vec<crate::models::OpsHealthAlertView>,
+    /// Upstream hosts seen by the rate limiter, with their current tokens and backoff.
+    #[serde(default)]
+    pub rate_limits: Vec<crate::upstream_limiter::UpstreamHostLimitView>,
+    #[serde(default)]
+    pub circuits: Vec<crate::upstream_breaker::UpstreamCircuitView>,
+    /// This instance's standing in the leader lease (see [`crate::leader`]).
+    #[serde(default)]
+    pub leader: crate::leader::LeaderView,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3183,7 +6232,7 @@ pub struct OpsSparksView {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use axum::{
         extract::Query,
@@ -3198,7 +6247,7 @@ mod tests {
     use std::sync::{Arc, Mutex};
     use time::format_description::well_known::Rfc3339;
 
-    fn test_config(upstream_cart_url: String) -> RuntimeConfig {
+    pub(crate) fn test_config(upstream_cart_url: String) -> RuntimeConfig {
         RuntimeConfig {
             bind_addr: "127.0.0.1:0".to_string(),
             effective_version: "test".to_string(),
@@ -3209,13 +6258,32 @@ mod tests {
             update_check_timeout_ms: 1500,
             github_api_base_url: "https://api.github.com".to_string(),
             upstream_cart_url,
+            upstream_providers: Vec::new(),
+            upstream_archive_mode: crate::config::UpstreamArchiveMode::Off,
+            upstream_archive_dir: "upstream-archive".to_string(),
+            upstream_rate_per_second: 2.0,
+            upstream_rate_burst: 1,
+            upstream_proxies: Vec::new(),
+            upstream_breaker_failure_threshold: 5,
+            upstream_breaker_open_seconds: 60,
+            upstream_billing_cycles_enabled: false,
+            upstream_billing_cycles_refresh_seconds: 3600,
+            price_base_currency: "CNY".to_string(),
+            exchange_rates: Default::default(),
+            exchange_rates_file: None,
             lazycat_base_url: "https://lxc.lazycat.wiki".to_string(),
             lazycat_site_sync_interval_minutes: 5,
             lazycat_panel_sync_interval_minutes: 10,
             lazycat_panel_concurrency: 2,
             lazycat_panel_timeout_ms: 5_000,
             lazycat_allow_invalid_tls: true,
+            lazycat_proxy: None,
+            telegram_proxy: None,
+            github_proxy: None,
             telegram_api_base_url: "https://api.telegram.org".to_string(),
+            operator_telegram_bot_token: None,
+            operator_telegram_targets: Vec::new(),
+            operator_web_push_user_ids: Vec::new(),
             auth_user_header: Some("x-user".to_string()),
             dev_user_id: None,
             default_poll_interval_minutes: 1,
@@ -3225,10 +6293,36 @@ mod tests {
             notification_retention_days: 30,
             notification_retention_max_rows: 10_000,
             ops_worker_concurrency: 1,
+            ops_adaptive_concurrency: false,
+            ops_worker_concurrency_min: 1,
+            ops_worker_concurrency_max: 1,
+            ops_adaptive_latency_target_ms: 3000,
             ops_sse_replay_window_seconds: 3600,
             ops_log_retention_days: 7,
             ops_log_tail_limit_default: 200,
             ops_queue_task_limit_default: 200,
+            ops_lane_starvation_seconds: 60,
+            ops_retry_policies: Default::default(),
+            ops_retryable_error_codes: Vec::new(),
+            metrics_token: None,
+            otlp_endpoint: None,
+            otlp_service_name: "catnap".to_string(),
+            ops_admin_user_ids: Vec::new(),
+            ops_health_check_interval_seconds: 60,
+            ops_health_success_rate_min_pct: None,
+            ops_health_success_rate_window_minutes: 30,
+            ops_health_queue_depth_max: None,
+            ops_health_worker_error_minutes: None,
+            ops_health_no_success_minutes: None,
+            ops_digest_hour_utc: None,
+            hot_mode_minutes: 0,
+            hot_mode_interval_seconds: 15,
+            hot_mode_max_partitions: 3,
+            poll_forecast_enabled: false,
+            poll_forecast_dense_minutes: 1,
+            poll_forecast_sparse_factor: 2,
+            leader_lease_seconds: 0,
+            instance_id: None,
             db_url: "sqlite::memory:".to_string(),
             web_push_vapid_public_key: None,
             web_push_vapid_private_key: None,
@@ -3237,7 +6331,7 @@ mod tests {
         }
     }
 
-    async fn spawn_stub_server(app: Router) -> String {
+    pub(crate) async fn spawn_stub_server(app: Router) -> String {
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
         let addr = listener.local_addr().unwrap();
         tokio::spawn(async move {
@@ -3246,7 +6340,7 @@ mod tests {
         format!("http://{}", addr)
     }
 
-    async fn build_ops_manager_with_config(
+    pub(crate) async fn build_ops_manager_with_config(
         cfg: RuntimeConfig,
         upstream_cart_url: String,
     ) -> (OpsManager, SqlitePool) {
@@ -3284,6 +6378,7 @@ mod tests {
                 telegram_status: "pending".to_string(),
                 web_push_status: "skipped".to_string(),
                 items: Vec::new(),
+                matched_rule: None,
             },
         )
         .await
@@ -3304,10 +6399,11 @@ mod tests {
         .await
         .unwrap();
 
-        let record = crate::db::get_notification_record(&db, "u_1", &record_id)
-            .await
-            .unwrap()
-            .unwrap();
+        let record =
+            crate::db::get_notification_record(&db, &Default::default(), "u_1", &record_id)
+                .await
+                .unwrap()
+                .unwrap();
         assert_eq!(record.telegram_status, "error");
         assert_eq!(record.telegram_deliveries.len(), 1);
         assert_eq!(record.telegram_deliveries[0].target, "(config)");
@@ -3339,13 +6435,121 @@ mod tests {
         );
     }
 
+    fn pending_state(now: std::time::Instant, tasks: &[(&str, &str, u64)]) -> RuntimeState {
+        let mut st = RuntimeState {
+            deduped: 0,
+            lanes: Default::default(),
+            tasks: HashMap::new(),
+            workers: Vec::new(),
+            persisting: false,
+            control: OpsControlView::default(),
+            concurrency: ConcurrencyController::fixed(1),
+            last_concurrency_change: None,
+            hot: HashMap::new(),
+        };
+        for (fid, reason, waited_secs) in tasks {
+            let key = TaskKey::scoped(fid, None);
+            let lane = OpsLane::for_reason(reason);
+            st.lanes[lane as usize].push_back(key.clone());
+            st.tasks.insert(
+                key,
+                TaskEntry {
+                    state: TaskEntryState::Pending,
+                    lane,
+                    queued_at: now - std::time::Duration::from_secs(*waited_secs),
+                    enqueued_at: "2026-03-11T00:00:00Z".to_string(),
+                    reason_counts: HashMap::from([(reason.to_string(), 1)]),
+                    force_fetch: false,
+                    joiners: Vec::new(),
+                    poller_waiter_user_ids: HashSet::new(),
+                    attempt: 1,
+                    trace_origins: Vec::new(),
+                },
+            );
+        }
+        st
+    }
+
+    #[tokio::test]
+    async fn staged_queue_writes_land_in_the_order_they_were_staged() {
+        // Not started, so restoring the persisted queue cannot interleave with these writes.
+        let cfg = test_config("http://127.0.0.1:9/cart".to_string());
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
+            .await
+            .unwrap();
+        crate::db::init_db(&db).await.unwrap();
+        let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+            cfg.upstream_cart_url.clone(),
+        )));
+        let ops = OpsManager::new(cfg, db.clone(), catalog);
+        let mut st = pending_state(std::time::Instant::now(), &[("7", "poller_due", 0)]);
+        let key = TaskKey::scoped("7", None);
+        let entry = st.tasks.get_mut(&key).unwrap();
+        ops.stage_queue_upsert(&key, entry);
+        ops.stage_queue_delete(&key);
+        entry.attempt = 2;
+        ops.stage_queue_upsert(&key, entry);
+        ops.flush_queue_writes().await;
+        let attempt = sqlx::query("SELECT attempt FROM ops_queue_tasks WHERE fid = '7'")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(attempt, 2);
+
+        ops.stage_queue_delete(&key);
+        ops.flush_queue_writes().await;
+        let left = sqlx::query("SELECT COUNT(*) FROM ops_queue_tasks")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(left, 0);
+    }
+
+    #[test]
+    fn pop_next_prefers_higher_lanes_and_ages_starved_tasks() {
+        let now = std::time::Instant::now();
+        let starvation = std::time::Duration::from_secs(60);
+        let mut st = pending_state(
+            now,
+            &[
+                ("d1", "discovery_due", 30),
+                ("p1", "poller_due", 10),
+                ("m1", "manual_refresh", 0),
+                ("d2", "discovery_due", 200),
+            ],
+        );
+        let view = st.queue_view(OffsetDateTime::now_utc());
+        assert_eq!(
+            view.lanes
+                .iter()
+                .map(|l| l.lane.as_str())
+                .collect::<Vec<_>>(),
+            vec!["interactive", "poller", "discovery"]
+        );
+        assert_eq!(view.lanes[2].pending, 2);
+
+        // d1 heads the discovery lane with 30s waited, so it only goes after the poller task.
+        let order = std::iter::from_fn(|| st.pop_next(now, starvation))
+            .map(|key| key.fid)
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec!["m1", "p1", "d1", "d2"]);
+
+        // A discovery task waiting two starvation periods overtakes a fresh manual refresh.
+        let mut st = pending_state(
+            now,
+            &[("m1", "manual_refresh", 0), ("d1", "discovery_due", 130)],
+        );
+        assert_eq!(st.pop_next(now, starvation).unwrap().fid, "d1");
+    }
+
     #[tokio::test]
     async fn current_lifecycle_notify_state_reads_latest_joiners_and_reasons() {
         let (ops, _db) = build_ops_manager("https://example.invalid/cart".to_string()).await;
-        let key = TaskKey {
-            fid: "7".to_string(),
-            gid: Some("40".to_string()),
-        };
+        let key = TaskKey::scoped("7", Some("40".to_string()));
         let fallback_reason_counts = HashMap::from([("poller_due".to_string(), 1_i64)]);
         let fallback_waiters = HashSet::new();
 
@@ -3358,11 +6562,15 @@ mod tests {
                         run_id: 42,
                         started_at: "2026-03-11T00:00:00Z".to_string(),
                     },
+                    lane: OpsLane::Poller,
+                    queued_at: std::time::Instant::now(),
                     enqueued_at: "2026-03-11T00:00:00Z".to_string(),
                     reason_counts: fallback_reason_counts.clone(),
                     force_fetch: false,
                     joiners: Vec::new(),
                     poller_waiter_user_ids: HashSet::new(),
+                    attempt: 1,
+                    trace_origins: Vec::new(),
                 },
             );
         }
@@ -3403,75 +6611,1078 @@ mod tests {
             get(move |Query(q): Query<CartQuery>| {
                 let hits_for_handler = hits_for_handler.clone();
                 async move {
-                    hits_for_handler.fetch_add(1, Ordering::SeqCst);
-                    match (q.fid.as_deref(), q.gid.as_deref()) {
-                        (Some("2"), Some("56")) => (
-                            StatusCode::OK,
-                            include_str!("../tests/fixtures/cart-fid-2-gid-56.html"),
-                        ),
-                        _ => (StatusCode::NOT_FOUND, "not found"),
-                    }
+                    hits_for_handler.fetch_add(1, Ordering::SeqCst);
+                    match (q.fid.as_deref(), q.gid.as_deref()) {
+                        (Some("2"), Some("56")) => (
+                            StatusCode::OK,
+                            include_str!("../tests/fixtures/cart-fid-2-gid-56.html"),
+                        ),
+                        _ => (StatusCode::NOT_FOUND, "not found"),
+                    }
+                }
+            }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let (ops, db) = build_ops_manager(format!("{base}/cart")).await;
+
+        let now = OffsetDateTime::now_utc().format(&Rfc3339).unwrap();
+        sqlx::query(
+            "INSERT INTO catalog_url_cache (url_key, url, config_ids_json, last_success_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind("2:56")
+        .bind(format!("{base}/cart?fid=2&gid=56"))
+        .bind("[]")
+        .bind(&now)
+        .bind(&now)
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let entered = Arc::new(Notify::new());
+        let release = Arc::new(Notify::new());
+        set_cache_hit_test_hook(Some(CacheHitTestHook {
+            entered: entered.clone(),
+            release: release.clone(),
+        }))
+        .await;
+
+        ops.enqueue_background("2", Some("56"), "poller_due")
+            .await
+            .unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(2), entered.notified())
+            .await
+            .unwrap();
+
+        let ops_for_force = ops.clone();
+        let force_task = tokio::spawn(async move {
+            ops_for_force
+                .enqueue_and_wait_force_fetch("2", Some("56"), "manual_refresh")
+                .await
+                .unwrap()
+        });
+        tokio::task::yield_now().await;
+        release.notify_waiters();
+
+        let outcome = tokio::time::timeout(std::time::Duration::from_secs(5), force_task)
+            .await
+            .unwrap()
+            .unwrap();
+        set_cache_hit_test_hook(None).await;
+
+        assert!(outcome.ok);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let row = sqlx::query(
+            "SELECT fetch_action, cache_hit FROM ops_task_runs ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(row.get::<String, _>(0), "fetch");
+        assert_eq!(row.get::<i64, _>(1), 0);
+    }
+
+    #[tokio::test]
+    async fn unchanged_page_is_reported_as_not_modified() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_for_handler = hits.clone();
+        let upstream = Router::new().route(
+            "/cart",
+            get(move |headers: axum::http::HeaderMap| {
+                let hits_for_handler = hits_for_handler.clone();
+                async move {
+                    hits_for_handler.fetch_add(1, Ordering::SeqCst);
+                    if headers
+                        .get(axum::http::header::IF_NONE_MATCH)
+                        .is_some_and(|value| value == "\"v1\"")
+                    {
+                        return (StatusCode::NOT_MODIFIED, [("etag", "\"v1\"")], "");
+                    }
+                    (
+                        StatusCode::OK,
+                        [("etag", "\"v1\"")],
+                        include_str!("../tests/fixtures/cart-fid-2-gid-56.html"),
+                    )
+                }
+            }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let (ops, db) = build_ops_manager(format!("{base}/cart")).await;
+
+        let first = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+        assert!(first.ok);
+        let checked_at = sqlx::query("SELECT MAX(checked_at) FROM catalog_configs")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<Option<String>, _>(0);
+        assert!(checked_at.is_some());
+
+        let second = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+        assert!(second.ok);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let run = sqlx::query(
+            "SELECT fetch_action, fetch_http_status, cache_hit, parse_produced_configs FROM ops_task_runs WHERE id = ?",
+        )
+        .bind(second.run_id)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(run.get::<String, _>(0), "not_modified");
+        assert_eq!(run.get::<Option<i64>, _>(1), Some(304));
+        assert_eq!(run.get::<i64, _>(2), 0);
+        assert!(run.get::<Option<i64>, _>(3).unwrap_or(0) > 0);
+
+        let checked_after = sqlx::query("SELECT MAX(checked_at) FROM catalog_configs")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<Option<String>, _>(0);
+        assert_eq!(checked_after, checked_at);
+
+        let stats = ops
+            .stats(OpsRange::H24, OffsetDateTime::now_utc())
+            .await
+            .unwrap();
+        assert_eq!(stats.collection.not_modified, 1);
+    }
+
+    #[tokio::test]
+    async fn unchanged_page_still_refreshes_stale_billing_cycles() {
+        let scrapes = Arc::new(AtomicUsize::new(0));
+        let scrapes_for_handler = scrapes.clone();
+        let upstream = Router::new().route(
+            "/cart",
+            get(
+                move |Query(query): Query<HashMap<String, String>>,
+                      headers: axum::http::HeaderMap| {
+                    let scrapes_for_handler = scrapes_for_handler.clone();
+                    async move {
+                        if query.get("action").map(String::as_str) == Some("configureproduct") {
+                            scrapes_for_handler.fetch_add(1, Ordering::SeqCst);
+                            return (StatusCode::OK, [("etag", "\"p\"")], "<html></html>");
+                        }
+                        if headers
+                            .get(axum::http::header::IF_NONE_MATCH)
+                            .is_some_and(|value| value == "\"v1\"")
+                        {
+                            return (StatusCode::NOT_MODIFIED, [("etag", "\"v1\"")], "");
+                        }
+                        (
+                            StatusCode::OK,
+                            [("etag", "\"v1\"")],
+                            include_str!("../tests/fixtures/cart-fid-2-gid-56.html"),
+                        )
+                    }
+                },
+            ),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let mut cfg = test_config(format!("{base}/cart"));
+        cfg.upstream_billing_cycles_enabled = true;
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url).await;
+
+        assert!(
+            ops.enqueue_and_wait("2", Some("56"), "test")
+                .await
+                .unwrap()
+                .ok
+        );
+        let first_scrapes = scrapes.load(Ordering::SeqCst);
+        assert!(first_scrapes > 0);
+
+        // Still within the refresh window: the 304 run leaves the cycles alone.
+        let second = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+        assert!(second.ok);
+        assert_eq!(scrapes.load(Ordering::SeqCst), first_scrapes);
+
+        sqlx::query("UPDATE catalog_configs SET billing_cycles_checked_at = NULL")
+            .execute(&db)
+            .await
+            .unwrap();
+        let third = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+        assert!(third.ok);
+        let action = sqlx::query("SELECT fetch_action FROM ops_task_runs WHERE id = ?")
+            .bind(third.run_id)
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<String, _>(0);
+        assert_eq!(action, "not_modified");
+        assert_eq!(scrapes.load(Ordering::SeqCst), first_scrapes * 2);
+    }
+
+    #[tokio::test]
+    async fn retryable_failures_are_rescheduled_with_attempt_numbers() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_for_handler = hits.clone();
+        let upstream = Router::new().route(
+            "/cart",
+            get(move || {
+                let hits_for_handler = hits_for_handler.clone();
+                async move {
+                    if hits_for_handler.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (StatusCode::SERVICE_UNAVAILABLE, "busy")
+                    } else {
+                        (
+                            StatusCode::OK,
+                            include_str!("../tests/fixtures/cart-fid-2-gid-56.html"),
+                        )
+                    }
+                }
+            }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let mut cfg = test_config(format!("{base}/cart"));
+        cfg.ops_retry_policies =
+            crate::config::parse_ops_retry_policies("poller=2:1:1,interactive=1:0:0");
+        cfg.ops_retryable_error_codes = vec!["upstream_5xx".to_string()];
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url).await;
+
+        let first = ops
+            .enqueue_and_wait("2", Some("56"), "poller_due")
+            .await
+            .unwrap();
+        assert!(!first.ok);
+        let snapshot = ops.snapshot(OpsRange::H24, None, None).await.unwrap();
+        assert_eq!(snapshot.queue.retry_scheduled, 1);
+        assert_eq!(snapshot.tasks.len(), 1);
+        assert_eq!(snapshot.tasks[0].state, "retry_scheduled");
+        assert_eq!(snapshot.tasks[0].attempt, 2);
+        assert!(snapshot.tasks[0].retry_at.is_some());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let runs = loop {
+            let runs = sqlx::query(
+                "SELECT attempt, ok, error_code FROM ops_task_runs WHERE ended_at IS NOT NULL ORDER BY id",
+            )
+            .fetch_all(&db)
+            .await
+            .unwrap();
+            if runs.len() >= 2 || std::time::Instant::now() > deadline {
+                break runs;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        };
+        let runs = runs
+            .iter()
+            .map(|row| {
+                (
+                    row.get::<i64, _>(0),
+                    row.get::<i64, _>(1),
+                    row.get::<Option<String>, _>(2),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            runs,
+            vec![(1, 0, Some("upstream_5xx".to_string())), (2, 1, None)]
+        );
+
+        // Interactive refreshes fail fast with the default single attempt.
+        hits.store(0, Ordering::SeqCst);
+        let manual = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+        assert!(!manual.ok);
+        let snapshot = ops.snapshot(OpsRange::H24, None, None).await.unwrap();
+        assert!(snapshot.tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn poller_hears_the_first_failure_while_the_retry_waits_out_its_backoff() {
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async { (StatusCode::SERVICE_UNAVAILABLE, "busy") }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let mut cfg = test_config(format!("{base}/cart"));
+        cfg.ops_retry_policies = crate::config::parse_ops_retry_policies("poller=3:600:600");
+        cfg.ops_retryable_error_codes = vec!["upstream_5xx".to_string()];
+        let (ops, _db) = build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url).await;
+
+        let failed = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            ops.enqueue_and_wait_for_poller("2", Some("56"), "u_1"),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(!failed.ok);
+
+        // The retry stays queued, but no longer counts the poller as waiting on it.
+        let st = ops.inner.state.lock().await;
+        let entry = st.tasks.values().next().unwrap();
+        assert!(matches!(entry.state, TaskEntryState::RetryScheduled { .. }));
+        assert_eq!(entry.attempt, 2);
+        assert!(entry.poller_waiter_user_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn additional_provider_tasks_write_rows_tagged_with_that_provider() {
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async { include_str!("../tests/fixtures/cart-fid-7.html") }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let mut cfg = test_config("http://127.0.0.1:9/cart".to_string());
+        cfg.upstream_providers = vec![crate::config::UpstreamProviderConfig {
+            id: "shopa".to_string(),
+            cart_url: format!("{base}/cart"),
+        }];
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url).await;
+
+        let run = ops
+            .enqueue_and_wait("shopa:7", Some("40"), "manual_refresh")
+            .await
+            .unwrap();
+        assert!(run.ok);
+        let rows = sqlx::query(
+            "SELECT id, provider_id, country_id, source_fid FROM catalog_configs ORDER BY id",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert!(!rows.is_empty());
+        for row in rows {
+            assert!(row.get::<String, _>(0).starts_with("shopa:7:40:"));
+            assert_eq!(row.get::<String, _>(1), "shopa");
+            assert_eq!(row.get::<String, _>(2), "shopa:7");
+            assert_eq!(row.get::<Option<String>, _>(3).as_deref(), Some("7"));
+        }
+        let provider = sqlx::query("SELECT provider_id FROM ops_task_runs WHERE id = ?")
+            .bind(run.run_id)
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<String, _>(0);
+        assert_eq!(provider, "shopa");
+    }
+
+    #[tokio::test]
+    async fn provider_without_a_client_fails_only_its_own_tasks() {
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async { include_str!("../tests/fixtures/cart-fid-2-gid-56.html") }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let mut cfg = test_config(format!("{base}/cart"));
+        cfg.upstream_providers = vec![crate::config::UpstreamProviderConfig {
+            id: "shopa".to_string(),
+            cart_url: "ftp://127.0.0.1/cart".to_string(),
+        }];
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url).await;
+
+        let broken = ops
+            .enqueue_and_wait("shopa:7", Some("40"), "manual_refresh")
+            .await
+            .unwrap();
+        assert!(!broken.ok);
+        let code = sqlx::query("SELECT error_code FROM ops_task_runs WHERE id = ?")
+            .bind(broken.run_id)
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<Option<String>, _>(0);
+        assert_eq!(code.as_deref(), Some("upstream_init_failed"));
+
+        let healthy = ops
+            .enqueue_and_wait("2", Some("56"), "manual_refresh")
+            .await
+            .unwrap();
+        assert!(healthy.ok);
+        assert!(ops
+            .inner
+            .state
+            .lock()
+            .await
+            .workers
+            .iter()
+            .all(|worker| worker.last_error.is_none()));
+    }
+
+    #[tokio::test]
+    async fn persisted_queue_is_restored_and_interrupted_runs_are_closed() {
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async { include_str!("../tests/fixtures/cart-fid-2-gid-56.html") }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let cfg = test_config(format!("{base}/cart"));
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
+            .await
+            .unwrap();
+        crate::db::init_db(&db).await.unwrap();
+
+        // State left behind by a process that died while fetching fid=2/gid=56.
+        let run_id = sqlx::query(
+            r#"
+INSERT INTO ops_task_runs (provider_id, fid, gid, started_at, ended_at, ok, reason_counts_json)
+VALUES ('lazycat', '2', '56', '2026-01-01T00:00:00Z', NULL, 0, '{"poller_due":1}')
+"#,
+        )
+        .execute(&db)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        sqlx::query(
+            r#"
+INSERT INTO ops_queue_tasks (
+  fid, gid_key, gid, provider_id, state, enqueued_at, reason_counts_json, force_fetch,
+  run_id, started_at, updated_at
+)
+VALUES ('2', '56', '56', 'lazycat', 'running', '2026-01-01T00:00:00Z', '{"poller_due":1}', 1,
+        ?, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')
+"#,
+        )
+        .bind(run_id)
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+            cfg.upstream_cart_url.clone(),
+        )));
+        let ops = OpsManager::new(cfg, db.clone(), catalog);
+        ops.start();
+
+        let run = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+        assert!(run.ok);
+        let reasons = sqlx::query("SELECT reason_counts_json FROM ops_task_runs WHERE id = ?")
+            .bind(run.run_id)
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<String, _>(0);
+        let reasons: HashMap<String, i64> = serde_json::from_str(&reasons).unwrap();
+        assert_eq!(reasons.get("poller_due"), Some(&1));
+        assert_eq!(reasons.get("test"), Some(&1));
+
+        let interrupted =
+            sqlx::query("SELECT ended_at, error_code FROM ops_task_runs WHERE id = ?")
+                .bind(run_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert!(interrupted.get::<Option<String>, _>(0).is_some());
+        assert_eq!(
+            interrupted.get::<Option<String>, _>(1).as_deref(),
+            Some("interrupted")
+        );
+        let left = sqlx::query("SELECT COUNT(*) FROM ops_queue_tasks")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(left, 0);
+    }
+
+    #[tokio::test]
+    async fn only_the_lease_holder_runs_the_queue_until_a_follower_takes_over() {
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async { include_str!("../tests/fixtures/cart-fid-2-gid-56.html") }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let mut cfg = test_config(format!("{base}/cart"));
+        cfg.leader_lease_seconds = 30;
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
+            .await
+            .unwrap();
+        crate::db::init_db(&db).await.unwrap();
+        let instance = |id: &str| {
+            let mut cfg = cfg.clone();
+            cfg.instance_id = Some(id.to_string());
+            let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+                cfg.upstream_cart_url.clone(),
+            )));
+            OpsManager::new(cfg, db.clone(), catalog)
+        };
+
+        let first = instance("first");
+        first.start();
+        first.leader().wait_leader().await;
+        let second = instance("second");
+        second.start();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let lease = second
+            .snapshot(OpsRange::H24, None, None)
+            .await
+            .unwrap()
+            .leader;
+        assert!(lease.enabled);
+        assert!(!lease.leader);
+        assert_eq!(lease.instance_id, "second");
+        assert_eq!(lease.holder.as_deref(), Some("first"));
+        let lease = first
+            .snapshot(OpsRange::H24, None, None)
+            .await
+            .unwrap()
+            .leader;
+        assert!(lease.leader);
+
+        // The follower hands work to the leader and gets the leader's run back.
+        let forwarded = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            second.enqueue_and_wait("2", Some("56"), "test"),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(forwarded.ok);
+        assert_eq!(second.runtime_view().await.queue.pending, 0);
+        let (run_fid, reasons) = sqlx::query_as::<_, (String, String)>(
+            "SELECT fid, reason_counts_json FROM ops_task_runs WHERE id = ?",
+        )
+        .bind(forwarded.run_id)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(run_fid, "2");
+        assert!(reasons.contains("test"));
+        let left = sqlx::query("SELECT COUNT(*) FROM ops_queue_requests")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(left, 0);
+
+        // What the leader applied reaches the follower's catalog on its next heartbeat.
+        let applied_at = first.inner.catalog.read().await.fetched_at.clone();
+        assert_ne!(second.inner.catalog.read().await.fetched_at, applied_at);
+        assert_eq!(
+            second.renew_leader_lease(OffsetDateTime::now_utc()).await,
+            None
+        );
+        assert_eq!(second.inner.catalog.read().await.fetched_at, applied_at);
+
+        first.leader().release().await.unwrap();
+        assert!(!first.leader().is_leader());
+        assert_eq!(
+            second.renew_leader_lease(OffsetDateTime::now_utc()).await,
+            Some(LeadershipChange::Gained)
+        );
+        let local = second
+            .enqueue_and_wait("2", Some("56"), "test")
+            .await
+            .unwrap();
+        assert!(local.ok);
+        assert!(local.run_id > forwarded.run_id);
+        let logged = sqlx::query("SELECT COUNT(*) FROM ops_events WHERE event = 'ops.leader'")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(logged, 2);
+    }
+
+    #[tokio::test]
+    async fn forwarded_wait_ends_when_the_request_disappears() {
+        let mut cfg = test_config("http://127.0.0.1:9/cart".to_string());
+        cfg.leader_lease_seconds = 30;
+        cfg.instance_id = Some("follower".to_string());
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
+            .await
+            .unwrap();
+        crate::db::init_db(&db).await.unwrap();
+        let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+            cfg.upstream_cart_url.clone(),
+        )));
+        // Never started, so it stays a follower with no leader to answer.
+        let follower = OpsManager::new(cfg, db.clone(), catalog);
+
+        let waiting = tokio::spawn({
+            let follower = follower.clone();
+            async move { follower.enqueue_and_wait("2", Some("56"), "test").await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        // A leader that claimed the request died, and cleanup took the row.
+        sqlx::query("DELETE FROM ops_queue_requests")
+            .execute(&db)
+            .await
+            .unwrap();
+        let res = tokio::time::timeout(std::time::Duration::from_secs(3), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn follower_admin_actions_and_queue_views_go_through_the_leader() {
+        let mut cfg = test_config("http://127.0.0.1:9/cart".to_string());
+        cfg.leader_lease_seconds = 30;
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
+            .await
+            .unwrap();
+        crate::db::init_db(&db).await.unwrap();
+        let instance = |id: &str| {
+            let mut cfg = cfg.clone();
+            cfg.instance_id = Some(id.to_string());
+            let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+                cfg.upstream_cart_url.clone(),
+            )));
+            OpsManager::new(cfg, db.clone(), catalog)
+        };
+        let leader = instance("leader");
+        leader.start();
+        leader.leader().wait_leader().await;
+        let follower = instance("follower");
+        follower.start();
+        for _ in 0..50 {
+            if leader.inner.state.lock().await.persisting {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        let control = follower.pause("admin").await.unwrap();
+        assert!(control.paused);
+        assert!(leader.inner.state.lock().await.control.paused);
+        assert!(!follower.inner.state.lock().await.control.paused);
+
+        std::mem::drop(
+            leader
+                .enqueue("2", Some("57"), "test", false, None)
+                .await
+                .unwrap(),
+        );
+        let queue = follower.runtime_view().await.queue;
+        assert_eq!(queue.pending, 1);
+        assert!(queue.control.paused);
+        let snapshot = follower.snapshot(OpsRange::H24, None, None).await.unwrap();
+        assert_eq!(snapshot.tasks.len(), 1);
+        assert_eq!(snapshot.tasks[0].key.gid.as_deref(), Some("57"));
+
+        let canceled = follower
+            .cancel_tasks("admin", &[("2".to_string(), Some("57".to_string()))])
+            .await
+            .unwrap();
+        assert_eq!(canceled.canceled.len(), 1);
+        assert!(leader.inner.state.lock().await.tasks.is_empty());
+        assert_eq!(follower.runtime_view().await.queue.pending, 0);
+
+        assert!(!follower.resume("admin").await.unwrap().paused);
+        assert!(!leader.inner.state.lock().await.control.paused);
+        let left = sqlx::query("SELECT COUNT(*) FROM ops_admin_requests")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(left, 0);
+    }
+
+    #[tokio::test]
+    async fn health_alerts_open_once_and_resolve_through_operator_channels() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_for_handler = hits.clone();
+        let telegram = Router::new().route(
+            "/bottoken/sendMessage",
+            post(move || {
+                let hits_for_handler = hits_for_handler.clone();
+                async move {
+                    hits_for_handler.fetch_add(1, Ordering::SeqCst);
+                    (StatusCode::OK, r#"{"ok":true}"#)
+                }
+            }),
+        );
+        let base = spawn_stub_server(telegram).await;
+        let upstream_cart_url = "https://example.invalid/cart".to_string();
+        let mut cfg = test_config(upstream_cart_url.clone());
+        cfg.telegram_api_base_url = base;
+        cfg.operator_telegram_bot_token = Some("token".to_string());
+        cfg.operator_telegram_targets = vec!["@ops".to_string()];
+        cfg.operator_web_push_user_ids = vec!["u_ops".to_string()];
+        cfg.ops_health_queue_depth_max = Some(1);
+        let (ops, db) = build_ops_manager_with_config(cfg, upstream_cart_url).await;
+
+        ops.pause("admin").await.unwrap();
+        ops.enqueue_background("7", Some("40"), "test")
+            .await
+            .unwrap();
+        ops.enqueue_background("7", Some("41"), "test")
+            .await
+            .unwrap();
+
+        let opened = ops.check_health().await.unwrap();
+        assert!(matches!(&opened[..], [HealthTransition::Opened(b)] if b.rule == "queue_depth"));
+        // Still breached: no second alert.
+        assert!(ops.check_health().await.unwrap().is_empty());
+        let snap = ops.snapshot(OpsRange::H24, None, None).await.unwrap();
+        assert_eq!(snap.health_alerts.len(), 1);
+        assert_eq!(snap.health_alerts[0].value, 2.0);
+
+        ops.clear_queue("admin").await.unwrap();
+        assert_eq!(
+            ops.check_health().await.unwrap(),
+            vec![HealthTransition::Resolved {
+                rule: "queue_depth".to_string()
+            }]
+        );
+        assert!(crate::db::list_open_ops_health_alerts(&db)
+            .await
+            .unwrap()
+            .is_empty());
+
+        for _ in 0..50 {
+            if hits.load(Ordering::SeqCst) >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        let phases = sqlx::query(
+            "SELECT json_extract(data_json, '$.phase') FROM ops_events WHERE event = 'ops.health' ORDER BY id",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.get::<String, _>(0))
+        .collect::<Vec<_>>();
+        assert_eq!(phases, vec!["opened", "resolved"]);
+        // The operator has no Web Push subscription yet; the miss is logged.
+        let mut web_push_misses = 0;
+        for _ in 0..50 {
+            web_push_misses = sqlx::query(
+                "SELECT COUNT(*) FROM ops_events WHERE event = 'ops.log' AND json_extract(data_json, '$.scope') = 'alert.webPush'",
+            )
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+            if web_push_misses >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(web_push_misses, 2);
+    }
+
+    #[test]
+    fn next_digest_at_is_the_next_matching_hour() {
+        use time::macros::datetime;
+        assert_eq!(
+            next_digest_at(datetime!(2026-03-10 00:30 UTC), 1),
+            datetime!(2026-03-10 01:00 UTC)
+        );
+        assert_eq!(
+            next_digest_at(datetime!(2026-03-10 01:00 UTC), 1),
+            datetime!(2026-03-11 01:00 UTC)
+        );
+    }
+
+    #[tokio::test]
+    async fn daily_digest_is_sent_to_operator_telegram() {
+        let texts = Arc::new(Mutex::new(Vec::<String>::new()));
+        let texts_for_handler = texts.clone();
+        let telegram = Router::new().route(
+            "/bottoken/sendMessage",
+            post(move |Json(body): Json<Value>| {
+                let texts_for_handler = texts_for_handler.clone();
+                async move {
+                    texts_for_handler
+                        .lock()
+                        .unwrap()
+                        .push(body["text"].as_str().unwrap_or_default().to_string());
+                    (StatusCode::OK, r#"{"ok":true}"#)
                 }
             }),
         );
-        let base = spawn_stub_server(upstream).await;
-        let (ops, db) = build_ops_manager(format!("{base}/cart")).await;
-
-        let now = OffsetDateTime::now_utc().format(&Rfc3339).unwrap();
+        let base = spawn_stub_server(telegram).await;
+        let upstream_cart_url = "https://example.invalid/cart".to_string();
+        let mut cfg = test_config(upstream_cart_url.clone());
+        cfg.telegram_api_base_url = base;
+        cfg.operator_telegram_bot_token = Some("token".to_string());
+        cfg.operator_telegram_targets = vec!["@ops".to_string()];
+        let (ops, db) = build_ops_manager_with_config(cfg, upstream_cart_url).await;
         sqlx::query(
-            "INSERT INTO catalog_url_cache (url_key, url, config_ids_json, last_success_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+            r#"
+INSERT INTO ops_task_runs (fid, gid, started_at, ended_at, ok, fetch_action, reason_counts_json, error_code)
+VALUES ('7', '40', '2026-03-10T12:00:00Z', '2026-03-10T12:00:01Z', 0, 'fetch', '{}', 'upstream_5xx')
+"#,
         )
-        .bind("2:56")
-        .bind(format!("{base}/cart?fid=2&gid=56"))
-        .bind("[]")
-        .bind(&now)
-        .bind(&now)
         .execute(&db)
         .await
         .unwrap();
 
-        let entered = Arc::new(Notify::new());
-        let release = Arc::new(Notify::new());
-        set_cache_hit_test_hook(Some(CacheHitTestHook {
-            entered: entered.clone(),
-            release: release.clone(),
-        }))
-        .await;
+        let digest = ops
+            .send_daily_digest(time::macros::datetime!(2026-03-11 01:00 UTC))
+            .await
+            .unwrap();
+        assert_eq!(digest.tasks.failure, 1);
 
-        ops.enqueue_background("2", Some("56"), "poller_due")
+        for _ in 0..50 {
+            if !texts.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let texts = texts.lock().unwrap().clone();
+        assert_eq!(texts.len(), 1);
+        assert!(texts[0].starts_with("【每日运维摘要】2026-03-10 01:00 UTC → 2026-03-11 01:00 UTC"));
+        assert!(texts[0].contains("失败：upstream_5xx 1"));
+        let events = sqlx::query("SELECT COUNT(*) FROM ops_events WHERE event = 'ops.digest'")
+            .fetch_one(&db)
             .await
             .unwrap();
-        tokio::time::timeout(std::time::Duration::from_secs(2), entered.notified())
+        assert_eq!(events.get::<i64, _>(0), 1);
+    }
+
+    #[tokio::test]
+    async fn admin_controls_drain_pause_cancel_and_survive_restart() {
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async {
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                include_str!("../tests/fixtures/cart-fid-2-gid-56.html")
+            }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let cfg = test_config(format!("{base}/cart"));
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
             .await
             .unwrap();
+        crate::db::init_db(&db).await.unwrap();
+        let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+            cfg.upstream_cart_url.clone(),
+        )));
 
-        let ops_for_force = ops.clone();
-        let force_task = tokio::spawn(async move {
-            ops_for_force
-                .enqueue_and_wait_force_fetch("2", Some("56"), "manual_refresh")
-                .await
-                .unwrap()
-        });
-        tokio::task::yield_now().await;
-        release.notify_waiters();
+        let ops = OpsManager::new(cfg.clone(), db.clone(), catalog.clone());
+        ops.start();
+        let running = ops
+            .enqueue("2", Some("56"), "test", false, None)
+            .await
+            .unwrap();
+        let control = ops.drain("admin").await.unwrap();
+        assert!(control.draining);
+        let refused = ops.enqueue("2", Some("57"), "test", false, None).await;
+        assert!(refused.is_err());
+        assert!(running.await.unwrap().ok);
+        let mut control = OpsControlView::default();
+        for _ in 0..50 {
+            control = ops.inner.state.lock().await.control.clone();
+            if control.paused {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(control.paused);
+        assert!(!control.draining);
+        assert_eq!(control.paused_by.as_deref(), Some("admin"));
+
+        // A second manager on the same database stands in for a restarted process.
+        let restarted = OpsManager::new(cfg, db.clone(), catalog);
+        restarted.start();
+        for _ in 0..50 {
+            if restarted.inner.state.lock().await.persisting {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(restarted.inner.state.lock().await.control.paused);
 
-        let outcome = tokio::time::timeout(std::time::Duration::from_secs(5), force_task)
+        let canceled = restarted
+            .enqueue("2", Some("57"), "test", false, None)
+            .await
+            .unwrap();
+        let cleared = restarted
+            .enqueue("2", Some("58"), "test", false, None)
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let queue = restarted.runtime_view().await.queue;
+        assert_eq!(queue.pending, 2);
+        assert_eq!(queue.running, 0);
+
+        let res = restarted
+            .cancel_tasks(
+                "admin",
+                &[
+                    ("2".to_string(), Some("57".to_string())),
+                    ("2".to_string(), Some("99".to_string())),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.canceled.len(), 1);
+        assert_eq!(res.not_found.len(), 1);
+        assert!(canceled.await.is_err());
+        let res = restarted.clear_queue("admin").await.unwrap();
+        assert_eq!(res.canceled.len(), 1);
+        assert_eq!(res.queue.pending, 0);
+        assert!(cleared.await.is_err());
+        let left = sqlx::query("SELECT COUNT(*) FROM ops_queue_tasks")
+            .fetch_one(&db)
             .await
             .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(left, 0);
+
+        restarted.resume("admin").await.unwrap();
+        let paused = sqlx::query("SELECT paused FROM ops_control_state WHERE id = 1")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(paused, 0);
+        let admin_events = sqlx::query("SELECT COUNT(*) FROM ops_events WHERE event = 'ops.admin'")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(admin_events, 5);
+    }
+
+    #[tokio::test]
+    async fn paused_queue_releases_and_refuses_poller_waiters() {
+        let cfg = test_config("http://127.0.0.1:9/cart".to_string());
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&cfg.db_url)
+            .await
             .unwrap();
-        set_cache_hit_test_hook(None).await;
+        crate::db::init_db(&db).await.unwrap();
+        let catalog = Arc::new(RwLock::new(CatalogSnapshot::empty(
+            cfg.upstream_cart_url.clone(),
+        )));
+        // Not started, so queued tasks stay queued.
+        let ops = OpsManager::new(cfg, db, catalog);
+
+        let queued = ops
+            .enqueue("2", Some("56"), "poller_due", false, Some("u_1"))
+            .await
+            .unwrap();
+        let manual = ops
+            .enqueue("2", Some("57"), "test", false, None)
+            .await
+            .unwrap();
+        ops.pause("admin").await.unwrap();
+        assert!(queued.await.is_err());
+        assert!(ops
+            .enqueue_and_wait_for_poller("2", Some("58"), "u_1")
+            .await
+            .is_err());
+
+        let st = ops.inner.state.lock().await;
+        assert_eq!(st.tasks.len(), 2);
+        assert!(st
+            .tasks
+            .values()
+            .all(|entry| entry.poller_waiter_user_ids.is_empty()));
+        drop(st);
+        // Other callers keep queueing and waiting through a pause.
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(100), manual)
+                .await
+                .is_err()
+        );
+    }
 
-        assert!(outcome.ok);
-        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    #[tokio::test]
+    async fn circuit_breaker_pauses_poller_tasks_and_records_the_outage() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
 
-        let row = sqlx::query(
-            "SELECT fetch_action, cache_hit FROM ops_task_runs ORDER BY id DESC LIMIT 1",
+        let mut cfg = test_config(format!("http://{addr}/cart"));
+        cfg.upstream_breaker_failure_threshold = 2;
+        cfg.upstream_breaker_open_seconds = 1;
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url).await;
+
+        for _ in 0..2 {
+            let run = ops.enqueue_and_wait("2", Some("56"), "test").await.unwrap();
+            assert!(!run.ok);
+        }
+        let outages = crate::db::list_upstream_outages(&db, Some("lazycat"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(outages.len(), 1);
+        assert_eq!(outages[0].ended_at, None);
+        assert_eq!(outages[0].failure_count, 2);
+
+        // A poller task waits out the open circuit without a failed run, but the poller itself
+        // is told right away so it can move on to other partitions.
+        let parked = tokio::time::timeout(
+            std::time::Duration::from_millis(500),
+            ops.enqueue_and_wait_for_poller("2", Some("56"), "u_1"),
         )
-        .fetch_one(&db)
         .await
         .unwrap();
-        assert_eq!(row.get::<String, _>(0), "fetch");
-        assert_eq!(row.get::<i64, _>(1), 0);
+        assert!(parked.is_err());
+        let runs = sqlx::query("SELECT COUNT(*) FROM ops_task_runs")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+        assert_eq!(runs, 2);
+        let queue = ops.runtime_view().await.queue;
+        assert_eq!(queue.retry_scheduled, 1);
+
+        let upstream = Router::new().route(
+            "/cart",
+            get(|| async { include_str!("../tests/fixtures/cart-fid-2-gid-56.html") }),
+        );
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, upstream).await.unwrap();
+        });
+
+        // The parked task then runs as the probe on its own.
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        loop {
+            let ok_runs = sqlx::query(
+                "SELECT COUNT(*) FROM ops_task_runs WHERE ok = 1 AND ended_at IS NOT NULL",
+            )
+            .fetch_one(&db)
+            .await
+            .unwrap()
+            .get::<i64, _>(0);
+            if ok_runs == 1 {
+                break;
+            }
+            assert!(std::time::Instant::now() < deadline);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        let outages = crate::db::list_upstream_outages(&db, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(outages.len(), 1);
+        assert!(outages[0].ended_at.is_some());
+        assert!(outages[0].duration_seconds.is_some());
+
+        let phases =
+            sqlx::query("SELECT data_json FROM ops_events WHERE event = 'ops.outage' ORDER BY id")
+                .fetch_all(&db)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| {
+                    serde_json::from_str::<serde_json::Value>(&row.get::<String, _>(0)).unwrap()
+                        ["phase"]
+                        .as_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+        assert_eq!(phases, vec!["opened", "closed"]);
+        let circuit_open_runs =
+            sqlx::query("SELECT COUNT(*) FROM ops_task_runs WHERE error_code = 'circuit_open'")
+                .fetch_one(&db)
+                .await
+                .unwrap()
+                .get::<i64, _>(0);
+        assert_eq!(circuit_open_runs, 0);
     }
 
     async fn seed_catalog_config(db: &SqlitePool, id: &str, name: &str, qty: i64, price: f64) {
@@ -3515,6 +7726,71 @@ INSERT INTO catalog_configs (
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn parser_drift_is_recorded_without_touching_lifecycle() {
+        let broken_html = r#"
+<!doctype html>
+<html lang="zh-CN">
+  <body>
+    <div class="firstgroup_box_group">
+      <div class="secondgroup_item pointer active" onclick="window.location.href='/cart?fid=7&gid=40'">
+        <a class="yy-bth-text-a">Tokyo</a>
+      </div>
+    </div>
+    <div class="card cartitem shadow w-100">
+      <div class="card-body">
+        <h4>Tokyo Mini</h4>
+        <p class="card-text">库存： 3</p>
+      </div>
+      <div class="text-right">¥ <span class="price-tag">12.00</span> 元 / 月</div>
+      <div class="card-footer">
+        <a href="/cart?action=configureproduct&pid=301">立即购买</a>
+      </div>
+    </div>
+  </body>
+</html>
+"#;
+        let upstream = Router::new().route("/cart", get(move || async move { broken_html }));
+        let base = spawn_stub_server(upstream).await;
+        let (ops, db) = build_ops_manager(format!("{base}/cart")).await;
+        seed_catalog_config(&db, "lc_seeded", "Tokyo Mini", 3, 12.0).await;
+
+        let outcome = ops
+            .enqueue_and_wait_force_fetch("7", Some("40"), "manual_refresh")
+            .await
+            .unwrap();
+        assert!(!outcome.ok);
+
+        let run =
+            sqlx::query("SELECT error_code, fetch_http_status FROM ops_task_runs WHERE id = ?")
+                .bind(outcome.run_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(
+            run.get::<Option<String>, _>(0).as_deref(),
+            Some("parser_drift")
+        );
+        assert_eq!(run.get::<Option<i64>, _>(1), Some(200));
+
+        let state =
+            sqlx::query("SELECT lifecycle_state FROM catalog_configs WHERE id = 'lc_seeded'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(state.get::<String, _>(0), "active");
+
+        let alerts = sqlx::query("SELECT COUNT(*) FROM ops_events WHERE event = 'ops.alert'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(alerts.get::<i64, _>(0), 1);
+        assert!(
+            !ops.alert_operator("parser_drift", "7:40", "again", Value::Null)
+                .await
+        );
+    }
+
     async fn seed_notification_user(
         db: &SqlitePool,
         cfg: &RuntimeConfig,
@@ -3621,12 +7897,10 @@ WHERE user_id = ?
                 listed_event_ids: Vec::new(),
                 listed_pending_zero_stock_ids: vec!["cfg_pending".to_string()],
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -3681,12 +7955,10 @@ WHERE user_id = ?
                 listed_event_ids: Vec::new(),
                 listed_pending_zero_stock_ids: vec!["cfg_pending_only_ops".to_string()],
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -3732,6 +8004,7 @@ WHERE user_id = ?
             &db,
             &[crate::upstream::ConfigBase {
                 id: "lc:7:40:test".to_string(),
+                provider_id: "lazycat".to_string(),
                 country_id: "7".to_string(),
                 region_id: Some("40".to_string()),
                 name: "德国特惠年付 Mini".to_string(),
@@ -3804,12 +8077,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["lc:7:40:test".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: vec!["lc:7:40:test".to_string()],
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-10T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -3827,6 +8098,69 @@ WHERE user_id = ?
         assert_eq!(rows[1].get::<String, _>(1), "catalog.config.removed");
     }
 
+    #[tokio::test]
+    async fn config_added_notifications_follow_monitoring_rules() {
+        let upstream_cart_url = "https://example.invalid/cart".to_string();
+        let cfg = test_config(upstream_cart_url.clone());
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), upstream_cart_url).await;
+        seed_catalog_config(&db, "lc:7:40:rule", "Rule Config", 2, 9.99).await;
+        for (user_id, rule) in [
+            ("u_no_rules", None),
+            (
+                "u_in_stock",
+                Some(r#"[{"kind":"quantityAtLeast","min":1}]"#),
+            ),
+            ("u_cheap", Some(r#"[{"kind":"priceAtMost","amount":5}]"#)),
+        ] {
+            seed_notification_user(&db, &cfg, user_id, false, true, false).await;
+            if let Some(conditions) = rule {
+                crate::db::insert_monitoring_rule(
+                    &db,
+                    user_id,
+                    "partition",
+                    "7::",
+                    "有货",
+                    conditions,
+                    true,
+                )
+                .await
+                .unwrap();
+            }
+        }
+
+        ops.notify_lifecycle_events(
+            43,
+            &HashMap::from([("manual_refresh".to_string(), 1_i64)]),
+            &HashSet::new(),
+            &crate::db::ApplyCatalogUrlResult {
+                listed_ids: vec!["lc:7:40:rule".to_string()],
+                listed_event_ids: vec!["lc:7:40:rule".to_string()],
+                listed_pending_zero_stock_ids: Vec::new(),
+                delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
+                fetched_at: "2026-03-10T00:00:00Z".to_string(),
+            },
+            &TaskKey::scoped("7", Some("40".to_string())),
+        )
+        .await
+        .unwrap();
+
+        let rows = sqlx::query(
+            "SELECT user_id, matched_rule_json, summary FROM notification_records WHERE kind = 'catalog.config.added' ORDER BY user_id ASC",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get::<String, _>(0), "u_in_stock");
+        let matched: Value = serde_json::from_str(&rows[0].get::<String, _>(1)).unwrap();
+        assert_eq!(matched["name"], "有货");
+        assert_eq!(matched["scopeKey"], "7::");
+        assert!(rows[0].get::<String, _>(2).contains("规则：有货"));
+        assert_eq!(rows[1].get::<String, _>(0), "u_no_rules");
+        assert!(rows[1].get::<Option<String>, _>(1).is_none());
+    }
+
     #[tokio::test]
     async fn config_lifecycle_notifications_route_country_direct_packages_to_country_scope() {
         let (ops, db) = build_ops_manager("https://example.com/cart".to_string()).await;
@@ -3846,6 +8180,7 @@ WHERE user_id = ?
             &db,
             &[crate::upstream::ConfigBase {
                 id: "lc:us:default:test".to_string(),
+                provider_id: "lazycat".to_string(),
                 country_id: "us".to_string(),
                 region_id: None,
                 name: "VPS • 4C/8G（国家默认）".to_string(),
@@ -3901,12 +8236,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["lc:us:default:test".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: vec!["lc:us:default:test".to_string()],
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-10T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "us".to_string(),
-                gid: None,
-            },
+            &TaskKey::scoped("us", None),
         )
         .await
         .unwrap();
@@ -3960,6 +8293,7 @@ WHERE user_id = ?
             &db,
             &[crate::upstream::ConfigBase {
                 id: "lc:7:40:test".to_string(),
+                provider_id: "lazycat".to_string(),
                 country_id: "7".to_string(),
                 region_id: Some("40".to_string()),
                 name: "德国特惠年付 Mini".to_string(),
@@ -4129,6 +8463,7 @@ WHERE user_id = ?
             &db,
             &[crate::upstream::ConfigBase {
                 id: "lc:8:41:test".to_string(),
+                provider_id: "lazycat".to_string(),
                 country_id: "8".to_string(),
                 region_id: Some("41".to_string()),
                 name: "芬兰精品年付 Mini".to_string(),
@@ -4244,12 +8579,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_monitored".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4323,12 +8656,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_pure_poller_cold".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4387,12 +8718,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_relisted".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4454,12 +8783,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_waiting_mixed".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4467,6 +8794,74 @@ WHERE user_id = ?
         assert_eq!(hits.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn monitored_restock_opens_a_hot_mode_window_that_queues_bursts_until_it_expires() {
+        let upstream_cart_url = "https://example.invalid/cart".to_string();
+        let mut cfg = test_config(upstream_cart_url.clone());
+        cfg.hot_mode_minutes = 10;
+        let (ops, db) = build_ops_manager_with_config(cfg.clone(), upstream_cart_url).await;
+        ops.pause("admin").await.unwrap();
+
+        seed_listed_user(&db, &cfg, "u_1", true).await;
+        seed_catalog_config(&db, "cfg_hot", "Hot", 3, 9.99).await;
+        sqlx::query(
+            "INSERT INTO monitoring_configs (user_id, config_id, enabled, created_at, updated_at) VALUES (?, ?, 1, ?, ?)",
+        )
+        .bind("u_1")
+        .bind("cfg_hot")
+        .bind("2026-03-11T00:00:00Z")
+        .bind("2026-03-11T00:00:00Z")
+        .execute(&db)
+        .await
+        .unwrap();
+        let applied = |restocked: &str| crate::db::ApplyCatalogUrlResult {
+            listed_ids: Vec::new(),
+            listed_event_ids: Vec::new(),
+            listed_pending_zero_stock_ids: Vec::new(),
+            delisted_ids: Vec::new(),
+            restocked_ids: vec![restocked.to_string()],
+            fetched_at: "2026-03-11T00:00:00Z".to_string(),
+        };
+        let key = TaskKey::scoped("7", Some("40".to_string()));
+
+        // Nobody monitors this config.
+        ops.maybe_enter_hot_mode(&key, &applied("cfg_other"))
+            .await
+            .unwrap();
+        assert!(ops.inner.state.lock().await.hot.is_empty());
+
+        ops.maybe_enter_hot_mode(&key, &applied("cfg_hot"))
+            .await
+            .unwrap();
+        ops.tick_hot_mode(OffsetDateTime::now_utc()).await.unwrap();
+        let snapshot = ops.snapshot(OpsRange::H24, None, None).await.unwrap();
+        let task = snapshot
+            .tasks
+            .iter()
+            .find(|t| t.key.fid == "7" && t.key.gid.as_deref() == Some("40"))
+            .expect("hot partition is queued");
+        assert_eq!(task.reason_counts.get("hot_mode"), Some(&1));
+        let hot = task.hot.as_ref().expect("task shows its hot window");
+        assert_eq!(hot.trigger, "restock");
+        assert_eq!(hot.config_ids, vec!["cfg_hot".to_string()]);
+        assert_eq!(hot.interval_seconds, 15);
+
+        ops.tick_hot_mode(OffsetDateTime::now_utc() + time::Duration::minutes(11))
+            .await
+            .unwrap();
+        assert!(ops.inner.state.lock().await.hot.is_empty());
+        let phases = sqlx::query(
+            "SELECT json_extract(data_json, '$.phase') FROM ops_events WHERE event = 'ops.hot' ORDER BY id",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.get::<String, _>(0))
+        .collect::<Vec<_>>();
+        assert_eq!(phases, vec!["started", "ended"]);
+    }
+
     #[tokio::test]
     async fn notify_lifecycle_events_sends_restock_to_monitored_users_on_non_poller_runs() {
         let hits = Arc::new(AtomicUsize::new(0));
@@ -4516,12 +8911,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_monitored_fallback".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4589,12 +8982,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_monitored_only".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4665,12 +9056,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_mixed".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4732,12 +9121,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_web_push".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
@@ -4786,12 +9173,10 @@ WHERE user_id = ?
                 listed_event_ids: vec!["cfg_listed".to_string()],
                 listed_pending_zero_stock_ids: Vec::new(),
                 delisted_ids: Vec::new(),
+                restocked_ids: Vec::new(),
                 fetched_at: "2026-03-11T00:00:00Z".to_string(),
             },
-            &TaskKey {
-                fid: "7".to_string(),
-                gid: Some("40".to_string()),
-            },
+            &TaskKey::scoped("7", Some("40".to_string())),
         )
         .await
         .unwrap();
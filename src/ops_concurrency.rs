@@ -0,0 +1,202 @@
+use crate::config::RuntimeConfig;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Failure share of a sample window above which the limit is halved.
+const ERROR_RATE_THRESHOLD: f64 = 0.2;
+/// Fewest fetches a window needs before the controller acts on it.
+const MIN_WINDOW_SAMPLES: usize = 4;
+
+/// Observation of one task run that actually went to the upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchSample {
+    pub elapsed: Duration,
+    pub failed: bool,
+}
+
+/// One limit adjustment, as surfaced in `ops.concurrency` events and the worker view.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyChange {
+    pub from: usize,
+    pub to: usize,
+    /// `backlog` (additive increase), `latency` or `errors` (multiplicative decrease).
+    pub reason: String,
+    pub avg_fetch_ms: i64,
+    pub error_rate_pct: f64,
+    pub queue_depth: usize,
+}
+
+/// AIMD controller for the number of active ops workers. Each window of fetch samples either
+/// adds one worker (healthy upstream, backlog larger than the limit) or halves the limit (slow or
+/// failing upstream). With `adaptive` off the limit stays at its initial value.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyController {
+    adaptive: bool,
+    min: usize,
+    max: usize,
+    limit: usize,
+    latency_target: Duration,
+    window: Vec<FetchSample>,
+}
+
+impl ConcurrencyController {
+    pub fn from_config(cfg: &RuntimeConfig) -> Self {
+        let initial = cfg.ops_worker_concurrency.max(1);
+        if !cfg.ops_adaptive_concurrency {
+            return Self::fixed(initial);
+        }
+        let min = cfg.ops_worker_concurrency_min.max(1);
+        let max = cfg.ops_worker_concurrency_max.max(min);
+        Self {
+            adaptive: true,
+            min,
+            max,
+            limit: initial.clamp(min, max),
+            latency_target: Duration::from_millis(cfg.ops_adaptive_latency_target_ms.max(1)),
+            window: Vec::new(),
+        }
+    }
+
+    pub fn fixed(limit: usize) -> Self {
+        Self {
+            adaptive: false,
+            min: limit,
+            max: limit,
+            limit,
+            latency_target: Duration::MAX,
+            window: Vec::new(),
+        }
+    }
+
+    pub fn adaptive(&self) -> bool {
+        self.adaptive
+    }
+
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Number of workers to spawn: the upper bound, since idle ones above the limit just park.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Adds a sample and, once the window is full, decides on a new limit.
+    pub fn observe(
+        &mut self,
+        sample: FetchSample,
+        queue_depth: usize,
+    ) -> Option<ConcurrencyChange> {
+        if !self.adaptive {
+            return None;
+        }
+        self.window.push(sample);
+        if self.window.len() < MIN_WINDOW_SAMPLES.max(self.limit) {
+            return None;
+        }
+        let samples = std::mem::take(&mut self.window);
+        let failed = samples.iter().filter(|s| s.failed).count();
+        let error_rate = failed as f64 / samples.len() as f64;
+        let avg = samples.iter().map(|s| s.elapsed).sum::<Duration>() / samples.len() as u32;
+
+        let (to, reason) = if error_rate > ERROR_RATE_THRESHOLD {
+            ((self.limit / 2).max(self.min), "errors")
+        } else if avg > self.latency_target {
+            ((self.limit / 2).max(self.min), "latency")
+        } else if queue_depth > self.limit {
+            ((self.limit + 1).min(self.max), "backlog")
+        } else {
+            return None;
+        };
+        if to == self.limit {
+            return None;
+        }
+        let change = ConcurrencyChange {
+            from: self.limit,
+            to,
+            reason: reason.to_string(),
+            avg_fetch_ms: avg.as_millis() as i64,
+            error_rate_pct: (error_rate * 1000.0).round() / 10.0,
+            queue_depth,
+        };
+        self.limit = to;
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(limit: usize) -> ConcurrencyController {
+        ConcurrencyController {
+            adaptive: true,
+            min: 1,
+            max: 6,
+            limit,
+            latency_target: Duration::from_millis(1000),
+            window: Vec::new(),
+        }
+    }
+
+    fn sample(ms: u64, failed: bool) -> FetchSample {
+        FetchSample {
+            elapsed: Duration::from_millis(ms),
+            failed,
+        }
+    }
+
+    #[test]
+    fn increases_additively_and_decreases_multiplicatively() {
+        let mut c = controller(4);
+        for _ in 0..3 {
+            assert_eq!(c.observe(sample(200, false), 20), None);
+        }
+        let change = c.observe(sample(200, false), 20).unwrap();
+        assert_eq!(
+            (change.from, change.to, change.reason.as_str()),
+            (4, 5, "backlog")
+        );
+
+        // A healthy window without backlog keeps the limit.
+        for _ in 0..5 {
+            assert_eq!(c.observe(sample(200, false), 2), None);
+        }
+        assert_eq!(c.limit(), 5);
+
+        for _ in 0..4 {
+            assert_eq!(c.observe(sample(3000, false), 20), None);
+        }
+        let change = c.observe(sample(3000, false), 20).unwrap();
+        assert_eq!(
+            (change.from, change.to, change.reason.as_str()),
+            (5, 2, "latency")
+        );
+
+        for _ in 0..3 {
+            c.observe(sample(100, true), 20);
+        }
+        let change = c.observe(sample(100, false), 20).unwrap();
+        assert_eq!((change.to, change.reason.as_str()), (1, "errors"));
+        assert_eq!(change.error_rate_pct, 75.0);
+
+        // Already at the floor: further bad windows change nothing.
+        for _ in 0..4 {
+            assert_eq!(c.observe(sample(100, true), 20), None);
+        }
+    }
+
+    #[test]
+    fn fixed_controller_never_changes() {
+        let mut c = ConcurrencyController::fixed(3);
+        for _ in 0..20 {
+            assert_eq!(c.observe(sample(10, false), 100), None);
+        }
+        assert_eq!((c.min(), c.limit(), c.max()), (3, 3, 3));
+    }
+}
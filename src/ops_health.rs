@@ -0,0 +1,291 @@
+use crate::config::RuntimeConfig;
+use std::collections::HashSet;
+
+/// Finished runs a success-rate window needs before the rule judges it.
+pub const MIN_SUCCESS_RATE_SAMPLES: i64 = 5;
+
+/// Operator health rules; each is off until its threshold is configured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthRules {
+    pub success_rate_min_pct: Option<f64>,
+    pub success_rate_window_minutes: i64,
+    pub queue_depth_max: Option<i64>,
+    pub worker_error_minutes: Option<i64>,
+    pub no_success_minutes: Option<i64>,
+}
+
+/// What a health check sees: run counts over the success-rate window, the live queue and workers,
+/// and how long ago the last upstream fetch succeeded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthSample {
+    pub window_total: i64,
+    pub window_success: i64,
+    /// Pending plus scheduled retries.
+    pub queue_depth: i64,
+    /// `(worker id, seconds in the error state)` of every worker currently in error.
+    pub worker_errors: Vec<(String, i64)>,
+    /// Seconds since the last successful upstream fetch, or since startup when there was none.
+    pub seconds_since_success: i64,
+}
+
+/// A rule whose threshold is currently crossed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breach {
+    pub rule: &'static str,
+    pub value: f64,
+    pub threshold: f64,
+    pub message: String,
+}
+
+/// Outcome of one enabled rule for one check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleStatus {
+    pub rule: &'static str,
+    pub breach: Option<Breach>,
+}
+
+/// Alert state changes a check leads to. Rules stay open until they check healthy again, so a
+/// lasting breach is reported once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthTransition {
+    Opened(Breach),
+    Resolved { rule: String },
+}
+
+impl HealthRules {
+    pub fn from_config(cfg: &RuntimeConfig) -> Self {
+        Self {
+            success_rate_min_pct: cfg.ops_health_success_rate_min_pct,
+            success_rate_window_minutes: cfg.ops_health_success_rate_window_minutes.max(1),
+            queue_depth_max: cfg.ops_health_queue_depth_max,
+            worker_error_minutes: cfg.ops_health_worker_error_minutes,
+            no_success_minutes: cfg.ops_health_no_success_minutes,
+        }
+    }
+
+    pub fn any_enabled(&self) -> bool {
+        self.success_rate_min_pct.is_some()
+            || self.queue_depth_max.is_some()
+            || self.worker_error_minutes.is_some()
+            || self.no_success_minutes.is_some()
+    }
+
+    pub fn evaluate(&self, sample: &HealthSample) -> Vec<RuleStatus> {
+        let mut out = Vec::new();
+        if let Some(min_pct) = self.success_rate_min_pct {
+            let breach = (sample.window_total >= MIN_SUCCESS_RATE_SAMPLES)
+                .then(|| sample.window_success as f64 * 100.0 / sample.window_total as f64)
+                .filter(|pct| *pct < min_pct)
+                .map(|pct| Breach {
+                    rule: "success_rate",
+                    value: (pct * 10.0).round() / 10.0,
+                    threshold: min_pct,
+                    message: format!(
+                        "ops success rate {:.1}% over the last {} min is below {min_pct}% ({}/{} runs ok)",
+                        pct,
+                        self.success_rate_window_minutes,
+                        sample.window_success,
+                        sample.window_total
+                    ),
+                });
+            out.push(RuleStatus {
+                rule: "success_rate",
+                breach,
+            });
+        }
+        if let Some(max) = self.queue_depth_max {
+            let breach = (sample.queue_depth > max).then(|| Breach {
+                rule: "queue_depth",
+                value: sample.queue_depth as f64,
+                threshold: max as f64,
+                message: format!("ops queue depth {} is above {max}", sample.queue_depth),
+            });
+            out.push(RuleStatus {
+                rule: "queue_depth",
+                breach,
+            });
+        }
+        if let Some(minutes) = self.worker_error_minutes {
+            let stuck = sample
+                .worker_errors
+                .iter()
+                .filter(|(_, secs)| *secs >= minutes * 60)
+                .collect::<Vec<_>>();
+            let breach = stuck
+                .iter()
+                .map(|(_, secs)| *secs)
+                .max()
+                .map(|longest| Breach {
+                    rule: "worker_error",
+                    value: (longest / 60) as f64,
+                    threshold: minutes as f64,
+                    message: format!(
+                        "ops workers {} in error for {}+ min",
+                        stuck
+                            .iter()
+                            .map(|(id, _)| id.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        minutes
+                    ),
+                });
+            out.push(RuleStatus {
+                rule: "worker_error",
+                breach,
+            });
+        }
+        if let Some(minutes) = self.no_success_minutes {
+            let breach = (sample.seconds_since_success >= minutes * 60).then(|| Breach {
+                rule: "no_success",
+                value: (sample.seconds_since_success / 60) as f64,
+                threshold: minutes as f64,
+                message: format!(
+                    "no successful upstream fetch in the last {} min",
+                    sample.seconds_since_success / 60
+                ),
+            });
+            out.push(RuleStatus {
+                rule: "no_success",
+                breach,
+            });
+        }
+        out
+    }
+}
+
+/// Compares a check with the alerts currently open. Open alerts of rules that are no longer
+/// configured resolve as well.
+pub fn plan_transitions(
+    statuses: Vec<RuleStatus>,
+    open: &HashSet<String>,
+) -> Vec<HealthTransition> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    for status in statuses {
+        seen.insert(status.rule);
+        match (status.breach, open.contains(status.rule)) {
+            (Some(breach), false) => out.push(HealthTransition::Opened(breach)),
+            (None, true) => out.push(HealthTransition::Resolved {
+                rule: status.rule.to_string(),
+            }),
+            _ => {}
+        }
+    }
+    let mut stale = open
+        .iter()
+        .filter(|rule| !seen.contains(rule.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    stale.sort();
+    out.extend(
+        stale
+            .into_iter()
+            .map(|rule| HealthTransition::Resolved { rule }),
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> HealthRules {
+        HealthRules {
+            success_rate_min_pct: Some(80.0),
+            success_rate_window_minutes: 30,
+            queue_depth_max: Some(10),
+            worker_error_minutes: Some(5),
+            no_success_minutes: Some(60),
+        }
+    }
+
+    fn breached(statuses: &[RuleStatus]) -> Vec<&'static str> {
+        statuses
+            .iter()
+            .filter(|s| s.breach.is_some())
+            .map(|s| s.rule)
+            .collect()
+    }
+
+    #[test]
+    fn evaluates_each_configured_rule() {
+        let healthy = HealthSample {
+            window_total: 20,
+            window_success: 19,
+            queue_depth: 3,
+            worker_errors: vec![("w2".to_string(), 120)],
+            seconds_since_success: 30,
+        };
+        let statuses = rules().evaluate(&healthy);
+        assert_eq!(statuses.len(), 4);
+        assert!(breached(&statuses).is_empty());
+
+        let degraded = HealthSample {
+            window_total: 10,
+            window_success: 5,
+            queue_depth: 11,
+            worker_errors: vec![("w1".to_string(), 600), ("w2".to_string(), 60)],
+            seconds_since_success: 3700,
+        };
+        let statuses = rules().evaluate(&degraded);
+        assert_eq!(
+            breached(&statuses),
+            vec!["success_rate", "queue_depth", "worker_error", "no_success"]
+        );
+        let worker = statuses[2].breach.as_ref().unwrap();
+        assert_eq!(worker.message, "ops workers w1 in error for 5+ min");
+        assert_eq!(statuses[0].breach.as_ref().unwrap().value, 50.0);
+
+        // Too few runs to judge the success rate.
+        let quiet = HealthSample {
+            window_total: 2,
+            window_success: 0,
+            ..Default::default()
+        };
+        assert!(breached(&rules().evaluate(&quiet)).is_empty());
+
+        let off = HealthRules {
+            success_rate_min_pct: None,
+            success_rate_window_minutes: 30,
+            queue_depth_max: None,
+            worker_error_minutes: None,
+            no_success_minutes: None,
+        };
+        assert!(!off.any_enabled());
+        assert!(off.evaluate(&degraded).is_empty());
+    }
+
+    #[test]
+    fn transitions_open_once_and_resolve_when_healthy() {
+        let status = |rule, breach: bool| RuleStatus {
+            rule,
+            breach: breach.then(|| Breach {
+                rule,
+                value: 1.0,
+                threshold: 0.0,
+                message: String::new(),
+            }),
+        };
+        let mut open = HashSet::new();
+        let t = plan_transitions(vec![status("queue_depth", true)], &open);
+        assert!(matches!(&t[..], [HealthTransition::Opened(b)] if b.rule == "queue_depth"));
+
+        open.insert("queue_depth".to_string());
+        assert!(plan_transitions(vec![status("queue_depth", true)], &open).is_empty());
+        assert_eq!(
+            plan_transitions(vec![status("queue_depth", false)], &open),
+            vec![HealthTransition::Resolved {
+                rule: "queue_depth".to_string()
+            }]
+        );
+
+        // The rule was switched off while its alert was open.
+        open.insert("no_success".to_string());
+        assert_eq!(
+            plan_transitions(vec![status("queue_depth", true)], &open),
+            vec![HealthTransition::Resolved {
+                rule: "no_success".to_string()
+            }]
+        );
+    }
+}
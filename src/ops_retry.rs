@@ -0,0 +1,139 @@
+use crate::config::{OpsRetryPolicyConfig, RuntimeConfig};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Error codes retried when neither `CATNAP_OPS_RETRYABLE_ERROR_CODES` nor the class policy says
+/// otherwise: transient network trouble and server-side failures, never parse/apply errors.
+pub const DEFAULT_RETRYABLE_ERROR_CODES: &[&str] = &[
+    "upstream_timeout",
+    "upstream_connect",
+    "upstream_5xx",
+    "upstream_throttled",
+    "proxy_error",
+];
+
+/// Retry policy of one ops task class (`interactive`, `poller`, `discovery`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpsRetryPolicy {
+    /// Total runs including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub retryable_error_codes: Vec<String>,
+}
+
+impl OpsRetryPolicy {
+    fn from_config(cfg: &OpsRetryPolicyConfig, default_codes: &[String]) -> Self {
+        Self {
+            max_attempts: cfg.max_attempts.max(1),
+            base_delay: Duration::from_secs(cfg.base_delay_seconds),
+            max_delay: Duration::from_secs(cfg.max_delay_seconds.max(cfg.base_delay_seconds)),
+            retryable_error_codes: cfg
+                .retryable_error_codes
+                .clone()
+                .unwrap_or_else(|| default_codes.to_vec()),
+        }
+    }
+
+    /// Whether run number `attempt` (1-based) failing with `error_code` gets another run.
+    pub fn should_retry(&self, attempt: u32, error_code: &str) -> bool {
+        attempt < self.max_attempts
+            && self
+                .retryable_error_codes
+                .iter()
+                .any(|code| code == error_code)
+    }
+
+    /// Delay before the run after `attempt`: `base * 2^(attempt-1)` capped at `max_delay`, then
+    /// scaled into 80–120% by `jitter` (expected in `0.0..1.0`) so failed keys do not retry in
+    /// lockstep.
+    pub fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        exp.mul_f64(0.8 + 0.4 * jitter.clamp(0.0, 1.0))
+    }
+}
+
+/// Per-class policies with the built-in defaults filled in.
+#[derive(Debug, Clone)]
+pub struct OpsRetryPolicies {
+    by_class: HashMap<String, OpsRetryPolicy>,
+    fallback: OpsRetryPolicy,
+}
+
+impl OpsRetryPolicies {
+    pub fn from_config(cfg: &RuntimeConfig) -> Self {
+        let mut classes = default_policy_configs();
+        for (class, policy) in &cfg.ops_retry_policies {
+            classes.insert(class.clone(), policy.clone());
+        }
+        let by_class = classes
+            .iter()
+            .map(|(class, policy)| {
+                (
+                    class.clone(),
+                    OpsRetryPolicy::from_config(policy, &cfg.ops_retryable_error_codes),
+                )
+            })
+            .collect();
+        Self {
+            by_class,
+            fallback: OpsRetryPolicy::from_config(
+                &OpsRetryPolicyConfig {
+                    max_attempts: 1,
+                    base_delay_seconds: 0,
+                    max_delay_seconds: 0,
+                    retryable_error_codes: None,
+                },
+                &cfg.ops_retryable_error_codes,
+            ),
+        }
+    }
+
+    pub fn for_class(&self, class: &str) -> &OpsRetryPolicy {
+        self.by_class.get(class).unwrap_or(&self.fallback)
+    }
+}
+
+/// User-initiated refreshes fail fast (the user is waiting and can click again); background
+/// classes retry with backoff well below their own re-enqueue cadence.
+fn default_policy_configs() -> HashMap<String, OpsRetryPolicyConfig> {
+    let policy = |max_attempts, base_delay_seconds, max_delay_seconds| OpsRetryPolicyConfig {
+        max_attempts,
+        base_delay_seconds,
+        max_delay_seconds,
+        retryable_error_codes: None,
+    };
+    HashMap::from([
+        ("interactive".to_string(), policy(1, 0, 0)),
+        ("poller".to_string(), policy(3, 10, 120)),
+        ("discovery".to_string(), policy(2, 60, 1800)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_only_listed_codes_within_max_attempts() {
+        let policy = OpsRetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(25),
+            retryable_error_codes: vec!["upstream_timeout".to_string()],
+        };
+        assert!(policy.should_retry(1, "upstream_timeout"));
+        assert!(policy.should_retry(2, "upstream_timeout"));
+        assert!(!policy.should_retry(3, "upstream_timeout"));
+        assert!(!policy.should_retry(1, "parser_drift"));
+
+        assert_eq!(policy.backoff(1, 0.5), Duration::from_secs(10));
+        assert_eq!(policy.backoff(2, 0.5), Duration::from_secs(20));
+        assert_eq!(policy.backoff(3, 0.5), Duration::from_secs(25));
+        assert_eq!(policy.backoff(1, 0.0), Duration::from_secs(8));
+        assert_eq!(policy.backoff(1, 1.0), Duration::from_secs(12));
+    }
+}
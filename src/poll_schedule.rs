@@ -0,0 +1,340 @@
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, UtcOffset, Weekday};
+
+/// How far ahead a fetch is searched for before a partition counts as fully quiet.
+const SEARCH_MINUTES: i64 = 8 * 24 * 60;
+const MAX_EVERY_MINUTES: i64 = 24 * 60;
+
+/// One schedule rule as stored and exchanged over the API. `days` (`mon`..`sun`, empty = every
+/// day) and the local `start`/`end` window (`HH:MM`, end exclusive, wrapping past midnight when
+/// `end` is earlier) narrow when the rule applies; it then either sets the poll interval or keeps
+/// the target quiet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollScheduleRule {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub every_minutes: Option<i64>,
+    #[serde(default)]
+    pub quiet: bool,
+}
+
+/// Poll cadence in effect at a given minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    Every(i64),
+    Quiet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompiledRule {
+    /// Bit `n` set = applies on the weekday `n` days from Monday.
+    days: u8,
+    /// Local `(start, end)` minute of day.
+    window: Option<(u16, u16)>,
+    slot: Slot,
+}
+
+/// A validated schedule. Rules are checked in order and the first match wins; outside every rule
+/// the user's global poll interval applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollSchedule {
+    offset: UtcOffset,
+    rules: Vec<CompiledRule>,
+}
+
+impl PollSchedule {
+    pub fn parse(utc_offset: &str, rules: &[PollScheduleRule]) -> anyhow::Result<Self> {
+        let offset = parse_utc_offset(utc_offset)?;
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(idx, rule)| compile_rule(rule).map_err(|err| anyhow!("rule {}: {err}", idx + 1)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { offset, rules })
+    }
+
+    /// The slot of the first matching rule, or `None` when the default interval applies.
+    pub fn slot_at(&self, at: OffsetDateTime) -> Option<Slot> {
+        let local = at.to_offset(self.offset);
+        let day = 1u8 << local.weekday().number_days_from_monday();
+        let minute = local.hour() as u16 * 60 + local.minute() as u16;
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.days & day != 0
+                    && rule.window.is_none_or(|(start, end)| {
+                        if start < end {
+                            (start..end).contains(&minute)
+                        } else {
+                            minute >= start || minute < end
+                        }
+                    })
+            })
+            .map(|rule| rule.slot)
+    }
+}
+
+/// Slot of a partition polled on behalf of several configs: the shortest interval any of them
+/// wants, quiet only when all of them are. `None` entries fall back to `default_minutes`.
+pub fn combined_slot(
+    schedules: &[Option<&PollSchedule>],
+    default_minutes: i64,
+    at: OffsetDateTime,
+) -> Slot {
+    schedules
+        .iter()
+        .map(|schedule| {
+            schedule
+                .and_then(|s| s.slot_at(at))
+                .unwrap_or(Slot::Every(default_minutes))
+        })
+        .fold(Slot::Quiet, |acc, slot| match (acc, slot) {
+            (Slot::Every(a), Slot::Every(b)) => Slot::Every(a.min(b)),
+            (Slot::Quiet, other) | (other, Slot::Quiet) => other,
+        })
+}
+
+/// Earliest time at or after `from` with a non-quiet slot whose interval has elapsed since
+/// `last`. A target never polled is due at the first non-quiet minute. `None` when every minute of
+/// the search horizon is quiet.
+pub fn next_fetch(
+    schedules: &[Option<&PollSchedule>],
+    default_minutes: i64,
+    last: Option<OffsetDateTime>,
+    from: OffsetDateTime,
+) -> Option<(OffsetDateTime, i64)> {
+    let minute_start = from
+        .replace_second(0)
+        .and_then(|t| t.replace_nanosecond(0))
+        .unwrap_or(from);
+    for step in 0..SEARCH_MINUTES {
+        let t = minute_start + time::Duration::minutes(step);
+        let Slot::Every(minutes) = combined_slot(schedules, default_minutes, t) else {
+            continue;
+        };
+        let mut candidate = t.max(from);
+        if let Some(last) = last {
+            candidate = candidate.max(last + time::Duration::minutes(minutes));
+        }
+        if candidate < t + time::Duration::minutes(1) {
+            return Some((candidate, minutes));
+        }
+    }
+    None
+}
+
+pub fn parse_utc_offset(value: &str) -> anyhow::Result<UtcOffset> {
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("z") {
+        return Ok(UtcOffset::UTC);
+    }
+    let (sign, rest) = if let Some(rest) = value.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = value.strip_prefix('-') {
+        (-1, rest)
+    } else {
+        bail!("utc offset must look like +08:00");
+    };
+    let (hours, minutes) = rest
+        .split_once(':')
+        .ok_or_else(|| anyhow!("utc offset must look like +08:00"))?;
+    let hours = hours.parse::<i8>()?;
+    let minutes = minutes.parse::<i8>()?;
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        bail!("utc offset out of range");
+    }
+    Ok(UtcOffset::from_hms(sign * hours, sign * minutes, 0)?)
+}
+
+fn compile_rule(rule: &PollScheduleRule) -> anyhow::Result<CompiledRule> {
+    let mut days = 0u8;
+    for day in &rule.days {
+        days |= 1 << parse_weekday(day)?.number_days_from_monday();
+    }
+    if days == 0 {
+        days = 0x7f;
+    }
+    let window = match (rule.start.as_deref(), rule.end.as_deref()) {
+        (None, None) => None,
+        (Some(start), Some(end)) => {
+            let window = (parse_minute_of_day(start)?, parse_minute_of_day(end)?);
+            if window.0 == window.1 {
+                bail!("start and end must differ");
+            }
+            Some(window)
+        }
+        _ => bail!("start and end must be set together"),
+    };
+    let slot = match (rule.quiet, rule.every_minutes) {
+        (true, None) => Slot::Quiet,
+        (false, Some(minutes)) if (1..=MAX_EVERY_MINUTES).contains(&minutes) => {
+            Slot::Every(minutes)
+        }
+        (false, Some(_)) => bail!("everyMinutes must be between 1 and {MAX_EVERY_MINUTES}"),
+        _ => bail!("set exactly one of everyMinutes or quiet"),
+    };
+    Ok(CompiledRule { days, window, slot })
+}
+
+fn parse_weekday(value: &str) -> anyhow::Result<Weekday> {
+    Ok(match value.trim().to_ascii_lowercase().as_str() {
+        "mon" => Weekday::Monday,
+        "tue" => Weekday::Tuesday,
+        "wed" => Weekday::Wednesday,
+        "thu" => Weekday::Thursday,
+        "fri" => Weekday::Friday,
+        "sat" => Weekday::Saturday,
+        "sun" => Weekday::Sunday,
+        other => bail!("unknown day {other:?}"),
+    })
+}
+
+fn parse_minute_of_day(value: &str) -> anyhow::Result<u16> {
+    let (hours, minutes) = value
+        .trim()
+        .split_once(':')
+        .ok_or_else(|| anyhow!("time must look like HH:MM"))?;
+    let hours = hours.parse::<u16>()?;
+    let minutes = minutes.parse::<u16>()?;
+    if hours > 23 || minutes > 59 {
+        bail!("time out of range");
+    }
+    Ok(hours * 60 + minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn rule(start: &str, end: &str, every: i64) -> PollScheduleRule {
+        PollScheduleRule {
+            days: Vec::new(),
+            start: Some(start.to_string()),
+            end: Some(end.to_string()),
+            every_minutes: Some(every),
+            quiet: false,
+        }
+    }
+
+    fn weekends_quiet() -> PollScheduleRule {
+        PollScheduleRule {
+            days: vec!["sat".to_string(), "sun".to_string()],
+            start: None,
+            end: None,
+            every_minutes: None,
+            quiet: true,
+        }
+    }
+
+    #[test]
+    fn evening_window_in_beijing_time_overrides_the_default() {
+        let schedule = PollSchedule::parse("+08:00", &[rule("20:00", "23:00", 2)]).unwrap();
+        // 2026-10-14 is a Wednesday; 12:00 UTC is 20:00 in Beijing.
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-14 12:00 UTC)),
+            Some(Slot::Every(2))
+        );
+        assert_eq!(schedule.slot_at(datetime!(2026-10-14 15:00 UTC)), None);
+
+        let schedules = [Some(&schedule)];
+        // Polled at 11:50 UTC with the 30 min default: the window opening at 12:00 pulls the next
+        // fetch forward.
+        let (at, every) = next_fetch(
+            &schedules,
+            30,
+            Some(datetime!(2026-10-14 11:50 UTC)),
+            datetime!(2026-10-14 11:51 UTC),
+        )
+        .unwrap();
+        assert_eq!((at, every), (datetime!(2026-10-14 12:00 UTC), 2));
+
+        let (at, _) = next_fetch(
+            &schedules,
+            30,
+            Some(datetime!(2026-10-14 12:00:30 UTC)),
+            datetime!(2026-10-14 12:01 UTC),
+        )
+        .unwrap();
+        assert_eq!(at, datetime!(2026-10-14 12:02:30 UTC));
+
+        // Past the window the default interval applies again.
+        let (at, every) = next_fetch(
+            &schedules,
+            30,
+            Some(datetime!(2026-10-14 14:59 UTC)),
+            datetime!(2026-10-14 15:00 UTC),
+        )
+        .unwrap();
+        assert_eq!((at, every), (datetime!(2026-10-14 15:29 UTC), 30));
+    }
+
+    #[test]
+    fn quiet_days_skip_to_the_next_allowed_minute() {
+        let schedule = PollSchedule::parse("+09:00", &[weekends_quiet()]).unwrap();
+        let schedules = [Some(&schedule)];
+        // Saturday 10:00 in Tokyo.
+        let (at, _) = next_fetch(&schedules, 10, None, datetime!(2026-10-17 01:00 UTC)).unwrap();
+        // Monday 00:00 in Tokyo.
+        assert_eq!(at, datetime!(2026-10-18 15:00 UTC));
+
+        // Another config in the same partition keeps polling on weekends.
+        let (at, every) = next_fetch(
+            &[Some(&schedule), None],
+            10,
+            None,
+            datetime!(2026-10-17 01:00 UTC),
+        )
+        .unwrap();
+        assert_eq!((at, every), (datetime!(2026-10-17 01:00 UTC), 10));
+
+        let always_quiet = PollSchedule::parse(
+            "+00:00",
+            &[PollScheduleRule {
+                days: Vec::new(),
+                ..weekends_quiet()
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            next_fetch(
+                &[Some(&always_quiet)],
+                10,
+                None,
+                datetime!(2026-10-17 01:00 UTC)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn windows_wrap_past_midnight_and_invalid_rules_are_rejected() {
+        let schedule = PollSchedule::parse("-05:00", &[rule("22:00", "02:00", 5)]).unwrap();
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-14 06:30 UTC)),
+            Some(Slot::Every(5))
+        );
+        assert_eq!(schedule.slot_at(datetime!(2026-10-14 07:30 UTC)), None);
+
+        assert!(PollSchedule::parse("08:00", &[]).is_err());
+        assert!(PollSchedule::parse("+00:00", &[rule("24:00", "01:00", 5)]).is_err());
+        assert!(PollSchedule::parse("+00:00", &[rule("01:00", "02:00", 0)]).is_err());
+        let both = PollScheduleRule {
+            quiet: true,
+            ..rule("01:00", "02:00", 5)
+        };
+        assert!(PollSchedule::parse("+00:00", &[both]).is_err());
+        let bad_day = PollScheduleRule {
+            days: vec!["someday".to_string()],
+            ..weekends_quiet()
+        };
+        assert!(PollSchedule::parse("+00:00", &[bad_day]).is_err());
+    }
+}
@@ -1,20 +1,30 @@
 use crate::defaults::{
     FIXED_CATALOG_TOPOLOGY_PROBE_INTERVAL_MINUTES, FIXED_CATALOG_TOPOLOGY_REFRESH_INTERVAL_HOURS,
 };
+use crate::models::PartitionPollPlanView;
+use crate::monitoring_rules::{RuleState, RuleVerdict};
+use crate::poll_schedule::{self, PollSchedule, PollScheduleRule};
+use crate::restock_forecast::RestockProfile;
 use crate::upstream::{
     catalog_region_key, parse_configs, parse_region_notice, parse_regions,
     retain_country_direct_configs, CatalogSnapshot, UpstreamClient,
 };
+use crate::upstream_archive::UpstreamArchive;
+use crate::RuntimeConfig;
 use crate::{app::AppState, db};
-use crate::{models::Money, notification_content};
-use sqlx::Row;
-use std::collections::{HashMap, HashSet};
-use time::OffsetDateTime;
-use tracing::{info, warn};
+use crate::{
+    models::{BillingCycleOption, Money},
+    notification_content,
+};
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::{info, warn, Instrument};
 
 const INVENTORY_HISTORY_RETENTION_DAYS: i64 = 30;
 const DISCOVERY_INTERVAL_SECONDS: i64 = 5 * 60;
 const TOPOLOGY_RETRY_SECONDS: i64 = 5 * 60;
+const RESTOCK_FORECAST_REFRESH_MINUTES: i64 = 60;
 
 #[derive(Debug, Default, PartialEq, Eq)]
 struct TopologyNotificationChanges {
@@ -51,7 +61,11 @@ pub async fn spawn(state: AppState) {
 }
 
 pub async fn refresh_catalog_topology(state: &AppState, reason: &str) -> anyhow::Result<()> {
-    let upstream = UpstreamClient::new(state.config.upstream_cart_url.clone())?;
+    let upstream = UpstreamClient::new(
+        state.config.upstream_cart_url.clone(),
+        &state.config.upstream_proxies,
+    )?
+    .with_archive(UpstreamArchive::shared(&state.config)?);
     let has_existing_catalog_state = db::has_catalog_topology(&state.db).await?
         || !db::list_known_catalog_targets(&state.db).await?.is_empty();
     match upstream.fetch_topology().await {
@@ -93,7 +107,12 @@ pub async fn refresh_catalog_topology(state: &AppState, reason: &str) -> anyhow:
                     !current_target_keys.contains(&catalog_region_key(fid, gid.as_deref()))
                 })
                 .collect::<Vec<_>>();
-            let retired_ids = db::retire_catalog_targets(&state.db, &removed_targets).await?;
+            let retired_ids = db::retire_catalog_targets(
+                &state.db,
+                crate::storefront::DEFAULT_PROVIDER_ID,
+                &removed_targets,
+            )
+            .await?;
             persist_topology_region_notices(state, &topology).await?;
             let added_catalog_fetch_failures = prefetch_added_target_catalogs(
                 state,
@@ -143,7 +162,11 @@ pub async fn refresh_catalog_topology(state: &AppState, reason: &str) -> anyhow:
 }
 
 pub async fn probe_catalog_topology(state: &AppState, reason: &str) -> anyhow::Result<()> {
-    let upstream = UpstreamClient::new(state.config.upstream_cart_url.clone())?;
+    let upstream = UpstreamClient::new(
+        state.config.upstream_cart_url.clone(),
+        &state.config.upstream_proxies,
+    )?
+    .with_archive(UpstreamArchive::shared(&state.config)?);
     let has_existing_catalog_state = db::has_catalog_topology(&state.db).await?
         || !db::list_known_catalog_targets(&state.db).await?.is_empty();
     match upstream.fetch_topology().await {
@@ -628,13 +651,20 @@ fn collect_topology_notification_changes(
 }
 
 async fn run(state: AppState) -> anyhow::Result<()> {
-    let mut next_due: HashMap<String, OffsetDateTime> = HashMap::new();
+    let mut next_check: HashMap<String, OffsetDateTime> = HashMap::new();
     let mut last_cleanup: Option<OffsetDateTime> = None;
     let mut next_topology_due: Option<OffsetDateTime> = None;
     let mut next_topology_probe_due: Option<OffsetDateTime> = None;
     let mut next_discovery_due: Option<OffsetDateTime> = None;
+    let mut next_forecast_due: Option<OffsetDateTime> = None;
 
     loop {
+        // Followers only serve the API; the leader instance does all background work.
+        if !state.ops.leader().is_leader() {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            continue;
+        }
+
         let users = sqlx::query("SELECT id FROM users")
             .fetch_all(&state.db)
             .await?
@@ -684,6 +714,7 @@ async fn run(state: AppState) -> anyhow::Result<()> {
         let mut topology_job_ran = false;
         if next_topology_due.is_some_and(|due| now >= due) {
             topology_job_ran = true;
+            refresh_additional_provider_topologies(&state, "topology_refresh").await;
             match refresh_catalog_topology(&state, "topology_refresh").await {
                 Ok(_) => {
                     next_topology_due = Some(now.saturating_add(topology_interval));
@@ -714,29 +745,73 @@ async fn run(state: AppState) -> anyhow::Result<()> {
             if let Err(err) = enqueue_discovery_targets(&state).await {
                 warn!(error = %err, "enqueue discovery targets failed");
             }
+            enqueue_additional_provider_targets(&state).await;
             next_discovery_due =
                 Some(now.saturating_add(time::Duration::seconds(DISCOVERY_INTERVAL_SECONDS)));
         }
 
+        if next_forecast_due.is_none_or(|due| now >= due) {
+            if let Err(err) = crate::restock_forecast::refresh_profiles(&state.db, now).await {
+                warn!(error = %err, "restock forecast refresh failed");
+            }
+            next_forecast_due =
+                Some(now.saturating_add(time::Duration::minutes(RESTOCK_FORECAST_REFRESH_MINUTES)));
+        }
+
         for user_id in users {
             let settings = db::get_settings(&state.db, &user_id).await?;
             if let Err(err) = crate::lazycat::maybe_spawn_due_sync(&state, &user_id).await {
                 warn!(user_id, error = %err, "lazycat sync scheduling failed");
             }
-            let due = next_due.get(&user_id).copied().unwrap_or(now);
-            if now < due {
+            if next_check.get(&user_id).is_some_and(|due| now < *due) {
+                continue;
+            }
+
+            let plans = match plan_monitoring_polls(
+                &state.db,
+                &state.config,
+                &user_id,
+                settings.poll_interval_minutes.max(1),
+                now,
+            )
+            .await
+            {
+                Ok(plans) => plans,
+                Err(err) => {
+                    warn!(user_id, error = %err, "poll planning failed");
+                    continue;
+                }
+            };
+            let (due, pending): (Vec<_>, Vec<_>) = plans
+                .into_iter()
+                .partition(|plan| plan.next_fetch_at.is_some_and(|at| at <= now));
+            // Re-plan at least every minute so schedule and monitoring changes apply promptly.
+            let recheck = pending
+                .iter()
+                .filter_map(|plan| plan.next_fetch_at)
+                .fold(now + time::Duration::minutes(1), OffsetDateTime::min);
+            next_check.insert(user_id.clone(), recheck);
+            if due.is_empty() {
                 continue;
             }
 
-            let interval = settings.poll_interval_minutes.max(1);
             let jitter_pct = settings.poll_jitter_pct.clamp(0.0, 1.0);
-            let jitter_s = (interval as f64 * 60.0 * jitter_pct * fastrand::f64()) as i64;
-            next_due.insert(
-                user_id.clone(),
-                now.saturating_add(time::Duration::seconds(interval * 60 + jitter_s)),
-            );
+            let jitter_fraction = fastrand::f64();
+            let polled_at = now.format(&Rfc3339)?;
+            for plan in &due {
+                let interval = plan.every_minutes.unwrap_or(1);
+                let jitter_s = (interval as f64 * 60.0 * jitter_pct * jitter_fraction) as i64;
+                db::record_monitoring_poll(
+                    &state.db,
+                    &user_id,
+                    &plan.partition_key,
+                    &polled_at,
+                    jitter_s,
+                )
+                .await?;
+            }
 
-            if let Err(err) = poll_once(&state, &user_id, &settings).await {
+            if let Err(err) = poll_once(&state, &user_id, &settings, &due).await {
                 warn!(user_id, error = %err, "poll failed");
             }
         }
@@ -766,6 +841,179 @@ async fn run(state: AppState) -> anyhow::Result<()> {
     }
 }
 
+/// Planned poll of one monitored partition. Fetches are per partition, so its cadence is the
+/// fastest any of its configs asks for (see [`poll_schedule::combined_slot`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionPollPlan {
+    pub country_id: String,
+    pub region_id: Option<String>,
+    pub partition_key: String,
+    pub config_ids: Vec<String>,
+    pub active_config_ids: Vec<String>,
+    pub last_polled_at: Option<OffsetDateTime>,
+    pub next_fetch_at: Option<OffsetDateTime>,
+    pub every_minutes: Option<i64>,
+    pub schedule_scopes: Vec<String>,
+}
+
+impl PartitionPollPlan {
+    pub fn to_view(&self) -> PartitionPollPlanView {
+        let format = |t: OffsetDateTime| t.format(&Rfc3339).ok();
+        PartitionPollPlanView {
+            country_id: self.country_id.clone(),
+            region_id: self.region_id.clone(),
+            config_ids: self.config_ids.clone(),
+            active_config_ids: self.active_config_ids.clone(),
+            last_polled_at: self.last_polled_at.and_then(format),
+            next_fetch_at: self.next_fetch_at.and_then(format),
+            every_minutes: self.every_minutes,
+            schedule_scopes: self.schedule_scopes.clone(),
+        }
+    }
+}
+
+/// Plans the next fetch of every partition the user monitors. A config follows its own schedule,
+/// else its region's, else its country's, else (with `poll_forecast_enabled`) the restock
+/// forecast of its partition, else the global `default_minutes`.
+pub async fn plan_monitoring_polls(
+    db: &SqlitePool,
+    cfg: &RuntimeConfig,
+    user_id: &str,
+    default_minutes: i64,
+    now: OffsetDateTime,
+) -> anyhow::Result<Vec<PartitionPollPlan>> {
+    let targets = db::list_enabled_monitoring_targets(db, user_id).await?;
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut schedules: HashMap<(String, String), PollSchedule> = HashMap::new();
+    for row in db::list_poll_schedules(db, user_id).await? {
+        let parsed = serde_json::from_str::<Vec<PollScheduleRule>>(&row.rules_json)
+            .map_err(anyhow::Error::from)
+            .and_then(|rules| PollSchedule::parse(&row.utc_offset, &rules));
+        match parsed {
+            Ok(schedule) => {
+                schedules.insert((row.scope_kind, row.scope_key), schedule);
+            }
+            Err(err) => {
+                warn!(user_id, scope = %row.scope_key, error = %err, "ignoring invalid poll schedule")
+            }
+        }
+    }
+    let poll_state = db::list_monitoring_poll_state(db, user_id).await?;
+    if cfg.poll_forecast_enabled {
+        let keys = targets
+            .iter()
+            .map(|(_, country_id, region_id)| {
+                db::monitoring_partition_key(country_id, region_id.as_deref())
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        for (key, (profile_json, _)) in db::list_restock_profiles(db, "partition", &keys).await? {
+            let schedule = serde_json::from_str::<RestockProfile>(&profile_json)
+                .map_err(anyhow::Error::from)
+                .and_then(|profile| {
+                    profile.poll_schedule(
+                        cfg.poll_forecast_dense_minutes,
+                        default_minutes * cfg.poll_forecast_sparse_factor,
+                    )
+                });
+            match schedule {
+                Ok(Some(schedule)) => {
+                    schedules.insert(("forecast".to_string(), key), schedule);
+                }
+                Ok(None) => {}
+                Err(err) => warn!(partition = %key, error = %err, "ignoring restock forecast"),
+            }
+        }
+    }
+
+    type Partition = (
+        String,
+        Option<String>,
+        Vec<(String, Option<(String, String)>)>,
+    );
+    let mut partitions: BTreeMap<String, Partition> = BTreeMap::new();
+    for (config_id, country_id, region_id) in targets {
+        let key = db::monitoring_partition_key(&country_id, region_id.as_deref());
+        let scope = [
+            ("config".to_string(), config_id.clone()),
+            ("partition".to_string(), key.clone()),
+            (
+                "partition".to_string(),
+                db::monitoring_partition_key(&country_id, None),
+            ),
+            ("forecast".to_string(), key.clone()),
+        ]
+        .into_iter()
+        .find(|scope| schedules.contains_key(scope));
+        partitions
+            .entry(key)
+            .or_insert_with(|| (country_id, region_id, Vec::new()))
+            .2
+            .push((config_id, scope));
+    }
+
+    let mut out = Vec::with_capacity(partitions.len());
+    for (partition_key, (country_id, region_id, configs)) in partitions {
+        let mut scopes: Vec<Option<(String, String)>> = Vec::new();
+        for (_, scope) in &configs {
+            if !scopes.contains(scope) {
+                scopes.push(scope.clone());
+            }
+        }
+        let resolved = scopes
+            .iter()
+            .map(|scope| scope.as_ref().map(|k| &schedules[k]))
+            .collect::<Vec<_>>();
+
+        let (last_polled_at, jitter_s) = poll_state
+            .get(&partition_key)
+            .map(|(last, jitter)| (OffsetDateTime::parse(last, &Rfc3339).ok(), *jitter))
+            .unwrap_or((None, 0));
+        let next = poll_schedule::next_fetch(
+            &resolved,
+            default_minutes,
+            last_polled_at.map(|t| t + time::Duration::seconds(jitter_s)),
+            now,
+        );
+        let active_config_ids = next
+            .map(|(at, _)| {
+                configs
+                    .iter()
+                    .filter(|(_, scope)| {
+                        let schedule = scope.as_ref().map(|k| &schedules[k]);
+                        poll_schedule::combined_slot(&[schedule], default_minutes, at)
+                            != poll_schedule::Slot::Quiet
+                    })
+                    .map(|(id, _)| id.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        out.push(PartitionPollPlan {
+            country_id,
+            region_id,
+            partition_key,
+            config_ids: configs.into_iter().map(|(id, _)| id).collect(),
+            active_config_ids,
+            last_polled_at,
+            next_fetch_at: next.map(|(at, _)| at),
+            every_minutes: next.map(|(_, minutes)| minutes),
+            schedule_scopes: scopes
+                .into_iter()
+                .map(|scope| match scope {
+                    Some((kind, key)) => format!("{kind}:{key}"),
+                    None => "default".to_string(),
+                })
+                .collect(),
+        });
+    }
+    Ok(out)
+}
+
 async fn enqueue_discovery_targets(state: &AppState) -> anyhow::Result<()> {
     let targets = known_catalog_targets(state).await?;
     if targets.is_empty() {
@@ -782,6 +1030,148 @@ async fn enqueue_discovery_targets(state: &AppState) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Discovery for storefronts configured in `CATNAP_UPSTREAM_PROVIDERS`. Like the default
+/// storefront, the targets already stored for a provider are queued as `discovery_due` tasks; a
+/// provider without any yet gets its topology refreshed first.
+async fn enqueue_additional_provider_targets(state: &AppState) {
+    let registry = crate::storefront::StorefrontRegistry::from_config(&state.config);
+    for provider in registry.additional() {
+        let provider_id = provider.id().to_string();
+        let targets = match db::list_known_provider_catalog_targets(&state.db, &provider_id).await {
+            Ok(v) => v,
+            Err(err) => {
+                warn!(provider_id, error = %err, "provider discovery failed");
+                continue;
+            }
+        };
+        if targets.is_empty() {
+            if let Err(err) =
+                refresh_provider_topology(state, provider.clone(), "discovery_due").await
+            {
+                warn!(provider_id, error = %err, "provider topology refresh failed");
+            }
+            continue;
+        }
+        enqueue_provider_targets(state, &provider_id, &targets).await;
+    }
+}
+
+/// Refreshes the topology of every additional storefront. Failures are logged per provider.
+pub async fn refresh_additional_provider_topologies(state: &AppState, reason: &str) {
+    let registry = crate::storefront::StorefrontRegistry::from_config(&state.config);
+    for provider in registry.additional() {
+        if let Err(err) = refresh_provider_topology(state, provider.clone(), reason).await {
+            warn!(provider_id = provider.id(), error = %err, "provider topology refresh failed");
+        }
+    }
+}
+
+/// Fetches one additional storefront's topology through ops, retires the targets it no longer
+/// lists and queues the ones it newly lists.
+async fn refresh_provider_topology(
+    state: &AppState,
+    provider: std::sync::Arc<dyn crate::storefront::StorefrontProvider>,
+    reason: &str,
+) -> anyhow::Result<()> {
+    let provider_id = provider.id().to_string();
+    let topology = match state.ops.fetch_provider_topology(provider).await {
+        Ok(v) => v,
+        Err(err) => {
+            log_topology_failure(
+                state,
+                reason,
+                &format!("provider topology refresh failed: provider={provider_id}"),
+                &err,
+            )
+            .await;
+            return Err(err);
+        }
+    };
+    let previous_targets = db::list_known_provider_catalog_targets(&state.db, &provider_id).await?;
+    if topology.countries.is_empty() && !previous_targets.is_empty() {
+        anyhow::bail!("refusing empty topology refresh while catalog state already exists");
+    }
+    let current_targets = provider_topology_targets(&topology);
+    let current_target_keys = current_targets
+        .iter()
+        .map(|(fid, gid)| catalog_region_key(fid, gid.as_deref()))
+        .collect::<HashSet<_>>();
+    let previous_target_keys = previous_targets
+        .iter()
+        .map(|(fid, gid)| catalog_region_key(fid, gid.as_deref()))
+        .collect::<HashSet<_>>();
+    // An ambiguous country page neither proves nor disproves its direct configs.
+    let removed_targets = previous_targets
+        .into_iter()
+        .filter(|(fid, gid)| {
+            let ambiguous = gid.is_none() && topology.ambiguous_country_ids.contains(fid);
+            !ambiguous && !current_target_keys.contains(&catalog_region_key(fid, gid.as_deref()))
+        })
+        .collect::<Vec<_>>();
+    let added_targets = current_targets
+        .into_iter()
+        .filter(|(fid, gid)| {
+            !previous_target_keys.contains(&catalog_region_key(fid, gid.as_deref()))
+        })
+        .collect::<Vec<_>>();
+    let retired_ids = db::retire_catalog_targets(&state.db, &provider_id, &removed_targets).await?;
+    enqueue_provider_targets(state, &provider_id, &added_targets).await;
+    let _ = state
+        .ops
+        .log(
+            "info",
+            "catalog.topology",
+            &format!("provider topology refresh ok: provider={provider_id} reason={reason}"),
+            Some(serde_json::json!({
+                "providerId": provider_id,
+                "reason": reason,
+                "requestCount": topology.request_count,
+                "addedTargetCount": added_targets.len(),
+                "removedTargetCount": removed_targets.len(),
+                "retiredConfigCount": retired_ids.len(),
+            })),
+        )
+        .await;
+    Ok(())
+}
+
+/// `(fid, gid)` targets of a provider topology, in the shop's raw (unscoped) ids.
+fn provider_topology_targets(
+    topology: &crate::upstream::CatalogTopologySnapshot,
+) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    let mut countries_with_regions = HashSet::new();
+    for region in &topology.regions {
+        countries_with_regions.insert(region.country_id.clone());
+        out.push((region.country_id.clone(), Some(region.id.clone())));
+    }
+    for country in &topology.countries {
+        if !countries_with_regions.contains(&country.id)
+            && !topology.ambiguous_country_ids.contains(&country.id)
+        {
+            out.push((country.id.clone(), None));
+        }
+    }
+    out
+}
+
+async fn enqueue_provider_targets(
+    state: &AppState,
+    provider_id: &str,
+    targets: &[(String, Option<String>)],
+) {
+    for (fid, gid) in targets {
+        let country_id = crate::storefront::scoped_country_id(provider_id, fid);
+        if let Err(err) = state
+            .ops
+            .enqueue_background(&country_id, gid.as_deref(), "discovery_due")
+            .await
+        {
+            warn!(provider_id, error = %err, "provider discovery enqueue failed");
+        }
+    }
+}
+
 pub async fn known_catalog_targets(
     state: &AppState,
 ) -> anyhow::Result<Vec<(String, Option<String>)>> {
@@ -927,41 +1317,42 @@ async fn apply_topology_snapshot(
     snap.topology_message = None;
 }
 
+#[tracing::instrument(name = "poller.poll_once", skip_all, fields(user_id = %user_id))]
 async fn poll_once(
     state: &AppState,
     user_id: &str,
     settings: &db::SettingsRow,
+    partitions: &[PartitionPollPlan],
 ) -> anyhow::Result<()> {
     #[derive(Clone)]
     struct PollState {
         inventory_quantity: i64,
         price: Money,
         digest: String,
+        billing_cycles: Vec<BillingCycleOption>,
     }
 
-    let enabled = db::list_enabled_monitoring_config_ids(&state.db, user_id).await?;
-    if enabled.is_empty() {
-        return Ok(());
+    impl PollState {
+        fn snapshot(&self) -> notification_content::MonitoringSnapshot<'_> {
+            notification_content::MonitoringSnapshot {
+                inventory_quantity: self.inventory_quantity,
+                price: &self.price,
+                digest: &self.digest,
+                billing_cycles: &self.billing_cycles,
+            }
+        }
     }
 
-    let mut by_region: HashMap<(String, Option<String>), Vec<String>> = HashMap::new();
-    let placeholders = std::iter::repeat_n("?", enabled.len())
-        .collect::<Vec<_>>()
-        .join(",");
-    let sql = format!(
-        r#"SELECT id, country_id, region_id FROM catalog_configs WHERE id IN ({placeholders})"#
-    );
-    let mut q = sqlx::query(&sql);
-    for id in &enabled {
-        q = q.bind(id);
-    }
-    let rows = q.fetch_all(&state.db).await?;
-    for row in rows {
-        let id = row.get::<String, _>(0);
-        let fid = row.get::<String, _>(1);
-        let gid = row.get::<Option<String>, _>(2);
-        by_region.entry((fid, gid)).or_default().push(id);
-    }
+    let rates = crate::pricing::ExchangeRates::from_config(&state.config);
+    let by_region = partitions
+        .iter()
+        .filter(|plan| !plan.active_config_ids.is_empty())
+        .map(|plan| {
+            (
+                (plan.country_id.clone(), plan.region_id.clone()),
+                plan.active_config_ids.clone(),
+            )
+        });
 
     for ((fid, gid), ids) in by_region {
         let placeholders = std::iter::repeat_n("?", ids.len())
@@ -977,10 +1368,12 @@ async fn poll_once(
             q = q.bind(id);
         }
         let old_rows = q.fetch_all(&state.db).await?;
+        let mut old_cycles = db::list_billing_cycles_for_configs(&state.db, &rates, &ids).await?;
         let mut old_by_id: HashMap<String, PollState> = HashMap::new();
         for r in old_rows {
+            let id = r.get::<String, _>(0);
             old_by_id.insert(
-                r.get::<String, _>(0),
+                id.clone(),
                 PollState {
                     inventory_quantity: r.get::<i64, _>(1),
                     price: Money {
@@ -989,14 +1382,25 @@ async fn poll_once(
                         period: r.get::<String, _>(4),
                     },
                     digest: r.get::<String, _>(5),
+                    billing_cycles: old_cycles.remove(&id).unwrap_or_default(),
                 },
             );
         }
 
-        let run = state
+        // A partition that cannot be fetched right now (paused queue, open circuit) must not
+        // hold up the others or the rest of the poller loop.
+        let run = match state
             .ops
             .enqueue_and_wait_for_poller(&fid, gid.as_deref(), user_id)
-            .await?;
+            .await
+        {
+            Ok(run) => run,
+            Err(err) => {
+                warn!(user_id, fid, gid = ?gid, error = %err, "poll fetch skipped");
+                continue;
+            }
+        };
+        let mut new_cycles = db::list_billing_cycles_for_configs(&state.db, &rates, &ids).await?;
 
         for id in ids {
             let old = old_by_id.get(&id).cloned();
@@ -1020,24 +1424,28 @@ async fn poll_once(
                     period: new_row.get::<String, _>(4),
                 },
                 digest: new_row.get::<String, _>(5),
+                billing_cycles: new_cycles.remove(&id).unwrap_or_default(),
             };
 
             let notification = old.as_ref().and_then(|old_state| {
                 notification_content::build_monitoring_change_notification(
                     &new_name,
-                    &notification_content::MonitoringSnapshot {
-                        inventory_quantity: old_state.inventory_quantity,
-                        price: &old_state.price,
-                        digest: &old_state.digest,
-                    },
-                    &notification_content::MonitoringSnapshot {
-                        inventory_quantity: new_state.inventory_quantity,
-                        price: &new_state.price,
-                        digest: &new_state.digest,
-                    },
+                    &old_state.snapshot(),
+                    &new_state.snapshot(),
                     settings.site_base_url.as_deref(),
                 )
             });
+            let notification = apply_monitoring_rules(
+                state,
+                user_id,
+                (&id, &fid, gid.as_deref()),
+                &new_name,
+                old.as_ref()
+                    .map(|old_state| (old_state.snapshot(), new_state.snapshot())),
+                notification,
+                settings.site_base_url.as_deref(),
+            )
+            .await?;
 
             let events = notification
                 .as_ref()
@@ -1061,7 +1469,7 @@ async fn poll_once(
                 );
                 db::insert_log(&state.db, Some(user_id), "info", "poll", &msg, None).await?;
 
-                let item = db::load_notification_record_item_snapshot(&state.db, &id)
+                let item = db::load_notification_record_item_snapshot(&state.db, &rates, &id)
                     .await?
                     .ok_or_else(|| {
                         anyhow::anyhow!("missing notification snapshot for config {id}")
@@ -1093,6 +1501,9 @@ async fn poll_once(
                         },
                         web_push_status: "skipped".to_string(),
                         items: vec![item],
+                        matched_rule: notification
+                            .as_ref()
+                            .and_then(|item| item.matched_rule.clone()),
                     },
                 )
                 .await?;
@@ -1112,12 +1523,20 @@ async fn poll_once(
                         .map(str::trim)
                         .filter(|value| !value.is_empty());
                     if let Some(token) = token.filter(|_| !settings.telegram_targets.is_empty()) {
+                        let deliver_span = tracing::info_span!(
+                            "notify.deliver",
+                            user_id = %user_id,
+                            config_id = %id,
+                            events = %events.join("+"),
+                            telegram = tracing::field::Empty,
+                        );
                         let deliveries = crate::notifications::send_telegram_to_targets(
-                            &state.config.telegram_api_base_url,
+                            &state.config,
                             token,
                             &settings.telegram_targets,
                             &telegram_text,
                         )
+                        .instrument(deliver_span.clone())
                         .await;
                         db::replace_notification_record_deliveries(
                             &state.db,
@@ -1127,6 +1546,7 @@ async fn poll_once(
                         )
                         .await?;
                         let status = db::aggregate_telegram_status(true, &deliveries);
+                        deliver_span.record("telegram", status.as_str());
                         db::update_notification_record_channel_status(
                             &state.db, &record_id, "telegram", &status,
                         )
@@ -1212,13 +1632,199 @@ async fn poll_once(
     Ok(())
 }
 
+/// Narrows a monitored change to the user's rules: without rules `notification` passes through;
+/// with rules only the first firing one alerts, even on changes the standard events ignore.
+/// `states` is `None` for configs seen for the first time, which stay silent as before.
+async fn apply_monitoring_rules(
+    state: &AppState,
+    user_id: &str,
+    (config_id, country_id, region_id): (&str, &str, Option<&str>),
+    name: &str,
+    states: Option<(
+        notification_content::MonitoringSnapshot<'_>,
+        notification_content::MonitoringSnapshot<'_>,
+    )>,
+    notification: Option<notification_content::MonitoringChangeNotification>,
+    site_base_url: Option<&str>,
+) -> anyhow::Result<Option<notification_content::MonitoringChangeNotification>> {
+    let Some((old, new)) = states else {
+        return Ok(notification);
+    };
+    let rules = crate::monitoring_rules::load_rules_for_config(
+        &state.db, user_id, config_id, country_id, region_id,
+    )
+    .await?;
+    if rules.is_empty() {
+        return Ok(notification);
+    }
+    let rates = crate::pricing::ExchangeRates::from_config(&state.config);
+    let specs = db::load_notification_record_item_snapshot(&state.db, &rates, config_id)
+        .await?
+        .map(|item| item.specs)
+        .unwrap_or_default();
+    let old_state = RuleState {
+        quantity: old.inventory_quantity,
+        price: old.price,
+        specs: &specs,
+        rates: &rates,
+    };
+    let new_state = RuleState {
+        quantity: new.inventory_quantity,
+        price: new.price,
+        specs: &specs,
+        rates: &rates,
+    };
+    Ok(
+        match crate::monitoring_rules::evaluate(&rules, Some(&old_state), &new_state) {
+            RuleVerdict::NoRules => notification,
+            RuleVerdict::Suppressed => None,
+            RuleVerdict::Matched(rule) => {
+                let mut notification = notification.unwrap_or_else(|| {
+                    notification_content::build_rule_match_notification(
+                        name,
+                        &old,
+                        &new,
+                        site_base_url,
+                    )
+                });
+                notification_content::attach_matched_rule(&mut notification, rule.matched_view());
+                Some(notification)
+            }
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn monitoring_rules_suppress_restocks_and_alert_on_changes_events_ignore() {
+        use crate::ops::tests::{build_ops_manager_with_config, spawn_stub_server, test_config};
+        use axum::{routing::get, Router};
+        use std::sync::Arc;
+
+        let page = Arc::new(std::sync::Mutex::new(
+            include_str!("../tests/fixtures/cart-fid-7.html").to_string(),
+        ));
+        let upstream = Router::new().route(
+            "/cart",
+            get({
+                let page = page.clone();
+                move || {
+                    let body = page.lock().unwrap().clone();
+                    async move { axum::response::Html(body) }
+                }
+            }),
+        );
+        let base = spawn_stub_server(upstream).await;
+        let cfg = test_config(format!("{base}/cart"));
+        let (ops, db) =
+            build_ops_manager_with_config(cfg.clone(), cfg.upstream_cart_url.clone()).await;
+        let state = AppState {
+            config: cfg.clone(),
+            db: db.clone(),
+            catalog: Arc::new(tokio::sync::RwLock::new(CatalogSnapshot::empty(
+                cfg.upstream_cart_url.clone(),
+            ))),
+            catalog_refresh: crate::catalog_refresh::CatalogRefreshManager::default(),
+            ops: ops.clone(),
+            update_cache: Arc::new(tokio::sync::RwLock::new(
+                crate::update_check::UpdateCheckCache::default(),
+            )),
+            lazycat_sync_users: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
+        };
+        assert!(
+            ops.enqueue_and_wait("7", Some("40"), "test")
+                .await
+                .unwrap()
+                .ok
+        );
+        db::ensure_user(&db, &cfg, "u_1").await.unwrap();
+        let settings = db::get_settings(&db, "u_1").await.unwrap();
+
+        // Mini restocks 0 → 2, which its rule asks to ignore; Basic goes 1 → 5, which the
+        // standard events ignore but its rule asks for.
+        let (mini, basic) = ("lc:7:40:127", "lc:7:40:128");
+        for (config_id, name, min) in [(mini, "三台起", 3), (basic, "五台起", 5)] {
+            db::insert_monitoring_rule(
+                &db,
+                "u_1",
+                "config",
+                config_id,
+                name,
+                &format!(r#"[{{"kind":"quantityAtLeast","min":{min}}}]"#),
+                true,
+            )
+            .await
+            .unwrap();
+        }
+        {
+            let mut page = page.lock().unwrap();
+            *page = page
+                .replace("库存： 0", "库存： 2")
+                .replace("库存： 1", "库存： 5");
+        }
+        sqlx::query("UPDATE catalog_url_cache SET last_success_at = '2026-01-01T00:00:00Z'")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let ids = vec![mini.to_string(), basic.to_string()];
+        let plan = PartitionPollPlan {
+            country_id: "7".to_string(),
+            region_id: Some("40".to_string()),
+            partition_key: db::monitoring_partition_key("7", Some("40")),
+            config_ids: ids.clone(),
+            active_config_ids: ids,
+            last_polled_at: None,
+            next_fetch_at: None,
+            every_minutes: None,
+            schedule_scopes: Vec::new(),
+        };
+        poll_once(&state, "u_1", &settings, &[plan]).await.unwrap();
+
+        let quantities = sqlx::query(
+            "SELECT inventory_quantity FROM catalog_configs WHERE id IN (?, ?) ORDER BY id",
+        )
+        .bind(mini)
+        .bind(basic)
+        .fetch_all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.get::<i64, _>(0))
+        .collect::<Vec<_>>();
+        assert_eq!(quantities, vec![2, 5]);
+
+        let records = sqlx::query(
+            r#"
+SELECT r.kind, r.title, r.matched_rule_json, i.config_id
+FROM notification_records r
+JOIN notification_record_items i ON i.record_id = r.id
+WHERE r.user_id = 'u_1'
+"#,
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].get::<String, _>(3), basic);
+        assert_eq!(records[0].get::<String, _>(0), "monitoring.rule");
+        assert_eq!(records[0].get::<String, _>(1), "规则命中");
+        let matched = serde_json::from_str::<serde_json::Value>(
+            &records[0].get::<Option<String>, _>(2).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(matched["name"], "五台起");
+        assert_eq!(matched["scopeKey"], basic);
+        assert_eq!(matched["conditions"][0]["min"], 5);
+    }
+
     #[test]
     fn merge_topology_probe_result_keeps_previous_targets_and_adds_new_ones() {
         let previous = CatalogSnapshot {
+            provider_id: crate::storefront::DEFAULT_PROVIDER_ID.to_string(),
             countries: vec![crate::models::Country {
                 id: "2".to_string(),
                 name: "CN".to_string(),
@@ -0,0 +1,176 @@
+use crate::config::RuntimeConfig;
+use crate::models::Money;
+use anyhow::Context;
+use std::collections::HashMap;
+
+/// Currency of a cart price line. `¥`/`元` are read as CNY since the shops we watch price in
+/// yuan; `HK$` is checked before the plain dollar sign.
+pub fn detect_currency(price_text: &str) -> Option<&'static str> {
+    let compact: String = price_text
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect();
+    let upper = compact.to_ascii_uppercase();
+    const MARKERS: &[(&str, &[&str])] = &[
+        ("HKD", &["HK$", "HKD", "港元", "港币"]),
+        ("USD", &["US$", "USD", "$", "美元"]),
+        ("EUR", &["EUR", "€", "欧元"]),
+        ("GBP", &["GBP", "£", "英镑"]),
+        ("JPY", &["JPY", "円", "日元"]),
+        ("CNY", &["CNY", "RMB", "¥", "￥", "元"]),
+    ];
+    MARKERS
+        .iter()
+        .find(|(_, markers)| markers.iter().any(|marker| upper.contains(marker)))
+        .map(|(code, _)| *code)
+}
+
+/// Length of a billing period in months; `None` for one-off or unknown periods.
+pub fn period_months(period: &str) -> Option<f64> {
+    match period {
+        "month" => Some(1.0),
+        "quarter" => Some(3.0),
+        "semiannual" => Some(6.0),
+        "year" => Some(12.0),
+        "biennial" => Some(24.0),
+        "triennial" => Some(36.0),
+        _ => None,
+    }
+}
+
+/// Maps a WHMCS billing-cycle value or label (`annually`, `季付`, `Semi-Annually`) onto the
+/// period names used by [`period_months`].
+pub fn billing_cycle_period(label: &str) -> Option<&'static str> {
+    let compact: String = label
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '-' && *ch != '_')
+        .collect::<String>()
+        .to_lowercase();
+    // Longer cycles first: `triennially` and `semiannually` both contain `annually`.
+    const CYCLES: &[(&str, &[&str])] = &[
+        ("triennial", &["triennial", "三年"]),
+        ("biennial", &["biennial", "两年", "二年"]),
+        ("semiannual", &["semiannual", "半年"]),
+        ("quarter", &["quarter", "季"]),
+        ("year", &["annual", "yearly", "年"]),
+        ("month", &["month", "月"]),
+    ];
+    CYCLES
+        .iter()
+        .find(|(_, markers)| markers.iter().any(|marker| compact.contains(marker)))
+        .map(|(period, _)| *period)
+}
+
+/// Conversion table into one base currency. `rates[code]` is the base amount for one unit of
+/// `code`; the base currency itself is implicit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl Default for ExchangeRates {
+    fn default() -> Self {
+        Self {
+            base: "CNY".to_string(),
+            rates: HashMap::new(),
+        }
+    }
+}
+
+impl ExchangeRates {
+    /// Table of the runtime config. At startup `main` folds `CATNAP_EXCHANGE_RATES_FILE` (see
+    /// [`Self::load_file`]) into `cfg.exchange_rates`, so this is the complete table.
+    pub fn from_config(cfg: &RuntimeConfig) -> Self {
+        Self {
+            base: cfg.price_base_currency.clone(),
+            rates: cfg.exchange_rates.clone(),
+        }
+    }
+
+    /// Overlays rates from a JSON object file such as `{"USD": 7.2, "EUR": 7.8}`.
+    pub fn load_file(&mut self, path: &str) -> anyhow::Result<()> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("read exchange rate file {path}"))?;
+        let parsed = serde_json::from_str::<HashMap<String, f64>>(&raw)
+            .with_context(|| format!("parse exchange rate file {path}"))?;
+        for (code, rate) in parsed {
+            let code = code.trim().to_ascii_uppercase();
+            if !code.is_empty() && rate.is_finite() && rate > 0.0 {
+                self.rates.insert(code, rate);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn convert(&self, amount: f64, currency: &str) -> Option<f64> {
+        if currency.eq_ignore_ascii_case(&self.base) {
+            return Some(amount);
+        }
+        self.rates
+            .get(&currency.to_ascii_uppercase())
+            .map(|rate| amount * rate)
+    }
+
+    /// Price per month in the base currency, or `None` when the period or currency is unknown.
+    pub fn monthly_equivalent(&self, price: &Money) -> Option<Money> {
+        let months = period_months(&price.period)?;
+        let amount = self.convert(price.amount, &price.currency)? / months;
+        Some(Money {
+            amount: (amount * 100.0).round() / 100.0,
+            currency: self.base.clone(),
+            period: "month".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(amount: f64, currency: &str, period: &str) -> Money {
+        Money {
+            amount,
+            currency: currency.to_string(),
+            period: period.to_string(),
+        }
+    }
+
+    #[test]
+    fn detects_currency_from_price_line() {
+        assert_eq!(detect_currency("¥ 25.00 元 / 月"), Some("CNY"));
+        assert_eq!(detect_currency("$ 4.99 USD / mo"), Some("USD"));
+        assert_eq!(detect_currency("HK$ 38 / 月"), Some("HKD"));
+        assert_eq!(detect_currency("€3.50 / month"), Some("EUR"));
+        assert_eq!(detect_currency("25.00 / 月"), None);
+    }
+
+    #[test]
+    fn maps_billing_cycle_labels() {
+        assert_eq!(billing_cycle_period("monthly"), Some("month"));
+        assert_eq!(billing_cycle_period("Semi-Annually"), Some("semiannual"));
+        assert_eq!(billing_cycle_period("triennially"), Some("triennial"));
+        assert_eq!(billing_cycle_period("annually"), Some("year"));
+        assert_eq!(billing_cycle_period("季付"), Some("quarter"));
+        assert_eq!(billing_cycle_period("两年付"), Some("biennial"));
+        assert_eq!(billing_cycle_period("onetime"), None);
+    }
+
+    #[test]
+    fn monthly_equivalent_converts_period_and_currency() {
+        let rates = ExchangeRates {
+            base: "CNY".to_string(),
+            rates: HashMap::from([("USD".to_string(), 7.0)]),
+        };
+        assert_eq!(
+            rates.monthly_equivalent(&money(120.0, "CNY", "year")),
+            Some(money(10.0, "CNY", "month"))
+        );
+        assert_eq!(
+            rates.monthly_equivalent(&money(12.0, "USD", "year")),
+            Some(money(7.0, "CNY", "month"))
+        );
+        assert_eq!(rates.monthly_equivalent(&money(5.0, "EUR", "month")), None);
+        assert_eq!(rates.monthly_equivalent(&money(5.0, "CNY", "once")), None);
+    }
+}
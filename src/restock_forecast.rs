@@ -0,0 +1,407 @@
+use crate::db;
+use crate::poll_schedule::{PollSchedule, PollScheduleRule};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Restocks a profile needs before it predicts anything.
+pub const MIN_EVENTS: usize = 3;
+/// Days of `inventory_samples_1m` history the profiles learn from.
+pub const HISTORY_DAYS: i64 = 30;
+/// Restocks of several configs in one partition this close together count as one event.
+const PARTITION_EVENT_MERGE_MINUTES: i64 = 10;
+/// Relative likelihood of an hour of day (1.0 = uniform over the day) from which it counts as a
+/// likely restock time. A weekday the history covered must also be at least as likely as
+/// uniform, so a weekly pattern marks that day's restock hours rather than the whole day.
+const LIKELY_SCORE: f64 = 2.0;
+/// Additive smoothing of the histograms, so a handful of events cannot rule out an hour.
+const SMOOTHING: f64 = 0.5;
+
+/// What the history says about when a config or partition restocks. Histograms are in UTC,
+/// weekdays start on Monday.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestockProfile {
+    pub events: usize,
+    pub hour_histogram: Vec<u32>,
+    pub weekday_histogram: Vec<u32>,
+    /// Median time between consecutive restocks.
+    pub median_gap_minutes: Option<i64>,
+    #[serde(default)]
+    pub first_restock_at: Option<String>,
+    pub last_restock_at: Option<String>,
+}
+
+/// A stretch of consecutive likely hours.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestockWindow {
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+    /// Peak relative likelihood within the window.
+    pub score: f64,
+}
+
+impl RestockProfile {
+    /// Builds a profile from restock times sorted oldest first.
+    pub fn from_events(events: &[OffsetDateTime]) -> Self {
+        let mut hour_histogram = vec![0u32; 24];
+        let mut weekday_histogram = vec![0u32; 7];
+        for at in events {
+            hour_histogram[at.hour() as usize] += 1;
+            weekday_histogram[at.weekday().number_days_from_monday() as usize] += 1;
+        }
+        let mut gaps = events
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).whole_minutes())
+            .collect::<Vec<_>>();
+        gaps.sort_unstable();
+        Self {
+            events: events.len(),
+            hour_histogram,
+            weekday_histogram,
+            median_gap_minutes: gaps.get(gaps.len() / 2).copied(),
+            first_restock_at: events.first().and_then(|at| at.format(&Rfc3339).ok()),
+            last_restock_at: events.last().and_then(|at| at.format(&Rfc3339).ok()),
+        }
+    }
+
+    pub fn predictable(&self) -> bool {
+        self.events >= MIN_EVENTS
+    }
+
+    /// Likelihood of a restock in the hour containing `at`, relative to a uniform spread over the
+    /// week. Hour of day and weekday are treated as independent.
+    pub fn score(&self, at: OffsetDateTime) -> f64 {
+        let (hour, day) = self.factors(at);
+        hour * day
+    }
+
+    /// Whether the hour containing `at` is a likely restock time: its hour of day is well above
+    /// uniform and its weekday is not below it, unless the history never covered that weekday.
+    fn likely(&self, at: OffsetDateTime) -> bool {
+        let (hour, day) = self.factors(at);
+        hour >= LIKELY_SCORE && (day >= 1.0 || !self.covers_weekday(at.weekday()))
+    }
+
+    /// Whether `weekday` falls between the first and last restock, so a lack of restocks on it
+    /// says something. Profiles without a first restock count as covering every day.
+    fn covers_weekday(&self, weekday: time::Weekday) -> bool {
+        let parse = |at: Option<&str>| OffsetDateTime::parse(at?, &Rfc3339).ok();
+        let (Some(first), Some(last)) = (
+            parse(self.first_restock_at.as_deref()),
+            parse(self.last_restock_at.as_deref()),
+        ) else {
+            return true;
+        };
+        let span_days = (last.date() - first.date()).whole_days();
+        span_days >= 6
+            || (0..=span_days)
+                .any(|d| (first.date() + time::Duration::days(d)).weekday() == weekday)
+    }
+
+    /// Smoothed likelihood of the hour of day and of the weekday of `at`, each relative to
+    /// uniform.
+    fn factors(&self, at: OffsetDateTime) -> (f64, f64) {
+        let n = self.events as f64;
+        let hour = self
+            .hour_histogram
+            .get(at.hour() as usize)
+            .copied()
+            .unwrap_or(0) as f64;
+        let day = self
+            .weekday_histogram
+            .get(at.weekday().number_days_from_monday() as usize)
+            .copied()
+            .unwrap_or(0) as f64;
+        let p_hour = (hour + SMOOTHING) / (n + 24.0 * SMOOTHING);
+        let p_day = (day + SMOOTHING) / (n + 7.0 * SMOOTHING);
+        (p_hour * 24.0, p_day * 7.0)
+    }
+
+    /// Upcoming likely windows within `horizon_hours` of `now`, earliest first.
+    pub fn predict_windows(
+        &self,
+        now: OffsetDateTime,
+        horizon_hours: i64,
+        limit: usize,
+    ) -> Vec<RestockWindow> {
+        if !self.predictable() {
+            return Vec::new();
+        }
+        let hour_start = now
+            .replace_minute(0)
+            .and_then(|t| t.replace_second(0))
+            .and_then(|t| t.replace_nanosecond(0))
+            .unwrap_or(now);
+        let mut windows: Vec<RestockWindow> = Vec::new();
+        for step in 0..horizon_hours {
+            let start = hour_start + time::Duration::hours(step);
+            if !self.likely(start) {
+                continue;
+            }
+            let score = self.score(start);
+            let end = start + time::Duration::hours(1);
+            match windows.last_mut() {
+                Some(last) if last.end == start => {
+                    last.end = end;
+                    last.score = last.score.max(score);
+                }
+                _ => {
+                    if windows.len() == limit {
+                        break;
+                    }
+                    windows.push(RestockWindow { start, end, score });
+                }
+            }
+        }
+        windows
+    }
+
+    /// When the median gap since the last restock runs out, if that is still ahead of `now`.
+    pub fn expected_by_gap(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let last = OffsetDateTime::parse(self.last_restock_at.as_deref()?, &Rfc3339).ok()?;
+        let at = last + time::Duration::minutes(self.median_gap_minutes?);
+        (at > now).then_some(at)
+    }
+
+    /// Poll schedule (UTC) that polls every `dense_minutes` during likely hours and every
+    /// `sparse_minutes` otherwise; `None` without enough history.
+    pub fn poll_schedule(
+        &self,
+        dense_minutes: i64,
+        sparse_minutes: i64,
+    ) -> anyhow::Result<Option<PollSchedule>> {
+        if !self.predictable() {
+            return Ok(None);
+        }
+        const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+        let monday = time::Date::from_iso_week_date(2024, 1, time::Weekday::Monday)?;
+        let mut rules = Vec::new();
+        for (day_idx, day) in DAYS.iter().enumerate() {
+            let date = monday + time::Duration::days(day_idx as i64);
+            let mut hour = 0u8;
+            while hour < 24 {
+                let likely =
+                    |h: u8| self.likely(date.with_hms(h, 0, 0).expect("valid hour").assume_utc());
+                if !likely(hour) {
+                    hour += 1;
+                    continue;
+                }
+                let start = hour;
+                while hour < 24 && likely(hour) {
+                    hour += 1;
+                }
+                rules.push(dense_rule(day, start, hour, dense_minutes));
+            }
+        }
+        rules.push(PollScheduleRule {
+            days: Vec::new(),
+            start: None,
+            end: None,
+            every_minutes: Some(sparse_minutes),
+            quiet: false,
+        });
+        PollSchedule::parse("+00:00", &rules).map(Some)
+    }
+}
+
+/// Dense rule for the likely hours `start..end` of `day`; a run over the whole day has no time
+/// range, since a range needs distinct bounds.
+fn dense_rule(day: &str, start: u8, end: u8, every_minutes: i64) -> PollScheduleRule {
+    let all_day = start == 0 && end >= 24;
+    PollScheduleRule {
+        days: vec![day.to_string()],
+        start: (!all_day).then(|| format!("{start:02}:00")),
+        end: (!all_day).then(|| format!("{:02}:00", end % 24)),
+        every_minutes: Some(every_minutes),
+        quiet: false,
+    }
+}
+
+/// Relearns every config and partition profile from the sample history, replacing the stored
+/// ones. Returns how many profiles were written.
+pub async fn refresh_profiles(db: &SqlitePool, now: OffsetDateTime) -> anyhow::Result<usize> {
+    let since = (now - time::Duration::days(HISTORY_DAYS)).format(&Rfc3339)?;
+    let mut by_config: BTreeMap<String, Vec<OffsetDateTime>> = BTreeMap::new();
+    let mut by_partition: BTreeMap<String, Vec<OffsetDateTime>> = BTreeMap::new();
+    for event in db::list_restock_events(db, &since).await? {
+        let Ok(at) = OffsetDateTime::parse(&event.ts_minute, &Rfc3339) else {
+            continue;
+        };
+        by_config.entry(event.config_id).or_default().push(at);
+        by_partition
+            .entry(db::monitoring_partition_key(
+                &event.country_id,
+                event.region_id.as_deref(),
+            ))
+            .or_default()
+            .push(at);
+    }
+
+    let mut profiles = Vec::with_capacity(by_config.len() + by_partition.len());
+    for (config_id, events) in by_config {
+        profiles.push((
+            "config",
+            config_id,
+            serde_json::to_string(&RestockProfile::from_events(&events))?,
+        ));
+    }
+    for (partition_key, mut events) in by_partition {
+        events.sort();
+        profiles.push((
+            "partition",
+            partition_key,
+            serde_json::to_string(&RestockProfile::from_events(&merge_close_events(events)))?,
+        ));
+    }
+    db::replace_restock_profiles(db, &profiles, &now.format(&Rfc3339)?).await?;
+    Ok(profiles.len())
+}
+
+/// Collapses restocks of sibling configs that land within a few minutes of each other.
+fn merge_close_events(sorted: Vec<OffsetDateTime>) -> Vec<OffsetDateTime> {
+    let mut out: Vec<OffsetDateTime> = Vec::with_capacity(sorted.len());
+    for at in sorted {
+        if out
+            .last()
+            .is_some_and(|last| at - *last < time::Duration::minutes(PARTITION_EVENT_MERGE_MINUTES))
+        {
+            continue;
+        }
+        out.push(at);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    /// Restocks at 20:xx UTC on five consecutive days, starting Monday 2026-10-12.
+    fn evening_profile() -> RestockProfile {
+        let events = (0..5)
+            .map(|day| datetime!(2026-10-12 20:05 UTC) + time::Duration::days(day))
+            .collect::<Vec<_>>();
+        RestockProfile::from_events(&events)
+    }
+
+    #[test]
+    fn learns_histograms_and_the_typical_gap() {
+        let profile = evening_profile();
+        assert_eq!(profile.events, 5);
+        assert_eq!(profile.hour_histogram[20], 5);
+        assert_eq!(profile.weekday_histogram, vec![1, 1, 1, 1, 1, 0, 0]);
+        assert_eq!(profile.median_gap_minutes, Some(24 * 60));
+        assert_eq!(
+            profile.expected_by_gap(datetime!(2026-10-17 12:00 UTC)),
+            Some(datetime!(2026-10-17 20:05 UTC))
+        );
+        assert!(profile.score(datetime!(2026-10-19 20:30 UTC)) > LIKELY_SCORE);
+        assert!(profile.score(datetime!(2026-10-19 08:30 UTC)) < 1.0);
+
+        assert!(!RestockProfile::from_events(&[datetime!(2026-10-12 20:05 UTC)]).predictable());
+    }
+
+    #[test]
+    fn predicts_upcoming_windows_and_a_dense_schedule() {
+        let profile = evening_profile();
+        // Monday 2026-10-19, 10:00 UTC.
+        let windows = profile.predict_windows(datetime!(2026-10-19 10:00 UTC), 48, 5);
+        assert_eq!(
+            windows.iter().map(|w| (w.start, w.end)).collect::<Vec<_>>(),
+            vec![
+                (
+                    datetime!(2026-10-19 20:00 UTC),
+                    datetime!(2026-10-19 21:00 UTC)
+                ),
+                (
+                    datetime!(2026-10-20 20:00 UTC),
+                    datetime!(2026-10-20 21:00 UTC)
+                ),
+            ]
+        );
+
+        let schedule = profile.poll_schedule(1, 10).unwrap().unwrap();
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-19 20:15 UTC)),
+            Some(crate::poll_schedule::Slot::Every(1))
+        );
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-19 12:00 UTC)),
+            Some(crate::poll_schedule::Slot::Every(10))
+        );
+    }
+
+    #[test]
+    fn weekly_pattern_densifies_only_that_days_restock_hour() {
+        // Four Mondays in a row at 20:00 UTC, starting 2026-09-21.
+        let events = (0..4)
+            .map(|week| datetime!(2026-09-21 20:00 UTC) + time::Duration::weeks(week))
+            .collect::<Vec<_>>();
+        let profile = RestockProfile::from_events(&events);
+        assert!(profile.score(datetime!(2026-10-19 10:00 UTC)) > LIKELY_SCORE);
+
+        // Sunday 2026-10-18, 12:00 UTC.
+        let windows = profile.predict_windows(datetime!(2026-10-18 12:00 UTC), 48, 5);
+        assert_eq!(
+            windows.iter().map(|w| (w.start, w.end)).collect::<Vec<_>>(),
+            vec![(
+                datetime!(2026-10-19 20:00 UTC),
+                datetime!(2026-10-19 21:00 UTC)
+            )]
+        );
+
+        let schedule = profile.poll_schedule(1, 10).unwrap().unwrap();
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-19 20:15 UTC)),
+            Some(crate::poll_schedule::Slot::Every(1))
+        );
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-19 10:00 UTC)),
+            Some(crate::poll_schedule::Slot::Every(10))
+        );
+        assert_eq!(
+            schedule.slot_at(datetime!(2026-10-20 20:15 UTC)),
+            Some(crate::poll_schedule::Slot::Every(10))
+        );
+    }
+
+    #[test]
+    fn short_daily_history_still_predicts_unseen_weekdays() {
+        // Restocks at 20:00 UTC Monday to Thursday only.
+        let events = (0..4)
+            .map(|day| datetime!(2026-10-12 20:00 UTC) + time::Duration::days(day))
+            .collect::<Vec<_>>();
+        let profile = RestockProfile::from_events(&events);
+        // Friday 2026-10-16, 12:00 UTC.
+        let windows = profile.predict_windows(datetime!(2026-10-16 12:00 UTC), 24, 5);
+        assert_eq!(
+            windows.iter().map(|w| w.start).collect::<Vec<_>>(),
+            vec![datetime!(2026-10-16 20:00 UTC)]
+        );
+    }
+
+    #[test]
+    fn whole_day_runs_become_all_day_rules() {
+        let rule = dense_rule("mon", 0, 24, 1);
+        assert_eq!((rule.start, rule.end), (None, None));
+        let rule = dense_rule("mon", 22, 24, 1);
+        assert_eq!(
+            (rule.start.as_deref(), rule.end.as_deref()),
+            (Some("22:00"), Some("00:00"))
+        );
+        assert!(PollSchedule::parse("+00:00", &[dense_rule("mon", 0, 24, 1)]).is_ok());
+    }
+
+    #[test]
+    fn sibling_restocks_merge_into_one_partition_event() {
+        let merged = merge_close_events(vec![
+            datetime!(2026-10-12 20:00 UTC),
+            datetime!(2026-10-12 20:03 UTC),
+            datetime!(2026-10-12 21:00 UTC),
+        ]);
+        assert_eq!(merged.len(), 2);
+    }
+}
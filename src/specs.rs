@@ -0,0 +1,256 @@
+use crate::models::{NormalizedSpecs, Spec};
+
+/// Bump when the rules below change; `db::init_db` re-normalizes rows stored with an older version.
+pub const SPEC_NORMALIZATION_VERSION: i64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpecKind {
+    Cpu,
+    Memory,
+    Disk,
+    Traffic,
+    Bandwidth,
+    Ipv4,
+    Ipv6,
+    Nat,
+}
+
+fn classify_key(key: &str) -> Option<SpecKind> {
+    let k = key.trim().to_lowercase();
+    let kind = if k.contains("ipv6") {
+        SpecKind::Ipv6
+    } else if k.contains("ipv4") || k == "ip" || k.contains("公网ip") || k.contains("ip数量") {
+        SpecKind::Ipv4
+    } else if k.contains("nat") {
+        SpecKind::Nat
+    } else if k.contains("cpu") || k.contains("核心") || k.contains("处理器") || k == "core" {
+        SpecKind::Cpu
+    } else if k.contains("内存") || k == "ram" || k == "mem" || k.contains("memory") {
+        SpecKind::Memory
+    } else if k.contains("硬盘")
+        || k.contains("磁盘")
+        || k.contains("系统盘")
+        || k.contains("存储")
+        || k.contains("disk")
+        || k.contains("storage")
+        || k == "ssd"
+        || k == "nvme"
+    {
+        SpecKind::Disk
+    } else if k.contains("流量") || k.contains("traffic") || k.contains("transfer") {
+        SpecKind::Traffic
+    } else if k.contains("带宽") || k.contains("bandwidth") || k.contains("网速") || k == "port"
+    {
+        SpecKind::Bandwidth
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+/// Splits `"1.5 TB/月"` into `(1.5, "tb/月")`.
+fn parse_quantity(value: &str) -> Option<(f64, String)> {
+    let start = value.find(|ch: char| ch.is_ascii_digit())?;
+    let rest = &value[start..];
+    let end = rest
+        .find(|ch: char| !(ch.is_ascii_digit() || ch == '.'))
+        .unwrap_or(rest.len());
+    let number = rest[..end].trim_end_matches('.').parse::<f64>().ok()?;
+    let unit = rest[end..].trim_start().to_lowercase();
+    Some((number, unit))
+}
+
+fn is_unlimited(value: &str) -> bool {
+    let v = value.to_lowercase();
+    v.contains("不限") || v.contains("无限") || v.contains("unlimited") || v.contains('∞')
+}
+
+fn is_absent(value: &str) -> bool {
+    let v = value.trim().to_lowercase();
+    v == "0"
+        || v == "无"
+        || v == "none"
+        || v == "no"
+        || v.contains("不支持")
+        || v.contains("不提供")
+}
+
+/// Size in GB for disk/traffic style values (`G`, `GB`, `T`, `TB`, `M`).
+fn size_gb(value: &str) -> Option<f64> {
+    let (n, unit) = parse_quantity(value)?;
+    match unit.chars().next()? {
+        't' => Some(n * 1024.0),
+        'g' => Some(n),
+        'm' => Some(n / 1024.0),
+        _ => None,
+    }
+}
+
+fn memory_mb(value: &str) -> Option<i64> {
+    let (n, unit) = parse_quantity(value)?;
+    let mb = match unit.chars().next()? {
+        't' => n * 1024.0 * 1024.0,
+        'g' => n * 1024.0,
+        'm' => n,
+        'k' => n / 1024.0,
+        _ => return None,
+    };
+    Some(mb.round() as i64)
+}
+
+fn bandwidth_mbps(value: &str) -> Option<f64> {
+    let (n, unit) = parse_quantity(value)?;
+    match unit.chars().next()? {
+        'g' => Some(n * 1000.0),
+        'm' => Some(n),
+        'k' => Some(n / 1000.0),
+        _ => None,
+    }
+}
+
+fn disk_type(value: &str) -> Option<String> {
+    let v = value.to_lowercase();
+    ["nvme", "ssd", "hdd"]
+        .into_iter()
+        .find(|kind| v.contains(kind))
+        .map(str::to_string)
+}
+
+/// Reads the known spec keys into typed fields. Unknown keys are ignored here and stay in
+/// `specs` untouched; the first recognizable value wins when a key repeats.
+pub fn normalize_specs(name: &str, specs: &[Spec]) -> NormalizedSpecs {
+    let mut out = NormalizedSpecs::default();
+    for spec in specs {
+        let Some(kind) = classify_key(&spec.key) else {
+            continue;
+        };
+        let value = spec.value.trim();
+        match kind {
+            SpecKind::Cpu => {
+                if out.cpu_cores.is_none() {
+                    out.cpu_cores = parse_quantity(value).map(|(n, _)| n);
+                }
+            }
+            SpecKind::Memory => {
+                if out.memory_mb.is_none() {
+                    out.memory_mb = memory_mb(value);
+                }
+            }
+            SpecKind::Disk => {
+                if out.disk_gb.is_none() {
+                    out.disk_gb = size_gb(value);
+                }
+                if out.disk_type.is_none() {
+                    out.disk_type = disk_type(value).or_else(|| disk_type(&spec.key));
+                }
+            }
+            SpecKind::Traffic => {
+                if is_unlimited(value) {
+                    out.traffic_unlimited = true;
+                } else if out.traffic_gb.is_none() {
+                    out.traffic_gb = size_gb(value);
+                }
+            }
+            SpecKind::Bandwidth => {
+                if out.bandwidth_mbps.is_none() {
+                    out.bandwidth_mbps = bandwidth_mbps(value);
+                }
+            }
+            SpecKind::Ipv4 => {
+                let lower = value.to_lowercase();
+                if lower.contains("nat") || value.contains("共享") {
+                    out.network_type = Some("nat".to_string());
+                } else if is_absent(value) {
+                    out.ipv4_count.get_or_insert(0);
+                } else if let Some((n, _)) = parse_quantity(value) {
+                    out.ipv4_count.get_or_insert(n as i64);
+                } else if value.contains("独立") || lower.contains("dedicated") {
+                    out.ipv4_count.get_or_insert(1);
+                }
+            }
+            SpecKind::Ipv6 => {
+                if out.ipv6.is_none() && !value.is_empty() {
+                    out.ipv6 = Some(!is_absent(value));
+                }
+            }
+            SpecKind::Nat => {
+                out.network_type = Some("nat".to_string());
+            }
+        }
+    }
+
+    if out.network_type.is_none() {
+        if name.to_lowercase().contains("nat") {
+            out.network_type = Some("nat".to_string());
+        } else if out.ipv4_count.is_some_and(|n| n > 0) {
+            out.network_type = Some("dedicated".to_string());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(key: &str, value: &str) -> Spec {
+        Spec {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn normalizes_cart_and_english_spec_keys() {
+        let specs = vec![
+            spec("CPU", "1核心"),
+            spec("内存", "256M"),
+            spec("硬盘", "10G SSD"),
+            spec("流量", "1.5TB/月"),
+            spec("带宽", "1Gbps"),
+            spec("IPv4", "1个"),
+            spec("IPv6", "/64"),
+            spec("备注", "随时退款"),
+        ];
+        let n = normalize_specs("Frontier动态家宽 Mini", &specs);
+        assert_eq!(n.cpu_cores, Some(1.0));
+        assert_eq!(n.memory_mb, Some(256));
+        assert_eq!(n.disk_gb, Some(10.0));
+        assert_eq!(n.disk_type.as_deref(), Some("ssd"));
+        assert_eq!(n.traffic_gb, Some(1536.0));
+        assert!(!n.traffic_unlimited);
+        assert_eq!(n.bandwidth_mbps, Some(1000.0));
+        assert_eq!(n.ipv4_count, Some(1));
+        assert_eq!(n.ipv6, Some(true));
+        assert_eq!(n.network_type.as_deref(), Some("dedicated"));
+
+        let n = normalize_specs(
+            "HKG NAT Mini",
+            &[
+                spec("RAM", "2 GB"),
+                spec("Disk", "80 GB NVMe"),
+                spec("Traffic", "不限"),
+                spec("IPv6", "无"),
+            ],
+        );
+        assert_eq!(n.memory_mb, Some(2048));
+        assert_eq!(n.disk_type.as_deref(), Some("nvme"));
+        assert_eq!(n.traffic_gb, None);
+        assert!(n.traffic_unlimited);
+        assert_eq!(n.ipv6, Some(false));
+        assert_eq!(n.network_type.as_deref(), Some("nat"));
+    }
+
+    #[test]
+    fn unknown_or_unitless_values_stay_empty() {
+        let n = normalize_specs(
+            "X",
+            &[
+                spec("内存", "大"),
+                spec("带宽", "100"),
+                spec("系统", "Debian"),
+            ],
+        );
+        assert_eq!(n, NormalizedSpecs::default());
+    }
+}
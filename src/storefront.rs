@@ -0,0 +1,176 @@
+use crate::config::RuntimeConfig;
+use crate::models::{BillingCycleOption, Country, Region};
+use crate::upstream::ConfigBase;
+use std::sync::Arc;
+
+/// Provider id of the built-in lxc.lazycat.wiki storefront. Rows created before providers existed
+/// belong to it, and its ids stay unscoped so existing config ids and partitions keep working.
+pub const DEFAULT_PROVIDER_ID: &str = "lazycat";
+/// Config ids of the default storefront start with `lc:`; additional providers use their own id
+/// as the prefix, so neither id is available to them.
+pub const DEFAULT_CONFIG_ID_PREFIX: &str = "lc";
+
+/// Markup adapter for one shop. The client owns fetching and pacing; providers only turn cart
+/// pages into catalog data.
+pub trait StorefrontProvider: Send + Sync {
+    fn id(&self) -> &str;
+    fn cart_url(&self) -> &str;
+    fn parse_countries(&self, html: &str) -> Vec<Country>;
+    fn parse_regions(&self, fid: &str, html: &str) -> Vec<Region>;
+    fn parse_region_notice(&self, html: &str) -> Option<String>;
+    fn parse_configs(&self, fid: &str, gid: Option<&str>, html: &str) -> Vec<ConfigBase>;
+    /// Billing cycles offered on a product's configureproduct page.
+    fn parse_billing_cycles(&self, html: &str) -> Vec<BillingCycleOption>;
+    /// Structural problems that suggest the shop changed its markup; empty for a healthy page.
+    fn detect_parser_drift(&self, fid: &str, html: &str) -> Vec<String>;
+}
+
+/// WHMCS-style cart (`cart.php?fid=..&gid=..`) with the lazycat theme selectors.
+#[derive(Debug, Clone)]
+pub struct WhmcsCartProvider {
+    id: String,
+    cart_url: String,
+}
+
+impl WhmcsCartProvider {
+    pub fn new(id: impl Into<String>, cart_url: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            cart_url: cart_url.into(),
+        }
+    }
+}
+
+impl StorefrontProvider for WhmcsCartProvider {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn cart_url(&self) -> &str {
+        &self.cart_url
+    }
+
+    fn parse_countries(&self, html: &str) -> Vec<Country> {
+        crate::upstream::parse_countries(html)
+    }
+
+    fn parse_regions(&self, fid: &str, html: &str) -> Vec<Region> {
+        crate::upstream::parse_regions(fid, html)
+    }
+
+    fn parse_region_notice(&self, html: &str) -> Option<String> {
+        crate::upstream::parse_region_notice(html)
+    }
+
+    fn parse_configs(&self, fid: &str, gid: Option<&str>, html: &str) -> Vec<ConfigBase> {
+        crate::upstream::parse_configs_for_provider(&self.id, fid, gid, html)
+    }
+
+    fn parse_billing_cycles(&self, html: &str) -> Vec<BillingCycleOption> {
+        crate::upstream::parse_billing_cycles(html)
+    }
+
+    fn detect_parser_drift(&self, fid: &str, html: &str) -> Vec<String> {
+        crate::upstream::detect_parser_drift(&self.id, fid, html)
+    }
+}
+
+#[derive(Clone)]
+pub struct StorefrontRegistry {
+    providers: Vec<Arc<dyn StorefrontProvider>>,
+}
+
+impl StorefrontRegistry {
+    pub fn from_config(cfg: &RuntimeConfig) -> Self {
+        let mut providers: Vec<Arc<dyn StorefrontProvider>> = vec![Arc::new(
+            WhmcsCartProvider::new(DEFAULT_PROVIDER_ID, cfg.upstream_cart_url.clone()),
+        )];
+        for provider in &cfg.upstream_providers {
+            providers.push(Arc::new(WhmcsCartProvider::new(
+                provider.id.clone(),
+                provider.cart_url.clone(),
+            )));
+        }
+        Self { providers }
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<dyn StorefrontProvider>> {
+        self.providers
+            .iter()
+            .find(|provider| provider.id() == id)
+            .cloned()
+    }
+
+    pub fn providers(&self) -> &[Arc<dyn StorefrontProvider>] {
+        &self.providers
+    }
+
+    /// Shops configured next to the default one.
+    pub fn additional(&self) -> impl Iterator<Item = &Arc<dyn StorefrontProvider>> {
+        self.providers
+            .iter()
+            .filter(|provider| provider.id() != DEFAULT_PROVIDER_ID)
+    }
+}
+
+/// Country ids of additional providers are prefixed with the provider id so partitions, config
+/// ids and region notices of different shops never collide. The raw `fid` stays in `source_fid`.
+pub fn scoped_country_id(provider_id: &str, fid: &str) -> String {
+    if provider_id == DEFAULT_PROVIDER_ID {
+        fid.to_string()
+    } else {
+        format!("{provider_id}:{fid}")
+    }
+}
+
+/// Inverse of [`scoped_country_id`]: returns `(provider_id, fid)`.
+pub fn split_country_id(country_id: &str) -> (&str, &str) {
+    match country_id.split_once(':') {
+        Some((provider_id, fid)) if !provider_id.is_empty() && !fid.is_empty() => {
+            (provider_id, fid)
+        }
+        _ => (DEFAULT_PROVIDER_ID, country_id),
+    }
+}
+
+pub fn provider_region_key(provider_id: &str, fid: &str, gid: Option<&str>) -> String {
+    crate::upstream::catalog_region_key(&scoped_country_id(provider_id, fid), gid)
+}
+
+pub fn is_valid_provider_id(id: &str) -> bool {
+    id != DEFAULT_PROVIDER_ID
+        && id != DEFAULT_CONFIG_ID_PREFIX
+        && !id.is_empty()
+        && id.len() <= 32
+        && id
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_country_ids_round_trip() {
+        assert_eq!(scoped_country_id(DEFAULT_PROVIDER_ID, "7"), "7");
+        assert_eq!(scoped_country_id("shopa", "7"), "shopa:7");
+        assert_eq!(split_country_id("7"), (DEFAULT_PROVIDER_ID, "7"));
+        assert_eq!(split_country_id("shopa:7"), ("shopa", "7"));
+        assert_eq!(
+            provider_region_key(DEFAULT_PROVIDER_ID, "7", Some("40")),
+            "7:40"
+        );
+        assert_eq!(provider_region_key("shopa", "7", None), "shopa:7:0");
+    }
+
+    #[test]
+    fn provider_ids_are_restricted_to_slug_characters() {
+        assert!(is_valid_provider_id("shop-a_2"));
+        assert!(!is_valid_provider_id(""));
+        assert!(!is_valid_provider_id("Shop"));
+        assert!(!is_valid_provider_id("a:b"));
+        assert!(!is_valid_provider_id(DEFAULT_PROVIDER_ID));
+        assert!(!is_valid_provider_id(DEFAULT_CONFIG_ID_PREFIX));
+    }
+}
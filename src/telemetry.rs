@@ -0,0 +1,175 @@
+use crate::config::RuntimeConfig;
+use opentelemetry::trace::{SpanContext, TraceContextExt, TracerProvider as _};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing::warn;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// Keeps the OTLP pipeline alive; [`Telemetry::shutdown`] flushes spans still in the batch.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider {
+            if let Err(err) = provider.shutdown() {
+                warn!(error = %err, "otlp trace exporter shutdown failed");
+            }
+        }
+    }
+}
+
+/// Installs the global subscriber: the usual fmt output, plus OTLP/HTTP trace export when
+/// `CATNAP_OTLP_ENDPOINT` is set.
+pub fn init(cfg: &RuntimeConfig) -> anyhow::Result<Telemetry> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer());
+    let Some(endpoint) = cfg.otlp_endpoint.as_deref() else {
+        registry.init();
+        return Ok(Telemetry { provider: None });
+    };
+    let provider = build_provider(endpoint, &cfg.otlp_service_name)?;
+    registry
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("catnap")))
+        .init();
+    Ok(Telemetry {
+        provider: Some(provider),
+    })
+}
+
+fn build_provider(endpoint: &str, service_name: &str) -> anyhow::Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_endpoint(endpoint))
+        .build()?;
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build())
+}
+
+/// Accepts either the collector base URL (`http://localhost:4318`) or the full traces URL.
+fn traces_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}/v1/traces")
+    }
+}
+
+/// Context of the current span, to be carried across a queue hand-off. `None` when no span is
+/// being exported.
+pub fn current_span_context() -> Option<SpanContext> {
+    let cx = tracing::Span::current().context();
+    let span_context = cx.span().span_context().clone();
+    span_context.is_valid().then_some(span_context)
+}
+
+/// Parents `span` on the first origin and links the rest, so a task enqueued by several callers
+/// shows up in each of their traces.
+pub fn link_origins(span: &tracing::Span, origins: &[SpanContext]) {
+    let Some((first, rest)) = origins.split_first() else {
+        return;
+    };
+    let _ = span.set_parent(opentelemetry::Context::new().with_remote_span_context(first.clone()));
+    for origin in rest {
+        span.add_link(origin.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    /// Answers one OTLP/HTTP export with 200 and reports its path and body.
+    fn spawn_collector() -> (String, std::sync::mpsc::Receiver<(String, Vec<u8>)>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let mut stream = stream;
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+            let path = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+            let _ = tx.send((path, body));
+        });
+        (format!("http://{addr}"), rx)
+    }
+
+    #[test]
+    fn exports_spans_to_a_local_collector_with_queue_parenting() {
+        let (endpoint, received) = spawn_collector();
+        let provider = build_provider(&endpoint, "catnap-test").unwrap();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("catnap")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let enqueue = tracing::info_span!("ops.enqueue", fid = "2");
+            let origin = enqueue.in_scope(current_span_context).unwrap();
+            let other = tracing::info_span!("ops.enqueue", fid = "2")
+                .in_scope(current_span_context)
+                .unwrap();
+            drop(enqueue);
+
+            let task = tracing::info_span!("ops.task", fid = "2");
+            link_origins(&task, &[origin.clone(), other]);
+            let task_context = task.context().span().span_context().clone();
+            assert_eq!(task_context.trace_id(), origin.trace_id());
+        });
+        assert_eq!(current_span_context(), None);
+
+        provider.force_flush().unwrap();
+        let (path, body) = received
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(path, "/v1/traces");
+        assert!(body.windows(b"ops.task".len()).any(|w| w == b"ops.task"));
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn traces_endpoint_appends_signal_path_once() {
+        assert_eq!(
+            traces_endpoint("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_endpoint("http://collector:4318/v1/traces"),
+            "http://collector:4318/v1/traces"
+        );
+    }
+}
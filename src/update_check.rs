@@ -1,4 +1,5 @@
 use crate::config::RuntimeConfig;
+use crate::proxy::{self, ProxyClass};
 use serde::Deserialize;
 use std::sync::Arc;
 use std::time::Duration;
@@ -128,12 +129,13 @@ pub async fn maybe_refresh(
         }
     };
 
-    let client = match reqwest::Client::builder()
+    let builder = reqwest::Client::builder()
         .timeout(Duration::from_millis(
             cfg.update_check_timeout_ms.max(1) as u64
         ))
-        .user_agent(format!("catnap/{}", cfg.effective_version))
-        .build()
+        .user_agent(format!("catnap/{}", cfg.effective_version));
+    let client = match proxy::apply_proxy(builder, ProxyClass::Github, cfg.github_proxy.as_deref())
+        .and_then(|builder| Ok(builder.build()?))
     {
         Ok(c) => c,
         Err(_) => {
@@ -152,8 +154,13 @@ pub async fn maybe_refresh(
 
     let resp = match req.send().await {
         Ok(resp) => resp,
-        Err(_) => {
-            next.last_error = Some("update-check request failed".to_string());
+        Err(err) => {
+            let err = proxy::map_send_error(ProxyClass::Github, cfg.github_proxy.as_deref(), err);
+            next.last_error = Some(if err.is::<proxy::ProxyError>() {
+                err.to_string()
+            } else {
+                "update-check request failed".to_string()
+            });
             *cache.write().await = next;
             return;
         }
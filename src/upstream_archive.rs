@@ -0,0 +1,202 @@
+use crate::config::{RuntimeConfig, UpstreamArchiveMode};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// One cart page as the upstream served it. Stored as a standalone JSON file so a captured day
+/// can be copied into `tests/fixtures` or trimmed by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedResponse {
+    pub url: String,
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    pub fetched_at: String,
+}
+
+/// Local archive of upstream cart responses.
+///
+/// In record mode every fetched page is written to `<dir>/<yyyy-mm-dd>/`. In replay mode the
+/// archive is loaded once and responses are served per URL in capture order; when a URL runs out
+/// of captures its last response keeps being served, so an offline rerun can poll forever.
+pub struct UpstreamArchive {
+    mode: UpstreamArchiveMode,
+    dir: PathBuf,
+    seq: AtomicU64,
+    replay: Mutex<HashMap<String, ReplayQueue>>,
+}
+
+type SharedKey = (UpstreamArchiveMode, String);
+
+struct ReplayQueue {
+    entries: Vec<ArchivedResponse>,
+    next: usize,
+}
+
+impl UpstreamArchive {
+    pub fn open_record(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("create upstream archive dir {}", dir.display()))?;
+        Ok(Self {
+            mode: UpstreamArchiveMode::Record,
+            dir,
+            seq: AtomicU64::new(0),
+            replay: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn open_replay(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        let mut files = Vec::new();
+        collect_archive_files(&dir, &mut files)
+            .with_context(|| format!("read upstream archive dir {}", dir.display()))?;
+
+        let mut entries = Vec::with_capacity(files.len());
+        for path in files {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("read archived response {}", path.display()))?;
+            let entry = serde_json::from_str::<ArchivedResponse>(&raw)
+                .with_context(|| format!("parse archived response {}", path.display()))?;
+            entries.push((entry.fetched_at.clone(), path, entry));
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut replay = HashMap::<String, ReplayQueue>::new();
+        for (_, _, entry) in entries {
+            replay
+                .entry(entry.url.clone())
+                .or_insert_with(|| ReplayQueue {
+                    entries: Vec::new(),
+                    next: 0,
+                })
+                .entries
+                .push(entry);
+        }
+
+        Ok(Self {
+            mode: UpstreamArchiveMode::Replay,
+            dir,
+            seq: AtomicU64::new(0),
+            replay: Mutex::new(replay),
+        })
+    }
+
+    /// Process-wide archive for the configured mode, shared by the poller and all ops workers so
+    /// replay cursors advance once per fetch rather than once per client.
+    pub fn shared(cfg: &RuntimeConfig) -> anyhow::Result<Option<Arc<Self>>> {
+        static SHARED: OnceLock<Mutex<HashMap<SharedKey, Arc<UpstreamArchive>>>> = OnceLock::new();
+
+        if cfg.upstream_archive_mode == UpstreamArchiveMode::Off {
+            return Ok(None);
+        }
+        let key = (cfg.upstream_archive_mode, cfg.upstream_archive_dir.clone());
+        let mut shared = SHARED
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some(archive) = shared.get(&key) {
+            return Ok(Some(archive.clone()));
+        }
+        let archive = Arc::new(match cfg.upstream_archive_mode {
+            UpstreamArchiveMode::Record => Self::open_record(&cfg.upstream_archive_dir)?,
+            UpstreamArchiveMode::Replay => Self::open_replay(&cfg.upstream_archive_dir)?,
+            UpstreamArchiveMode::Off => unreachable!(),
+        });
+        shared.insert(key, archive.clone());
+        Ok(Some(archive))
+    }
+
+    pub fn mode(&self) -> UpstreamArchiveMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn record(&self, entry: ArchivedResponse) -> anyhow::Result<()> {
+        let day = entry.fetched_at.get(..10).unwrap_or("unknown").to_string();
+        let url_hash = hex::encode(Sha256::digest(entry.url.as_bytes()));
+        let stamp = OffsetDateTime::parse(&entry.fetched_at, &Rfc3339)
+            .map(|ts| ts.unix_timestamp_nanos())
+            .unwrap_or_default();
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let dir = self.dir.join(day);
+        let path = dir.join(format!("{stamp}-{seq:06}-{}.json", &url_hash[..12]));
+        let body = serde_json::to_vec_pretty(&entry)?;
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            std::fs::create_dir_all(&dir)?;
+            std::fs::write(&path, body)
+                .with_context(|| format!("write archived response {}", path.display()))?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub fn replay(&self, url: &str) -> Option<ArchivedResponse> {
+        let mut replay = self.replay.lock().unwrap_or_else(|err| err.into_inner());
+        let queue = replay.get_mut(url)?;
+        let idx = queue.next.min(queue.entries.len().checked_sub(1)?);
+        queue.next = (queue.next + 1).min(queue.entries.len());
+        queue.entries.get(idx).cloned()
+    }
+}
+
+fn collect_archive_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_archive_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(url: &str, body: &str, fetched_at: &str) -> ArchivedResponse {
+        ArchivedResponse {
+            url: url.to_string(),
+            status: 200,
+            headers: BTreeMap::new(),
+            body: body.to_string(),
+            fetched_at: fetched_at.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_serves_captures_in_order_and_repeats_the_last_one() {
+        let dir = std::env::temp_dir().join(format!("catnap-archive-{}", uuid::Uuid::new_v4()));
+        let recorder = UpstreamArchive::open_record(&dir).unwrap();
+        let url = "https://shop.example/cart?fid=7";
+        recorder
+            .record(response(url, "second", "2026-01-02T00:05:00Z"))
+            .await
+            .unwrap();
+        recorder
+            .record(response(url, "first", "2026-01-02T00:00:00Z"))
+            .await
+            .unwrap();
+
+        let replay = UpstreamArchive::open_replay(&dir).unwrap();
+        let bodies = (0..3)
+            .map(|_| replay.replay(url).unwrap().body)
+            .collect::<Vec<_>>();
+        assert_eq!(bodies, vec!["first", "second", "second"]);
+        assert!(replay.replay("https://shop.example/cart?fid=8").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
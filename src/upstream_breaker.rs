@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+pub const DEFAULT_OPEN_SECONDS: u64 = 60;
+/// Failed half-open probes double the wait up to this cap.
+const MAX_OPEN: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Circuit of one storefront as shown on the `#ops` page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamCircuitView {
+    pub provider_id: String,
+    pub state: String,
+    pub consecutive_failures: u32,
+    pub opened_at: Option<String>,
+    pub retry_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Whether a task may fetch from the storefront right now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admission {
+    Allowed,
+    /// The single half-open request that decides whether the circuit closes again.
+    Probe,
+    Rejected {
+        retry_at: String,
+    },
+}
+
+/// How a fetch went, as far as reachability of the storefront is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchOutcome {
+    Reachable,
+    Unreachable,
+    /// Neither proves nor disproves an outage (throttling, proxy trouble).
+    Inconclusive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transition {
+    Opened {
+        opened_at: String,
+        consecutive_failures: u32,
+    },
+    /// Another failure while open or half-open; the outage goes on until `retry_at`.
+    StillOpen {
+        retry_at: String,
+    },
+    Closed {
+        opened_at: Option<String>,
+    },
+}
+
+struct Circuit {
+    state: CircuitState,
+    consecutive_failures: u32,
+    reopen_count: u32,
+    opened_at: Option<OffsetDateTime>,
+    retry_at: Option<(Instant, OffsetDateTime)>,
+    probe_in_flight: bool,
+    last_error: Option<String>,
+}
+
+impl Circuit {
+    fn closed() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            reopen_count: 0,
+            opened_at: None,
+            retry_at: None,
+            probe_in_flight: false,
+            last_error: None,
+        }
+    }
+}
+
+/// Per-storefront circuit breaker for ops fetches.
+///
+/// `failure_threshold` consecutive unreachable fetches open the circuit. While open, pausable
+/// (poller-originated) tasks are rejected without touching the network; after the open period
+/// one request is let through as a half-open probe, whose success closes the circuit and whose
+/// failure reopens it for twice as long. User-initiated tasks are never rejected and count like
+/// any other fetch.
+pub struct UpstreamCircuitBreaker {
+    failure_threshold: u32,
+    open_for: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl UpstreamCircuitBreaker {
+    pub fn new(failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            open_for: open_for.max(Duration::from_secs(1)),
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn admit(&self, provider_id: &str, pausable: bool, now: Instant) -> Admission {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|err| err.into_inner());
+        let Some(circuit) = circuits.get_mut(provider_id) else {
+            return Admission::Allowed;
+        };
+        if circuit.state == CircuitState::Open
+            && circuit.retry_at.is_some_and(|(until, _)| until <= now)
+        {
+            circuit.state = CircuitState::HalfOpen;
+            circuit.probe_in_flight = false;
+        }
+        match circuit.state {
+            CircuitState::Closed => Admission::Allowed,
+            CircuitState::HalfOpen if !circuit.probe_in_flight => {
+                circuit.probe_in_flight = true;
+                Admission::Probe
+            }
+            _ if !pausable => Admission::Allowed,
+            _ => Admission::Rejected {
+                retry_at: circuit
+                    .retry_at
+                    .map(|(_, wall)| format_wall(wall))
+                    .unwrap_or_else(|| format_wall(OffsetDateTime::now_utc())),
+            },
+        }
+    }
+
+    /// When a pausable task would next be admitted, without claiming the half-open probe.
+    /// `None` means `admit` would let it through now.
+    pub fn paused_until(&self, provider_id: &str, now: Instant) -> Option<(Instant, String)> {
+        let circuits = self.circuits.lock().unwrap_or_else(|err| err.into_inner());
+        let circuit = circuits.get(provider_id)?;
+        match circuit.state {
+            CircuitState::Open => circuit
+                .retry_at
+                .filter(|(until, _)| *until > now)
+                .map(|(until, wall)| (until, format_wall(wall))),
+            CircuitState::HalfOpen if circuit.probe_in_flight => {
+                let wait = Duration::from_secs(1);
+                Some((now + wait, format_wall(OffsetDateTime::now_utc() + wait)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn record(
+        &self,
+        provider_id: &str,
+        outcome: FetchOutcome,
+        error: Option<&str>,
+        now: Instant,
+    ) -> Option<Transition> {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|err| err.into_inner());
+        let circuit = circuits
+            .entry(provider_id.to_string())
+            .or_insert_with(Circuit::closed);
+        match outcome {
+            FetchOutcome::Inconclusive => {
+                circuit.probe_in_flight = false;
+                None
+            }
+            FetchOutcome::Reachable => {
+                let was_closed = circuit.state == CircuitState::Closed;
+                let opened_at = circuit.opened_at.map(format_wall);
+                *circuit = Circuit::closed();
+                (!was_closed).then_some(Transition::Closed { opened_at })
+            }
+            FetchOutcome::Unreachable => {
+                circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+                circuit.last_error = error.map(str::to_string);
+                circuit.probe_in_flight = false;
+                match circuit.state {
+                    CircuitState::Closed
+                        if circuit.consecutive_failures >= self.failure_threshold =>
+                    {
+                        let wall = OffsetDateTime::now_utc();
+                        circuit.state = CircuitState::Open;
+                        circuit.reopen_count = 0;
+                        circuit.opened_at = Some(wall);
+                        circuit.retry_at = Some((now + self.open_for, wall + self.open_for));
+                        Some(Transition::Opened {
+                            opened_at: format_wall(wall),
+                            consecutive_failures: circuit.consecutive_failures,
+                        })
+                    }
+                    CircuitState::Closed => None,
+                    CircuitState::Open | CircuitState::HalfOpen => {
+                        circuit.reopen_count = circuit.reopen_count.saturating_add(1);
+                        let wait = self.open_period(circuit.reopen_count);
+                        let wall = OffsetDateTime::now_utc() + wait;
+                        circuit.state = CircuitState::Open;
+                        circuit.retry_at = Some((now + wait, wall));
+                        Some(Transition::StillOpen {
+                            retry_at: format_wall(wall),
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    fn open_period(&self, reopen_count: u32) -> Duration {
+        self.open_for
+            .saturating_mul(1 << reopen_count.min(16))
+            .min(MAX_OPEN.max(self.open_for))
+    }
+
+    pub fn snapshot(&self) -> Vec<UpstreamCircuitView> {
+        let circuits = self.circuits.lock().unwrap_or_else(|err| err.into_inner());
+        circuits
+            .iter()
+            .map(|(provider_id, circuit)| {
+                (
+                    provider_id.clone(),
+                    UpstreamCircuitView {
+                        provider_id: provider_id.clone(),
+                        state: circuit.state.as_str().to_string(),
+                        consecutive_failures: circuit.consecutive_failures,
+                        opened_at: circuit.opened_at.map(format_wall),
+                        retry_at: circuit
+                            .retry_at
+                            .filter(|_| circuit.state != CircuitState::Closed)
+                            .map(|(_, wall)| format_wall(wall)),
+                        last_error: circuit.last_error.clone(),
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+            .into_values()
+            .collect()
+    }
+}
+
+fn format_wall(ts: OffsetDateTime) -> String {
+    ts.format(&Rfc3339)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_probes_once_when_half_open() {
+        let breaker = UpstreamCircuitBreaker::new(2, Duration::from_secs(60));
+        let t0 = Instant::now();
+        assert_eq!(breaker.admit("lazycat", true, t0), Admission::Allowed);
+        assert_eq!(
+            breaker.record("lazycat", FetchOutcome::Unreachable, Some("refused"), t0),
+            None
+        );
+        assert!(matches!(
+            breaker.record("lazycat", FetchOutcome::Unreachable, Some("refused"), t0),
+            Some(Transition::Opened {
+                consecutive_failures: 2,
+                ..
+            })
+        ));
+
+        assert!(matches!(
+            breaker.admit("lazycat", true, t0),
+            Admission::Rejected { .. }
+        ));
+        assert_eq!(breaker.admit("lazycat", false, t0), Admission::Allowed);
+        assert_eq!(breaker.admit("other", true, t0), Admission::Allowed);
+        assert_eq!(
+            breaker.paused_until("lazycat", t0).map(|(until, _)| until),
+            Some(t0 + Duration::from_secs(60))
+        );
+        assert_eq!(breaker.paused_until("other", t0), None);
+
+        let t1 = t0 + Duration::from_secs(61);
+        assert_eq!(breaker.paused_until("lazycat", t1), None);
+        assert_eq!(breaker.admit("lazycat", true, t1), Admission::Probe);
+        assert!(breaker.paused_until("lazycat", t1).is_some());
+        assert!(matches!(
+            breaker.admit("lazycat", true, t1),
+            Admission::Rejected { .. }
+        ));
+
+        // A failed probe reopens for twice as long without a second "opened" transition.
+        assert!(matches!(
+            breaker.record("lazycat", FetchOutcome::Unreachable, None, t1),
+            Some(Transition::StillOpen { .. })
+        ));
+        assert!(matches!(
+            breaker.admit("lazycat", true, t1 + Duration::from_secs(61)),
+            Admission::Rejected { .. }
+        ));
+        let t2 = t1 + Duration::from_secs(121);
+        assert_eq!(breaker.admit("lazycat", true, t2), Admission::Probe);
+        assert_eq!(breaker.snapshot()[0].state, "half_open");
+
+        assert!(matches!(
+            breaker.record("lazycat", FetchOutcome::Reachable, None, t2),
+            Some(Transition::Closed { opened_at: Some(_) })
+        ));
+        assert_eq!(breaker.admit("lazycat", true, t2), Admission::Allowed);
+        assert_eq!(breaker.snapshot()[0].consecutive_failures, 0);
+    }
+
+    #[test]
+    fn inconclusive_probe_frees_the_probe_slot() {
+        let breaker = UpstreamCircuitBreaker::new(1, Duration::from_secs(1));
+        let t0 = Instant::now();
+        breaker.record("lazycat", FetchOutcome::Unreachable, None, t0);
+        let t1 = t0 + Duration::from_secs(2);
+        assert_eq!(breaker.admit("lazycat", true, t1), Admission::Probe);
+        breaker.record("lazycat", FetchOutcome::Inconclusive, None, t1);
+        assert_eq!(breaker.admit("lazycat", true, t1), Admission::Probe);
+    }
+}
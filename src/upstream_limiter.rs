@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use std::time::Instant;
+use time::{
+    format_description::well_known::{Rfc2822, Rfc3339},
+    OffsetDateTime,
+};
+
+pub const DEFAULT_RATE_PER_SECOND: f64 = 2.0;
+pub const DEFAULT_BURST: u32 = 1;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(15 * 60);
+/// Longer backoffs fail the fetch instead of parking the caller.
+const MAX_BLOCKING_WAIT: Duration = Duration::from_secs(30);
+
+/// Throttle state of one upstream host as shown on the `#ops` page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamHostLimitView {
+    pub host: String,
+    pub tokens: f64,
+    pub burst: u32,
+    pub rate_per_second: f64,
+    pub backoff_until: Option<String>,
+    pub consecutive_failures: u32,
+    pub last_status: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrottledError {
+    pub host: String,
+    pub until: String,
+}
+
+impl std::fmt::Display for ThrottledError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "upstream {} is backing off until {}",
+            self.host, self.until
+        )
+    }
+}
+
+impl std::error::Error for ThrottledError {}
+
+/// What a finished request tells the limiter about the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseSignal {
+    Ok,
+    Throttled { retry_after: Option<Duration> },
+}
+
+struct HostState {
+    tokens: f64,
+    refilled_at: Instant,
+    backoff_until: Option<(Instant, OffsetDateTime)>,
+    consecutive_failures: u32,
+    last_status: Option<u16>,
+}
+
+/// Per-host token bucket shared by every cart client in the process. Each request takes one
+/// token; throttling responses empty the bucket and push the host into exponential backoff
+/// (or the server's `Retry-After`), which successes clear again.
+pub struct UpstreamRateLimiter {
+    settings: Mutex<(f64, u32)>,
+    hosts: Mutex<HashMap<String, HostState>>,
+}
+
+impl Default for UpstreamRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_RATE_PER_SECOND, DEFAULT_BURST)
+    }
+}
+
+impl UpstreamRateLimiter {
+    pub fn new(rate_per_second: f64, burst: u32) -> Self {
+        Self {
+            settings: Mutex::new(sanitize(rate_per_second, burst)),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn configure(&self, rate_per_second: f64, burst: u32) {
+        *self.settings.lock().unwrap_or_else(|err| err.into_inner()) =
+            sanitize(rate_per_second, burst);
+    }
+
+    fn settings(&self) -> (f64, u32) {
+        *self.settings.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Waits for a token for `host`. Fails right away when the host is in a backoff longer than
+    /// `MAX_BLOCKING_WAIT`, so a long `Retry-After` does not pin every worker.
+    pub async fn acquire(&self, host: &str) -> Result<(), ThrottledError> {
+        loop {
+            let wait = {
+                let (rate, burst) = self.settings();
+                let mut hosts = self.hosts.lock().unwrap_or_else(|err| err.into_inner());
+                let now = Instant::now();
+                let state = hosts.entry(host.to_string()).or_insert_with(|| HostState {
+                    tokens: f64::from(burst),
+                    refilled_at: now,
+                    backoff_until: None,
+                    consecutive_failures: 0,
+                    last_status: None,
+                });
+                refill(state, now, rate, burst);
+
+                match state.backoff_until {
+                    Some((until, wall)) if until > now => {
+                        let remaining = until - now;
+                        if remaining > MAX_BLOCKING_WAIT {
+                            return Err(ThrottledError {
+                                host: host.to_string(),
+                                until: format_wall(wall),
+                            });
+                        }
+                        remaining
+                    }
+                    _ if state.tokens >= 1.0 => {
+                        state.tokens -= 1.0;
+                        return Ok(());
+                    }
+                    _ => Duration::from_secs_f64((1.0 - state.tokens) / rate),
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    pub fn record(&self, host: &str, status: u16, signal: ResponseSignal) {
+        let mut hosts = self.hosts.lock().unwrap_or_else(|err| err.into_inner());
+        let Some(state) = hosts.get_mut(host) else {
+            return;
+        };
+        state.last_status = Some(status);
+        match signal {
+            ResponseSignal::Ok => {
+                state.consecutive_failures = 0;
+                state.backoff_until = None;
+            }
+            ResponseSignal::Throttled { retry_after } => {
+                state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                let delay = backoff_delay(state.consecutive_failures, retry_after);
+                state.tokens = 0.0;
+                state.backoff_until =
+                    Some((Instant::now() + delay, OffsetDateTime::now_utc() + delay));
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<UpstreamHostLimitView> {
+        let (rate, burst) = self.settings();
+        let now = Instant::now();
+        let mut hosts = self.hosts.lock().unwrap_or_else(|err| err.into_inner());
+        hosts
+            .iter_mut()
+            .map(|(host, state)| {
+                refill(state, now, rate, burst);
+                (
+                    host.clone(),
+                    UpstreamHostLimitView {
+                        host: host.clone(),
+                        tokens: (state.tokens * 100.0).round() / 100.0,
+                        burst,
+                        rate_per_second: rate,
+                        backoff_until: state
+                            .backoff_until
+                            .filter(|(until, _)| *until > now)
+                            .map(|(_, wall)| format_wall(wall)),
+                        consecutive_failures: state.consecutive_failures,
+                        last_status: state.last_status,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+            .into_values()
+            .collect()
+    }
+}
+
+/// `Retry-After` when the server sent one, otherwise `BACKOFF_BASE` doubled per consecutive
+/// failure; both capped at `BACKOFF_MAX`.
+fn backoff_delay(consecutive_failures: u32, retry_after: Option<Duration>) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    retry_after
+        .unwrap_or_else(|| BACKOFF_BASE.saturating_mul(1 << exponent))
+        .min(BACKOFF_MAX)
+}
+
+fn format_wall(ts: OffsetDateTime) -> String {
+    ts.format(&Rfc3339)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string())
+}
+
+fn sanitize(rate_per_second: f64, burst: u32) -> (f64, u32) {
+    let rate = if rate_per_second.is_finite() && rate_per_second > 0.0 {
+        rate_per_second
+    } else {
+        DEFAULT_RATE_PER_SECOND
+    };
+    (rate, burst.max(1))
+}
+
+fn refill(state: &mut HostState, now: Instant, rate: f64, burst: u32) {
+    let elapsed = now
+        .saturating_duration_since(state.refilled_at)
+        .as_secs_f64();
+    state.tokens = (state.tokens + elapsed * rate).min(f64::from(burst));
+    state.refilled_at = now;
+}
+
+/// Limiter used by every [`crate::upstream::UpstreamClient`]; `main` applies the configured rate.
+pub fn shared() -> &'static UpstreamRateLimiter {
+    static LIMITER: OnceLock<UpstreamRateLimiter> = OnceLock::new();
+    LIMITER.get_or_init(UpstreamRateLimiter::default)
+}
+
+/// 429, 5xx and Cloudflare challenge pages mean "slow down"; everything else is a normal answer.
+pub fn classify_response(
+    status: u16,
+    headers: &BTreeMap<String, String>,
+    body: &str,
+) -> ResponseSignal {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    };
+    let challenge = header("cf-mitigated").is_some_and(|v| v.eq_ignore_ascii_case("challenge"))
+        || (matches!(status, 403 | 503)
+            && (body.contains("challenge-platform")
+                || body.contains("cf-chl")
+                || body.contains("<title>Just a moment...</title>")));
+    if status == 429 || status >= 500 || challenge {
+        ResponseSignal::Throttled {
+            retry_after: header("retry-after").and_then(parse_retry_after),
+        }
+    } else {
+        ResponseSignal::Ok
+    }
+}
+
+/// `Retry-After` as delta-seconds or an HTTP date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let delta = at - OffsetDateTime::now_utc();
+    Some(Duration::from_secs_f64(delta.as_seconds_f64().max(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_throttling_responses() {
+        let mut headers = BTreeMap::new();
+        assert_eq!(classify_response(200, &headers, ""), ResponseSignal::Ok);
+        assert_eq!(classify_response(404, &headers, ""), ResponseSignal::Ok);
+        assert_eq!(
+            classify_response(503, &headers, ""),
+            ResponseSignal::Throttled { retry_after: None }
+        );
+        assert_eq!(
+            classify_response(
+                403,
+                &headers,
+                "<html><title>Just a moment...</title></html>"
+            ),
+            ResponseSignal::Throttled { retry_after: None }
+        );
+        headers.insert("Retry-After".to_string(), "120".to_string());
+        assert_eq!(
+            classify_response(429, &headers, ""),
+            ResponseSignal::Throttled {
+                retry_after: Some(Duration::from_secs(120))
+            }
+        );
+        assert!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT").is_some());
+    }
+
+    #[test]
+    fn backoff_doubles_per_failure_and_honors_retry_after() {
+        assert_eq!(backoff_delay(1, None), Duration::from_secs(2));
+        assert_eq!(backoff_delay(3, None), Duration::from_secs(8));
+        assert_eq!(backoff_delay(40, None), BACKOFF_MAX);
+        assert_eq!(
+            backoff_delay(1, Some(Duration::from_secs(90))),
+            Duration::from_secs(90)
+        );
+    }
+
+    #[tokio::test]
+    async fn long_backoff_fails_fast_until_a_success_clears_it() {
+        let limiter = UpstreamRateLimiter::new(50.0, 2);
+        for _ in 0..3 {
+            limiter.acquire("shop").await.unwrap();
+        }
+
+        limiter.record(
+            "shop",
+            429,
+            ResponseSignal::Throttled {
+                retry_after: Some(Duration::from_secs(600)),
+            },
+        );
+        let view = limiter.snapshot().pop().unwrap();
+        assert_eq!(view.consecutive_failures, 1);
+        assert_eq!(view.last_status, Some(429));
+        assert!(view.backoff_until.is_some());
+        let err = limiter.acquire("shop").await.unwrap_err();
+        assert_eq!(err.host, "shop");
+
+        limiter.record("shop", 200, ResponseSignal::Ok);
+        let view = limiter.snapshot().pop().unwrap();
+        assert_eq!(view.consecutive_failures, 0);
+        assert_eq!(view.backoff_until, None);
+        limiter.acquire("shop").await.unwrap();
+    }
+}
@@ -26,13 +26,32 @@ fn test_config() -> RuntimeConfig {
         update_check_timeout_ms: 1500,
         github_api_base_url: "https://api.github.com".to_string(),
         upstream_cart_url: "https://lxc.lazycat.wiki/cart".to_string(),
+        upstream_providers: Vec::new(),
+        upstream_archive_mode: catnap::config::UpstreamArchiveMode::Off,
+        upstream_archive_dir: "upstream-archive".to_string(),
+        upstream_rate_per_second: 2.0,
+        upstream_rate_burst: 1,
+        upstream_proxies: Vec::new(),
+        upstream_breaker_failure_threshold: 5,
+        upstream_breaker_open_seconds: 60,
+        upstream_billing_cycles_enabled: false,
+        upstream_billing_cycles_refresh_seconds: 3600,
+        price_base_currency: "CNY".to_string(),
+        exchange_rates: Default::default(),
+        exchange_rates_file: None,
         lazycat_base_url: "https://lxc.lazycat.wiki".to_string(),
         lazycat_site_sync_interval_minutes: 5,
         lazycat_panel_sync_interval_minutes: 10,
         lazycat_panel_concurrency: 2,
         lazycat_panel_timeout_ms: 5_000,
         lazycat_allow_invalid_tls: true,
+        lazycat_proxy: None,
+        telegram_proxy: None,
+        github_proxy: None,
         telegram_api_base_url: "https://api.telegram.org".to_string(),
+        operator_telegram_bot_token: None,
+        operator_telegram_targets: Vec::new(),
+        operator_web_push_user_ids: Vec::new(),
         auth_user_header: Some("x-user".to_string()),
         dev_user_id: None,
         default_poll_interval_minutes: 1,
@@ -42,10 +61,36 @@ fn test_config() -> RuntimeConfig {
         notification_retention_days: 30,
         notification_retention_max_rows: 50_000,
         ops_worker_concurrency: 1,
+        ops_adaptive_concurrency: false,
+        ops_worker_concurrency_min: 1,
+        ops_worker_concurrency_max: 1,
+        ops_adaptive_latency_target_ms: 3000,
         ops_sse_replay_window_seconds: 3600,
         ops_log_retention_days: 7,
         ops_log_tail_limit_default: 200,
         ops_queue_task_limit_default: 200,
+        ops_lane_starvation_seconds: 60,
+        ops_retry_policies: Default::default(),
+        ops_retryable_error_codes: Vec::new(),
+        metrics_token: None,
+        otlp_endpoint: None,
+        otlp_service_name: "catnap".to_string(),
+        ops_admin_user_ids: Vec::new(),
+        ops_health_check_interval_seconds: 60,
+        ops_health_success_rate_min_pct: None,
+        ops_health_success_rate_window_minutes: 30,
+        ops_health_queue_depth_max: None,
+        ops_health_worker_error_minutes: None,
+        ops_health_no_success_minutes: None,
+        ops_digest_hour_utc: None,
+        hot_mode_minutes: 0,
+        hot_mode_interval_seconds: 15,
+        hot_mode_max_partitions: 3,
+        poll_forecast_enabled: false,
+        poll_forecast_dense_minutes: 1,
+        poll_forecast_sparse_factor: 2,
+        leader_lease_seconds: 0,
+        instance_id: None,
         db_url: "sqlite::memory:".to_string(),
         web_push_vapid_public_key: None,
         web_push_vapid_private_key: None,
@@ -161,6 +206,7 @@ async fn make_app_with_config(cfg: RuntimeConfig) -> TestApp {
     ));
 
     let snapshot = catnap::upstream::CatalogSnapshot {
+        provider_id: "lazycat".to_string(),
         countries,
         regions,
         region_notices: vec![catnap::models::RegionNotice {
@@ -506,6 +552,173 @@ async fn ui_requires_user_header_with_html_401() {
     assert!(content_type.contains("text/html"));
 }
 
+#[tokio::test]
+async fn ops_admin_actions_are_limited_to_configured_admins() {
+    let mut cfg = test_config();
+    cfg.ops_admin_user_ids = vec!["u_admin".to_string()];
+    let t = make_app_with_config(cfg).await;
+
+    let post = |uri: &str, user: &str, body: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("host", "example.com")
+            .header("x-user", user)
+            .header("origin", "http://example.com")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(post("/api/ops/admin/pause", "u_1", ""))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(post("/api/ops/admin/pause", "u_admin", ""))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["paused"], true);
+    assert_eq!(json["pausedBy"], "u_admin");
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(post("/api/ops/admin/cancel", "u_admin", r#"{"keys":[]}"#))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(post(
+            "/api/ops/admin/cancel",
+            "u_admin",
+            r#"{"keys":[{"fid":"2","gid":"56"}]}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["notFound"][0]["fid"], "2");
+    assert_eq!(json["queue"]["control"]["paused"], true);
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(post("/api/ops/admin/resume", "u_admin", ""))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["paused"], false);
+}
+
+#[tokio::test]
+async fn ops_admin_actions_are_denied_when_no_admins_are_configured() {
+    let t = make_app().await;
+    for action in ["pause", "drain", "clear"] {
+        let res = t
+            .app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/ops/admin/{action}"))
+                    .header("host", "example.com")
+                    .header("x-user", "u_1")
+                    .header("origin", "http://example.com")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN, "{action}");
+    }
+}
+
+#[tokio::test]
+async fn metrics_require_their_own_token() {
+    let disabled = make_app().await;
+    let res = disabled
+        .app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .header("x-user", "u_1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let mut cfg = test_config();
+    cfg.metrics_token = Some("scrape-secret".to_string());
+    let t = make_app_with_config(cfg).await;
+    seed_lazycat_machine(&t, "u_1", 101, "a@example.com", "HK Premium", "1.2.3.4").await;
+
+    let metrics_request = |token: Option<&str>| {
+        let mut req = Request::builder()
+            .uri("/metrics")
+            .header("host", "example.com");
+        if let Some(token) = token {
+            req = req.header("authorization", format!("Bearer {token}"));
+        }
+        req.body(Body::empty()).unwrap()
+    };
+
+    // The user header alone does not grant access.
+    let res = t
+        .app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .header("x-user", "u_1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = t
+        .app
+        .clone()
+        .oneshot(metrics_request(Some("wrong")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(metrics_request(Some("scrape-secret")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("# TYPE catnap_ops_queue_tasks gauge"));
+    assert!(body.contains("catnap_ops_workers{state=\"idle\"} 1"));
+    assert!(body.contains("# TYPE catnap_ops_task_duration_seconds histogram"));
+    assert!(body.contains(
+        "catnap_lazycat_traffic_used_gb{user_id=\"u_1\",service_id=\"101\",service_name=\"HK Premium\"}"
+    ));
+}
+
 #[tokio::test]
 async fn same_origin_is_enforced_for_api_requests() {
     let t = make_app().await;
@@ -2785,6 +2998,357 @@ async fn monitoring_partition_toggle_accepts_country_scope_with_null_region() {
     assert_eq!(row.get::<i64, _>(2), 1);
 }
 
+#[tokio::test]
+async fn poll_schedules_resolve_per_partition_and_config() {
+    let t = make_app().await;
+    let (status, _) = authed_json(
+        &t,
+        "u_1",
+        Method::PATCH,
+        "/api/monitoring/configs/lc:7:40:128",
+        Some(serde_json::json!({ "enabled": true })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, json) =
+        authed_json(&t, "u_1", Method::GET, "/api/monitoring/schedules", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["schedules"], serde_json::json!([]));
+    let default_minutes = json["defaultIntervalMinutes"].as_i64().unwrap();
+    let partition = &json["partitions"][0];
+    assert_eq!(partition["countryId"], "7");
+    assert_eq!(partition["regionId"], "40");
+    assert_eq!(partition["scheduleScopes"], serde_json::json!(["default"]));
+    assert_eq!(partition["everyMinutes"], default_minutes);
+    assert!(partition["nextFetchAt"].is_string());
+
+    // A country-wide quiet schedule covers the region.
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::PUT,
+        "/api/monitoring/schedules/partitions",
+        Some(serde_json::json!({
+            "countryId": "7",
+            "regionId": null,
+            "utcOffset": "+09:00",
+            "rules": [{ "quiet": true }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["schedules"][0]["scope"], "partition");
+    assert_eq!(json["schedules"][0]["countryId"], "7");
+    assert!(json["schedules"][0].get("regionId").is_none());
+    let partition = &json["partitions"][0];
+    assert_eq!(
+        partition["scheduleScopes"],
+        serde_json::json!(["partition:7::"])
+    );
+    assert!(partition["nextFetchAt"].is_null());
+    assert_eq!(partition["activeConfigIds"], serde_json::json!([]));
+
+    // The config's own schedule wins over the partition.
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::PUT,
+        "/api/monitoring/schedules/configs/lc:7:40:128",
+        Some(serde_json::json!({ "rules": [{ "everyMinutes": 2 }] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let partition = &json["partitions"][0];
+    assert_eq!(
+        partition["scheduleScopes"],
+        serde_json::json!(["config:lc:7:40:128"])
+    );
+    assert_eq!(partition["everyMinutes"], 2);
+    assert_eq!(
+        partition["activeConfigIds"],
+        serde_json::json!(["lc:7:40:128"])
+    );
+
+    let polled_at = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+    catnap::db::record_monitoring_poll(
+        &t.db,
+        "u_1",
+        "7::40",
+        &polled_at.format(&Rfc3339).unwrap(),
+        0,
+    )
+    .await
+    .unwrap();
+    let (_, json) = authed_json(&t, "u_1", Method::GET, "/api/monitoring/schedules", None).await;
+    let next = OffsetDateTime::parse(
+        json["partitions"][0]["nextFetchAt"].as_str().unwrap(),
+        &Rfc3339,
+    )
+    .unwrap();
+    assert_eq!(next, polled_at + Duration::minutes(2));
+
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::PUT,
+        "/api/monitoring/schedules/configs/lc:7:40:128",
+        Some(serde_json::json!({ "rules": [{ "everyMinutes": 2, "quiet": true }] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+
+    // Empty rules remove the config schedule again.
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::PUT,
+        "/api/monitoring/schedules/configs/lc:7:40:128",
+        Some(serde_json::json!({ "rules": [] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["schedules"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        json["partitions"][0]["scheduleScopes"],
+        serde_json::json!(["partition:7::"])
+    );
+}
+
+#[tokio::test]
+async fn monitoring_rules_crud_and_matched_rule_on_records() {
+    let t = make_app().await;
+    let (status, json) = authed_json(&t, "u_1", Method::GET, "/api/monitoring/rules", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["rules"], serde_json::json!([]));
+
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::POST,
+        "/api/monitoring/rules",
+        Some(serde_json::json!({
+            "countryId": "7",
+            "regionId": null,
+            "name": "便宜有货",
+            "conditions": [
+                { "kind": "quantityAtLeast", "min": 3 },
+                { "kind": "priceAtMost", "amount": 30 }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let rule = &json["rules"][0];
+    assert_eq!(rule["scope"], "partition");
+    assert_eq!(rule["countryId"], "7");
+    assert!(rule.get("regionId").is_none());
+    assert_eq!(rule["enabled"], true);
+    assert_eq!(rule["conditions"][1]["kind"], "priceAtMost");
+    let rule_id = rule["id"].as_i64().unwrap();
+
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::POST,
+        "/api/monitoring/rules",
+        Some(serde_json::json!({
+            "configId": "lc:7:40:128",
+            "name": "IPv4",
+            "conditions": [{ "kind": "specsContain", "text": "IPv4" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["rules"][1]["scope"], "config");
+    assert_eq!(json["rules"][1]["configId"], "lc:7:40:128");
+
+    let rules = catnap::monitoring_rules::load_rules_for_config(
+        &t.db,
+        "u_1",
+        "lc:7:40:128",
+        "7",
+        Some("40"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(rules.len(), 2);
+
+    for body in [
+        serde_json::json!({ "countryId": "7", "name": "", "conditions": [{ "kind": "quantityAtLeast", "min": 1 }] }),
+        serde_json::json!({ "countryId": "7", "name": "x", "conditions": [] }),
+        serde_json::json!({ "countryId": "7", "name": "x", "conditions": [{ "kind": "priceDropPct", "pct": 0 }] }),
+        serde_json::json!({ "countryId": "999", "name": "x", "conditions": [{ "kind": "quantityAtLeast", "min": 1 }] }),
+        serde_json::json!({ "configId": "lc:missing", "name": "x", "conditions": [{ "kind": "quantityAtLeast", "min": 1 }] }),
+    ] {
+        let (status, json) =
+            authed_json(&t, "u_1", Method::POST, "/api/monitoring/rules", Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    }
+
+    // Disabled rules no longer apply.
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::PUT,
+        &format!("/api/monitoring/rules/{rule_id}"),
+        Some(serde_json::json!({
+            "name": "有货",
+            "conditions": [{ "kind": "quantityAtLeast", "min": 1 }],
+            "enabled": false
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["rules"][0]["name"], "有货");
+    assert_eq!(json["rules"][0]["enabled"], false);
+    let rules = catnap::monitoring_rules::load_rules_for_config(
+        &t.db,
+        "u_1",
+        "lc:7:40:128",
+        "7",
+        Some("40"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].name, "IPv4");
+
+    // Rules are per user.
+    let (status, _) = authed_json(
+        &t,
+        "u_2",
+        Method::DELETE,
+        &format!("/api/monitoring/rules/{rule_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::DELETE,
+        &format!("/api/monitoring/rules/{rule_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["rules"].as_array().unwrap().len(), 1);
+
+    let item = catnap::db::load_notification_record_item_snapshot(
+        &t.db,
+        &Default::default(),
+        "lc:7:40:128",
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let record_id = catnap::db::insert_notification_record(
+        &t.db,
+        "u_1",
+        &catnap::models::NotificationRecordDraft {
+            kind: "monitoring.rule".to_string(),
+            title: "【规则命中】".to_string(),
+            summary: "库存 3｜规则：IPv4".to_string(),
+            partition_label: item.partition_label.clone(),
+            telegram_status: "skipped".to_string(),
+            web_push_status: "skipped".to_string(),
+            items: vec![item],
+            matched_rule: Some(rules[0].matched_view()),
+        },
+    )
+    .await
+    .unwrap();
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::GET,
+        &format!("/api/notifications/records/{record_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["matchedRule"]["name"], "IPv4");
+    assert_eq!(json["matchedRule"]["scopeKey"], "lc:7:40:128");
+    assert_eq!(json["matchedRule"]["conditions"][0]["text"], "IPv4");
+}
+
+#[tokio::test]
+async fn restock_forecast_predicts_windows_from_inventory_history() {
+    let t = make_app().await;
+    let now = OffsetDateTime::now_utc();
+    let restock_hour = now
+        .replace_minute(0)
+        .and_then(|at| at.replace_second(0))
+        .and_then(|at| at.replace_nanosecond(0))
+        .unwrap()
+        + Duration::hours(3);
+    // Sold out until 5 past the same hour on each of the last four days.
+    for day in 1..=4 {
+        let restock = restock_hour - Duration::days(day) + Duration::minutes(5);
+        for (at, quantity) in [(restock - Duration::minutes(1), 0), (restock, 4)] {
+            sqlx::query(
+                "INSERT INTO inventory_samples_1m (config_id, ts_minute, inventory_quantity) VALUES (?, ?, ?)",
+            )
+            .bind("lc:7:40:128")
+            .bind(at.format(&Rfc3339).unwrap())
+            .bind(quantity)
+            .execute(&t.db)
+            .await
+            .unwrap();
+        }
+    }
+    assert_eq!(
+        catnap::restock_forecast::refresh_profiles(&t.db, now)
+            .await
+            .unwrap(),
+        2
+    );
+
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::POST,
+        "/api/inventory/restock-forecast",
+        Some(serde_json::json!({ "configIds": ["lc:7:40:128", "missing"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["horizonHours"], 48);
+    let configs = json["configs"].as_array().unwrap();
+    assert_eq!(configs.len(), 1);
+    assert_eq!(configs[0]["configId"], "lc:7:40:128");
+    assert_eq!(configs[0]["profile"]["events"], 4);
+    assert_eq!(configs[0]["profile"]["medianGapMinutes"], 24 * 60);
+    let first = OffsetDateTime::parse(
+        configs[0]["windows"][0]["start"].as_str().unwrap(),
+        &Rfc3339,
+    )
+    .unwrap();
+    assert_eq!(first, restock_hour);
+    assert!(configs[0]["expectedByGapAt"].is_string());
+
+    let partitions = json["partitions"].as_array().unwrap();
+    assert_eq!(partitions.len(), 1);
+    assert_eq!(partitions[0]["countryId"], "7");
+    assert_eq!(partitions[0]["regionId"], "40");
+    assert!(partitions[0].get("configId").is_none());
+    assert_eq!(partitions[0]["profile"]["events"], 4);
+
+    let (status, json) = authed_json(
+        &t,
+        "u_1",
+        Method::POST,
+        "/api/inventory/restock-forecast",
+        Some(serde_json::json!({ "configIds": [] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+}
+
 #[tokio::test]
 async fn settings_migrates_legacy_monitoring_flags_into_new_hierarchy() {
     let cfg = test_config();
@@ -3779,10 +4343,11 @@ async fn notification_records_api_lists_and_fetches_detail() {
         .await
         .unwrap()
         .get::<String, _>(0);
-    let item = catnap::db::load_notification_record_item_snapshot(&t.db, &config_id)
-        .await
-        .unwrap()
-        .unwrap();
+    let item =
+        catnap::db::load_notification_record_item_snapshot(&t.db, &Default::default(), &config_id)
+            .await
+            .unwrap()
+            .unwrap();
 
     let older_id = catnap::db::insert_notification_record(
         &t.db,
@@ -3795,6 +4360,7 @@ async fn notification_records_api_lists_and_fetches_detail() {
             telegram_status: "success".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -3810,6 +4376,7 @@ async fn notification_records_api_lists_and_fetches_detail() {
             telegram_status: "error".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -3910,10 +4477,11 @@ async fn notification_records_api_includes_telegram_deliveries() {
         .await
         .unwrap()
         .get::<String, _>(0);
-    let item = catnap::db::load_notification_record_item_snapshot(&t.db, &config_id)
-        .await
-        .unwrap()
-        .unwrap();
+    let item =
+        catnap::db::load_notification_record_item_snapshot(&t.db, &Default::default(), &config_id)
+            .await
+            .unwrap()
+            .unwrap();
 
     let record_id = catnap::db::insert_notification_record(
         &t.db,
@@ -3926,6 +4494,7 @@ async fn notification_records_api_includes_telegram_deliveries() {
             telegram_status: "pending".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -22,13 +22,32 @@ fn base_test_config() -> RuntimeConfig {
         update_check_timeout_ms: 1500,
         github_api_base_url: "https://api.github.com".to_string(),
         upstream_cart_url: "http://127.0.0.1:0/cart".to_string(),
+        upstream_providers: Vec::new(),
+        upstream_archive_mode: catnap::config::UpstreamArchiveMode::Off,
+        upstream_archive_dir: "upstream-archive".to_string(),
+        upstream_rate_per_second: 2.0,
+        upstream_rate_burst: 1,
+        upstream_proxies: Vec::new(),
+        upstream_breaker_failure_threshold: 5,
+        upstream_breaker_open_seconds: 60,
+        upstream_billing_cycles_enabled: false,
+        upstream_billing_cycles_refresh_seconds: 3600,
+        price_base_currency: "CNY".to_string(),
+        exchange_rates: Default::default(),
+        exchange_rates_file: None,
         lazycat_base_url: "https://lxc.lazycat.wiki".to_string(),
         lazycat_site_sync_interval_minutes: 5,
         lazycat_panel_sync_interval_minutes: 10,
         lazycat_panel_concurrency: 2,
         lazycat_panel_timeout_ms: 5_000,
         lazycat_allow_invalid_tls: true,
+        lazycat_proxy: None,
+        telegram_proxy: None,
+        github_proxy: None,
         telegram_api_base_url: "https://api.telegram.org".to_string(),
+        operator_telegram_bot_token: None,
+        operator_telegram_targets: Vec::new(),
+        operator_web_push_user_ids: Vec::new(),
         auth_user_header: Some("x-user".to_string()),
         dev_user_id: None,
         default_poll_interval_minutes: 1,
@@ -38,10 +57,36 @@ fn base_test_config() -> RuntimeConfig {
         notification_retention_days: 30,
         notification_retention_max_rows: 50_000,
         ops_worker_concurrency: 2,
+        ops_adaptive_concurrency: false,
+        ops_worker_concurrency_min: 1,
+        ops_worker_concurrency_max: 2,
+        ops_adaptive_latency_target_ms: 3000,
         ops_sse_replay_window_seconds: 3600,
         ops_log_retention_days: 7,
         ops_log_tail_limit_default: 200,
         ops_queue_task_limit_default: 200,
+        ops_lane_starvation_seconds: 60,
+        ops_retry_policies: Default::default(),
+        ops_retryable_error_codes: Vec::new(),
+        metrics_token: None,
+        otlp_endpoint: None,
+        otlp_service_name: "catnap".to_string(),
+        ops_admin_user_ids: Vec::new(),
+        ops_health_check_interval_seconds: 60,
+        ops_health_success_rate_min_pct: None,
+        ops_health_success_rate_window_minutes: 30,
+        ops_health_queue_depth_max: None,
+        ops_health_worker_error_minutes: None,
+        ops_health_no_success_minutes: None,
+        ops_digest_hour_utc: None,
+        hot_mode_minutes: 0,
+        hot_mode_interval_seconds: 15,
+        hot_mode_max_partitions: 3,
+        poll_forecast_enabled: false,
+        poll_forecast_dense_minutes: 1,
+        poll_forecast_sparse_factor: 2,
+        leader_lease_seconds: 0,
+        instance_id: None,
         db_url: "sqlite::memory:".to_string(),
         web_push_vapid_public_key: None,
         web_push_vapid_private_key: None,
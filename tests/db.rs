@@ -15,13 +15,32 @@ fn test_config() -> RuntimeConfig {
         update_check_timeout_ms: 1500,
         github_api_base_url: "https://api.github.com".to_string(),
         upstream_cart_url: "https://lxc.lazycat.wiki/cart".to_string(),
+        upstream_providers: Vec::new(),
+        upstream_archive_mode: catnap::config::UpstreamArchiveMode::Off,
+        upstream_archive_dir: "upstream-archive".to_string(),
+        upstream_rate_per_second: 2.0,
+        upstream_rate_burst: 1,
+        upstream_proxies: Vec::new(),
+        upstream_breaker_failure_threshold: 5,
+        upstream_breaker_open_seconds: 60,
+        upstream_billing_cycles_enabled: false,
+        upstream_billing_cycles_refresh_seconds: 3600,
+        price_base_currency: "CNY".to_string(),
+        exchange_rates: Default::default(),
+        exchange_rates_file: None,
         lazycat_base_url: "https://lxc.lazycat.wiki".to_string(),
         lazycat_site_sync_interval_minutes: 5,
         lazycat_panel_sync_interval_minutes: 10,
         lazycat_panel_concurrency: 2,
         lazycat_panel_timeout_ms: 5_000,
         lazycat_allow_invalid_tls: true,
+        lazycat_proxy: None,
+        telegram_proxy: None,
+        github_proxy: None,
         telegram_api_base_url: "https://api.telegram.org".to_string(),
+        operator_telegram_bot_token: None,
+        operator_telegram_targets: Vec::new(),
+        operator_web_push_user_ids: Vec::new(),
         auth_user_header: Some("x-user".to_string()),
         dev_user_id: None,
         default_poll_interval_minutes: 1,
@@ -31,10 +50,36 @@ fn test_config() -> RuntimeConfig {
         notification_retention_days: 30,
         notification_retention_max_rows: 50_000,
         ops_worker_concurrency: 1,
+        ops_adaptive_concurrency: false,
+        ops_worker_concurrency_min: 1,
+        ops_worker_concurrency_max: 1,
+        ops_adaptive_latency_target_ms: 3000,
         ops_sse_replay_window_seconds: 3600,
         ops_log_retention_days: 7,
         ops_log_tail_limit_default: 200,
         ops_queue_task_limit_default: 200,
+        ops_lane_starvation_seconds: 60,
+        ops_retry_policies: Default::default(),
+        ops_retryable_error_codes: Vec::new(),
+        metrics_token: None,
+        otlp_endpoint: None,
+        otlp_service_name: "catnap".to_string(),
+        ops_admin_user_ids: Vec::new(),
+        ops_health_check_interval_seconds: 60,
+        ops_health_success_rate_min_pct: None,
+        ops_health_success_rate_window_minutes: 30,
+        ops_health_queue_depth_max: None,
+        ops_health_worker_error_minutes: None,
+        ops_health_no_success_minutes: None,
+        ops_digest_hour_utc: None,
+        hot_mode_minutes: 0,
+        hot_mode_interval_seconds: 15,
+        hot_mode_max_partitions: 3,
+        poll_forecast_enabled: false,
+        poll_forecast_dense_minutes: 1,
+        poll_forecast_sparse_factor: 2,
+        leader_lease_seconds: 0,
+        instance_id: None,
         db_url: "sqlite::memory:".to_string(),
         web_push_vapid_public_key: None,
         web_push_vapid_private_key: None,
@@ -413,6 +458,7 @@ async fn apply_catalog_url_fetch_success_delays_listed_until_first_positive_inve
     assert!(res.listed_ids.contains(&config.id));
     assert!(res.listed_pending_zero_stock_ids.contains(&config.id));
     assert!(res.listed_event_ids.is_empty());
+    assert!(res.restocked_ids.is_empty());
 
     let row = sqlx::query("SELECT lifecycle_listed_event_at FROM catalog_configs WHERE id = ?")
         .bind(&config.id)
@@ -452,6 +498,7 @@ async fn apply_catalog_url_fetch_success_delays_listed_until_first_positive_inve
     assert!(res2.listed_ids.is_empty());
     assert!(res2.listed_pending_zero_stock_ids.is_empty());
     assert_eq!(res2.listed_event_ids, vec![config.id.clone()]);
+    assert_eq!(res2.restocked_ids, vec![config.id.clone()]);
 
     let row2 = sqlx::query("SELECT lifecycle_listed_event_at FROM catalog_configs WHERE id = ?")
         .bind(&config.id)
@@ -479,6 +526,7 @@ async fn apply_catalog_url_fetch_success_delays_listed_until_first_positive_inve
     assert!(res3.listed_ids.is_empty());
     assert!(res3.listed_pending_zero_stock_ids.is_empty());
     assert!(res3.listed_event_ids.is_empty());
+    assert!(res3.restocked_ids.is_empty());
 }
 
 #[tokio::test]
@@ -555,8 +603,15 @@ async fn retire_catalog_targets_delists_configs_and_hides_known_targets() {
         vec![("7".to_string(), Some("40".to_string()))]
     );
 
+    let targets = [("7".to_string(), Some("40".to_string()))];
+    // The same raw target of another provider leaves the default provider's rows alone.
+    let retired = catnap::db::retire_catalog_targets(&db, "hk", &targets)
+        .await
+        .unwrap();
+    assert!(retired.is_empty());
+
     let retired =
-        catnap::db::retire_catalog_targets(&db, &[("7".to_string(), Some("40".to_string()))])
+        catnap::db::retire_catalog_targets(&db, catnap::storefront::DEFAULT_PROVIDER_ID, &targets)
             .await
             .unwrap();
     assert_eq!(retired, vec![configs[0].id.clone()]);
@@ -573,6 +628,120 @@ async fn retire_catalog_targets_delists_configs_and_hides_known_targets() {
         .is_empty());
 }
 
+#[tokio::test]
+async fn catalog_configs_store_normalized_specs_and_backfill_old_rows() {
+    let cfg = test_config();
+    let db = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&cfg.db_url)
+        .await
+        .unwrap();
+
+    catnap::db::init_db(&db).await.unwrap();
+
+    let configs =
+        catnap::upstream::parse_configs("7", Some("40"), include_str!("fixtures/cart-fid-7.html"));
+    catnap::db::upsert_catalog_configs(&db, &configs)
+        .await
+        .unwrap();
+
+    let views = catnap::db::list_catalog_configs_view(
+        &db,
+        &Default::default(),
+        "u_1",
+        Some("7"),
+        Some("40"),
+    )
+    .await
+    .unwrap();
+    let mini = views.iter().find(|v| v.name.ends_with("Mini")).unwrap();
+    assert_eq!(mini.normalized_specs.cpu_cores, Some(1.0));
+    assert_eq!(mini.normalized_specs.memory_mb, Some(256));
+    assert_eq!(mini.specs.len(), 2);
+
+    sqlx::query(
+        "UPDATE catalog_configs SET spec_memory_mb = NULL, spec_cpu_cores = NULL, spec_normalized_version = 0",
+    )
+    .execute(&db)
+    .await
+    .unwrap();
+    catnap::db::init_db(&db).await.unwrap();
+
+    let row = sqlx::query("SELECT spec_memory_mb FROM catalog_configs WHERE id = ?")
+        .bind(&mini.id)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<Option<i64>, _>(0), Some(256));
+}
+
+#[tokio::test]
+async fn billing_cycles_are_replaced_per_config_and_shown_in_views() {
+    let cfg = test_config();
+    let db = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&cfg.db_url)
+        .await
+        .unwrap();
+
+    catnap::db::init_db(&db).await.unwrap();
+
+    let configs =
+        catnap::upstream::parse_configs("7", Some("40"), include_str!("fixtures/cart-fid-7.html"));
+    catnap::db::upsert_catalog_configs(&db, &configs)
+        .await
+        .unwrap();
+    let id = configs[0].id.clone();
+    assert_eq!(
+        catnap::db::get_billing_cycles_checked_at(&db, &id)
+            .await
+            .unwrap(),
+        None
+    );
+
+    let html = r#"
+    <label data-billingcycle="月付" data-amount="9.34" data-billingcycleId="monthly">月付</label>
+    <label data-billingcycle="季付" data-amount="27.00" data-billingcycleId="quarterly">季付</label>
+    "#;
+    catnap::db::replace_config_billing_cycles(
+        &db,
+        &id,
+        &catnap::upstream::parse_billing_cycles(html),
+    )
+    .await
+    .unwrap();
+    let html = r#"<label data-billingcycle="季付" data-amount="25.00" data-billingcycleId="quarterly">季付</label>"#;
+    catnap::db::replace_config_billing_cycles(
+        &db,
+        &id,
+        &catnap::upstream::parse_billing_cycles(html),
+    )
+    .await
+    .unwrap();
+
+    assert!(catnap::db::get_billing_cycles_checked_at(&db, &id)
+        .await
+        .unwrap()
+        .is_some());
+    let rates = catnap::pricing::ExchangeRates {
+        base: "USD".to_string(),
+        rates: std::collections::HashMap::from([("CNY".to_string(), 0.5)]),
+    };
+    let views = catnap::db::list_catalog_configs_view(&db, &rates, "u_1", Some("7"), Some("40"))
+        .await
+        .unwrap();
+    let view = views.iter().find(|v| v.id == id).unwrap();
+    assert_eq!(view.billing_cycles.len(), 1);
+    assert_eq!(view.billing_cycles[0].price.period, "quarter");
+    assert_eq!(view.billing_cycles[0].price.amount, 25.0);
+    let monthly = view.billing_cycles[0].monthly_price.as_ref().unwrap();
+    assert_eq!((monthly.amount, monthly.currency.as_str()), (4.17, "USD"));
+    assert!(views
+        .iter()
+        .filter(|v| v.id != id)
+        .all(|v| v.billing_cycles.is_empty()));
+}
+
 #[tokio::test]
 async fn load_catalog_snapshot_round_trips_topology_state() {
     let cfg = test_config();
@@ -687,10 +856,14 @@ async fn cleanup_notification_records_applies_day_and_row_limits() {
     catnap::db::upsert_catalog_configs(&db, &configs)
         .await
         .unwrap();
-    let item = catnap::db::load_notification_record_item_snapshot(&db, &configs[0].id)
-        .await
-        .unwrap()
-        .unwrap();
+    let item = catnap::db::load_notification_record_item_snapshot(
+        &db,
+        &Default::default(),
+        &configs[0].id,
+    )
+    .await
+    .unwrap()
+    .unwrap();
 
     let old_id = catnap::db::insert_notification_record(
         &db,
@@ -703,6 +876,7 @@ async fn cleanup_notification_records_applies_day_and_row_limits() {
             telegram_status: "success".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -718,6 +892,7 @@ async fn cleanup_notification_records_applies_day_and_row_limits() {
             telegram_status: "success".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -733,6 +908,7 @@ async fn cleanup_notification_records_applies_day_and_row_limits() {
             telegram_status: "success".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -748,6 +924,7 @@ async fn cleanup_notification_records_applies_day_and_row_limits() {
             telegram_status: "success".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![item.clone()],
+            matched_rule: None,
         },
     )
     .await
@@ -833,6 +1010,7 @@ async fn notification_records_preserve_item_order() {
 
     let items = catnap::db::load_notification_record_item_snapshots(
         &db,
+        &Default::default(),
         &configs
             .iter()
             .map(|config| config.id.clone())
@@ -853,12 +1031,13 @@ async fn notification_records_preserve_item_order() {
             telegram_status: "success".to_string(),
             web_push_status: "skipped".to_string(),
             items: vec![items[1].clone(), items[0].clone()],
+            matched_rule: None,
         },
     )
     .await
     .unwrap();
 
-    let listed = catnap::db::list_notification_records(&db, "u_1", None, 20)
+    let listed = catnap::db::list_notification_records(&db, &Default::default(), "u_1", None, 20)
         .await
         .unwrap()
         .0;
@@ -874,7 +1053,7 @@ async fn notification_records_preserve_item_order() {
         items[0].config_id.as_deref()
     );
 
-    let detail = catnap::db::get_notification_record(&db, "u_1", &record_id)
+    let detail = catnap::db::get_notification_record(&db, &Default::default(), "u_1", &record_id)
         .await
         .unwrap()
         .unwrap();
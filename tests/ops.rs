@@ -21,13 +21,32 @@ fn test_config() -> RuntimeConfig {
         update_check_timeout_ms: 1500,
         github_api_base_url: "https://api.github.com".to_string(),
         upstream_cart_url: "https://lxc.lazycat.wiki/cart".to_string(),
+        upstream_providers: Vec::new(),
+        upstream_archive_mode: catnap::config::UpstreamArchiveMode::Off,
+        upstream_archive_dir: "upstream-archive".to_string(),
+        upstream_rate_per_second: 2.0,
+        upstream_rate_burst: 1,
+        upstream_proxies: Vec::new(),
+        upstream_breaker_failure_threshold: 5,
+        upstream_breaker_open_seconds: 60,
+        upstream_billing_cycles_enabled: false,
+        upstream_billing_cycles_refresh_seconds: 3600,
+        price_base_currency: "CNY".to_string(),
+        exchange_rates: Default::default(),
+        exchange_rates_file: None,
         lazycat_base_url: "https://lxc.lazycat.wiki".to_string(),
         lazycat_site_sync_interval_minutes: 5,
         lazycat_panel_sync_interval_minutes: 10,
         lazycat_panel_concurrency: 2,
         lazycat_panel_timeout_ms: 5_000,
         lazycat_allow_invalid_tls: true,
+        lazycat_proxy: None,
+        telegram_proxy: None,
+        github_proxy: None,
         telegram_api_base_url: "https://api.telegram.org".to_string(),
+        operator_telegram_bot_token: None,
+        operator_telegram_targets: Vec::new(),
+        operator_web_push_user_ids: Vec::new(),
         auth_user_header: Some("x-user".to_string()),
         dev_user_id: None,
         default_poll_interval_minutes: 1,
@@ -37,10 +56,36 @@ fn test_config() -> RuntimeConfig {
         notification_retention_days: 30,
         notification_retention_max_rows: 50_000,
         ops_worker_concurrency: 1,
+        ops_adaptive_concurrency: false,
+        ops_worker_concurrency_min: 1,
+        ops_worker_concurrency_max: 1,
+        ops_adaptive_latency_target_ms: 3000,
         ops_sse_replay_window_seconds: 3600,
         ops_log_retention_days: 7,
         ops_log_tail_limit_default: 200,
         ops_queue_task_limit_default: 200,
+        ops_lane_starvation_seconds: 60,
+        ops_retry_policies: Default::default(),
+        ops_retryable_error_codes: Vec::new(),
+        metrics_token: None,
+        otlp_endpoint: None,
+        otlp_service_name: "catnap".to_string(),
+        ops_admin_user_ids: Vec::new(),
+        ops_health_check_interval_seconds: 60,
+        ops_health_success_rate_min_pct: None,
+        ops_health_success_rate_window_minutes: 30,
+        ops_health_queue_depth_max: None,
+        ops_health_worker_error_minutes: None,
+        ops_health_no_success_minutes: None,
+        ops_digest_hour_utc: None,
+        hot_mode_minutes: 0,
+        hot_mode_interval_seconds: 15,
+        hot_mode_max_partitions: 3,
+        poll_forecast_enabled: false,
+        poll_forecast_dense_minutes: 1,
+        poll_forecast_sparse_factor: 2,
+        leader_lease_seconds: 0,
+        instance_id: None,
         db_url: "sqlite::memory:".to_string(),
         web_push_vapid_public_key: None,
         web_push_vapid_private_key: None,
@@ -109,10 +154,76 @@ async fn ops_state_snapshot_works() {
     assert!(json["queue"].get("oldestWaitSeconds").is_some());
     assert!(json["queue"].get("reasonCounts").is_some());
     assert!(json.get("workers").is_some());
+    assert_eq!(json["concurrency"]["limit"], 1);
+    assert_eq!(json["concurrency"]["adaptive"], false);
     assert!(json.get("stats").is_some());
     assert!(json["stats"]["collection"].get("cacheHits").is_some());
     assert!(json.get("topology").is_some());
     assert!(json.get("logTail").is_some());
+    assert_eq!(json["healthAlerts"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn ops_alerts_list_open_and_resolved_health_alerts() {
+    let t = make_app().await;
+    catnap::db::open_ops_health_alert(
+        &t.db,
+        "queue_depth",
+        "2026-01-01T00:00:00Z",
+        12.0,
+        10.0,
+        "deep",
+    )
+    .await
+    .unwrap();
+    catnap::db::resolve_ops_health_alert(&t.db, "queue_depth", "2026-01-01T00:05:00Z")
+        .await
+        .unwrap();
+    catnap::db::open_ops_health_alert(
+        &t.db,
+        "no_success",
+        "2026-01-01T01:00:00Z",
+        61.0,
+        60.0,
+        "stale",
+    )
+    .await
+    .unwrap();
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(authed(Request::builder().uri("/api/ops/alerts?limit=1")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["items"][0]["rule"], "no_success");
+    let cursor = json["nextCursor"].as_str().unwrap().to_string();
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(authed(
+            Request::builder().uri(format!("/api/ops/alerts?cursor={cursor}")),
+        ))
+        .await
+        .unwrap();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["items"][0]["rule"], "queue_depth");
+    assert_eq!(json["items"][0]["resolvedAt"], "2026-01-01T00:05:00Z");
+
+    let res = t
+        .app
+        .oneshot(authed(Request::builder().uri("/api/ops/alerts?open=true")))
+        .await
+        .unwrap();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["items"].as_array().unwrap().len(), 1);
+    assert_eq!(json["items"][0]["rule"], "no_success");
 }
 
 #[tokio::test]
@@ -231,3 +342,371 @@ async fn ops_stream_replays_events_by_last_event_id() {
     assert!(buf.contains("ops.log"));
     assert!(buf.contains("hello-2"));
 }
+
+#[tokio::test]
+async fn ops_stream_filters_by_event_key_and_level() {
+    let t = make_app().await;
+    let ts = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
+    let insert = |event: &'static str, data: serde_json::Value| {
+        let db = t.db.clone();
+        let ts = ts.clone();
+        async move {
+            sqlx::query("INSERT INTO ops_events (ts, event, data_json) VALUES (?, ?, ?)")
+                .bind(&ts)
+                .bind(event)
+                .bind(data.to_string())
+                .execute(&db)
+                .await
+                .unwrap()
+                .last_insert_rowid()
+        }
+    };
+    let log = |level: &str, message: &str, fid: &str| {
+        serde_json::json!({
+            "ts": ts,
+            "level": level,
+            "scope": "ops.task",
+            "message": message,
+            "meta": { "fid": fid, "gid": "56" },
+        })
+    };
+
+    let anchor = insert("ops.log", log("info", "anchor", "2")).await;
+    insert("ops.log", log("info", "too-quiet", "2")).await;
+    insert("ops.log", log("warn", "other-partition", "3")).await;
+    insert(
+        "ops.task",
+        serde_json::json!({ "phase": "enqueued", "key": { "fid": "2", "gid": "56" } }),
+    )
+    .await;
+    insert("ops.log", log("warn", "match-warn", "2")).await;
+    insert("ops.log", log("error", "match-last", "2")).await;
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(authed(
+            Request::builder()
+                .uri("/api/ops/stream?events=ops.log&fid=2&gid=56&level=warn")
+                .header("last-event-id", anchor.to_string()),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let mut stream = res.into_body().into_data_stream();
+    let mut buf = String::new();
+    for _ in 0..8 {
+        if let Some(chunk) = stream.next().await {
+            buf.push_str(&String::from_utf8_lossy(&chunk.unwrap()));
+            if buf.contains("match-last") {
+                break;
+            }
+        }
+    }
+    assert!(buf.contains("event: ops.hello"));
+    assert!(!buf.contains("event: ops.metrics"));
+    assert!(buf.contains("match-warn"));
+    assert!(buf.contains("match-last"));
+    assert!(!buf.contains("too-quiet"));
+    assert!(!buf.contains("other-partition"));
+    assert!(!buf.contains("event: ops.task"));
+
+    let res = t
+        .app
+        .oneshot(authed(Request::builder().uri("/api/ops/stream?level=loud")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn ops_runs_are_filtered_and_paginated_with_notifications() {
+    let t = make_app().await;
+    let runs = [
+        (
+            "2",
+            Some("56"),
+            "2026-10-10T10:00:00Z",
+            0,
+            "fetch",
+            Some("upstream_timeout"),
+        ),
+        (
+            "2",
+            Some("56"),
+            "2026-10-11T10:00:00Z",
+            0,
+            "fetch",
+            Some("upstream_timeout"),
+        ),
+        ("2", Some("56"), "2026-10-12T10:00:00Z", 1, "fetch", None),
+        (
+            "2",
+            Some("57"),
+            "2026-10-12T11:00:00Z",
+            0,
+            "fetch",
+            Some("upstream_timeout"),
+        ),
+        (
+            "2",
+            Some("56"),
+            "2026-10-17T10:00:00Z",
+            0,
+            "fetch",
+            Some("upstream_timeout"),
+        ),
+    ];
+    let mut ids = Vec::new();
+    for (fid, gid, started_at, ok, action, error_code) in runs {
+        let id = sqlx::query(
+            r#"
+INSERT INTO ops_task_runs (
+  provider_id, fid, gid, started_at, ended_at, ok, fetch_action, reason_counts_json, error_code
+)
+VALUES ('lazycat', ?, ?, ?, ?, ?, ?, '{"poller_due":1}', ?)
+"#,
+        )
+        .bind(fid)
+        .bind(gid)
+        .bind(started_at)
+        .bind(started_at)
+        .bind(ok)
+        .bind(action)
+        .bind(error_code)
+        .execute(&t.db)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        ids.push(id);
+    }
+    sqlx::query(
+        "INSERT INTO ops_notify_runs (task_run_id, ts, channel, result) VALUES (?, ?, 'telegram', 'success')",
+    )
+    .bind(ids[2])
+    .bind("2026-10-12T10:00:01Z")
+    .execute(&t.db)
+    .await
+    .unwrap();
+
+    let get = |uri: String| {
+        let app = t.app.clone();
+        async move {
+            let res = app
+                .oneshot(authed(Request::builder().uri(uri)))
+                .await
+                .unwrap();
+            let status = res.status();
+            let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+            )
+        }
+    };
+
+    let base = "/api/ops/runs?fid=2&gid=56&ok=false&errorCode=upstream_timeout&fetchAction=fetch\
+                &from=2026-10-09T00:00:00%2B08:00&to=2026-10-16T00:00:00Z&limit=1";
+    let (status, page1) = get(base.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page1["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page1["items"][0]["id"], ids[1]);
+    assert_eq!(page1["items"][0]["ok"], false);
+    assert_eq!(page1["items"][0]["reasonCounts"]["poller_due"], 1);
+    let cursor = page1["nextCursor"].as_str().unwrap().to_string();
+
+    let (_, page2) = get(format!("{base}&cursor={cursor}")).await;
+    assert_eq!(page2["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page2["items"][0]["id"], ids[0]);
+    let (_, page3) = get(format!("{base}&cursor={}", ids[0])).await;
+    assert!(page3["items"].as_array().unwrap().is_empty());
+    assert!(page3["nextCursor"].is_null());
+
+    let (_, ok_runs) = get("/api/ops/runs?ok=true".to_string()).await;
+    assert_eq!(ok_runs["items"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        ok_runs["items"][0]["notifications"][0]["channel"],
+        "telegram"
+    );
+
+    let (status, _) = get("/api/ops/runs?from=yesterday".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn ops_digest_aggregates_the_last_day() {
+    let t = make_app().await;
+    let runs = [
+        ("2026-10-16T12:00:00Z", 1, "fetch", None, Some(100)),
+        ("2026-10-17T09:00:00Z", 1, "fetch", None, Some(300)),
+        ("2026-10-17T10:00:00Z", 1, "not_modified", None, Some(200)),
+        ("2026-10-17T11:00:00Z", 1, "cache", None, None),
+        (
+            "2026-10-17T12:00:00Z",
+            0,
+            "fetch",
+            Some("upstream_timeout"),
+            Some(5000),
+        ),
+        (
+            "2026-10-17T13:00:00Z",
+            0,
+            "fetch",
+            Some("parser_drift"),
+            Some(400),
+        ),
+        (
+            "2026-10-17T14:00:00Z",
+            0,
+            "fetch",
+            Some("upstream_timeout"),
+            Some(6000),
+        ),
+    ];
+    for (ts, ok, action, error_code, elapsed) in runs {
+        sqlx::query(
+            r#"
+INSERT INTO ops_task_runs (
+  provider_id, fid, gid, started_at, ended_at, ok, fetch_action, reason_counts_json, error_code,
+  fetch_elapsed_ms
+)
+VALUES ('lazycat', '2', '56', ?, ?, ?, ?, '{"poller_due":1}', ?, ?)
+"#,
+        )
+        .bind(ts)
+        .bind(ts)
+        .bind(ok)
+        .bind(action)
+        .bind(error_code)
+        .bind(elapsed)
+        .execute(&t.db)
+        .await
+        .unwrap();
+    }
+    for (ts, channel, result) in [
+        ("2026-10-17T09:00:00Z", "telegram", "success"),
+        ("2026-10-17T09:00:01Z", "telegram", "error"),
+        ("2026-10-17T09:00:02Z", "webPush", "skipped"),
+    ] {
+        sqlx::query(
+            "INSERT INTO ops_notify_runs (task_run_id, ts, channel, result) VALUES (1, ?, ?, ?)",
+        )
+        .bind(ts)
+        .bind(channel)
+        .bind(result)
+        .execute(&t.db)
+        .await
+        .unwrap();
+    }
+    for (id, name, state, listed_at, delisted_at) in [
+        (
+            "cfg_tokyo",
+            "Tokyo Mini",
+            "active",
+            "2026-10-17T08:00:00Z",
+            None,
+        ),
+        (
+            "cfg_osaka",
+            "Osaka Mini",
+            "delisted",
+            "2026-01-01T00:00:00Z",
+            Some("2026-10-17T09:00:00Z"),
+        ),
+        (
+            "cfg_old",
+            "Old Mini",
+            "active",
+            "2026-01-01T00:00:00Z",
+            None,
+        ),
+    ] {
+        sqlx::query(
+            r#"
+INSERT INTO catalog_configs (
+  id, country_id, region_id, name, specs_json,
+  price_amount, price_currency, price_period,
+  inventory_status, inventory_quantity, checked_at, config_digest,
+  lifecycle_state, lifecycle_listed_at, lifecycle_delisted_at
+) VALUES (?, '7', '40', ?, '[]', 12.0, 'CNY', 'month', 'in_stock', 1, ?, 'digest', ?, ?, ?)
+"#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(listed_at)
+        .bind(state)
+        .bind(listed_at)
+        .bind(delisted_at)
+        .execute(&t.db)
+        .await
+        .unwrap();
+    }
+    catnap::db::record_catalog_topology_changes(
+        &t.db,
+        &[catnap::db::CatalogTopologyChange {
+            kind: "partition.removed",
+            country_id: "7",
+            country_name: "日本",
+            region_id: Some("41"),
+            region_name: Some("大阪"),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let to = OffsetDateTime::now_utc().format(&Rfc3339).unwrap();
+    let res = t
+        .app
+        .clone()
+        .oneshot(authed(
+            Request::builder().uri(format!("/api/ops/digest?to={}", to.replace('+', "%2B"))),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["topology"][0]["kind"], "partition.removed");
+    assert_eq!(json["topology"][0]["label"], "日本 / 大阪");
+
+    let res = t
+        .app
+        .clone()
+        .oneshot(authed(
+            Request::builder().uri("/api/ops/digest?to=2026-10-18T00:00:00Z"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["from"], "2026-10-17T00:00:00Z");
+    assert_eq!(json["tasks"]["total"], 6);
+    assert_eq!(json["tasks"]["failure"], 3);
+    assert_eq!(json["tasks"]["cacheHits"], 1);
+    assert_eq!(json["failures"][0]["key"], "upstream_timeout");
+    assert_eq!(json["failures"][0]["count"], 2);
+    assert_eq!(json["latency"]["samples"], 5);
+    assert_eq!(json["latency"]["p50Ms"], 400);
+    assert_eq!(json["latency"]["p99Ms"], 6000);
+    assert_eq!(json["notify"][0]["channel"], "telegram");
+    assert_eq!(json["notify"][0]["error"], 1);
+    assert_eq!(json["notify"][1]["skipped"], 1);
+    assert_eq!(json["configsAdded"][0]["id"], "cfg_tokyo");
+    assert_eq!(json["configsAdded"].as_array().unwrap().len(), 1);
+    assert_eq!(json["configsRemoved"][0]["name"], "Osaka Mini");
+    assert_eq!(json["configsRemoved"].as_array().unwrap().len(), 1);
+    // The topology change was recorded now, outside the requested day.
+    assert_eq!(json["topology"], serde_json::json!([]));
+
+    let res = t
+        .app
+        .oneshot(authed(
+            Request::builder().uri("/api/ops/digest?to=yesterday"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
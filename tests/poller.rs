@@ -17,13 +17,32 @@ fn test_config() -> RuntimeConfig {
         update_check_timeout_ms: 1500,
         github_api_base_url: "https://api.github.com".to_string(),
         upstream_cart_url: "https://example.invalid/cart".to_string(),
+        upstream_providers: Vec::new(),
+        upstream_archive_mode: catnap::config::UpstreamArchiveMode::Off,
+        upstream_archive_dir: "upstream-archive".to_string(),
+        upstream_rate_per_second: 2.0,
+        upstream_rate_burst: 1,
+        upstream_proxies: Vec::new(),
+        upstream_breaker_failure_threshold: 5,
+        upstream_breaker_open_seconds: 60,
+        upstream_billing_cycles_enabled: false,
+        upstream_billing_cycles_refresh_seconds: 3600,
+        price_base_currency: "CNY".to_string(),
+        exchange_rates: Default::default(),
+        exchange_rates_file: None,
         lazycat_base_url: "https://lxc.lazycat.wiki".to_string(),
         lazycat_site_sync_interval_minutes: 5,
         lazycat_panel_sync_interval_minutes: 10,
         lazycat_panel_concurrency: 2,
         lazycat_panel_timeout_ms: 5_000,
         lazycat_allow_invalid_tls: true,
+        lazycat_proxy: None,
+        telegram_proxy: None,
+        github_proxy: None,
         telegram_api_base_url: "https://api.telegram.org".to_string(),
+        operator_telegram_bot_token: None,
+        operator_telegram_targets: Vec::new(),
+        operator_web_push_user_ids: Vec::new(),
         auth_user_header: Some("x-user".to_string()),
         dev_user_id: None,
         default_poll_interval_minutes: 1,
@@ -33,10 +52,36 @@ fn test_config() -> RuntimeConfig {
         notification_retention_days: 30,
         notification_retention_max_rows: 50_000,
         ops_worker_concurrency: 1,
+        ops_adaptive_concurrency: false,
+        ops_worker_concurrency_min: 1,
+        ops_worker_concurrency_max: 1,
+        ops_adaptive_latency_target_ms: 3000,
         ops_sse_replay_window_seconds: 3600,
         ops_log_retention_days: 7,
         ops_log_tail_limit_default: 200,
         ops_queue_task_limit_default: 200,
+        ops_lane_starvation_seconds: 60,
+        ops_retry_policies: Default::default(),
+        ops_retryable_error_codes: Vec::new(),
+        metrics_token: None,
+        otlp_endpoint: None,
+        otlp_service_name: "catnap".to_string(),
+        ops_admin_user_ids: Vec::new(),
+        ops_health_check_interval_seconds: 60,
+        ops_health_success_rate_min_pct: None,
+        ops_health_success_rate_window_minutes: 30,
+        ops_health_queue_depth_max: None,
+        ops_health_worker_error_minutes: None,
+        ops_health_no_success_minutes: None,
+        ops_digest_hour_utc: None,
+        hot_mode_minutes: 0,
+        hot_mode_interval_seconds: 15,
+        hot_mode_max_partitions: 3,
+        poll_forecast_enabled: false,
+        poll_forecast_dense_minutes: 1,
+        poll_forecast_sparse_factor: 2,
+        leader_lease_seconds: 0,
+        instance_id: None,
         db_url: "sqlite::memory:".to_string(),
         web_push_vapid_public_key: None,
         web_push_vapid_private_key: None,
@@ -997,3 +1042,82 @@ WHERE user_id = ?
         .unwrap();
     assert_eq!(rows.get::<i64, _>(0), 0);
 }
+
+#[tokio::test]
+async fn additional_provider_topology_refresh_retires_only_that_providers_removed_targets() {
+    let root_html = r#"
+<!doctype html>
+<div class="firstgroup_item" onclick="window.location.href='/cart?fid=7'">
+  <span class="yy-bth-text-a">JP</span>
+</div>
+"#;
+    let fid_html = include_str!("fixtures/cart-fid-7.html");
+
+    #[derive(serde::Deserialize)]
+    struct CartQuery {
+        fid: Option<String>,
+    }
+
+    let upstream = Router::new().route(
+        "/cart",
+        axum::routing::get(
+            move |axum::extract::Query(q): axum::extract::Query<CartQuery>| async move {
+                match q.fid.as_deref() {
+                    None => root_html,
+                    Some("7") => fid_html,
+                    Some(_) => "not found",
+                }
+            },
+        ),
+    );
+    let base = spawn_stub_server(upstream).await;
+
+    let mut cfg = test_config();
+    cfg.upstream_providers = vec![catnap::config::UpstreamProviderConfig {
+        id: "shopa".to_string(),
+        cart_url: format!("{base}/cart"),
+    }];
+
+    let db = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&cfg.db_url)
+        .await
+        .unwrap();
+    catnap::db::init_db(&db).await.unwrap();
+
+    let mut kept = catnap::upstream::parse_configs_for_provider("shopa", "7", None, fid_html);
+    kept.truncate(1);
+    let mut removed = catnap::upstream::parse_configs_for_provider("shopa", "9", None, fid_html);
+    removed.truncate(1);
+    let mut default_provider = catnap::upstream::parse_configs("9", None, fid_html);
+    default_provider.truncate(1);
+    for configs in [&kept, &removed, &default_provider] {
+        catnap::db::upsert_catalog_configs(&db, configs)
+            .await
+            .unwrap();
+    }
+
+    let state = build_state(cfg.clone(), db.clone()).await;
+    catnap::poller::refresh_additional_provider_topologies(&state, "test").await;
+
+    for (id, expected) in [
+        (&kept[0].id, "active"),
+        (&removed[0].id, "delisted"),
+        (&default_provider[0].id, "active"),
+    ] {
+        let row = sqlx::query("SELECT lifecycle_state FROM catalog_configs WHERE id = ?")
+            .bind(id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>(0), expected, "{id}");
+    }
+
+    let logged = sqlx::query(
+        "SELECT COUNT(*) FROM ops_events WHERE event = 'ops.log' AND data_json LIKE '%provider topology refresh ok%'",
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(logged.get::<i64, _>(0), 1);
+}
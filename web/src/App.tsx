@@ -319,7 +319,7 @@ export type CatalogRefreshStatus = {
   current?: {
     urlKey: string;
     url: string;
-    action: "fetch" | "cache";
+    action: "fetch" | "cache" | "not_modified";
     note?: string | null;
   } | null;
 };
@@ -372,6 +372,12 @@ export type NotificationRecord = {
   webPushStatus: string;
   telegramDeliveries?: NotificationRecordDelivery[];
   items: NotificationRecordItem[];
+  matchedRule?: {
+    ruleId: number;
+    name: string;
+    scope: string;
+    scopeKey: string;
+  };
 };
 
 type TelegramTestDeliveryResult = {
@@ -399,6 +405,7 @@ export type OpsRateBucket = {
   failure: number;
   successRatePct: number;
   cacheHits: number;
+  notModified?: number;
 };
 
 export type OpsSparksResponse = {
@@ -416,6 +423,15 @@ export type OpsSseUi = {
   lastReset: { serverTime: string; reason: string; details?: string | null } | null;
 };
 
+export type OpsControl = {
+  paused: boolean;
+  pausedAt: string | null;
+  pausedBy: string | null;
+  draining: boolean;
+  drainingSince: string | null;
+  drainingBy: string | null;
+};
+
 export type OpsStateResponse = {
   serverTime: string;
   range: OpsRange;
@@ -423,23 +439,55 @@ export type OpsStateResponse = {
   queue: {
     pending: number;
     running: number;
+    retryScheduled?: number;
     deduped: number;
     oldestWaitSeconds: number | null;
     reasonCounts: Record<string, number>;
+    lanes?: Array<{
+      lane: "interactive" | "poller" | "discovery";
+      pending: number;
+      running: number;
+      oldestWaitSeconds: number | null;
+    }>;
+    control?: OpsControl;
   };
   workers: Array<{
     workerId: string;
-    state: "idle" | "running" | "error";
+    state: "idle" | "running" | "error" | "parked";
     task: { fid: string; gid: string | null } | null;
     startedAt: string | null;
     lastError: { ts: string; message: string } | null;
   }>;
+  concurrency?: {
+    adaptive: boolean;
+    limit: number;
+    min: number;
+    max: number;
+    lastChange: {
+      at: string;
+      from: number;
+      to: number;
+      reason: "backlog" | "latency" | "errors";
+      avgFetchMs: number;
+      errorRatePct: number;
+      queueDepth: number;
+    } | null;
+  };
   tasks: Array<{
     key: { fid: string; gid: string | null };
-    state: "pending" | "running";
+    state: "pending" | "running" | "retry_scheduled";
     enqueuedAt: string;
     reasonCounts: Record<string, number>;
     lastRun: { endedAt: string; ok: boolean } | null;
+    attempt?: number;
+    retryAt?: string | null;
+    hot?: {
+      trigger: "restock" | "listed";
+      configIds: string[];
+      startedAt: string;
+      until: string;
+      intervalSeconds: number;
+    } | null;
   }>;
   stats: {
     collection: OpsRateBucket;
@@ -452,6 +500,43 @@ export type OpsStateResponse = {
     requestCount: number;
     message: string | null;
   };
+  rateLimits?: Array<{
+    host: string;
+    tokens: number;
+    burst: number;
+    ratePerSecond: number;
+    backoffUntil: string | null;
+    consecutiveFailures: number;
+    lastStatus: number | null;
+  }>;
+  circuits?: Array<{
+    providerId: string;
+    state: "closed" | "open" | "half_open";
+    consecutiveFailures: number;
+    openedAt: string | null;
+    retryAt: string | null;
+    lastError: string | null;
+  }>;
+  leader?: {
+    enabled: boolean;
+    instanceId: string;
+    leader: boolean;
+    holder: string | null;
+    acquiredAt: string | null;
+    expiresAt: string | null;
+    leaseSeconds: number;
+    lastHeartbeatAt: string | null;
+    lastError: string | null;
+  };
+  healthAlerts?: Array<{
+    id: number;
+    rule: "success_rate" | "queue_depth" | "worker_error" | "no_success";
+    openedAt: string;
+    resolvedAt: string | null;
+    value: number;
+    threshold: number;
+    message: string;
+  }>;
   logTail: Array<{
     eventId: number;
     ts: string;
@@ -6352,6 +6437,19 @@ function defaultCreateEventSource(url: string): EventSource {
   return new EventSource(url);
 }
 
+function opsLaneLabel(lane: string): string {
+  switch (lane) {
+    case "interactive":
+      return "手动";
+    case "poller":
+      return "轮询";
+    case "discovery":
+      return "发现";
+    default:
+      return lane;
+  }
+}
+
 function opsRangeLabel(range: OpsRange): string {
   if (range === "24h") return "24小时";
   if (range === "7d") return "7天";
@@ -6517,6 +6615,25 @@ export function OpsView({
   });
   const [toast, setToast] = useState<{ text: string; tone: "warn" | "err" | "ok" } | null>(null);
   const logRef = useRef<HTMLDivElement | null>(null);
+  const [adminBusy, setAdminBusy] = useState<boolean>(false);
+
+  const runAdminAction = useCallback(
+    async (action: "pause" | "resume" | "drain" | "clear" | "cancel", body?: unknown) => {
+      setAdminBusy(true);
+      try {
+        await api<unknown>(`/api/ops/admin/${action}`, {
+          method: "POST",
+          headers: { "content-type": "application/json" },
+          body: JSON.stringify(body ?? {}),
+        });
+      } catch (e) {
+        setToast({ tone: "err", text: `操作失败：${String(e)}` });
+      } finally {
+        setAdminBusy(false);
+      }
+    },
+    [],
+  );
 
   const formatClock = useCallback((iso: string) => {
     const ts = Date.parse(iso);
@@ -6617,10 +6734,15 @@ export function OpsView({
     const onWorkers = (ev: MessageEvent) => {
       noteEventId(ev);
       try {
-        const data = JSON.parse(ev.data) as { workers?: OpsStateResponse["workers"] };
+        const data = JSON.parse(ev.data) as {
+          workers?: OpsStateResponse["workers"];
+          concurrency?: OpsStateResponse["concurrency"];
+        };
         const workers = data.workers;
         if (!workers) return;
-        setSnap((prev) => (prev ? { ...prev, workers } : prev));
+        setSnap((prev) =>
+          prev ? { ...prev, workers, concurrency: data.concurrency ?? prev.concurrency } : prev,
+        );
       } catch {
         // ignore
       }
@@ -6630,10 +6752,16 @@ export function OpsView({
       noteEventId(ev);
       try {
         const data = JSON.parse(ev.data) as {
-          phase?: "enqueued" | "started" | "finished";
+          phase?: "enqueued" | "started" | "finished" | "canceled";
           key?: { fid: string; gid: string | null };
           reasonCounts?: Record<string, number> | null;
-          run?: { runId: number; endedAt?: string | null; ok?: boolean | null } | null;
+          retryAt?: string | null;
+          run?: {
+            runId: number;
+            attempt?: number;
+            endedAt?: string | null;
+            ok?: boolean | null;
+          } | null;
         };
         const phase = data.phase;
         const key = data.key;
@@ -6645,11 +6773,28 @@ export function OpsView({
           for (const t of prev.tasks) byKey.set(`${t.key.fid}:${t.key.gid ?? ""}`, t);
           const existing = byKey.get(keyStr);
 
-          if (phase === "finished") {
+          if (phase === "canceled") {
             byKey.delete(keyStr);
             return { ...prev, tasks: Array.from(byKey.values()) };
           }
 
+          if (phase === "finished") {
+            if (existing && data.retryAt) {
+              byKey.set(keyStr, {
+                ...existing,
+                state: "retry_scheduled",
+                attempt: (data.run?.attempt ?? existing.attempt ?? 1) + 1,
+                retryAt: data.retryAt,
+                lastRun: data.run?.endedAt
+                  ? { endedAt: data.run.endedAt, ok: false }
+                  : existing.lastRun,
+              });
+            } else {
+              byKey.delete(keyStr);
+            }
+            return { ...prev, tasks: Array.from(byKey.values()) };
+          }
+
           const next: OpsStateResponse["tasks"][number] = {
             key: { fid: key.fid, gid: key.gid },
             state: phase === "started" ? "running" : (existing?.state ?? "pending"),
@@ -6659,6 +6804,9 @@ export function OpsView({
               number
             >,
             lastRun: existing?.lastRun ?? null,
+            attempt: data.run?.attempt ?? existing?.attempt,
+            retryAt: phase === "started" ? null : (existing?.retryAt ?? null),
+            hot: existing?.hot ?? null,
           };
           byKey.set(keyStr, next);
           return { ...prev, tasks: Array.from(byKey.values()) };
@@ -6692,6 +6840,67 @@ export function OpsView({
       }
     };
 
+    const onOutage = (ev: MessageEvent) => {
+      noteEventId(ev);
+      try {
+        const data = JSON.parse(ev.data) as { phase: "opened" | "closed"; providerId: string };
+        setToast({
+          tone: data.phase === "opened" ? "warn" : "ok",
+          text:
+            data.phase === "opened"
+              ? `上游 ${data.providerId} 不可达，已暂停轮询任务`
+              : `上游 ${data.providerId} 已恢复`,
+        });
+      } catch {
+        // ignore
+      }
+      void refresh();
+    };
+
+    const onHealth = (ev: MessageEvent) => {
+      noteEventId(ev);
+      try {
+        const data = JSON.parse(ev.data) as {
+          phase: "opened" | "resolved";
+          alert: { message: string };
+        };
+        setToast({
+          tone: data.phase === "opened" ? "warn" : "ok",
+          text:
+            data.phase === "opened"
+              ? `健康告警：${data.alert.message}`
+              : `健康告警已恢复：${data.alert.message}`,
+        });
+      } catch {
+        // ignore
+      }
+      void refresh();
+    };
+
+    const onAdmin = (ev: MessageEvent) => {
+      noteEventId(ev);
+      try {
+        const data = JSON.parse(ev.data) as {
+          action: "pause" | "resume" | "drain" | "drained" | "cancel" | "clear";
+          actor: string;
+          canceled?: unknown[];
+        };
+        const text =
+          data.action === "pause"
+            ? `队列已暂停（${data.actor}）`
+            : data.action === "resume"
+              ? `队列已恢复（${data.actor}）`
+              : data.action === "drain"
+                ? `队列排空中（${data.actor}）`
+                : data.action === "drained"
+                  ? "队列已排空并暂停"
+                  : `已取消 ${data.canceled?.length ?? 0} 个任务（${data.actor}）`;
+        setToast({ tone: data.action === "resume" ? "ok" : "warn", text });
+      } catch {
+        // ignore
+      }
+    };
+
     const onReset = (ev: MessageEvent) => {
       noteEventId(ev);
       try {
@@ -6729,6 +6938,9 @@ export function OpsView({
     es.addEventListener("ops.task", onTask as EventListener);
     es.addEventListener("ops.log", onLog as EventListener);
     es.addEventListener("ops.reset", onReset as EventListener);
+    es.addEventListener("ops.outage", onOutage as EventListener);
+    es.addEventListener("ops.admin", onAdmin as EventListener);
+    es.addEventListener("ops.health", onHealth as EventListener);
 
     return () => {
       closed = true;
@@ -6774,7 +6986,11 @@ export function OpsView({
     return null;
   }, [snap?.logTail]);
 
-  const workerConcurrency = snap?.workers.length ?? 0;
+  const workerConcurrency = snap?.concurrency?.limit ?? snap?.workers.length ?? 0;
+  const concurrencyChange = snap?.concurrency?.lastChange ?? null;
+  const throttledHosts = (snap?.rateLimits ?? []).filter((item) => item.backoffUntil);
+  const openCircuits = (snap?.circuits ?? []).filter((item) => item.state !== "closed");
+  const healthAlerts = snap?.healthAlerts ?? [];
   const rangeText = opsRangeLabel(range);
 
   return (
@@ -6786,6 +7002,17 @@ export function OpsView({
           <p className="muted">Loading…</p>
         ) : (
           <div className="ops-layout">
+            {healthAlerts.length > 0 ? (
+              <div className="ops-health-alerts" role="alert">
+                {healthAlerts.map((alert) => (
+                  <div key={alert.id} className="ops-health-alert">
+                    <span className="ops-dot err" aria-hidden="true" />
+                    <span>{alert.message}</span>
+                    <span className="muted">{`自 ${formatClock(alert.openedAt)}`}</span>
+                  </div>
+                ))}
+              </div>
+            ) : null}
             <div className="ops-kpi-grid">
               <div
                 className="ops-kpi-card"
@@ -6801,8 +7028,34 @@ export function OpsView({
                   <span className="ops-kpi-value">{formatCompactCount(snap.queue.pending)}</span>
                   <span className="ops-kpi-unit">待处理</span>
                 </div>
-                <div className="ops-kpi-sub">{`运行中：${snap.queue.running} • 合并：${snap.queue.deduped}`}</div>
+                <div className="ops-kpi-sub">{`运行中：${snap.queue.running} • 待重试：${snap.queue.retryScheduled ?? 0} • 合并：${snap.queue.deduped}`}</div>
+                {(snap.queue.lanes ?? []).some((lane) => lane.pending > 0) ? (
+                  <div className="ops-kpi-meta">
+                    {(snap.queue.lanes ?? [])
+                      .filter((lane) => lane.pending > 0)
+                      .map(
+                        (lane) =>
+                          `${opsLaneLabel(lane.lane)} ${lane.pending}（${lane.oldestWaitSeconds ?? 0}s）`,
+                      )
+                      .join(" • ")}
+                  </div>
+                ) : null}
                 <div className="ops-kpi-meta">{`最老等待：${snap.queue.oldestWaitSeconds ?? 0}s • 更新：${formatClock(snap.serverTime)}${loading ? "（刷新中）" : ""}`}</div>
+                {snap.queue.control?.paused || snap.queue.control?.draining ? (
+                  <div className="ops-kpi-meta">
+                    {snap.queue.control.paused
+                      ? `已暂停：${snap.queue.control.pausedBy ?? "-"} ${snap.queue.control.pausedAt ? formatClock(snap.queue.control.pausedAt) : ""}`
+                      : `排空中：${snap.queue.control.drainingBy ?? "-"} ${snap.queue.control.drainingSince ? formatClock(snap.queue.control.drainingSince) : ""}`}
+                  </div>
+                ) : null}
+                {snap.leader?.enabled ? (
+                  <div className="ops-kpi-meta">
+                    {snap.leader.leader
+                      ? `主实例：${snap.leader.instanceId}${snap.leader.acquiredAt ? ` 自 ${formatClock(snap.leader.acquiredAt)}` : ""}`
+                      : `从实例：${snap.leader.instanceId} • 主实例：${snap.leader.holder ?? "-"}（后台任务暂停）`}
+                    {snap.leader.lastError ? ` • 续租失败：${snap.leader.lastError}` : ""}
+                  </div>
+                ) : null}
                 <Sparkline values={snap.sparks.volume} stroke="var(--ops-green)" />
               </div>
 
@@ -6820,7 +7073,7 @@ export function OpsView({
                   <span className="ops-kpi-value">{`${snap.stats.collection.successRatePct.toFixed(1)}%`}</span>
                 </div>
                 <div className="ops-kpi-sub">{`成功：${snap.stats.collection.success} • 失败：${snap.stats.collection.failure}`}</div>
-                <div className="ops-kpi-meta">{`cache hit：${snap.stats.collection.cacheHits} • 口径：${rangeText}`}</div>
+                <div className="ops-kpi-meta">{`cache hit：${snap.stats.collection.cacheHits} • 未变更：${snap.stats.collection.notModified ?? 0} • 口径：${rangeText}`}</div>
                 <Sparkline values={snap.sparks.collectionSuccessRatePct} stroke="var(--ops-blue)" />
               </div>
 
@@ -6879,7 +7132,42 @@ export function OpsView({
             <div className="ops-block-grid">
               <section className="ops-block">
                 <div className="ops-block-head">
-                  <div className="ops-block-title">{`工作者（并发=${workerConcurrency}）`}</div>
+                  <div className="ops-block-title">
+                    {snap.concurrency?.adaptive
+                      ? `工作者（并发=${workerConcurrency}，自适应 ${snap.concurrency.min}–${snap.concurrency.max}）`
+                      : `工作者（并发=${workerConcurrency}）`}
+                  </div>
+                  {concurrencyChange ? (
+                    <div className="ops-block-subtitle muted">
+                      {`${formatClock(concurrencyChange.at)} 并发 ${concurrencyChange.from} → ${concurrencyChange.to}（${
+                        concurrencyChange.reason === "backlog"
+                          ? "积压"
+                          : concurrencyChange.reason === "latency"
+                            ? "延迟升高"
+                            : "错误率升高"
+                      }：平均 ${concurrencyChange.avgFetchMs}ms，错误 ${concurrencyChange.errorRatePct}%，排队 ${concurrencyChange.queueDepth}）`}
+                    </div>
+                  ) : null}
+                  {throttledHosts.length > 0 ? (
+                    <div className="ops-block-subtitle muted">
+                      {`上游限流：${throttledHosts
+                        .map(
+                          (item) =>
+                            `${item.host} 退避至 ${formatClock(item.backoffUntil ?? "")}（HTTP ${item.lastStatus ?? "—"}）`,
+                        )
+                        .join(" • ")}`}
+                    </div>
+                  ) : null}
+                  {openCircuits.length > 0 ? (
+                    <div className="ops-block-subtitle muted">
+                      {`上游熔断：${openCircuits
+                        .map(
+                          (item) =>
+                            `${item.providerId} ${item.state === "open" ? "已熔断" : "探测中"}，下次探测 ${formatClock(item.retryAt ?? "")}`,
+                        )
+                        .join(" • ")}`}
+                    </div>
+                  ) : null}
                 </div>
                 <div className="ops-block-divider" />
                 <div className="ops-workers">
@@ -6901,7 +7189,9 @@ export function OpsView({
                         ? "ops-dot ok"
                         : w.state === "error"
                           ? "ops-dot err"
-                          : "ops-dot idle";
+                          : w.state === "parked"
+                            ? "ops-dot pend"
+                            : "ops-dot idle";
 
                     return (
                       <div className="ops-worker" key={w.workerId}>
@@ -6934,6 +7224,45 @@ export function OpsView({
                 <div className="ops-block-head">
                   <div className="ops-block-title">队列任务</div>
                   <div className="ops-block-subtitle muted">{`discovery=${snap.queue.reasonCounts.discovery_due ?? 0} • poller=${snap.queue.reasonCounts.poller_due ?? 0} • manual=${snap.queue.reasonCounts.manual_refresh ?? 0}`}</div>
+                  <div className="ops-admin-actions">
+                    {snap.queue.control?.paused || snap.queue.control?.draining ? (
+                      <button
+                        type="button"
+                        className="pill sm"
+                        disabled={adminBusy}
+                        onClick={() => void runAdminAction("resume")}
+                      >
+                        恢复
+                      </button>
+                    ) : (
+                      <>
+                        <button
+                          type="button"
+                          className="pill sm"
+                          disabled={adminBusy}
+                          onClick={() => void runAdminAction("pause")}
+                        >
+                          暂停
+                        </button>
+                        <button
+                          type="button"
+                          className="pill sm"
+                          disabled={adminBusy}
+                          onClick={() => void runAdminAction("drain")}
+                        >
+                          排空
+                        </button>
+                      </>
+                    )}
+                    <button
+                      type="button"
+                      className="pill warn sm"
+                      disabled={adminBusy}
+                      onClick={() => void runAdminAction("clear")}
+                    >
+                      清空队列
+                    </button>
+                  </div>
                 </div>
                 <div className="ops-block-divider" />
                 <div className="ops-tasks">
@@ -6946,7 +7275,12 @@ export function OpsView({
                   <div className="ops-block-divider thin" />
                   {snap.tasks.length ? (
                     snap.tasks.map((t) => {
-                      const dotClass = t.state === "running" ? "ops-dot ok" : "ops-dot pend";
+                      const dotClass =
+                        t.state === "running"
+                          ? "ops-dot ok"
+                          : t.state === "retry_scheduled"
+                            ? "ops-dot warn"
+                            : "ops-dot pend";
                       const reasons = Object.entries(t.reasonCounts)
                         .map(([k, v]) => {
                           const short =
@@ -6958,9 +7292,12 @@ export function OpsView({
                           return `${short}=${v}`;
                         })
                         .join(", ");
-                      const lastText = t.lastRun
-                        ? `${t.lastRun.ok ? "成功" : "失败"} ${formatClock(t.lastRun.endedAt)}`
-                        : "—";
+                      const lastText =
+                        t.state === "retry_scheduled" && t.retryAt
+                          ? `第 ${t.attempt ?? 2} 次尝试 ${formatClock(t.retryAt)}`
+                          : t.lastRun
+                            ? `${t.lastRun.ok ? "成功" : "失败"} ${formatClock(t.lastRun.endedAt)}`
+                            : "—";
                       return (
                         <div className="ops-task" key={`${t.key.fid}:${t.key.gid ?? ""}`}>
                           <div className="ops-task-state">
@@ -6968,11 +7305,37 @@ export function OpsView({
                               <span className={dotClass} />
                             </span>
                           </div>
-                          <div className="mono">{`${t.key.fid} / ${t.key.gid ?? "-"}`}</div>
+                          <div className="mono">
+                            {`${t.key.fid} / ${t.key.gid ?? "-"}`}
+                            {t.hot ? (
+                              <span
+                                className="pill warn sm ops-task-hot"
+                                title={`${t.hot.trigger === "restock" ? "补货" : "上新"}：${t.hot.configIds.join(", ")}`}
+                              >
+                                {`热点 ${t.hot.intervalSeconds}s 至 ${formatClock(t.hot.until)}`}
+                              </span>
+                            ) : null}
+                          </div>
                           <div className="mono" title={reasons || "—"}>
                             {reasons || "—"}
                           </div>
-                          <div className="muted ops-tasks-right">{lastText}</div>
+                          <div className="muted ops-tasks-right">
+                            {lastText}
+                            {t.state !== "running" ? (
+                              <button
+                                type="button"
+                                className="pill warn sm"
+                                disabled={adminBusy}
+                                onClick={() =>
+                                  void runAdminAction("cancel", {
+                                    keys: [{ fid: t.key.fid, gid: t.key.gid }],
+                                  })
+                                }
+                              >
+                                取消
+                              </button>
+                            ) : null}
+                          </div>
                         </div>
                       );
                     })